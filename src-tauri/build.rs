@@ -10,6 +10,17 @@ fn main() {
             .flag_if_supported("/EHsc")
             .warnings(true)
             .compile("scanner");
+        println!("cargo:rustc-link-lib=shlwapi");
+        println!("cargo:rustc-link-lib=advapi32");
+        println!("cargo:rustc-link-lib=ole32");
+        println!("cargo:rustc-link-lib=uuid");
+        println!("cargo:rustc-link-lib=rstrtmgr");
+        println!("cargo:rustc-link-lib=mpr");
+        println!("cargo:rustc-link-lib=windowscodecs");
+        println!("cargo:rustc-link-lib=mfplat");
+        println!("cargo:rustc-link-lib=mfreadwrite");
+        println!("cargo:rustc-link-lib=mfuuid");
+        println!("cargo:rustc-link-lib=version");
 
         let windows = tauri_build::WindowsAttributes::new()
             .app_manifest(include_str!("windows-app-manifest.xml"));
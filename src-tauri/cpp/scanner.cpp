@@ -5,6 +5,20 @@
 #include <windows.h>
 #include <winioctl.h>
 #include <shellapi.h>
+#include <exdisp.h>
+#include <shlobj.h>
+#include <shlwapi.h>
+#include <sddl.h>
+#include <aclapi.h>
+#include <restartmanager.h>
+#include <winnetwk.h>
+#include <wincodec.h>
+#include <mfapi.h>
+#include <mfidl.h>
+#include <mfreadwrite.h>
+// Defines the PKEY_* constants below instead of expecting them from an import library.
+#include <initguid.h>
+#include <propkey.h>
 
 #include <algorithm>
 #include <atomic>
@@ -13,9 +27,13 @@
 #include <cstdint>
 #include <cstdlib>
 #include <cstring>
+#include <cwchar>
 #include <cwctype>
+#include <iterator>
 #include <limits>
+#include <memory>
 #include <mutex>
+#include <random>
 
 #include <shared_mutex>
 #include <string>
@@ -31,13 +49,18 @@ struct RawUsnEntry {
   uint64_t parent_frn;
   std::wstring name;
   bool is_directory;
+  uint32_t attributes;
   uint32_t reason;
+  // Read by the live watcher for files that still exist; the records carry neither.
+  uint64_t size = 0;
+  int64_t modified_unix = 0;
 };
 
 struct NodeEntry {
   uint64_t parent_frn;
   std::wstring name;
   bool is_directory;
+  uint32_t attributes;
 };
 
 struct IndexedFile {
@@ -45,6 +68,11 @@ struct IndexedFile {
   uint64_t parent_frn;
   std::wstring path;
   bool is_directory;
+  uint32_t attributes;
+  // As of the scan or the latest live update; 0 for folders and for files whose metadata
+  // could not be read.
+  uint64_t size = 0;
+  int64_t modified_unix = 0;
 };
 
 struct ScanSnapshot {
@@ -57,6 +85,12 @@ struct ScanSnapshot {
   bool live_updates_supported = false;
 };
 
+// Half-open UTF-16 offsets of a query match, which map directly onto JS string indices.
+struct MatchRange {
+  uint32_t start;
+  uint32_t end;
+};
+
 struct SearchRow {
   std::wstring name;
   std::wstring path;
@@ -65,6 +99,48 @@ struct SearchRow {
   int64_t created_unix;
   int64_t modified_unix;
   bool is_directory;
+  bool is_reparse_point;
+  std::wstring target_path;
+  std::vector<MatchRange> name_ranges = {};
+  std::vector<MatchRange> path_ranges = {};
+  // Resolved owner account, only filled in when the search filtered by owner.
+  std::wstring owner = {};
+  // 8.3 short name, only filled in when short-name matching was requested and it differs
+  // from the long name.
+  std::wstring short_name = {};
+  // Relevance of the name to the query in [0, 1]; negative unless results are sorted by it.
+  double score = -1.0;
+  // Online-only cloud file (OneDrive and similar); reading its content downloads it.
+  bool is_cloud_placeholder = false;
+  // NTFS file reference number on MFT-scanned volumes, otherwise an id assigned by the scan.
+  uint64_t file_id = 0;
+};
+
+enum class SearchScope : uint32_t {
+  Path = 0,
+  Name = 1,
+};
+
+enum class SearchSortOrder : uint32_t {
+  // Index order, stopping at the limit.
+  Index = 0,
+  // Best-named matches first, ties broken by most recently modified.
+  RelevanceRecency = 1,
+};
+
+enum class MatchPosition : uint32_t {
+  Anywhere = 0,
+  // The name starts with the query.
+  Prefix = 1,
+  // The base name before the extension, or the whole name, ends with the query.
+  Suffix = 2,
+};
+
+enum class QueryMatchMode : uint32_t {
+  // Every whitespace-separated token must appear, in any order.
+  Tokens = 0,
+  // The query must appear as one literal substring.
+  Phrase = 1,
 };
 
 enum class ContentSearchMode {
@@ -104,6 +180,14 @@ struct DuplicateGroupRow {
   std::vector<DuplicateFileRow> files;
 };
 
+enum class DuplicateGroupMode : uint32_t {
+  Content = 0,
+  Size = 1,
+  Name = 2,
+  // Size plus a hash of the first and last 64 KB; no full read or byte comparison.
+  Partial = 3,
+};
+
 struct DriveInfo {
   std::wstring letter;
   std::wstring path;
@@ -111,6 +195,23 @@ struct DriveInfo {
   std::wstring drive_type;
   bool is_ntfs;
   bool can_open_volume;
+  std::wstring volume_guid;
+  uint32_t serial_number;
+  // `\\server\share` behind a mapped network drive or a registered network root.
+  std::wstring unc_target;
+  // How indexing reads the drive: "mft", "usn", "walk" or "unsupported" (see DriveScanMode).
+  std::wstring scan_mode;
+};
+
+struct DriveIndexState {
+  std::wstring drive;
+  uint64_t indexed_count = 0;
+  bool ready = false;
+  std::string error;
+  // When the drive's last successful scan finished; zero until then.
+  int64_t indexed_unix = 0;
+  // A USN watcher is currently applying live changes for the drive.
+  bool live_updates = false;
 };
 
 std::shared_mutex g_index_mutex;
@@ -122,18 +223,84 @@ std::wstring g_root_path;
 std::atomic<bool> g_is_indexing{false};
 std::atomic<bool> g_is_ready{false};
 std::atomic<uint64_t> g_indexed_count{0};
+// Diagnostics for the latest indexing run: MFT enumeration output read and USN records
+// parsed, plus GetTickCount64 stamps of when the run started and finished (zero while open).
+std::atomic<uint64_t> g_index_bytes_read{0};
+std::atomic<uint64_t> g_index_records_processed{0};
+std::atomic<uint64_t> g_index_run_started_ms{0};
+std::atomic<uint64_t> g_index_run_finished_ms{0};
+// When the last successful indexing run completed and how long it took; zero before one has.
+std::atomic<int64_t> g_last_index_completed_unix{0};
+std::atomic<uint64_t> g_last_index_duration_ms{0};
 std::atomic<bool> g_include_directories{false};
+std::atomic<bool> g_include_system_files{false};
+// Indexing threads run in background processing mode (very low I/O and memory priority).
+std::atomic<bool> g_low_priority_indexing{false};
+// Soft cap on the index's estimated heap use in bytes (zero = unlimited), the estimate as of the
+// last index run or budget change, and whether entries were dropped to honour the cap.
+std::atomic<uint64_t> g_index_memory_budget{0};
+std::atomic<uint64_t> g_index_memory_bytes{0};
+std::atomic<bool> g_index_trimmed{false};
 std::atomic<bool> g_scan_all_drives_mode{false};
+std::atomic<uint32_t> g_indexing_threads{1};
 std::atomic<uint64_t> g_indexing_request_token{0};
 std::atomic<uint64_t> g_live_watcher_token{0};
 std::atomic<bool> g_duplicate_scan_running{false};
 std::atomic<bool> g_duplicate_cancel_requested{false};
 std::atomic<uint64_t> g_search_request_token{0};
+// Caller-chosen id of the search holding the current request token (zero for none), and
+// ids cancelled before their searches started, oldest first and at most
+// kMaxPendingSearchCancels of them. Guarded by g_search_id_mutex.
+constexpr size_t kMaxPendingSearchCancels = 64;
+std::mutex g_search_id_mutex;
+uint64_t g_active_search_id = 0;
+std::vector<uint64_t> g_cancelled_search_ids;
 std::atomic<uint64_t> g_duplicate_progress_done{0};
 std::atomic<uint64_t> g_duplicate_progress_total{0};
 std::atomic<uint64_t> g_duplicate_groups_found{0};
+// Groups confirmed since the last omni_take_duplicate_groups_json call, in discovery order.
+std::mutex g_duplicate_pending_mutex;
+std::vector<DuplicateGroupRow> g_duplicate_pending_groups;
+// Files whose size or modified time changed between enumeration and hashing in the current or
+// last duplicate scan, so groups containing them may be wrong.
+std::mutex g_duplicate_volatile_mutex;
+std::vector<std::wstring> g_duplicate_volatile_files;
+// Per-scan I/O limits: hashing workers (zero = automatic) and read throughput (zero = none).
+std::atomic<uint32_t> g_duplicate_io_concurrency{0};
+std::atomic<uint64_t> g_duplicate_throttle_bytes_per_sec{0};
+std::mutex g_duplicate_throttle_mutex;
+std::chrono::steady_clock::time_point g_duplicate_throttle_next_read{};
 std::mutex g_error_mutex;
 std::string g_last_error;
+std::mutex g_drive_state_mutex;
+std::vector<DriveIndexState> g_drive_index_states;
+std::mutex g_network_roots_mutex;
+std::vector<std::wstring> g_network_roots;
+// Bumped by every mutation of `g_indexed_files`, so derived structures know when to rebuild.
+std::atomic<uint64_t> g_index_generation{0};
+// Entries added, removed and rewritten in place by live updates since they were last taken
+// with omni_take_index_update_counts.
+std::atomic<uint64_t> g_index_added_count{0};
+std::atomic<uint64_t> g_index_removed_count{0};
+std::atomic<uint64_t> g_index_modified_count{0};
+
+// Lowercase name bigrams (`c0 << 16 | c1`, with a NUL before the first character so it forms
+// one too) mapped to the sorted index positions of the names containing them. Serves short
+// name queries and exact path lookups without a full scan. Built on first use, kept current
+// by single-entry live updates and dropped when the whole index is rebuilt.
+struct NameBigramIndex {
+  bool built = false;
+  std::unordered_map<uint32_t, std::vector<uint32_t>> postings;
+};
+std::mutex g_name_bigram_mutex;
+NameBigramIndex g_name_bigram_index;
+constexpr size_t kShortQueryMaxLength = 2;
+
+struct DirectorySizeSummary {
+  uint64_t total_bytes = 0;
+  uint64_t file_count = 0;
+  uint64_t subdir_count = 0;
+};
 
 std::vector<DriveInfo> list_drives_internal();
 
@@ -208,6 +375,120 @@ bool PathStartsWithInsensitive(const std::wstring& path,
   return path.size() == prefix.size() || path[prefix.size()] == L'\\';
 }
 
+std::wstring NormalizeScopePath(std::wstring path) {
+  for (wchar_t& ch : path) {
+    if (ch == L'/') {
+      ch = L'\\';
+    }
+  }
+  // Keep the separator on drive roots ("C:\\") but drop it everywhere else.
+  while (path.size() > 3 && path.back() == L'\\') {
+    path.pop_back();
+  }
+  if (path.size() == 2 && path[1] == L':') {
+    path.push_back(L'\\');
+  }
+  return path;
+}
+
+bool IsPathWithinScope(const std::wstring& path, const std::wstring& scope) {
+  if (scope.empty()) {
+    return true;
+  }
+  if (scope.back() == L'\\') {
+    return path.size() > scope.size() &&
+           CompareStringOrdinal(path.c_str(), static_cast<int>(scope.size()),
+                                scope.c_str(), static_cast<int>(scope.size()),
+                                TRUE) == CSTR_EQUAL;
+  }
+  return path.size() > scope.size() && PathStartsWithInsensitive(path, scope);
+}
+
+// A search scope where a segment that is exactly "*" matches any single folder name,
+// e.g. "C:\Users\*\Downloads".
+struct PathScope {
+  std::wstring literal;
+  std::vector<std::wstring> segments;
+  bool has_wildcard = false;
+};
+
+PathScope BuildPathScope(const std::wstring& scope) {
+  PathScope out;
+  out.literal = scope;
+  if (scope.find(L'*') == std::wstring::npos) {
+    return out;
+  }
+  size_t start = 0;
+  while (start < scope.size()) {
+    size_t end = scope.find(L'\\', start);
+    if (end == std::wstring::npos) {
+      end = scope.size();
+    }
+    if (end > start) {
+      out.segments.push_back(scope.substr(start, end - start));
+    }
+    start = end + 1;
+  }
+  out.has_wildcard = true;
+  return out;
+}
+
+bool IsPathWithinPathScope(const std::wstring& path, const PathScope& scope) {
+  if (!scope.has_wildcard) {
+    return IsPathWithinScope(path, scope.literal);
+  }
+  size_t position = 0;
+  for (const std::wstring& segment : scope.segments) {
+    if (position >= path.size()) {
+      return false;
+    }
+    size_t end = path.find(L'\\', position);
+    if (end == std::wstring::npos) {
+      end = path.size();
+    }
+    const size_t length = end - position;
+    if (length == 0) {
+      return false;
+    }
+    if (segment != L"*" &&
+        (length != segment.size() ||
+         CompareStringOrdinal(path.c_str() + position, static_cast<int>(length),
+                              segment.c_str(), static_cast<int>(length),
+                              TRUE) != CSTR_EQUAL)) {
+      return false;
+    }
+    position = end + 1;
+  }
+  // Only entries strictly inside the scope match, as with literal scopes.
+  return position < path.size();
+}
+
+// Non-empty segments of `path`: `C:\Users\me` has three and `\\server\share` two.
+size_t CountPathSegments(const std::wstring& path) {
+  size_t count = 0;
+  bool in_segment = false;
+  for (const wchar_t ch : path) {
+    if (ch == L'\\' || ch == L'/') {
+      in_segment = false;
+    } else if (!in_segment) {
+      in_segment = true;
+      ++count;
+    }
+  }
+  return count;
+}
+
+std::wstring ParentPathOf(const std::wstring& path) {
+  const size_t slash = path.find_last_of(L"\\/");
+  if (slash == std::wstring::npos) {
+    return L"";
+  }
+  if (slash == 2 && path.size() > 1 && path[1] == L':') {
+    return path.substr(0, 3);
+  }
+  return path.substr(0, slash);
+}
+
 std::string DescribeWin32Error(const DWORD error_code) {
   LPSTR message_buffer = nullptr;
   const DWORD flags = FORMAT_MESSAGE_ALLOCATE_BUFFER | FORMAT_MESSAGE_FROM_SYSTEM |
@@ -285,6 +566,27 @@ bool IsIndexingCancelled(const uint64_t request_token) {
   return g_indexing_request_token.load(std::memory_order_acquire) != request_token;
 }
 
+// Puts the calling thread in background processing mode for its lifetime when low-priority
+// indexing was requested. Nested scopes on the same thread leave the mode to the outermost.
+struct IndexingPriorityScope {
+  bool entered = false;
+
+  IndexingPriorityScope() {
+    if (g_low_priority_indexing.load(std::memory_order_acquire)) {
+      entered = SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN) != FALSE;
+    }
+  }
+
+  ~IndexingPriorityScope() {
+    if (entered) {
+      SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_END);
+    }
+  }
+
+  IndexingPriorityScope(const IndexingPriorityScope&) = delete;
+  IndexingPriorityScope& operator=(const IndexingPriorityScope&) = delete;
+};
+
 bool IsDuplicateScanCancelRequested() {
   return g_duplicate_cancel_requested.load(std::memory_order_acquire);
 }
@@ -296,10 +598,57 @@ bool IsSearchCancelled(const uint64_t request_token) {
   return g_search_request_token.load(std::memory_order_acquire) != request_token;
 }
 
+// Paces duplicate-scan reads so all workers together stay under the throughput cap: each
+// read reserves a time slot sized by its bytes and waits for it, waking early on cancel.
+void ThrottleDuplicateRead(const uint64_t bytes) {
+  const uint64_t bytes_per_sec =
+      g_duplicate_throttle_bytes_per_sec.load(std::memory_order_acquire);
+  if (bytes_per_sec == 0 || bytes == 0) {
+    return;
+  }
+
+  const auto cost = std::chrono::nanoseconds(
+      static_cast<int64_t>(bytes * 1000000000ULL / bytes_per_sec));
+  std::chrono::steady_clock::time_point wake_at;
+  {
+    std::lock_guard<std::mutex> lock(g_duplicate_throttle_mutex);
+    const auto now = std::chrono::steady_clock::now();
+    if (g_duplicate_throttle_next_read < now) {
+      g_duplicate_throttle_next_read = now;
+    }
+    wake_at = g_duplicate_throttle_next_read;
+    g_duplicate_throttle_next_read += cost;
+  }
+
+  constexpr auto kMaxSleepSlice = std::chrono::milliseconds(100);
+  while (!IsDuplicateScanCancelRequested()) {
+    const auto now = std::chrono::steady_clock::now();
+    if (now >= wake_at) {
+      return;
+    }
+    std::this_thread::sleep_for(std::min<std::chrono::steady_clock::duration>(
+        wake_at - now, kMaxSleepSlice));
+  }
+}
+
 void ResetDuplicateProgress() {
   g_duplicate_progress_done.store(0, std::memory_order_release);
   g_duplicate_progress_total.store(0, std::memory_order_release);
   g_duplicate_groups_found.store(0, std::memory_order_release);
+  {
+    std::lock_guard<std::mutex> lock(g_duplicate_pending_mutex);
+    g_duplicate_pending_groups.clear();
+  }
+  std::lock_guard<std::mutex> volatile_lock(g_duplicate_volatile_mutex);
+  g_duplicate_volatile_files.clear();
+}
+
+// Counts the group just appended to `groups` and queues a copy for streaming to the UI.
+void PublishDuplicateGroup(const std::vector<DuplicateGroupRow>& groups) {
+  g_duplicate_groups_found.store(static_cast<uint64_t>(groups.size()),
+                                 std::memory_order_release);
+  std::lock_guard<std::mutex> lock(g_duplicate_pending_mutex);
+  g_duplicate_pending_groups.push_back(groups.back());
 }
 
 void AddDuplicateProgressTotal(const uint64_t units) {
@@ -316,6 +665,67 @@ void AddDuplicateProgressDone(const uint64_t units) {
   g_duplicate_progress_done.fetch_add(units, std::memory_order_acq_rel);
 }
 
+void ResetDriveIndexStates(const std::vector<std::wstring>& drives) {
+  std::lock_guard<std::mutex> lock(g_drive_state_mutex);
+  g_drive_index_states.clear();
+  for (const std::wstring& drive : drives) {
+    g_drive_index_states.push_back(DriveIndexState{drive, 0, false, "", 0, false});
+  }
+}
+
+int64_t CurrentUnixSeconds() {
+  return std::chrono::duration_cast<std::chrono::seconds>(
+             std::chrono::system_clock::now().time_since_epoch())
+      .count();
+}
+
+void RecordDriveIndexResult(const std::wstring& drive, const bool ok,
+                            const uint64_t indexed_count, const std::string& error) {
+  std::lock_guard<std::mutex> lock(g_drive_state_mutex);
+  for (DriveIndexState& state : g_drive_index_states) {
+    if (state.drive == drive) {
+      state.indexed_count = ok ? indexed_count : 0;
+      state.ready = ok;
+      state.error = ok ? "" : (error.empty() ? "Unknown indexing error." : error);
+      state.indexed_unix = ok ? CurrentUnixSeconds() : 0;
+      return;
+    }
+  }
+}
+
+void SetDriveLiveUpdates(const std::wstring& drive, const bool active) {
+  std::lock_guard<std::mutex> lock(g_drive_state_mutex);
+  for (DriveIndexState& state : g_drive_index_states) {
+    if (state.drive == drive) {
+      state.live_updates = active;
+      return;
+    }
+  }
+}
+
+std::vector<std::wstring> RegisteredNetworkRoots() {
+  std::lock_guard<std::mutex> lock(g_network_roots_mutex);
+  return g_network_roots;
+}
+
+// Drives plus registered network roots, which get their own per-root index state.
+std::vector<std::wstring> WithNetworkRoots(std::vector<std::wstring> drives) {
+  for (std::wstring& root : RegisteredNetworkRoots()) {
+    drives.push_back(std::move(root));
+  }
+  return drives;
+}
+
+std::wstring QueryMappedDriveTarget(const std::wstring& drive_letter) {
+  const std::wstring local_name = drive_letter + L":";
+  wchar_t remote_name[MAX_PATH * 2] = L"";
+  DWORD length = ARRAYSIZE(remote_name);
+  if (WNetGetConnectionW(local_name.c_str(), remote_name, &length) != NO_ERROR) {
+    return L"";
+  }
+  return remote_name;
+}
+
 std::wstring NormalizeDriveLetter(const char* drive_utf8) {
   std::wstring drive = Utf8ToWide(drive_utf8 == nullptr ? "C" : drive_utf8);
   if (drive.empty()) {
@@ -328,6 +738,30 @@ std::wstring NormalizeDriveLetter(const char* drive_utf8) {
   return std::wstring(1, candidate);
 }
 
+// Splits a comma-separated drive list ("C,D") into unique uppercase letters, keeping order.
+std::vector<std::wstring> ParseDriveList(const char* drives_utf8) {
+  std::vector<std::wstring> drives;
+  const std::wstring raw = Utf8ToWide(drives_utf8 == nullptr ? "" : drives_utf8);
+  size_t start = 0;
+  while (start < raw.size()) {
+    const size_t delimiter = raw.find(L',', start);
+    const size_t end = delimiter == std::wstring::npos ? raw.size() : delimiter;
+    const size_t first = raw.find_first_not_of(L" \t", start);
+    if (first != std::wstring::npos && first < end) {
+      const wchar_t letter = static_cast<wchar_t>(std::towupper(raw[first]));
+      if (letter >= L'A' && letter <= L'Z' &&
+          std::find(drives.begin(), drives.end(), std::wstring(1, letter)) == drives.end()) {
+        drives.push_back(std::wstring(1, letter));
+      }
+    }
+    if (delimiter == std::wstring::npos) {
+      break;
+    }
+    start = delimiter + 1;
+  }
+  return drives;
+}
+
 std::wstring DriveTypeToText(const UINT drive_type) {
   switch (drive_type) {
     case DRIVE_FIXED:
@@ -360,6 +794,44 @@ bool CanOpenVolume(const std::wstring& drive_letter) {
   return true;
 }
 
+bool HasQueryableUsnJournal(const std::wstring& drive_letter) {
+  const std::wstring volume_path = L"\\\\.\\" + drive_letter + L":";
+  HANDLE volume = CreateFileW(
+      volume_path.c_str(), GENERIC_READ,
+      FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr, OPEN_EXISTING,
+      FILE_ATTRIBUTE_NORMAL, nullptr);
+  if (volume == INVALID_HANDLE_VALUE) {
+    return false;
+  }
+  DWORD bytes = 0;
+  USN_JOURNAL_DATA_V0 journal{};
+  const bool has_journal = DeviceIoControl(volume, FSCTL_QUERY_USN_JOURNAL, nullptr, 0,
+                                           &journal, sizeof(journal), &bytes, nullptr) !=
+                           FALSE;
+  CloseHandle(volume);
+  return has_journal;
+}
+
+// Mirrors the scan path the indexer picks: "mft" for NTFS volumes it can open (fast MFT
+// enumeration plus live journal updates), "usn" for other openable volumes with a change
+// journal such as ReFS, "walk" for everything readable only through a recursive directory
+// walk (FAT/exFAT, network drives, running without administrator rights), and
+// "unsupported" when the volume reports no filesystem at all (empty card readers,
+// unformatted or locked volumes).
+std::wstring DriveScanMode(const std::wstring& drive_letter, const bool has_fs,
+                           const std::wstring& filesystem, const bool can_open_volume) {
+  if (!has_fs) {
+    return L"unsupported";
+  }
+  if (can_open_volume) {
+    return L"mft";
+  }
+  if (ToLower(filesystem) == L"refs" && HasQueryableUsnJournal(drive_letter)) {
+    return L"usn";
+  }
+  return L"walk";
+}
+
 std::wstring ExtractExtensionLower(const std::wstring& file_name) {
   const size_t dot = file_name.find_last_of(L'.');
   if (dot == std::wstring::npos || dot == 0 || dot + 1 >= file_name.size()) {
@@ -376,6 +848,54 @@ std::wstring ExtractFileNameFromPath(const std::wstring& path) {
   return path.substr(slash + 1);
 }
 
+// NTFS metadata (`$MFT`, `$Recycle.Bin`, ...), anything beneath it, System Volume Information,
+// and the paging/hibernation files: entries that clutter results and cannot be opened.
+bool IsSystemArtifactPath(const std::wstring& path) {
+  static const wchar_t* const kSystemFileNames[] = {
+      L"pagefile.sys", L"hiberfil.sys", L"swapfile.sys", L"dumpstack.log", L"dumpstack.log.tmp",
+  };
+  size_t start = 0;
+  while (start < path.size()) {
+    size_t end = path.find_first_of(L"\\/", start);
+    if (end == std::wstring::npos) {
+      end = path.size();
+    }
+    const size_t length = end - start;
+    if (length > 0) {
+      if (path[start] == L'$') {
+        return true;
+      }
+      // Compared in place: this runs for every indexed path.
+      const auto segment_is = [&](const wchar_t* name) {
+        return std::wcslen(name) == length && _wcsnicmp(path.c_str() + start, name, length) == 0;
+      };
+      if (segment_is(L"System Volume Information")) {
+        return true;
+      }
+      if (end == path.size()) {
+        for (const wchar_t* name : kSystemFileNames) {
+          if (segment_is(name)) {
+            return true;
+          }
+        }
+      }
+    }
+    start = end + 1;
+  }
+  return false;
+}
+
+void RemoveSystemArtifacts(std::vector<IndexedFile>* files) {
+  if (g_include_system_files.load(std::memory_order_acquire)) {
+    return;
+  }
+  files->erase(std::remove_if(files->begin(), files->end(),
+                              [](const IndexedFile& file) {
+                                return IsSystemArtifactPath(file.path);
+                              }),
+               files->end());
+}
+
 std::wstring IndexedFileName(const IndexedFile& file) {
   return ExtractFileNameFromPath(file.path);
 }
@@ -387,6 +907,87 @@ std::wstring IndexedFileExtensionLower(const IndexedFile& file) {
   return ExtractExtensionLower(IndexedFileName(file));
 }
 
+// Matches every dotted suffix of the name, so compound filters like "tar.gz" work alongside
+// single extensions like "gz".
+bool ExtensionSetMatchesFile(const IndexedFile& file,
+                             const std::unordered_set<std::wstring>& extensions) {
+  if (file.is_directory) {
+    return false;
+  }
+  const std::wstring name = ToLower(IndexedFileName(file));
+  for (size_t dot = name.find(L'.', 1); dot != std::wstring::npos && dot + 1 < name.size();
+       dot = name.find(L'.', dot + 1)) {
+    if (extensions.find(name.substr(dot + 1)) != extensions.end()) {
+      return true;
+    }
+  }
+  return false;
+}
+
+bool IsReparsePoint(const IndexedFile& file) {
+  return (file.attributes & FILE_ATTRIBUTE_REPARSE_POINT) != 0;
+}
+
+// Cloud sync providers mark files whose content is not on disk with these attributes.
+constexpr uint32_t kCloudPlaceholderAttributes = FILE_ATTRIBUTE_OFFLINE |
+                                                 FILE_ATTRIBUTE_RECALL_ON_OPEN |
+                                                 FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+
+bool IsCloudPlaceholder(const IndexedFile& file) {
+  return !file.is_directory && (file.attributes & kCloudPlaceholderAttributes) != 0;
+}
+
+std::wstring StripExtendedPathPrefix(std::wstring path) {
+  if (path.rfind(L"\\\\?\\UNC\\", 0) == 0) {
+    return L"\\\\" + path.substr(8);
+  }
+  if (path.rfind(L"\\\\?\\", 0) == 0) {
+    return path.substr(4);
+  }
+  return path;
+}
+
+// Prefixes paths at or beyond MAX_PATH with `\\?\`, which Win32 file APIs need to accept
+// them; shorter or already prefixed paths are returned unchanged. The MFT scan indexes such
+// deeply nested entries, so per-file opens go through this.
+std::wstring ToExtendedLengthPath(const std::wstring& path) {
+  if (path.size() < MAX_PATH || path.rfind(L"\\\\?\\", 0) == 0) {
+    return path;
+  }
+  if (path.rfind(L"\\\\", 0) == 0) {
+    return L"\\\\?\\UNC\\" + path.substr(2);
+  }
+  return L"\\\\?\\" + path;
+}
+
+std::wstring ResolveReparseTarget(const std::wstring& path) {
+  // Opening without FILE_FLAG_OPEN_REPARSE_POINT follows the link, so the final
+  // path is the target. Dangling links fail to open and resolve to an empty path.
+  HANDLE handle = CreateFileW(
+      ToExtendedLengthPath(path).c_str(), 0, FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+      nullptr, OPEN_EXISTING, FILE_FLAG_BACKUP_SEMANTICS, nullptr);
+  if (handle == INVALID_HANDLE_VALUE) {
+    return L"";
+  }
+
+  std::wstring target(MAX_PATH, L'\0');
+  DWORD length = GetFinalPathNameByHandleW(handle, target.data(),
+                                           static_cast<DWORD>(target.size()),
+                                           FILE_NAME_NORMALIZED);
+  if (length >= target.size()) {
+    target.resize(static_cast<size_t>(length) + 1);
+    length = GetFinalPathNameByHandleW(handle, target.data(),
+                                       static_cast<DWORD>(target.size()),
+                                       FILE_NAME_NORMALIZED);
+  }
+  CloseHandle(handle);
+  if (length == 0 || length >= target.size()) {
+    return L"";
+  }
+  target.resize(length);
+  return StripExtendedPathPrefix(std::move(target));
+}
+
 uint32_t ToIndexSlot(const size_t index) {
   return static_cast<uint32_t>(index);
 }
@@ -426,10 +1027,27 @@ int64_t FileTimeToUnixSeconds(const FILETIME& file_time) {
       (value.QuadPart - kUnixEpochInWindowsTicks) / kTicksPerSecond);
 }
 
+// Fails for times before the FILETIME epoch (1601-01-01).
+bool UnixSecondsToFileTime(const int64_t unix_seconds, FILETIME* out_file_time) {
+  constexpr int64_t kTicksPerSecond = 10000000LL;
+  constexpr int64_t kUnixEpochInWindowsSeconds = 11644473600LL;
+  if (unix_seconds < -kUnixEpochInWindowsSeconds ||
+      unix_seconds > std::numeric_limits<int64_t>::max() / kTicksPerSecond -
+                         kUnixEpochInWindowsSeconds) {
+    return false;
+  }
+  ULARGE_INTEGER value;
+  value.QuadPart =
+      static_cast<uint64_t>((unix_seconds + kUnixEpochInWindowsSeconds) * kTicksPerSecond);
+  out_file_time->dwLowDateTime = value.LowPart;
+  out_file_time->dwHighDateTime = value.HighPart;
+  return true;
+}
+
 bool ReadFileMetadata(const std::wstring& path, uint64_t* size, int64_t* created_unix,
                       int64_t* modified_unix) {
   WIN32_FILE_ATTRIBUTE_DATA data{};
-  if (!GetFileAttributesExW(path.c_str(), GetFileExInfoStandard, &data)) {
+  if (!GetFileAttributesExW(ToExtendedLengthPath(path).c_str(), GetFileExInfoStandard, &data)) {
     return false;
   }
   *size = (static_cast<uint64_t>(data.nFileSizeHigh) << 32) | data.nFileSizeLow;
@@ -438,6 +1056,70 @@ bool ReadFileMetadata(const std::wstring& path, uint64_t* size, int64_t* created
   return true;
 }
 
+// Re-reads `file`'s metadata after hashing and records it as volatile when the size or
+// modified time no longer match what enumeration saw.
+void CheckDuplicateFileStable(const DuplicateFileRow& file) {
+  uint64_t size = 0;
+  int64_t created = 0;
+  int64_t modified = 0;
+  if (ReadFileMetadata(file.path, &size, &created, &modified) && size == file.size &&
+      modified == file.modified_unix) {
+    return;
+  }
+  std::lock_guard<std::mutex> lock(g_duplicate_volatile_mutex);
+  for (const std::wstring& path : g_duplicate_volatile_files) {
+    if (PathEqualsInsensitive(path, file.path)) {
+      return;
+    }
+  }
+  g_duplicate_volatile_files.push_back(file.path);
+}
+
+// Non-overlapping, case-insensitive occurrences of `needle_lower` in `text`.
+std::vector<MatchRange> FindMatchRanges(const std::wstring& text,
+                                        const std::wstring& needle_lower) {
+  std::vector<MatchRange> ranges;
+  if (needle_lower.empty() || needle_lower.size() > text.size()) {
+    return ranges;
+  }
+  const size_t last_start = text.size() - needle_lower.size();
+  size_t i = 0;
+  while (i <= last_start) {
+    if (CompareStringOrdinal(text.c_str() + i, static_cast<int>(needle_lower.size()),
+                             needle_lower.c_str(), static_cast<int>(needle_lower.size()),
+                             TRUE) == CSTR_EQUAL) {
+      ranges.push_back(MatchRange{static_cast<uint32_t>(i),
+                                  static_cast<uint32_t>(i + needle_lower.size())});
+      i += needle_lower.size();
+      continue;
+    }
+    ++i;
+  }
+  return ranges;
+}
+
+// Occurrences of any of `tokens` in `text`, sorted and with overlaps merged.
+std::vector<MatchRange> FindTokenMatchRanges(const std::wstring& text,
+                                             const std::vector<std::wstring>& tokens) {
+  std::vector<MatchRange> ranges;
+  for (const std::wstring& token : tokens) {
+    const std::vector<MatchRange> token_ranges = FindMatchRanges(text, token);
+    ranges.insert(ranges.end(), token_ranges.begin(), token_ranges.end());
+  }
+  std::sort(ranges.begin(), ranges.end(),
+            [](const MatchRange& a, const MatchRange& b) { return a.start < b.start; });
+  std::vector<MatchRange> merged;
+  merged.reserve(ranges.size());
+  for (const MatchRange& range : ranges) {
+    if (!merged.empty() && range.start <= merged.back().end) {
+      merged.back().end = std::max(merged.back().end, range.end);
+    } else {
+      merged.push_back(range);
+    }
+  }
+  return merged;
+}
+
 bool ContainsCaseInsensitive(const std::wstring& text, const std::wstring& needle_lower) {
   if (needle_lower.empty()) {
     return true;
@@ -459,36 +1141,152 @@ bool ContainsCaseInsensitive(const std::wstring& text, const std::wstring& needl
   return false;
 }
 
-std::wstring TrimWhitespace(const std::wstring& value) {
-  size_t start = 0;
-  while (start < value.size() && std::iswspace(value[start])) {
-    ++start;
+// Matches `query_lower` at the start or end of `name` as `position` asks, storing the
+// matched span in `range`.
+bool MatchesNamePosition(const std::wstring& name, const std::wstring& query_lower,
+                         const MatchPosition position, MatchRange* range) {
+  const size_t length = query_lower.size();
+  if (length == 0 || length > name.size()) {
+    return false;
   }
+  const auto matches_at = [&](const size_t start) {
+    return CompareStringOrdinal(name.c_str() + start, static_cast<int>(length),
+                                query_lower.c_str(), static_cast<int>(length),
+                                TRUE) == CSTR_EQUAL;
+  };
 
-  size_t end = value.size();
-  while (end > start && std::iswspace(value[end - 1])) {
-    --end;
+  size_t start = 0;
+  if (position == MatchPosition::Prefix) {
+    if (!matches_at(0)) {
+      return false;
+    }
+  } else {
+    const size_t dot = name.rfind(L'.');
+    const size_t stem_end = dot == std::wstring::npos || dot == 0 ? name.size() : dot;
+    if (stem_end >= length && matches_at(stem_end - length)) {
+      start = stem_end - length;
+    } else if (matches_at(name.size() - length)) {
+      start = name.size() - length;
+    } else {
+      return false;
+    }
   }
-
-  return value.substr(start, end - start);
+  *range = MatchRange{static_cast<uint32_t>(start), static_cast<uint32_t>(start + length)};
+  return true;
 }
 
-std::wstring CollapseWhitespace(const std::wstring& value) {
-  std::wstring collapsed;
-  collapsed.reserve(value.size());
-  bool previous_was_space = false;
-  for (const wchar_t ch : value) {
-    if (std::iswspace(ch)) {
-      if (!previous_was_space) {
-        collapsed.push_back(L' ');
-        previous_was_space = true;
-      }
-      continue;
+bool ContainsAllTokens(const std::wstring& text, const std::vector<std::wstring>& tokens) {
+  for (const std::wstring& token : tokens) {
+    if (!ContainsCaseInsensitive(text, token)) {
+      return false;
     }
-    previous_was_space = false;
-    collapsed.push_back(ch);
   }
-  return TrimWhitespace(collapsed);
+  return true;
+}
+
+// How well a lowercased name matches one lowercased needle: a prefix beats a match at a word
+// start, which beats one mid-word; a needle only found elsewhere in the path scores lowest.
+double NameNeedleRelevance(const std::wstring& name_lower, const std::wstring& needle_lower) {
+  const size_t first = name_lower.find(needle_lower);
+  if (first == std::wstring::npos) {
+    return 0.2;
+  }
+  if (first == 0) {
+    return 0.8;
+  }
+  for (size_t at = first; at != std::wstring::npos; at = name_lower.find(needle_lower, at + 1)) {
+    if (!std::iswalnum(name_lower[at - 1])) {
+      return 0.6;
+    }
+  }
+  return 0.4;
+}
+
+// Relevance of `name` to the query in [0, 1]: 1 for the exact name (with or without its
+// extension), otherwise the mean relevance of each token. Fuzzy matches score below any
+// literal one.
+double NameRelevance(const std::wstring& name, const std::wstring& query_lower,
+                     const std::vector<std::wstring>& tokens, const bool literal_match) {
+  if (query_lower.empty()) {
+    return 0.0;
+  }
+  if (!literal_match) {
+    return 0.1;
+  }
+  const std::wstring name_lower = ToLower(name);
+  const size_t dot = name_lower.rfind(L'.');
+  if (name_lower == query_lower ||
+      (dot == query_lower.size() && name_lower.compare(0, dot, query_lower) == 0)) {
+    return 1.0;
+  }
+  if (tokens.size() <= 1) {
+    return NameNeedleRelevance(name_lower, query_lower);
+  }
+  double total = 0.0;
+  for (const std::wstring& token : tokens) {
+    total += NameNeedleRelevance(name_lower, token);
+  }
+  return total / static_cast<double>(tokens.size());
+}
+
+// Smallest edit distance between `needle_lower` and any substring of `text` (Sellers'
+// algorithm: a match may start anywhere for free). Stops early on an exact match.
+uint32_t ApproximateSubstringDistance(const std::wstring& text,
+                                      const std::wstring& needle_lower) {
+  const size_t needle_size = needle_lower.size();
+  thread_local std::vector<uint32_t> column;
+  column.resize(needle_size + 1);
+  for (size_t i = 0; i <= needle_size; ++i) {
+    column[i] = static_cast<uint32_t>(i);
+  }
+  uint32_t best = column[needle_size];
+  for (const wchar_t raw : text) {
+    const wchar_t ch = static_cast<wchar_t>(std::towlower(raw));
+    uint32_t diagonal = column[0];
+    for (size_t i = 1; i <= needle_size; ++i) {
+      const uint32_t above = column[i];
+      const uint32_t substitution = diagonal + (needle_lower[i - 1] == ch ? 0 : 1);
+      column[i] = std::min({above + 1, column[i - 1] + 1, substitution});
+      diagonal = above;
+    }
+    best = std::min(best, column[needle_size]);
+    if (best == 0) {
+      break;
+    }
+  }
+  return best;
+}
+
+std::wstring TrimWhitespace(const std::wstring& value) {
+  size_t start = 0;
+  while (start < value.size() && std::iswspace(value[start])) {
+    ++start;
+  }
+
+  size_t end = value.size();
+  while (end > start && std::iswspace(value[end - 1])) {
+    --end;
+  }
+
+  return value.substr(start, end - start);
+}
+
+std::wstring CollapseWhitespace(const std::wstring& value) {
+  std::wstring collapsed;
+  collapsed.reserve(value.size());
+  bool previous_was_space = false;
+  for (const wchar_t ch : value) {
+    if (std::iswspace(ch)) {
+      if (!previous_was_space) {
+        collapsed.push_back(L' ');
+        previous_was_space = true;
+      }
+      continue;
+    }
+    previous_was_space = false;
+    collapsed.push_back(ch);
+  }
+  return TrimWhitespace(collapsed);
 }
 
 bool IsDirectoryExtensionAlias(const std::wstring& value) {
@@ -970,7 +1768,7 @@ bool SearchFileContent(const std::wstring& path, const std::wstring& needle_lowe
   }
 
   HANDLE file = CreateFileW(
-      path.c_str(), GENERIC_READ,
+      ToExtendedLengthPath(path).c_str(), GENERIC_READ,
       FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr, OPEN_EXISTING,
       FILE_ATTRIBUTE_NORMAL | FILE_FLAG_SEQUENTIAL_SCAN, nullptr);
   if (file == INVALID_HANDLE_VALUE) {
@@ -1040,8 +1838,7 @@ bool MatchesQueryExtensionFilters(
     return false;
   }
 
-  return extension_filters.find(IndexedFileExtensionLower(file)) !=
-         extension_filters.end();
+  return ExtensionSetMatchesFile(file, extension_filters);
 }
 
 
@@ -1085,9 +1882,26 @@ void AppendEscapedJsonString(std::string* out, const std::string& value) {
   }
 }
 
-std::string SearchRowsToJson(const std::vector<SearchRow>& rows) {
+void AppendMatchRangesJson(std::string* json, const std::vector<MatchRange>& ranges) {
+  json->push_back('[');
+  for (size_t i = 0; i < ranges.size(); ++i) {
+    if (i > 0) {
+      json->push_back(',');
+    }
+    json->push_back('[');
+    json->append(std::to_string(ranges[i].start));
+    json->push_back(',');
+    json->append(std::to_string(ranges[i].end));
+    json->push_back(']');
+  }
+  json->push_back(']');
+}
+
+// `lightweight` rows carry only the name, path and directory flag, for type-ahead callers
+// that show nothing else.
+std::string SearchRowsToJson(const std::vector<SearchRow>& rows, bool lightweight = false) {
   std::string json;
-  json.reserve(rows.size() * 176);
+  json.reserve(rows.size() * (lightweight ? 96 : 176));
   json.push_back('[');
   for (size_t i = 0; i < rows.size(); ++i) {
     if (i > 0) {
@@ -1097,6 +1911,11 @@ std::string SearchRowsToJson(const std::vector<SearchRow>& rows) {
     AppendEscapedJsonString(&json, WideToUtf8(rows[i].name));
     json.append("\",\"path\":\"");
     AppendEscapedJsonString(&json, WideToUtf8(rows[i].path));
+    if (lightweight) {
+      json.append("\",\"isDirectory\":");
+      json.append(rows[i].is_directory ? "true}" : "false}");
+      continue;
+    }
     json.append("\",\"extension\":\"");
     AppendEscapedJsonString(&json, WideToUtf8(rows[i].extension));
     json.append("\",\"size\":");
@@ -1107,6 +1926,46 @@ std::string SearchRowsToJson(const std::vector<SearchRow>& rows) {
     json.append(std::to_string(rows[i].modified_unix));
     json.append(",\"isDirectory\":");
     json.append(rows[i].is_directory ? "true" : "false");
+    json.append(",\"isReparsePoint\":");
+    json.append(rows[i].is_reparse_point ? "true" : "false");
+    json.append(",\"targetPath\":");
+    if (rows[i].target_path.empty()) {
+      json.append("null");
+    } else {
+      json.push_back('"');
+      AppendEscapedJsonString(&json, WideToUtf8(rows[i].target_path));
+      json.push_back('"');
+    }
+    json.append(",\"nameRanges\":");
+    AppendMatchRangesJson(&json, rows[i].name_ranges);
+    json.append(",\"pathRanges\":");
+    AppendMatchRangesJson(&json, rows[i].path_ranges);
+    if (!rows[i].owner.empty()) {
+      json.append(",\"owner\":\"");
+      AppendEscapedJsonString(&json, WideToUtf8(rows[i].owner));
+      json.push_back('"');
+    }
+    if (!rows[i].short_name.empty()) {
+      json.append(",\"shortName\":\"");
+      AppendEscapedJsonString(&json, WideToUtf8(rows[i].short_name));
+      json.push_back('"');
+    }
+    if (rows[i].path.size() >= MAX_PATH) {
+      json.append(",\"longPath\":true");
+    }
+    if (rows[i].is_cloud_placeholder) {
+      json.append(",\"isCloudPlaceholder\":true");
+    }
+    if (rows[i].file_id != 0) {
+      json.append(",\"fileId\":");
+      json.append(std::to_string(rows[i].file_id));
+    }
+    if (rows[i].score >= 0.0) {
+      char score_buffer[32];
+      std::snprintf(score_buffer, sizeof(score_buffer), "%.3f", rows[i].score);
+      json.append(",\"score\":");
+      json.append(score_buffer);
+    }
     json.push_back('}');
   }
   json.push_back(']');
@@ -1174,7 +2033,21 @@ std::string DriveRowsToJson(const std::vector<DriveInfo>& rows) {
     json.append(rows[i].is_ntfs ? "true" : "false");
     json.append(",\"canOpenVolume\":");
     json.append(rows[i].can_open_volume ? "true" : "false");
-    json.push_back('}');
+    json.append(",\"volumeGuid\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(rows[i].volume_guid));
+    json.append("\",\"serialNumber\":");
+    json.append(std::to_string(rows[i].serial_number));
+    json.append(",\"uncTarget\":");
+    if (rows[i].unc_target.empty()) {
+      json.append("null");
+    } else {
+      json.push_back('"');
+      AppendEscapedJsonString(&json, WideToUtf8(rows[i].unc_target));
+      json.push_back('"');
+    }
+    json.append(",\"scanMode\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(rows[i].scan_mode));
+    json.append("\"}");
   }
   json.push_back(']');
   return json;
@@ -1236,7 +2109,19 @@ std::string DuplicateScanStatusToJson() {
   json.append(std::to_string(groups_found));
   json.append(",\"progressPercent\":");
   json.append(percent_buffer);
-  json.push_back('}');
+  json.append(",\"volatileFiles\":[");
+  {
+    std::lock_guard<std::mutex> lock(g_duplicate_volatile_mutex);
+    for (size_t i = 0; i < g_duplicate_volatile_files.size(); ++i) {
+      if (i > 0) {
+        json.push_back(',');
+      }
+      json.push_back('"');
+      AppendEscapedJsonString(&json, WideToUtf8(g_duplicate_volatile_files[i]));
+      json.push_back('"');
+    }
+  }
+  json.append("]}");
   return json;
 }
 
@@ -1295,6 +2180,7 @@ bool ParseUsnRecord(const BYTE* record_ptr, DWORD record_length, RawUsnEntry* ou
     out->frn = v2->FileReferenceNumber;
     out->parent_frn = v2->ParentFileReferenceNumber;
     out->is_directory = (v2->FileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
+    out->attributes = v2->FileAttributes;
     out->reason = v2->Reason;
     out->name.assign(name_ptr, v2->FileNameLength / sizeof(wchar_t));
     return true;
@@ -1316,6 +2202,7 @@ bool ParseUsnRecord(const BYTE* record_ptr, DWORD record_length, RawUsnEntry* ou
     out->frn = FileId128ToU64(v3->FileReferenceNumber);
     out->parent_frn = FileId128ToU64(v3->ParentFileReferenceNumber);
     out->is_directory = (v3->FileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
+    out->attributes = v3->FileAttributes;
     out->reason = v3->Reason;
     out->name.assign(name_ptr, v3->FileNameLength / sizeof(wchar_t));
     return true;
@@ -1368,7 +2255,127 @@ bool ResolvePathForFrn(
   return true;
 }
 
+void MarkIndexChangedLocked() {
+  g_index_generation.fetch_add(1, std::memory_order_acq_rel);
+}
+
+uint32_t NameBigramKey(const wchar_t first, const wchar_t second) {
+  return (static_cast<uint32_t>(static_cast<uint16_t>(std::towlower(first))) << 16) |
+         static_cast<uint16_t>(std::towlower(second));
+}
+
+// Distinct bigram keys of `name`, the first one pairing a NUL with its first character.
+std::vector<uint32_t> NameBigramKeys(const std::wstring& name) {
+  std::vector<uint32_t> keys;
+  keys.reserve(name.size());
+  wchar_t previous = L'\0';
+  for (const wchar_t ch : name) {
+    keys.push_back(NameBigramKey(previous, ch));
+    previous = ch;
+  }
+  std::sort(keys.begin(), keys.end());
+  keys.erase(std::unique(keys.begin(), keys.end()), keys.end());
+  return keys;
+}
+
+// Drops the bigram index so the next lookup rebuilds it; for changes to the whole index.
+// The caller must hold `g_index_mutex` exclusively.
+void ResetNameBigramIndexLocked() {
+  std::lock_guard<std::mutex> bigram_lock(g_name_bigram_mutex);
+  std::unordered_map<uint32_t, std::vector<uint32_t>>().swap(g_name_bigram_index.postings);
+  g_name_bigram_index.built = false;
+}
+
+// Files `position` under each bigram of `name`, or takes it out again with `remove`. The
+// caller must hold `g_index_mutex` exclusively and `g_name_bigram_mutex`.
+void UpdateNameBigramsLocked(const uint32_t position, const std::wstring& name,
+                             const bool remove) {
+  for (const uint32_t key : NameBigramKeys(name)) {
+    std::vector<uint32_t>& positions = g_name_bigram_index.postings[key];
+    const auto it = std::lower_bound(positions.begin(), positions.end(), position);
+    if (remove) {
+      if (it != positions.end() && *it == position) {
+        positions.erase(it);
+      }
+      if (positions.empty()) {
+        g_name_bigram_index.postings.erase(key);
+      }
+    } else if (it == positions.end() || *it != position) {
+      positions.insert(it, position);
+    }
+  }
+}
+
+// Keeps a built bigram index current when the entry at `position` changes from `old_name`
+// to `new_name`; an empty name stands for no entry. The caller must hold `g_index_mutex`
+// exclusively.
+void MoveNameBigramsLocked(const uint32_t position, const std::wstring& old_name,
+                           const std::wstring& new_name) {
+  std::lock_guard<std::mutex> bigram_lock(g_name_bigram_mutex);
+  if (!g_name_bigram_index.built || ToLower(old_name) == ToLower(new_name)) {
+    return;
+  }
+  UpdateNameBigramsLocked(position, old_name, true);
+  UpdateNameBigramsLocked(position, new_name, false);
+}
+
+// Sorted index positions that may hold an entry whose name contains `text_lower`: every name
+// containing a one-character text, or, for longer text, the names sharing its rarest bigram.
+// The caller must hold `g_index_mutex`, at least shared, and compare the candidates itself.
+std::vector<uint32_t> LookupNameBigramsLocked(const std::wstring& text_lower) {
+  std::lock_guard<std::mutex> bigram_lock(g_name_bigram_mutex);
+  std::unordered_map<uint32_t, std::vector<uint32_t>>& postings = g_name_bigram_index.postings;
+  if (!g_name_bigram_index.built) {
+    postings.clear();
+    for (size_t i = 0; i < g_indexed_files.size(); ++i) {
+      for (const uint32_t key : NameBigramKeys(IndexedFileName(g_indexed_files[i]))) {
+        postings[key].push_back(static_cast<uint32_t>(i));
+      }
+    }
+    g_name_bigram_index.built = true;
+  }
+
+  std::vector<uint32_t> positions;
+  if (text_lower.empty()) {
+    return positions;
+  }
+  if (text_lower.size() == 1) {
+    // Each occurrence of a character ends some bigram, so the union covers every name.
+    const uint32_t second = NameBigramKey(L'\0', text_lower[0]);
+    std::vector<uint8_t> matched(g_indexed_files.size(), 0);
+    for (const auto& posting : postings) {
+      if ((posting.first & 0xFFFFu) != second) {
+        continue;
+      }
+      for (const uint32_t position : posting.second) {
+        matched[position] = 1;
+      }
+    }
+    for (size_t i = 0; i < matched.size(); ++i) {
+      if (matched[i]) {
+        positions.push_back(static_cast<uint32_t>(i));
+      }
+    }
+    return positions;
+  }
+
+  const std::vector<uint32_t>* rarest = nullptr;
+  for (size_t i = 0; i + 1 < text_lower.size(); ++i) {
+    const auto found = postings.find(NameBigramKey(text_lower[i], text_lower[i + 1]));
+    if (found == postings.end()) {
+      return positions;
+    }
+    if (rarest == nullptr || found->second.size() < rarest->size()) {
+      rarest = &found->second;
+    }
+  }
+  positions = *rarest;
+  return positions;
+}
+
 void RebuildFilePositionLookupLocked() {
+  MarkIndexChangedLocked();
+  ResetNameBigramIndexLocked();
   g_file_position_by_frn.clear();
   g_file_position_by_frn.reserve(g_indexed_files.size() * 2 + 1);
   for (size_t i = 0; i < g_indexed_files.size(); ++i) {
@@ -1405,15 +2412,35 @@ void ReleaseFileNodeNamesLocked() {
   }
 }
 
+constexpr uint64_t kHashNodeOverheadBytes = 2 * sizeof(void*);
+constexpr uint64_t kFilePositionEntryBytes =
+    sizeof(std::pair<const uint64_t, uint32_t>) + kHashNodeOverheadBytes;
+
+uint64_t IndexedFileMemoryBytes(const IndexedFile& file) {
+  return sizeof(IndexedFile) + file.path.capacity() * sizeof(wchar_t) +
+         kFilePositionEntryBytes;
+}
+
+// Keeps `g_index_memory_bytes` in step with live edits between full estimates, so live
+// inserts can be checked against the budget without walking the index. The caller must hold
+// `g_index_mutex` exclusively.
+void AdjustIndexMemoryBytesLocked(const uint64_t added, const uint64_t removed) {
+  const uint64_t bytes = g_index_memory_bytes.load(std::memory_order_acquire) + added;
+  g_index_memory_bytes.store(bytes - std::min(bytes, removed), std::memory_order_release);
+}
+
 void RemoveIndexedSubtreeByPathLocked(const std::wstring& root_path) {
+  MarkIndexChangedLocked();
   if (root_path.empty()) {
     return;
   }
 
+  uint64_t removed_bytes = 0;
   size_t write_index = 0;
   for (size_t read_index = 0; read_index < g_indexed_files.size(); ++read_index) {
     IndexedFile& file = g_indexed_files[read_index];
     if (PathStartsWithInsensitive(file.path, root_path)) {
+      removed_bytes += IndexedFileMemoryBytes(file);
       continue;
     }
 
@@ -1422,8 +2449,11 @@ void RemoveIndexedSubtreeByPathLocked(const std::wstring& root_path) {
     }
     ++write_index;
   }
+  AdjustIndexMemoryBytesLocked(0, removed_bytes);
 
   if (write_index < g_indexed_files.size()) {
+    g_index_removed_count.fetch_add(g_indexed_files.size() - write_index,
+                                    std::memory_order_relaxed);
     g_indexed_files.resize(write_index);
     RebuildFilePositionLookupLocked();
   }
@@ -1433,21 +2463,26 @@ void UpdateIndexedSubtreePathsLocked(const std::wstring& old_root_path,
                                      const std::wstring& new_root_path,
                                      const uint64_t root_frn,
                                      const uint64_t new_parent_frn) {
+  MarkIndexChangedLocked();
   if (old_root_path.empty() || new_root_path.empty() ||
       PathEqualsInsensitive(old_root_path, new_root_path)) {
     return;
   }
 
-  for (IndexedFile& file : g_indexed_files) {
+  for (size_t i = 0; i < g_indexed_files.size(); ++i) {
+    IndexedFile& file = g_indexed_files[i];
     if (!PathStartsWithInsensitive(file.path, old_root_path)) {
       continue;
     }
 
+    // Only the moved folder itself can change its name.
+    const std::wstring old_name = file.frn == root_frn ? IndexedFileName(file) : L"";
     std::wstring suffix = file.path.substr(old_root_path.size());
     file.path = new_root_path;
     file.path.append(suffix);
     if (file.frn == root_frn) {
       file.parent_frn = new_parent_frn;
+      MoveNameBigramsLocked(ToIndexSlot(i), old_name, IndexedFileName(file));
     }
   }
 }
@@ -1480,19 +2515,28 @@ void RemoveDirectorySubtreeNodesLocked(const uint64_t root_frn) {
 }
 
 void RemoveIndexedFileByFrnLocked(const uint64_t frn) {
+  MarkIndexChangedLocked();
   const auto position_it = g_file_position_by_frn.find(frn);
   if (position_it == g_file_position_by_frn.end()) {
     return;
   }
 
   const size_t remove_index = static_cast<size_t>(position_it->second);
+  AdjustIndexMemoryBytesLocked(0, IndexedFileMemoryBytes(g_indexed_files[remove_index]));
   const size_t last_index = g_indexed_files.size() - 1;
+  MoveNameBigramsLocked(ToIndexSlot(remove_index),
+                        IndexedFileName(g_indexed_files[remove_index]), L"");
   if (remove_index != last_index) {
+    // The last entry fills the gap, so its bigrams move to the freed position.
+    const std::wstring moved_name = IndexedFileName(g_indexed_files[last_index]);
+    MoveNameBigramsLocked(ToIndexSlot(last_index), moved_name, L"");
+    MoveNameBigramsLocked(ToIndexSlot(remove_index), L"", moved_name);
     g_indexed_files[remove_index] = std::move(g_indexed_files[last_index]);
     g_file_position_by_frn[g_indexed_files[remove_index].frn] = ToIndexSlot(remove_index);
   }
   g_indexed_files.pop_back();
   g_file_position_by_frn.erase(position_it);
+  g_index_removed_count.fetch_add(1, std::memory_order_relaxed);
 }
 
 bool RemoveIndexedFileByPathLocked(const std::wstring& path) {
@@ -1507,26 +2551,64 @@ bool RemoveIndexedFileByPathLocked(const std::wstring& path) {
   return false;
 }
 
+void UpdateIndexedAttributesByPathLocked(const std::wstring& path,
+                                         const uint32_t attributes) {
+  for (IndexedFile& file : g_indexed_files) {
+    if (PathEqualsInsensitive(file.path, path)) {
+      file.attributes = attributes;
+      const auto node_it = g_nodes.find(file.frn);
+      if (node_it != g_nodes.end()) {
+        node_it->second.attributes = attributes;
+      }
+      return;
+    }
+  }
+}
+
 void UpsertIndexedFileLocked(const uint64_t frn, const uint64_t parent_frn,
                              std::wstring full_path,
-                             const bool is_directory) {
+                             const bool is_directory,
+                             const uint32_t attributes, const uint64_t size,
+                             const int64_t modified_unix) {
+  if (!g_include_system_files.load(std::memory_order_acquire) &&
+      IsSystemArtifactPath(full_path)) {
+    // Covers live moves into `$Recycle.Bin` as well as newly created system files.
+    RemoveIndexedFileByFrnLocked(frn);
+    return;
+  }
+  MarkIndexChangedLocked();
   IndexedFile next_file{
       frn,
       parent_frn,
       std::move(full_path),
       is_directory,
+      attributes,
+      size,
+      modified_unix,
   };
   const auto position_it = g_file_position_by_frn.find(frn);
   if (position_it == g_file_position_by_frn.end()) {
     g_file_position_by_frn.emplace(frn, ToIndexSlot(g_indexed_files.size()));
     g_indexed_files.push_back(std::move(next_file));
+    MoveNameBigramsLocked(ToIndexSlot(g_indexed_files.size() - 1), L"",
+                          IndexedFileName(g_indexed_files.back()));
+    AdjustIndexMemoryBytesLocked(IndexedFileMemoryBytes(g_indexed_files.back()), 0);
+    g_index_added_count.fetch_add(1, std::memory_order_relaxed);
     return;
   }
 
-  g_indexed_files[static_cast<size_t>(position_it->second)] = std::move(next_file);
+  IndexedFile& previous_file = g_indexed_files[static_cast<size_t>(position_it->second)];
+  const std::wstring previous_name = IndexedFileName(previous_file);
+  const uint64_t previous_bytes = IndexedFileMemoryBytes(previous_file);
+  previous_file = std::move(next_file);
+  AdjustIndexMemoryBytesLocked(IndexedFileMemoryBytes(previous_file), previous_bytes);
+  MoveNameBigramsLocked(position_it->second, previous_name, IndexedFileName(previous_file));
+  g_index_modified_count.fetch_add(1, std::memory_order_relaxed);
 }
 
 void RebuildIndexedFilesFromNodesLocked() {
+  MarkIndexChangedLocked();
+  ResetNameBigramIndexLocked();
   std::vector<IndexedFile> previous_files = std::move(g_indexed_files);
   g_indexed_files.clear();
   g_file_position_by_frn.clear();
@@ -1582,6 +2664,9 @@ void RebuildIndexedFilesFromNodesLocked() {
         file.parent_frn,
         std::move(full_path),
         file.is_directory,
+        file.attributes,
+        file.size,
+        file.modified_unix,
     });
   }
 }
@@ -1641,7 +2726,8 @@ void ApplyUsnBatchLocked(const std::vector<RawUsnEntry>& entries) {
     }
 
     if (entry.is_directory) {
-      g_nodes[entry.frn] = NodeEntry{entry.parent_frn, entry.name, entry.is_directory};
+      g_nodes[entry.frn] =
+          NodeEntry{entry.parent_frn, entry.name, entry.is_directory, entry.attributes};
 
       resolving.clear();
       std::wstring full_path;
@@ -1656,6 +2742,17 @@ void ApplyUsnBatchLocked(const std::vector<RawUsnEntry>& entries) {
         continue;
       }
 
+      if (!g_include_system_files.load(std::memory_order_acquire) &&
+          IsSystemArtifactPath(full_path)) {
+        // A folder moved somewhere hidden (typically the Recycle Bin) takes its subtree along.
+        if (!old_directory_path.empty()) {
+          RemoveIndexedSubtreeByPathLocked(old_directory_path);
+        } else {
+          RemoveIndexedFileByFrnLocked(entry.frn);
+        }
+        continue;
+      }
+
       if (!old_directory_path.empty() &&
           !PathEqualsInsensitive(old_directory_path, full_path)) {
         UpdateIndexedSubtreePathsLocked(old_directory_path, full_path, entry.frn,
@@ -1667,7 +2764,8 @@ void ApplyUsnBatchLocked(const std::vector<RawUsnEntry>& entries) {
         continue;
       }
 
-      UpsertIndexedFileLocked(entry.frn, entry.parent_frn, std::move(full_path), true);
+      UpsertIndexedFileLocked(entry.frn, entry.parent_frn, std::move(full_path), true,
+                              entry.attributes, 0, 0);
       continue;
     }
 
@@ -1691,7 +2789,8 @@ void ApplyUsnBatchLocked(const std::vector<RawUsnEntry>& entries) {
     }
     full_path.append(entry.name);
 
-    UpsertIndexedFileLocked(entry.frn, entry.parent_frn, std::move(full_path), false);
+    UpsertIndexedFileLocked(entry.frn, entry.parent_frn, std::move(full_path), false,
+                            entry.attributes, entry.size, entry.modified_unix);
     g_nodes.erase(entry.frn);
   }
   g_indexed_count.store(static_cast<uint64_t>(g_indexed_files.size()),
@@ -1715,6 +2814,150 @@ void ApplyIndexedFilesOnlyLocked(std::vector<IndexedFile> files) {
   RebuildFilePositionLookupLocked();
 }
 
+// Rough heap footprint of the index: entries and their paths, the frn lookup, the directory
+// nodes kept for live updates and the name bigram index. The caller must hold `g_index_mutex`,
+// at least shared.
+uint64_t EstimateIndexMemoryLocked() {
+  uint64_t bytes = (g_indexed_files.capacity() - g_indexed_files.size()) * sizeof(IndexedFile);
+  for (const IndexedFile& file : g_indexed_files) {
+    bytes += IndexedFileMemoryBytes(file);
+  }
+  bytes += g_file_position_by_frn.bucket_count() * sizeof(void*);
+  for (const auto& pair : g_nodes) {
+    bytes += sizeof(pair) + kHashNodeOverheadBytes + pair.second.name.capacity() * sizeof(wchar_t);
+  }
+  bytes += g_nodes.bucket_count() * sizeof(void*);
+  std::lock_guard<std::mutex> bigram_lock(g_name_bigram_mutex);
+  for (const auto& posting : g_name_bigram_index.postings) {
+    bytes += sizeof(posting) + kHashNodeOverheadBytes +
+             posting.second.capacity() * sizeof(uint32_t);
+  }
+  bytes += g_name_bigram_index.postings.bucket_count() * sizeof(void*);
+  return bytes;
+}
+
+// Drops entries from `files` until `bytes`, their estimated footprint, fits `budget`: hidden
+// and system entries first, then the most deeply nested ones, which are the least likely to
+// be searched for. Returns whether entries were dropped.
+bool EvictFilesOverBudget(std::vector<IndexedFile>* files, uint64_t bytes,
+                          const uint64_t budget) {
+  if (bytes <= budget) {
+    return false;
+  }
+
+  // Higher ranks are evicted first: hidden or system entries, then by path depth.
+  constexpr uint32_t kHiddenSystemRank = 1u << 16;
+  std::vector<std::pair<uint32_t, uint32_t>> eviction_order;
+  eviction_order.reserve(files->size());
+  for (size_t i = 0; i < files->size(); ++i) {
+    const IndexedFile& file = (*files)[i];
+    const size_t depth = static_cast<size_t>(
+        std::count(file.path.begin(), file.path.end(), L'\\'));
+    uint32_t rank = static_cast<uint32_t>(std::min<size_t>(depth, kHiddenSystemRank - 1));
+    if ((file.attributes & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM)) != 0) {
+      rank |= kHiddenSystemRank;
+    }
+    eviction_order.emplace_back(rank, static_cast<uint32_t>(i));
+  }
+  std::sort(eviction_order.begin(), eviction_order.end(),
+            [](const auto& left, const auto& right) {
+              return left.first != right.first ? left.first > right.first
+                                               : left.second > right.second;
+            });
+
+  std::vector<bool> evicted(files->size(), false);
+  for (const auto& candidate : eviction_order) {
+    if (bytes <= budget) {
+      break;
+    }
+    bytes -= std::min(bytes, IndexedFileMemoryBytes((*files)[candidate.second]));
+    evicted[candidate.second] = true;
+  }
+  std::vector<std::pair<uint32_t, uint32_t>>().swap(eviction_order);
+
+  size_t write_index = 0;
+  for (size_t read_index = 0; read_index < files->size(); ++read_index) {
+    if (evicted[read_index]) {
+      continue;
+    }
+    if (write_index != read_index) {
+      (*files)[write_index] = std::move((*files)[read_index]);
+    }
+    ++write_index;
+  }
+  files->resize(write_index);
+  files->shrink_to_fit();
+  return true;
+}
+
+// Trims entries a scan is still accumulating to `g_index_memory_budget`, so a large volume
+// never holds more than the budget in flight before the index is enforced as a whole.
+// Returns whether entries were dropped.
+bool TrimScannedFilesToBudget(std::vector<IndexedFile>* files) {
+  const uint64_t budget = g_index_memory_budget.load(std::memory_order_acquire);
+  if (budget == 0) {
+    return false;
+  }
+  uint64_t bytes = (files->capacity() - files->size()) * sizeof(IndexedFile);
+  for (const IndexedFile& file : *files) {
+    bytes += IndexedFileMemoryBytes(file);
+  }
+  if (bytes <= budget) {
+    return false;
+  }
+  bytes = 0;
+  for (IndexedFile& file : *files) {
+    file.path.shrink_to_fit();
+    bytes += IndexedFileMemoryBytes(file);
+  }
+  files->shrink_to_fit();
+  return EvictFilesOverBudget(files, bytes, budget);
+}
+
+// Brings the index under `g_index_memory_budget`. Slack capacity and the name bigram index,
+// which short queries rebuild on demand, go first; if that is not enough, entries are
+// evicted as `EvictFilesOverBudget` ranks them. Directory nodes stay so live updates keep
+// resolving paths. Returns whether entries were dropped. The caller must hold
+// `g_index_mutex` exclusively.
+bool EnforceIndexMemoryBudgetLocked() {
+  const uint64_t budget = g_index_memory_budget.load(std::memory_order_acquire);
+  uint64_t bytes = EstimateIndexMemoryLocked();
+  if (budget == 0 || bytes <= budget) {
+    g_index_memory_bytes.store(bytes, std::memory_order_release);
+    return false;
+  }
+
+  for (IndexedFile& file : g_indexed_files) {
+    file.path.shrink_to_fit();
+  }
+  g_indexed_files.shrink_to_fit();
+  ResetNameBigramIndexLocked();
+  bytes = EstimateIndexMemoryLocked();
+  if (!EvictFilesOverBudget(&g_indexed_files, bytes, budget)) {
+    g_index_memory_bytes.store(bytes, std::memory_order_release);
+    return false;
+  }
+
+  RebuildFilePositionLookupLocked();
+  g_indexed_count.store(static_cast<uint64_t>(g_indexed_files.size()),
+                        std::memory_order_release);
+  g_index_memory_bytes.store(EstimateIndexMemoryLocked(), std::memory_order_release);
+  return true;
+}
+
+// Re-checks the budget after live updates using the running `g_index_memory_bytes`, and only
+// walks the index once that figure says it has grown past the budget. The caller must hold
+// `g_index_mutex` exclusively.
+void EnforceIndexMemoryBudgetAfterLiveUpdatesLocked() {
+  const uint64_t budget = g_index_memory_budget.load(std::memory_order_acquire);
+  if (budget == 0 || g_index_memory_bytes.load(std::memory_order_acquire) <= budget) {
+    return;
+  }
+  if (EnforceIndexMemoryBudgetLocked()) {
+    g_index_trimmed.store(true, std::memory_order_release);
+  }
+}
+
 std::string BuildDuplicateGroupId(const uint64_t size, const uint64_t hash_value,
                                   const uint32_t serial) {
   char buffer[64];
@@ -1740,13 +2983,44 @@ void FNV1aMixU64(const uint64_t value, uint64_t* hash) {
   FNV1aMixBuffer(bytes, sizeof(value), hash);
 }
 
+uint64_t HashWideString(const std::wstring& value) {
+  uint64_t hash = kFNVOffsetBasis;
+  FNV1aMixBuffer(reinterpret_cast<const BYTE*>(value.data()),
+                 value.size() * sizeof(wchar_t), &hash);
+  return hash;
+}
+
+// Builds a group from loosely matched files (same size or same name) whose sizes may differ.
+DuplicateGroupRow BuildLooseDuplicateGroupRow(const std::vector<DuplicateFileRow>& files,
+                                              const uint64_t hash_value,
+                                              const uint32_t serial,
+                                              const uint32_t max_files_per_group) {
+  DuplicateGroupRow row{};
+  uint64_t largest = 0;
+  uint64_t total = 0;
+  for (const DuplicateFileRow& file : files) {
+    largest = std::max(largest, file.size);
+    total += file.size;
+  }
+  row.group_id = BuildDuplicateGroupId(largest, hash_value, serial);
+  row.size = largest;
+  row.file_count = static_cast<uint32_t>(files.size());
+  row.total_bytes = total;
+  const size_t render_count = std::min<size_t>(files.size(), max_files_per_group);
+  row.files.reserve(render_count);
+  for (size_t i = 0; i < render_count; ++i) {
+    row.files.push_back(files[i]);
+  }
+  return row;
+}
+
 bool HashFileFNV1a64(const std::wstring& path, uint64_t* out_hash) {
   if (IsDuplicateScanCancelRequested()) {
     return false;
   }
 
   HANDLE file = CreateFileW(
-      path.c_str(), GENERIC_READ,
+      ToExtendedLengthPath(path).c_str(), GENERIC_READ,
       FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr, OPEN_EXISTING,
       FILE_ATTRIBUTE_NORMAL | FILE_FLAG_SEQUENTIAL_SCAN, nullptr);
   if (file == INVALID_HANDLE_VALUE) {
@@ -1767,6 +3041,7 @@ bool HashFileFNV1a64(const std::wstring& path, uint64_t* out_hash) {
       break;
     }
 
+    ThrottleDuplicateRead(kBufferSize);
     DWORD bytes_read = 0;
     const BOOL read_ok =
         ReadFile(file, buffer.data(), kBufferSize, &bytes_read, nullptr);
@@ -1801,7 +3076,7 @@ bool HashFileQuickSignature64(const DuplicateFileRow& file, uint64_t* out_hash)
   }
 
   HANDLE handle = CreateFileW(
-      file.path.c_str(), GENERIC_READ,
+      ToExtendedLengthPath(file.path).c_str(), GENERIC_READ,
       FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr, OPEN_EXISTING,
       FILE_ATTRIBUTE_NORMAL | FILE_FLAG_RANDOM_ACCESS, nullptr);
   if (handle == INVALID_HANDLE_VALUE) {
@@ -1816,6 +3091,7 @@ bool HashFileQuickSignature64(const DuplicateFileRow& file, uint64_t* out_hash)
 
   const DWORD first_bytes =
       static_cast<DWORD>(std::min<uint64_t>(file.size, kChunkBytes));
+  ThrottleDuplicateRead(first_bytes);
   DWORD first_read = 0;
   bool ok = ReadFile(handle, buffer.data(), first_bytes, &first_read, nullptr) != FALSE &&
             first_read == first_bytes;
@@ -1834,6 +3110,7 @@ bool HashFileQuickSignature64(const DuplicateFileRow& file, uint64_t* out_hash)
     offset.QuadPart = static_cast<LONGLONG>(file.size - tail_bytes);
     ok = SetFilePointerEx(handle, offset, nullptr, FILE_BEGIN) != FALSE;
     if (ok) {
+      ThrottleDuplicateRead(tail_bytes);
       DWORD tail_read = 0;
       ok = ReadFile(handle, buffer.data(), tail_bytes, &tail_read, nullptr) != FALSE &&
            tail_read == tail_bytes;
@@ -1858,7 +3135,7 @@ bool AreFilesByteEqual(const std::wstring& left_path, const std::wstring& right_
   }
 
   HANDLE left = CreateFileW(
-      left_path.c_str(), GENERIC_READ,
+      ToExtendedLengthPath(left_path).c_str(), GENERIC_READ,
       FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr, OPEN_EXISTING,
       FILE_ATTRIBUTE_NORMAL | FILE_FLAG_SEQUENTIAL_SCAN, nullptr);
   if (left == INVALID_HANDLE_VALUE) {
@@ -1866,7 +3143,7 @@ bool AreFilesByteEqual(const std::wstring& left_path, const std::wstring& right_
   }
 
   HANDLE right = CreateFileW(
-      right_path.c_str(), GENERIC_READ,
+      ToExtendedLengthPath(right_path).c_str(), GENERIC_READ,
       FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr, OPEN_EXISTING,
       FILE_ATTRIBUTE_NORMAL | FILE_FLAG_SEQUENTIAL_SCAN, nullptr);
   if (right == INVALID_HANDLE_VALUE) {
@@ -1893,6 +3170,7 @@ bool AreFilesByteEqual(const std::wstring& left_path, const std::wstring& right_
       break;
     }
 
+    ThrottleDuplicateRead(2ULL * kBufferSize);
     const BOOL left_ok = ReadFile(left, left_buffer.data(), kBufferSize, &left_read, nullptr);
     const BOOL right_ok =
         ReadFile(right, right_buffer.data(), kBufferSize, &right_read, nullptr);
@@ -1944,6 +3222,18 @@ size_t ComputeDuplicateWorkerCount(const size_t item_count) {
   return std::max<size_t>(1, std::min(usable_workers, item_count));
 }
 
+// Zero picks the default (half the logical CPUs, at most 8); requests are capped at the CPU count.
+uint32_t ResolveIndexingThreadCount(const uint32_t requested) {
+  uint32_t logical_cpus = std::thread::hardware_concurrency();
+  if (logical_cpus == 0) {
+    logical_cpus = 4;
+  }
+  if (requested == 0) {
+    return std::clamp<uint32_t>(logical_cpus / 2, 1, 8);
+  }
+  return std::clamp<uint32_t>(requested, 1, logical_cpus);
+}
+
 void HashFilesParallel(const std::vector<DuplicateFileRow>& files,
                        std::vector<uint64_t>* out_hashes,
                        std::vector<uint8_t>* out_ok_flags,
@@ -1955,7 +3245,11 @@ void HashFilesParallel(const std::vector<DuplicateFileRow>& files,
     return;
   }
 
-  const size_t worker_count = ComputeDuplicateWorkerCount(files.size());
+  const uint32_t io_concurrency = g_duplicate_io_concurrency.load(std::memory_order_acquire);
+  const size_t worker_count =
+      io_concurrency == 0
+          ? ComputeDuplicateWorkerCount(files.size())
+          : std::min<size_t>(io_concurrency, ComputeDuplicateWorkerCount(files.size()));
 
   std::atomic<size_t> next_index{0};
   std::vector<std::thread> workers;
@@ -1975,6 +3269,7 @@ void HashFilesParallel(const std::vector<DuplicateFileRow>& files,
         if (hash_fn != nullptr && hash_fn(files[index], &hash)) {
           (*out_hashes)[index] = hash;
           (*out_ok_flags)[index] = 1;
+          CheckDuplicateFileStable(files[index]);
         }
         if (track_progress) {
           AddDuplicateProgressDone(1);
@@ -1988,14 +3283,123 @@ void HashFilesParallel(const std::vector<DuplicateFileRow>& files,
   }
 }
 
+// Name mode groups files sharing a case-insensitive name, most copies first. Names come
+// straight from the index, so only files in a shared-name bucket are stat'ed and no size
+// floor is needed to keep it fast; `min_size` still applies when the caller sets one.
+std::vector<DuplicateGroupRow> FindNameDuplicateGroups(
+    const std::vector<IndexedFile>& indexed_snapshot, const uint64_t min_size,
+    const uint32_t max_groups, const uint32_t max_files_per_group,
+    const bool skip_reparse_points) {
+  std::unordered_map<std::wstring, std::vector<size_t>> name_buckets;
+  name_buckets.reserve(indexed_snapshot.size() / 4 + 1);
+  for (size_t index = 0; index < indexed_snapshot.size(); ++index) {
+    const IndexedFile& file = indexed_snapshot[index];
+    if (file.is_directory || (skip_reparse_points && IsReparsePoint(file)) ||
+        IsCloudPlaceholder(file)) {
+      continue;
+    }
+    name_buckets[ToLower(IndexedFileName(file))].push_back(index);
+  }
+
+  std::vector<size_t> candidates;
+  for (const auto& name_bucket : name_buckets) {
+    if (name_bucket.second.size() >= 2) {
+      candidates.insert(candidates.end(), name_bucket.second.begin(),
+                        name_bucket.second.end());
+    }
+  }
+  AddDuplicateProgressTotal(static_cast<uint64_t>(candidates.size()));
+
+  std::vector<DuplicateFileRow> rows(candidates.size());
+  std::vector<uint8_t> row_ok(candidates.size(), 0);
+  const size_t metadata_workers = ComputeDuplicateWorkerCount(candidates.size());
+  std::atomic<size_t> next_candidate{0};
+  std::vector<std::thread> metadata_threads;
+  metadata_threads.reserve(metadata_workers);
+  for (size_t worker = 0; worker < metadata_workers; ++worker) {
+    metadata_threads.emplace_back([&]() {
+      while (!IsDuplicateScanCancelRequested()) {
+        const size_t candidate = next_candidate.fetch_add(1, std::memory_order_relaxed);
+        if (candidate >= candidates.size()) {
+          return;
+        }
+        const IndexedFile& file = indexed_snapshot[candidates[candidate]];
+        DuplicateFileRow& row = rows[candidate];
+        if (ReadFileMetadata(file.path, &row.size, &row.created_unix, &row.modified_unix) &&
+            row.size >= min_size) {
+          row.name = IndexedFileName(file);
+          row.path = file.path;
+          row_ok[candidate] = 1;
+        }
+        AddDuplicateProgressDone(1);
+      }
+    });
+  }
+  for (std::thread& worker : metadata_threads) {
+    worker.join();
+  }
+  if (IsDuplicateScanCancelRequested()) {
+    return {};
+  }
+
+  // Candidates were laid out bucket by bucket in this same iteration order.
+  std::vector<std::pair<const std::wstring*, std::vector<DuplicateFileRow>>> ranked_buckets;
+  size_t next_row = 0;
+  for (const auto& name_bucket : name_buckets) {
+    if (name_bucket.second.size() < 2) {
+      continue;
+    }
+    std::vector<DuplicateFileRow> files;
+    for (size_t i = 0; i < name_bucket.second.size(); ++i, ++next_row) {
+      if (row_ok[next_row]) {
+        files.push_back(std::move(rows[next_row]));
+      }
+    }
+    if (files.size() >= 2) {
+      ranked_buckets.emplace_back(&name_bucket.first, std::move(files));
+    }
+  }
+  // Most-shared names first, so `max_groups` keeps the biggest clusters.
+  std::sort(ranked_buckets.begin(), ranked_buckets.end(),
+            [](const auto& left, const auto& right) {
+              if (left.second.size() != right.second.size()) {
+                return left.second.size() > right.second.size();
+              }
+              return *left.first < *right.first;
+            });
+
+  std::vector<DuplicateGroupRow> groups;
+  uint32_t group_serial = 0;
+  for (const auto& name_bucket : ranked_buckets) {
+    if (IsDuplicateScanCancelRequested() || groups.size() >= max_groups) {
+      break;
+    }
+    groups.push_back(BuildLooseDuplicateGroupRow(name_bucket.second,
+                                                 HashWideString(*name_bucket.first),
+                                                 group_serial++, max_files_per_group));
+    PublishDuplicateGroup(groups);
+  }
+  if (!IsDuplicateScanCancelRequested()) {
+    const uint64_t total = g_duplicate_progress_total.load(std::memory_order_acquire);
+    g_duplicate_progress_done.store(total, std::memory_order_release);
+  }
+  return groups;
+}
+
 std::vector<DuplicateGroupRow> find_duplicates_internal(const uint64_t min_size,
                                                         const uint32_t max_groups,
-                                                        const uint32_t max_files_per_group) {
+                                                        const uint32_t max_files_per_group,
+                                                        const bool skip_reparse_points,
+                                                        const DuplicateGroupMode group_mode) {
   std::vector<IndexedFile> indexed_snapshot;
   {
     std::shared_lock<std::shared_mutex> lock(g_index_mutex);
     indexed_snapshot = g_indexed_files;
   }
+  if (group_mode == DuplicateGroupMode::Name) {
+    return FindNameDuplicateGroups(indexed_snapshot, min_size, max_groups, max_files_per_group,
+                                   skip_reparse_points);
+  }
 
   std::vector<uint64_t> metadata_sizes(indexed_snapshot.size(), 0);
   std::vector<int64_t> metadata_created(indexed_snapshot.size(), 0);
@@ -2024,7 +3428,9 @@ std::vector<DuplicateGroupRow> find_duplicates_internal(const uint64_t min_size,
           return;
         }
         const IndexedFile& file = indexed_snapshot[index];
-        if (file.is_directory) {
+        // Hashing an online-only file would download it, so placeholders never take part.
+        if (file.is_directory || (skip_reparse_points && IsReparsePoint(file)) ||
+            IsCloudPlaceholder(file)) {
           AddDuplicateProgressDone(1);
           continue;
         }
@@ -2070,17 +3476,49 @@ std::vector<DuplicateGroupRow> find_duplicates_internal(const uint64_t min_size,
     });
   }
 
-  for (auto& size_bucket : size_buckets) {
-    if (IsDuplicateScanCancelRequested()) {
-      goto duplicate_finish;
-    }
-
-    const uint64_t file_size = size_bucket.first;
-    std::vector<DuplicateFileRow>& files = size_bucket.second;
-    if (files.size() < 2) {
-      continue;
+  // Size and name modes skip content hashing entirely.
+  if (group_mode == DuplicateGroupMode::Size) {
+    // Most-shared sizes first, so `max_groups` keeps the biggest clusters.
+    std::vector<const std::vector<DuplicateFileRow>*> ranked_buckets;
+    ranked_buckets.reserve(size_buckets.size());
+    for (const auto& size_bucket : size_buckets) {
+      if (size_bucket.second.size() >= 2) {
+        ranked_buckets.push_back(&size_bucket.second);
+      }
     }
-
+    std::sort(ranked_buckets.begin(), ranked_buckets.end(),
+              [](const std::vector<DuplicateFileRow>* left,
+                 const std::vector<DuplicateFileRow>* right) {
+                if (left->size() != right->size()) {
+                  return left->size() > right->size();
+                }
+                return left->front().size > right->front().size;
+              });
+    for (const std::vector<DuplicateFileRow>* files : ranked_buckets) {
+      if (IsDuplicateScanCancelRequested()) {
+        goto duplicate_finish;
+      }
+      groups.push_back(
+          BuildLooseDuplicateGroupRow(*files, 0, group_serial++, max_files_per_group));
+      PublishDuplicateGroup(groups);
+      if (groups.size() >= max_groups) {
+        goto duplicate_finish;
+      }
+    }
+    goto duplicate_finish;
+  }
+
+  for (auto& size_bucket : size_buckets) {
+    if (IsDuplicateScanCancelRequested()) {
+      goto duplicate_finish;
+    }
+
+    const uint64_t file_size = size_bucket.first;
+    std::vector<DuplicateFileRow>& files = size_bucket.second;
+    if (files.size() < 2) {
+      continue;
+    }
+
     // Fast path for empty files: same content by definition, no disk reads needed.
     if (file_size == 0) {
       DuplicateGroupRow row{};
@@ -2094,8 +3532,7 @@ std::vector<DuplicateGroupRow> find_duplicates_internal(const uint64_t min_size,
         row.files.push_back(files[i]);
       }
       groups.push_back(std::move(row));
-      g_duplicate_groups_found.store(static_cast<uint64_t>(groups.size()),
-                                     std::memory_order_release);
+      PublishDuplicateGroup(groups);
       if (groups.size() >= max_groups) {
         goto duplicate_finish;
       }
@@ -2121,6 +3558,31 @@ std::vector<DuplicateGroupRow> find_duplicates_internal(const uint64_t min_size,
       quick_buckets[quick_signatures[i]].push_back(i);
     }
 
+    if (group_mode == DuplicateGroupMode::Partial) {
+      for (const auto& quick_bucket : quick_buckets) {
+        const std::vector<size_t>& quick_indices = quick_bucket.second;
+        if (quick_indices.size() < 2) {
+          continue;
+        }
+        DuplicateGroupRow row{};
+        row.group_id = BuildDuplicateGroupId(file_size, quick_bucket.first, group_serial++);
+        row.size = file_size;
+        row.file_count = static_cast<uint32_t>(quick_indices.size());
+        row.total_bytes = file_size * static_cast<uint64_t>(quick_indices.size());
+        const size_t render_count = std::min<size_t>(quick_indices.size(), max_files_per_group);
+        row.files.reserve(render_count);
+        for (size_t i = 0; i < render_count; ++i) {
+          row.files.push_back(files[quick_indices[i]]);
+        }
+        groups.push_back(std::move(row));
+        PublishDuplicateGroup(groups);
+        if (groups.size() >= max_groups) {
+          goto duplicate_finish;
+        }
+      }
+      continue;
+    }
+
     for (const auto& quick_bucket : quick_buckets) {
       const std::vector<size_t>& quick_indices = quick_bucket.second;
       if (quick_indices.size() < 2) {
@@ -2193,8 +3655,7 @@ std::vector<DuplicateGroupRow> find_duplicates_internal(const uint64_t min_size,
             row.files.push_back(quick_candidates[cluster[i]]);
           }
           groups.push_back(std::move(row));
-          g_duplicate_groups_found.store(static_cast<uint64_t>(groups.size()),
-                                         std::memory_order_release);
+          PublishDuplicateGroup(groups);
 
           if (groups.size() >= max_groups) {
             goto duplicate_finish;
@@ -2225,39 +3686,22 @@ duplicate_finish:
   return groups;
 }
 
-bool scan_fallback_internal(const std::wstring& drive_letter, ScanSnapshot* out_snapshot,
-                            const bool include_directories,
-                            const uint64_t request_token, bool* out_cancelled,
-                            std::string* out_error) {
-  *out_cancelled = false;
-  out_snapshot->files.clear();
-  out_snapshot->nodes.clear();
-  out_snapshot->root_frn = 0;
-  out_snapshot->root_path.clear();
-  out_snapshot->journal_id = 0;
-  out_snapshot->journal_next_usn = 0;
-  out_snapshot->live_updates_supported = false;
-
-  const std::wstring root_path = drive_letter + L":\\";
-  const DWORD root_attributes = GetFileAttributesW(root_path.c_str());
-  if (root_attributes == INVALID_FILE_ATTRIBUTES ||
-      (root_attributes & FILE_ATTRIBUTE_DIRECTORY) == 0) {
-    *out_error = BuildWin32ErrorText(
-        "Fallback indexing failed because drive root is not accessible.",
-        GetLastError());
-    return false;
-  }
+uint64_t FindDataSize(const WIN32_FIND_DATAW& entry) {
+  return (static_cast<uint64_t>(entry.nFileSizeHigh) << 32) | entry.nFileSizeLow;
+}
 
-  std::vector<IndexedFile> files;
-  files.reserve(240000);
+// Enumerates `root_path` recursively into `files`, without crossing reparse points.
+// Entries get sequential synthetic FRNs from `next_frn`. Returns false when cancelled.
+// `report_progress` publishes the running count as the indexing progress.
+bool WalkDirectoryTree(const std::wstring& root_path, const bool include_directories,
+                       const uint64_t request_token, uint64_t* next_frn,
+                       std::vector<IndexedFile>* files, const bool report_progress = true) {
   std::vector<std::wstring> directories;
   directories.reserve(8192);
   directories.push_back(root_path);
 
-  uint64_t synthetic_frn = 1;
   while (!directories.empty()) {
     if (IsIndexingCancelled(request_token)) {
-      *out_cancelled = true;
       return false;
     }
 
@@ -2272,8 +3716,8 @@ bool scan_fallback_internal(const std::wstring& drive_letter, ScanSnapshot* out_
 
     WIN32_FIND_DATAW entry{};
     HANDLE find_handle = FindFirstFileExW(
-        pattern.c_str(), FindExInfoBasic, &entry, FindExSearchNameMatch, nullptr,
-        FIND_FIRST_EX_LARGE_FETCH);
+        ToExtendedLengthPath(pattern).c_str(), FindExInfoBasic, &entry, FindExSearchNameMatch,
+        nullptr, FIND_FIRST_EX_LARGE_FETCH);
     if (find_handle == INVALID_HANDLE_VALUE) {
       const DWORD error = GetLastError();
       if (!IsFallbackEnumerationSkippableError(error)) {
@@ -2285,7 +3729,6 @@ bool scan_fallback_internal(const std::wstring& drive_letter, ScanSnapshot* out_
     do {
       if (IsIndexingCancelled(request_token)) {
         FindClose(find_handle);
-        *out_cancelled = true;
         return false;
       }
 
@@ -2315,21 +3758,58 @@ bool scan_fallback_internal(const std::wstring& drive_letter, ScanSnapshot* out_
         }
       }
 
-      files.push_back(IndexedFile{
-          synthetic_frn++,
+      files->push_back(IndexedFile{
+          (*next_frn)++,
           0,
           std::move(full_path),
           is_directory,
+          entry.dwFileAttributes,
+          is_directory ? 0 : FindDataSize(entry),
+          is_directory ? 0 : FileTimeToUnixSeconds(entry.ftLastWriteTime),
       });
 
-      if ((files.size() & 0x0FFF) == 0) {
-        g_indexed_count.store(static_cast<uint64_t>(files.size()),
+      if (report_progress && (files->size() & 0x0FFF) == 0) {
+        g_indexed_count.store(static_cast<uint64_t>(files->size()),
                               std::memory_order_relaxed);
       }
     } while (FindNextFileW(find_handle, &entry) != FALSE);
 
     FindClose(find_handle);
   }
+  return true;
+}
+
+bool scan_fallback_internal(const std::wstring& drive_letter, ScanSnapshot* out_snapshot,
+                            const bool include_directories,
+                            const uint64_t request_token, bool* out_cancelled,
+                            std::string* out_error) {
+  *out_cancelled = false;
+  out_snapshot->files.clear();
+  out_snapshot->nodes.clear();
+  out_snapshot->root_frn = 0;
+  out_snapshot->root_path.clear();
+  out_snapshot->journal_id = 0;
+  out_snapshot->journal_next_usn = 0;
+  out_snapshot->live_updates_supported = false;
+
+  const std::wstring root_path = drive_letter + L":\\";
+  const DWORD root_attributes = GetFileAttributesW(root_path.c_str());
+  if (root_attributes == INVALID_FILE_ATTRIBUTES ||
+      (root_attributes & FILE_ATTRIBUTE_DIRECTORY) == 0) {
+    *out_error = BuildWin32ErrorText(
+        "Fallback indexing failed because drive root is not accessible.",
+        GetLastError());
+    return false;
+  }
+
+  std::vector<IndexedFile> files;
+  files.reserve(240000);
+  uint64_t synthetic_frn = 1;
+  if (!WalkDirectoryTree(root_path, include_directories, request_token, &synthetic_frn,
+                         &files)) {
+    *out_cancelled = true;
+    return false;
+  }
 
   out_snapshot->files = std::move(files);
   out_snapshot->nodes.clear();
@@ -2341,9 +3821,171 @@ bool scan_fallback_internal(const std::wstring& drive_letter, ScanSnapshot* out_
   return true;
 }
 
+// Folders on `drive_letter` where another volume is mounted. Volumes that also have a
+// drive letter can be skipped, since all-drives mode indexes them under that letter.
+std::vector<std::wstring> ListFolderMountPoints(const std::wstring& drive_letter,
+                                                const bool skip_lettered_volumes) {
+  std::vector<std::wstring> mounts;
+  wchar_t volume_name[MAX_PATH] = L"";
+  HANDLE find = FindFirstVolumeW(volume_name, MAX_PATH);
+  if (find == INVALID_HANDLE_VALUE) {
+    return mounts;
+  }
+  do {
+    DWORD length = 0;
+    GetVolumePathNamesForVolumeNameW(volume_name, nullptr, 0, &length);
+    if (length == 0) {
+      continue;
+    }
+    std::vector<wchar_t> names(length, L'\0');
+    if (!GetVolumePathNamesForVolumeNameW(volume_name, names.data(), length, &length)) {
+      continue;
+    }
+    std::vector<std::wstring> folder_paths;
+    bool has_drive_letter = false;
+    for (const wchar_t* cursor = names.data(); *cursor != L'\0';
+         cursor += std::wcslen(cursor) + 1) {
+      const std::wstring path = cursor;
+      if (path.size() <= 3) {
+        has_drive_letter = true;
+        continue;
+      }
+      if (path[1] == L':' && std::towupper(path[0]) == drive_letter[0]) {
+        folder_paths.push_back(NormalizeScopePath(path));
+      }
+    }
+    if (has_drive_letter && skip_lettered_volumes) {
+      continue;
+    }
+    mounts.insert(mounts.end(), folder_paths.begin(), folder_paths.end());
+  } while (FindNextVolumeW(find, volume_name, MAX_PATH));
+  FindVolumeClose(find);
+  return mounts;
+}
+
+constexpr uint64_t kMountedEntryFrnBase = 1ULL << 63;
+constexpr uint64_t kNetworkRootFrnBase = (1ULL << 63) | (1ULL << 62);
+
+// Walks each registered network root. Shares have no volume handle or USN journal, so they
+// always take the recursive walk and are not updated live. Returns false when cancelled.
+bool AppendNetworkRootFiles(const bool include_directories, const uint64_t request_token,
+                            std::vector<IndexedFile>* files) {
+  uint64_t next_frn = kNetworkRootFrnBase;
+  for (const std::wstring& root : RegisteredNetworkRoots()) {
+    const DWORD attributes = GetFileAttributesW(root.c_str());
+    if (attributes == INVALID_FILE_ATTRIBUTES || (attributes & FILE_ATTRIBUTE_DIRECTORY) == 0) {
+      RecordDriveIndexResult(
+          root, false, 0,
+          BuildWin32ErrorText("Network share is not accessible.", GetLastError()));
+      continue;
+    }
+    const size_t before = files->size();
+    if (!WalkDirectoryTree(root, include_directories, request_token, &next_frn, files)) {
+      return false;
+    }
+    RecordDriveIndexResult(root, true, static_cast<uint64_t>(files->size() - before), "");
+  }
+  return true;
+}
+
+// Appends the contents of volumes mounted into folders of `drive_letter`. Neither scan
+// crosses mount points on its own: the MFT only covers its own volume and the fallback
+// walk skips reparse points. Mounted entries get synthetic FRNs in a separate range and
+// are not kept current by the USN watcher. Returns false when cancelled.
+bool AppendMountedVolumeFiles(const std::wstring& drive_letter, const bool include_directories,
+                              const bool skip_lettered_volumes, const uint64_t request_token,
+                              ScanSnapshot* snapshot) {
+  uint64_t next_frn = kMountedEntryFrnBase;
+  for (const std::wstring& mount : ListFolderMountPoints(drive_letter, skip_lettered_volumes)) {
+    if (!WalkDirectoryTree(mount, include_directories, request_token, &next_frn,
+                           &snapshot->files)) {
+      return false;
+    }
+  }
+  return true;
+}
+
+// Fills the size and modified time of the files in `files`, which FSCTL_ENUM_USN_DATA does not
+// report, by listing every folder that holds some: one directory read covers all of a folder's
+// files, far fewer calls than opening each. Returns false when cancelled.
+bool LoadIndexedFileMetadata(std::vector<IndexedFile>* files, const uint64_t request_token,
+                             const uint32_t worker_count) {
+  std::unordered_map<uint64_t, std::vector<size_t>> positions_by_folder;
+  for (size_t i = 0; i < files->size(); ++i) {
+    if (!(*files)[i].is_directory) {
+      positions_by_folder[(*files)[i].parent_frn].push_back(i);
+    }
+  }
+  std::vector<const std::vector<size_t>*> folders;
+  folders.reserve(positions_by_folder.size());
+  for (const auto& pair : positions_by_folder) {
+    folders.push_back(&pair.second);
+  }
+
+  // Each folder's files belong to one worker, so workers never touch the same entry.
+  std::atomic<size_t> next_folder{0};
+  std::atomic<bool> cancelled{false};
+  const auto load_folders = [&]() {
+    const IndexingPriorityScope priority_scope;
+    std::unordered_map<std::wstring, size_t> position_by_name;
+    while (true) {
+      const size_t folder = next_folder.fetch_add(1, std::memory_order_relaxed);
+      if (folder >= folders.size()) {
+        return;
+      }
+      if (IsIndexingCancelled(request_token)) {
+        cancelled.store(true, std::memory_order_release);
+        return;
+      }
+      const std::vector<size_t>& positions = *folders[folder];
+      position_by_name.clear();
+      for (const size_t position : positions) {
+        position_by_name.emplace(IndexedFileName((*files)[position]), position);
+      }
+
+      std::wstring pattern = ParentPathOf((*files)[positions.front()].path);
+      if (!pattern.empty() && pattern.back() != L'\\') {
+        pattern.push_back(L'\\');
+      }
+      pattern.append(L"*");
+      WIN32_FIND_DATAW entry{};
+      HANDLE find_handle = FindFirstFileExW(
+          ToExtendedLengthPath(pattern).c_str(), FindExInfoBasic, &entry, FindExSearchNameMatch,
+          nullptr, FIND_FIRST_EX_LARGE_FETCH);
+      if (find_handle == INVALID_HANDLE_VALUE) {
+        continue;
+      }
+      do {
+        const auto found = position_by_name.find(entry.cFileName);
+        if (found == position_by_name.end()) {
+          continue;
+        }
+        IndexedFile& file = (*files)[found->second];
+        file.size = FindDataSize(entry);
+        file.modified_unix = FileTimeToUnixSeconds(entry.ftLastWriteTime);
+      } while (FindNextFileW(find_handle, &entry) != FALSE);
+      FindClose(find_handle);
+    }
+  };
+
+  const size_t thread_count = std::clamp<size_t>(
+      std::min<size_t>(worker_count, folders.size()), 1, 64);
+  std::vector<std::thread> threads;
+  threads.reserve(thread_count - 1);
+  for (size_t worker = 1; worker < thread_count; ++worker) {
+    threads.emplace_back(load_folders);
+  }
+  load_folders();
+  for (std::thread& thread : threads) {
+    thread.join();
+  }
+  return !cancelled.load(std::memory_order_acquire);
+}
+
 bool scan_mft_internal(const std::wstring& drive_letter, ScanSnapshot* out_snapshot,
                        const bool include_directories, const uint64_t request_token,
-                       bool* out_cancelled, std::string* out_error) {
+                       const uint32_t worker_count, bool* out_cancelled,
+                       std::string* out_error) {
   *out_cancelled = false;
   out_snapshot->files.clear();
   out_snapshot->nodes.clear();
@@ -2432,6 +4074,7 @@ bool scan_mft_internal(const std::wstring& drive_letter, ScanSnapshot* out_snaps
       return false;
     }
 
+    g_index_bytes_read.fetch_add(returned, std::memory_order_relaxed);
     if (returned <= sizeof(uint64_t)) {
       break;
     }
@@ -2440,6 +4083,7 @@ bool scan_mft_internal(const std::wstring& drive_letter, ScanSnapshot* out_snaps
         *reinterpret_cast<DWORDLONG*>(buffer.data());
 
     DWORD offset = sizeof(uint64_t);
+    uint64_t buffer_records = 0;
     while (offset + sizeof(DWORD) <= returned) {
       const BYTE* record_ptr = buffer.data() + offset;
       const DWORD record_length = *reinterpret_cast<const DWORD*>(record_ptr);
@@ -2450,7 +4094,8 @@ bool scan_mft_internal(const std::wstring& drive_letter, ScanSnapshot* out_snaps
       RawUsnEntry entry{};
       if (ParseUsnRecord(record_ptr, record_length, &entry) && !entry.name.empty()) {
         nodes[entry.frn] =
-            NodeEntry{entry.parent_frn, std::move(entry.name), entry.is_directory};
+            NodeEntry{entry.parent_frn, std::move(entry.name), entry.is_directory,
+                      entry.attributes};
         if (!entry.is_directory) {
           ++discovered_files;
           if ((discovered_files & 0x3FFF) == 0) {
@@ -2460,7 +4105,9 @@ bool scan_mft_internal(const std::wstring& drive_letter, ScanSnapshot* out_snaps
       }
 
       offset += record_length;
+      ++buffer_records;
     }
+    g_index_records_processed.fetch_add(buffer_records, std::memory_order_relaxed);
   }
 
   CloseHandle(volume);
@@ -2468,38 +4115,89 @@ bool scan_mft_internal(const std::wstring& drive_letter, ScanSnapshot* out_snaps
     *out_cancelled = true;
     return false;
   }
-  nodes[root_frn] = NodeEntry{root_frn, L"", true};
-
-  std::unordered_map<uint64_t, std::wstring> path_cache;
-  path_cache.reserve(nodes.size() / 2 + 1);
-  path_cache[root_frn] = root_path;
-  std::unordered_set<uint64_t> resolving;
-  std::vector<IndexedFile> files;
-  files.reserve(nodes.size() / 2 + 1);
+  nodes[root_frn] = NodeEntry{root_frn, L"", true, FILE_ATTRIBUTE_DIRECTORY};
 
+  std::vector<uint64_t> frns;
+  frns.reserve(nodes.size());
   for (const auto& pair : nodes) {
-    if (IsIndexingCancelled(request_token)) {
-      *out_cancelled = true;
-      return false;
-    }
-    const uint64_t frn = pair.first;
     const NodeEntry& node = pair.second;
     if (node.name.empty() || (node.is_directory && !include_directories)) {
       continue;
     }
-    resolving.clear();
-    std::wstring full_path;
-    const bool resolved = ResolvePathForFrn(frn, root_frn, root_path, nodes, &path_cache,
-                                            &resolving, &full_path);
-    if (!resolved || full_path.empty()) {
-      continue;
+    frns.push_back(pair.first);
+  }
+
+  // Each worker resolves a contiguous slice with its own path cache; `nodes` is read-only.
+  constexpr size_t kMinFrnsPerWorker = 16384;
+  const size_t path_workers = std::clamp<size_t>(
+      std::min<size_t>(worker_count, frns.size() / kMinFrnsPerWorker), 1, 64);
+  const size_t slice_size = (frns.size() + path_workers - 1) / path_workers;
+  std::vector<std::vector<IndexedFile>> worker_files(path_workers);
+  std::atomic<bool> path_cancelled{false};
+  const auto resolve_slice = [&](const size_t worker) {
+    const IndexingPriorityScope priority_scope;
+    const size_t begin = std::min(frns.size(), worker * slice_size);
+    const size_t end = std::min(frns.size(), begin + slice_size);
+    std::unordered_map<uint64_t, std::wstring> path_cache;
+    path_cache.reserve((end - begin) / 2 + 1);
+    path_cache[root_frn] = root_path;
+    std::unordered_set<uint64_t> resolving;
+    std::vector<IndexedFile>& files = worker_files[worker];
+    files.reserve(end - begin);
+    for (size_t i = begin; i < end; ++i) {
+      if (IsIndexingCancelled(request_token)) {
+        path_cancelled.store(true, std::memory_order_release);
+        return;
+      }
+      const uint64_t frn = frns[i];
+      const NodeEntry& node = nodes.at(frn);
+      resolving.clear();
+      std::wstring full_path;
+      const bool resolved = ResolvePathForFrn(frn, root_frn, root_path, nodes, &path_cache,
+                                              &resolving, &full_path);
+      if (!resolved || full_path.empty()) {
+        continue;
+      }
+      files.push_back(IndexedFile{
+          frn,
+          node.parent_frn,
+          std::move(full_path),
+          node.is_directory,
+          node.attributes,
+      });
     }
-    files.push_back(IndexedFile{
-        frn,
-        node.parent_frn,
-        std::move(full_path),
-        node.is_directory,
-    });
+  };
+
+  std::vector<std::thread> path_threads;
+  path_threads.reserve(path_workers - 1);
+  for (size_t worker = 1; worker < path_workers; ++worker) {
+    path_threads.emplace_back(resolve_slice, worker);
+  }
+  resolve_slice(0);
+  for (std::thread& thread : path_threads) {
+    thread.join();
+  }
+  if (path_cancelled.load(std::memory_order_acquire)) {
+    *out_cancelled = true;
+    return false;
+  }
+
+  std::vector<IndexedFile> files;
+  if (path_workers == 1) {
+    files = std::move(worker_files.front());
+  } else {
+    size_t total_files = 0;
+    for (const std::vector<IndexedFile>& slice : worker_files) {
+      total_files += slice.size();
+    }
+    files.reserve(total_files);
+    for (std::vector<IndexedFile>& slice : worker_files) {
+      std::move(slice.begin(), slice.end(), std::back_inserter(files));
+    }
+  }
+  if (!LoadIndexedFileMetadata(&files, request_token, worker_count)) {
+    *out_cancelled = true;
+    return false;
   }
 
   out_snapshot->files = std::move(files);
@@ -2515,6 +4213,46 @@ bool scan_mft_internal(const std::wstring& drive_letter, ScanSnapshot* out_snaps
   return true;
 }
 
+// Fills the size and modified time of the files in `entries` that still exist. Each is opened
+// by its file id on `volume`, so no path has to be resolved and the index lock is not needed;
+// a file with several records in the batch is read once.
+void ReadUsnEntryMetadata(HANDLE volume, std::vector<RawUsnEntry>* entries) {
+  std::unordered_map<uint64_t, std::pair<uint64_t, int64_t>> metadata_by_frn;
+  for (RawUsnEntry& entry : *entries) {
+    if (entry.is_directory || (entry.reason & USN_REASON_FILE_DELETE) != 0) {
+      continue;
+    }
+    auto found = metadata_by_frn.find(entry.frn);
+    if (found == metadata_by_frn.end()) {
+      std::pair<uint64_t, int64_t> metadata{0, 0};
+      FILE_ID_DESCRIPTOR id{};
+      id.dwSize = sizeof(id);
+      id.Type = FileIdType;
+      id.FileId.QuadPart = static_cast<LONGLONG>(entry.frn);
+      HANDLE handle =
+          OpenFileById(volume, &id, FILE_READ_ATTRIBUTES,
+                       FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr, 0);
+      if (handle != INVALID_HANDLE_VALUE) {
+        FILE_BASIC_INFO basic{};
+        FILE_STANDARD_INFO standard{};
+        if (GetFileInformationByHandleEx(handle, FileBasicInfo, &basic, sizeof(basic)) &&
+            GetFileInformationByHandleEx(handle, FileStandardInfo, &standard,
+                                         sizeof(standard))) {
+          FILETIME written{};
+          written.dwLowDateTime = basic.LastWriteTime.LowPart;
+          written.dwHighDateTime = static_cast<DWORD>(basic.LastWriteTime.HighPart);
+          metadata = {static_cast<uint64_t>(standard.EndOfFile.QuadPart),
+                      FileTimeToUnixSeconds(written)};
+        }
+        CloseHandle(handle);
+      }
+      found = metadata_by_frn.emplace(entry.frn, metadata).first;
+    }
+    entry.size = found->second.first;
+    entry.modified_unix = found->second.second;
+  }
+}
+
 void StartLiveUsnWatcher(const std::wstring& drive_letter, const uint64_t journal_id,
                          const int64_t start_usn) {
   if (journal_id == 0 || start_usn <= 0) {
@@ -2537,6 +4275,7 @@ void StartLiveUsnWatcher(const std::wstring& drive_letter, const uint64_t journa
       return;
     }
 
+    SetDriveLiveUpdates(drive_letter, true);
     READ_USN_JOURNAL_DATA_V0 read_data{};
     read_data.StartUsn = static_cast<USN>(start_usn);
     read_data.ReasonMask = 0xFFFFFFFF;
@@ -2609,11 +4348,17 @@ void StartLiveUsnWatcher(const std::wstring& drive_letter, const uint64_t journa
       if (batch.empty() || IsLiveWatcherCancelled(watcher_token)) {
         continue;
       }
+      ReadUsnEntryMetadata(volume, &batch);
 
       std::unique_lock<std::shared_mutex> lock(g_index_mutex);
       ApplyUsnBatchLocked(batch);
+      EnforceIndexMemoryBudgetAfterLiveUpdatesLocked();
     }
 
+    // A newer watcher or indexing run owns the drive state once this one is superseded.
+    if (!IsLiveWatcherCancelled(watcher_token)) {
+      SetDriveLiveUpdates(drive_letter, false);
+    }
     CloseHandle(volume);
   }).detach();
 }
@@ -2647,16 +4392,29 @@ std::vector<DriveInfo> list_drives_internal() {
     const std::wstring drive_letter(1, letter);
     const UINT drive_type = GetDriveTypeW(root.c_str());
     wchar_t filesystem_buffer[MAX_PATH] = L"";
+    DWORD serial_number = 0;
     const BOOL has_fs = GetVolumeInformationW(
-        root.c_str(), nullptr, 0, nullptr, nullptr, nullptr, filesystem_buffer,
+        root.c_str(), nullptr, 0, &serial_number, nullptr, nullptr, filesystem_buffer,
         MAX_PATH);
     const std::wstring filesystem = has_fs ? filesystem_buffer : L"";
     const bool is_ntfs = ToLower(filesystem) == L"ntfs";
     const bool can_open_volume = is_ntfs ? CanOpenVolume(drive_letter) : false;
 
+    // "\\?\Volume{GUID}\" stays stable when the drive letter is reassigned.
+    wchar_t volume_name_buffer[64] = L"";
+    const std::wstring volume_guid =
+        GetVolumeNameForVolumeMountPointW(root.c_str(), volume_name_buffer, 64)
+            ? volume_name_buffer
+            : L"";
+
     rows.push_back(DriveInfo{drive_letter, root, filesystem,
                              DriveTypeToText(drive_type), is_ntfs,
-                             can_open_volume});
+                             can_open_volume, volume_guid,
+                             has_fs ? static_cast<uint32_t>(serial_number) : 0,
+                             drive_type == DRIVE_REMOTE ? QueryMappedDriveTarget(drive_letter)
+                                                        : L"",
+                             DriveScanMode(drive_letter, has_fs != FALSE, filesystem,
+                                           can_open_volume)});
   }
 
   return rows;
@@ -2684,69 +4442,371 @@ std::vector<std::wstring> ResolveTargetDrivesForIndexing(
   return drives;
 }
 
+// Resolves the owner of `path` to "DOMAIN\name", caching lookups by SID string since most
+// files share a handful of owners. Returns an empty string when the owner is unavailable.
+std::wstring ResolveFileOwner(const std::wstring& path,
+                              std::unordered_map<std::wstring, std::wstring>* cache) {
+  PSID owner_sid = nullptr;
+  PSECURITY_DESCRIPTOR descriptor = nullptr;
+  if (GetNamedSecurityInfoW(path.c_str(), SE_FILE_OBJECT, OWNER_SECURITY_INFORMATION,
+                            &owner_sid, nullptr, nullptr, nullptr,
+                            &descriptor) != ERROR_SUCCESS) {
+    return L"";
+  }
+
+  std::wstring owner;
+  LPWSTR raw_sid = nullptr;
+  if (owner_sid != nullptr && ConvertSidToStringSidW(owner_sid, &raw_sid)) {
+    const std::wstring sid_text = raw_sid;
+    LocalFree(raw_sid);
+    const auto cached = cache->find(sid_text);
+    if (cached != cache->end()) {
+      owner = cached->second;
+    } else {
+      wchar_t name[256] = {};
+      wchar_t domain[256] = {};
+      DWORD name_length = static_cast<DWORD>(std::size(name));
+      DWORD domain_length = static_cast<DWORD>(std::size(domain));
+      SID_NAME_USE use = SidTypeUnknown;
+      if (LookupAccountSidW(nullptr, owner_sid, name, &name_length, domain, &domain_length,
+                            &use)) {
+        owner = domain_length > 0 ? std::wstring(domain) + L"\\" + name : std::wstring(name);
+      } else {
+        // Accounts from other machines or deleted users still match by their SID.
+        owner = sid_text;
+      }
+      cache->emplace(sid_text, owner);
+    }
+  }
+  LocalFree(descriptor);
+  return owner;
+}
+
+// Matches "DOMAIN\name" owners against a filter naming either the full account or the bare
+// user name.
+bool OwnerMatchesFilter(const std::wstring& owner, const std::wstring& filter_lower) {
+  if (owner.empty()) {
+    return false;
+  }
+  const std::wstring owner_lower = ToLower(owner);
+  if (owner_lower == filter_lower) {
+    return true;
+  }
+  const size_t separator = owner_lower.rfind(L'\\');
+  return separator != std::wstring::npos &&
+         owner_lower.compare(separator + 1, std::wstring::npos, filter_lower) == 0;
+}
+
+// The 8.3 short name of `path`, or empty when the volume keeps none or it equals the long name.
+std::wstring ReadShortName(const std::wstring& path) {
+  wchar_t buffer[MAX_PATH * 4] = L"";
+  const DWORD length =
+      GetShortPathNameW(ToExtendedLengthPath(path).c_str(), buffer, ARRAYSIZE(buffer));
+  if (length == 0 || length >= ARRAYSIZE(buffer)) {
+    return L"";
+  }
+  const std::wstring short_name = ExtractFileNameFromPath(std::wstring(buffer, length));
+  if (CompareStringOrdinal(short_name.c_str(), -1, ExtractFileNameFromPath(path).c_str(), -1,
+                           TRUE) == CSTR_EQUAL) {
+    return L"";
+  }
+  return short_name;
+}
+
+// Cheap pre-check before asking the file system for a short name: generated short names keep
+// at least the first two characters of the long name (spaces and dots dropped), so the query
+// text before its `~` has to start with those. Names that already fit 8.3 have no separate
+// short name.
+bool MayMatchShortName(const std::wstring& name, const std::wstring& query_lower) {
+  const size_t tilde = query_lower.find(L'~');
+  if (tilde == std::wstring::npos || tilde == 0) {
+    return false;
+  }
+  const size_t dot = name.find_last_of(L'.');
+  const size_t base_length = dot == std::wstring::npos ? name.size() : dot;
+  const size_t extension_length = dot == std::wstring::npos ? 0 : name.size() - dot - 1;
+  const bool fits_short_name = base_length <= 8 && extension_length <= 3 &&
+                               name.find(L' ') == std::wstring::npos &&
+                               name.find(L'.') == dot;
+  if (fits_short_name) {
+    return false;
+  }
+
+  std::wstring stripped;
+  stripped.reserve(name.size());
+  for (const wchar_t ch : ToLower(name)) {
+    if (ch != L' ' && ch != L'.') {
+      stripped.push_back(ch);
+    }
+  }
+  const std::wstring lead = query_lower.substr(0, std::min<size_t>(tilde, 2));
+  return stripped.find(lead) != std::wstring::npos;
+}
+
+// Finds the index position of the entry stored under exactly `path` (case-insensitive),
+// narrowing candidates through the name bigram index. The caller must hold `g_index_mutex`.
+bool FindIndexedPathLocked(const std::wstring& path, uint32_t* out_position) {
+  const size_t separator = path.find_last_of(L'\\');
+  if (separator == std::wstring::npos || separator + 1 >= path.size()) {
+    return false;
+  }
+  for (const uint32_t position : LookupNameBigramsLocked(ToLower(path.substr(separator + 1)))) {
+    const std::wstring& candidate = g_indexed_files[position].path;
+    if (candidate.size() == path.size() &&
+        CompareStringOrdinal(candidate.c_str(), static_cast<int>(candidate.size()),
+                             path.c_str(), static_cast<int>(path.size()),
+                             TRUE) == CSTR_EQUAL) {
+      *out_position = position;
+      return true;
+    }
+  }
+  return false;
+}
+
 }  // namespace
 
-extern "C" __declspec(dllexport) bool omni_start_indexing(
-    const char* drive_utf8, const bool include_directories,
-    const bool scan_all_drives) {
+struct OmniIndexingOptions {
+  const char* drive_utf8;
+  bool include_directories;
+  bool scan_all_drives;
+  uint32_t threads;
+  // Index the contents of volumes mounted into folders (NTFS mount points).
+  bool follow_mount_points;
+  // Keep NTFS metadata files and well-known system files such as pagefile.sys.
+  bool include_system_files;
+  // Run the scan in background processing mode so foreground apps keep the disk.
+  bool low_priority;
+};
+
+struct OmniSearchOptions {
+  const char* query_utf8;
+  const char* extension_utf8;
+  const char* within_path_utf8;
+  uint64_t min_size;
+  uint64_t max_size;
+  int64_t min_created_unix;
+  int64_t max_created_unix;
+  uint32_t limit;
+  uint32_t max_limit;
+  // Entries match when `(attributes & attributes_mask) == attributes_value`; a zero mask
+  // disables the filter.
+  uint32_t attributes_mask;
+  uint32_t attributes_value;
+  // A SearchScope: match the query against the full path (default) or the name only.
+  uint32_t search_in;
+  // Entries last modified before this are skipped; INT64_MIN disables the filter.
+  int64_t min_modified_unix;
+  // Entries last modified after this are skipped; INT64_MAX disables the filter.
+  int64_t max_modified_unix;
+  // Names that do not contain the query still match when some part of them is within this
+  // many edits of it; zero disables fuzzy matching.
+  uint32_t fuzzy_max_distance;
+  // Fuzzy matches also need `1 - distance / query length` of at least this score.
+  double fuzzy_min_score;
+  // Account name ("DOMAIN\name" or "name") the file owner must match; null or empty
+  // disables the filter.
+  const char* owner_utf8;
+  // Newline-separated paths; when set, only these entries are searched.
+  const char* path_set_utf8;
+  // Queries containing `~` also match entries by their 8.3 short name.
+  bool match_short_names;
+  // Only files whose name has no extension match, or, with an extension filter, either kind.
+  bool no_extension;
+  // A QueryMatchMode: match the query's tokens in any order (default) or as a phrase.
+  uint32_t match_mode;
+  // A SearchSortOrder; sorted searches rank up to kRelevanceCandidateCeiling matches before
+  // keeping the first `limit`.
+  uint32_t sort_order;
+  // A MatchPosition; anchored queries match the name as one phrase, without fuzzy matching.
+  uint32_t match_position;
+  // Scan position to continue from, taken from a previous page's `out_resume_at`.
+  uint32_t resume_from;
+  // When set, receives the position just past the last row of a page that filled `limit`,
+  // or UINT32_MAX once nothing is left to scan. Relevance-sorted and drive-distributed
+  // searches rank every match at once, so they always report UINT32_MAX.
+  uint32_t* out_resume_at;
+  // When set, receives the index generation a page was read from. With a nonzero
+  // `resume_from` it must hold the generation of the page that produced that position: any
+  // index change since then shifts positions, so the search fails instead of skipping or
+  // repeating entries.
+  uint64_t* index_generation;
+  // Folder levels below the scope an entry may sit at: 1 keeps only the scope's immediate
+  // children. The scope is `within_path`, or the entry's drive root without one. Zero means
+  // no limit.
+  uint32_t max_depth;
+  // Nonzero lets omni_cancel_search_id stop this search without touching other searches.
+  uint64_t search_id;
+  // Serialize only names, paths and directory flags.
+  bool lightweight;
+  // An internal scan (similar images, saved searches, cleanup...) rather than the search box:
+  // it neither supersedes the running search nor is cancelled by a newer one.
+  bool background;
+};
+
+struct OmniDuplicateScanOptions {
+  uint64_t min_size;
+  uint32_t max_groups;
+  uint32_t max_files_per_group;
+  bool skip_reparse_points;
+  uint32_t group_by;
+  // Hashing workers; zero picks one per spare CPU.
+  uint32_t io_concurrency;
+  // Combined read throughput cap for hashing; zero disables the throttle.
+  uint64_t throttle_bytes_per_sec;
+};
+
+namespace {
+
+// Stamps the end of an indexing run and, when it left a usable index, records when it
+// completed and how long it took.
+void FinishIndexRun() {
+  const uint64_t finished_ms = GetTickCount64();
+  g_index_run_finished_ms.store(finished_ms, std::memory_order_release);
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    return;
+  }
+  const uint64_t started_ms = g_index_run_started_ms.load(std::memory_order_acquire);
+  g_last_index_duration_ms.store(finished_ms >= started_ms ? finished_ms - started_ms : 0,
+                                 std::memory_order_release);
+  g_last_index_completed_unix.store(CurrentUnixSeconds(), std::memory_order_release);
+}
+
+}  // namespace
+
+extern "C" __declspec(dllexport) bool omni_start_indexing_with_options(
+    const OmniIndexingOptions* options) {
+  if (options == nullptr) {
+    SetLastErrorText("Indexing failed: missing options.");
+    return false;
+  }
+
+  const char* drive_utf8 = options->drive_utf8;
+  const bool include_directories = options->include_directories;
+  // Several listed drives ("C,D") are scanned the way all-drives mode scans, limited to them.
+  const std::vector<std::wstring> listed_drives = ParseDriveList(drive_utf8);
+  const bool scan_listed_drives = listed_drives.size() > 1 && !options->scan_all_drives;
+  const bool scan_all_drives = options->scan_all_drives || scan_listed_drives;
+  const bool follow_mount_points = options->follow_mount_points;
+  const uint32_t thread_count = ResolveIndexingThreadCount(options->threads);
+  g_indexing_threads.store(thread_count, std::memory_order_release);
   const uint64_t request_token =
       g_indexing_request_token.fetch_add(1, std::memory_order_acq_rel) + 1;
 
   g_is_indexing.store(true, std::memory_order_release);
   g_is_ready.store(false, std::memory_order_release);
   g_indexed_count.store(0, std::memory_order_release);
+  g_index_bytes_read.store(0, std::memory_order_release);
+  g_index_records_processed.store(0, std::memory_order_release);
+  g_index_run_finished_ms.store(0, std::memory_order_release);
+  g_index_run_started_ms.store(GetTickCount64(), std::memory_order_release);
   SetLastErrorText("");
   StopLiveWatcher();
   const std::wstring drive_letter = NormalizeDriveLetter(drive_utf8);
   g_include_directories.store(include_directories, std::memory_order_release);
+  g_include_system_files.store(options->include_system_files, std::memory_order_release);
+  g_low_priority_indexing.store(options->low_priority, std::memory_order_release);
   g_scan_all_drives_mode.store(scan_all_drives, std::memory_order_release);
 
   std::thread(
-      [drive_letter, include_directories, scan_all_drives, request_token]() {
+      [drive_letter, listed_drives, scan_listed_drives, include_directories, scan_all_drives,
+       follow_mount_points, request_token, thread_count]() {
+        const IndexingPriorityScope priority_scope;
         if (scan_all_drives) {
           const std::vector<std::wstring> target_drives =
-              ResolveTargetDrivesForIndexing(drive_letter, true);
+              scan_listed_drives ? listed_drives
+                                 : ResolveTargetDrivesForIndexing(drive_letter, true);
+          ResetDriveIndexStates(WithNetworkRoots(target_drives));
           std::vector<IndexedFile> merged_files;
           merged_files.reserve(300000);
           std::string combined_error;
           bool has_success = false;
 
-          for (const std::wstring& target_drive : target_drives) {
-            if (IsIndexingCancelled(request_token)) {
-              return;
-            }
-
-            ScanSnapshot snapshot;
-            std::string error;
-            bool cancelled = false;
-            const bool can_use_accelerated = CanOpenVolume(target_drive);
-            const bool ok = can_use_accelerated
-                                ? scan_mft_internal(target_drive, &snapshot,
-                                                    include_directories, request_token,
-                                                    &cancelled, &error)
-                                : scan_fallback_internal(target_drive, &snapshot,
-                                                         include_directories, request_token,
-                                                         &cancelled, &error);
-            if (cancelled || IsIndexingCancelled(request_token)) {
-              return;
+          // Drives are independent, so scan several at once and split the remaining
+          // workers among them for path resolution.
+          const size_t drive_workers = std::clamp<size_t>(
+              std::min<size_t>(thread_count, target_drives.size()), 1, 64);
+          const uint32_t threads_per_drive = std::max<uint32_t>(
+              1, thread_count / static_cast<uint32_t>(drive_workers));
+          std::vector<ScanSnapshot> snapshots(target_drives.size());
+          std::vector<std::string> errors(target_drives.size());
+          std::vector<uint8_t> succeeded(target_drives.size(), 0);
+          std::atomic<size_t> next_drive{0};
+          std::atomic<bool> any_cancelled{false};
+          std::atomic<bool> any_trimmed{false};
+          const auto scan_drives = [&]() {
+            const IndexingPriorityScope priority_scope;
+            while (true) {
+              const size_t index = next_drive.fetch_add(1, std::memory_order_relaxed);
+              if (index >= target_drives.size() || IsIndexingCancelled(request_token)) {
+                return;
+              }
+              const std::wstring& target_drive = target_drives[index];
+              bool cancelled = false;
+              const bool can_use_accelerated = CanOpenVolume(target_drive);
+              bool ok = can_use_accelerated
+                                  ? scan_mft_internal(target_drive, &snapshots[index],
+                                                      include_directories, request_token,
+                                                      threads_per_drive, &cancelled,
+                                                      &errors[index])
+                                  : scan_fallback_internal(target_drive, &snapshots[index],
+                                                           include_directories, request_token,
+                                                           &cancelled, &errors[index]);
+              if (ok && follow_mount_points &&
+                  !AppendMountedVolumeFiles(target_drive, include_directories, true,
+                                            request_token, &snapshots[index])) {
+                cancelled = true;
+              }
+              if (cancelled) {
+                any_cancelled.store(true, std::memory_order_release);
+                return;
+              }
+              RemoveSystemArtifacts(&snapshots[index].files);
+              // Only the files are merged, so the nodes can go as soon as the drive is done.
+              std::unordered_map<uint64_t, NodeEntry>().swap(snapshots[index].nodes);
+              if (TrimScannedFilesToBudget(&snapshots[index].files)) {
+                any_trimmed.store(true, std::memory_order_release);
+              }
+              succeeded[index] = ok ? 1 : 0;
             }
+          };
+          std::vector<std::thread> drive_threads;
+          drive_threads.reserve(drive_workers - 1);
+          for (size_t worker = 1; worker < drive_workers; ++worker) {
+            drive_threads.emplace_back(scan_drives);
+          }
+          scan_drives();
+          for (std::thread& thread : drive_threads) {
+            thread.join();
+          }
+          if (any_cancelled.load(std::memory_order_acquire) ||
+              IsIndexingCancelled(request_token)) {
+            return;
+          }
 
-            if (!ok) {
-              if (!error.empty()) {
+          std::vector<uint64_t> drive_counts(target_drives.size(), 0);
+          for (size_t index = 0; index < target_drives.size(); ++index) {
+            if (!succeeded[index]) {
+              RecordDriveIndexResult(target_drives[index], false, 0, errors[index]);
+              if (!errors[index].empty()) {
                 if (!combined_error.empty()) {
                   combined_error.append(" | ");
                 }
-                combined_error.append(WideToUtf8(target_drive));
+                combined_error.append(WideToUtf8(target_drives[index]));
                 combined_error.append(": ");
-                combined_error.append(error);
+                combined_error.append(errors[index]);
               }
               continue;
             }
 
             has_success = true;
-            for (IndexedFile& file : snapshot.files) {
+            drive_counts[index] = static_cast<uint64_t>(snapshots[index].files.size());
+            for (IndexedFile& file : snapshots[index].files) {
               merged_files.push_back(std::move(file));
             }
+            std::vector<IndexedFile>().swap(snapshots[index].files);
+            if (TrimScannedFilesToBudget(&merged_files)) {
+              any_trimmed.store(true, std::memory_order_release);
+            }
             g_indexed_count.store(static_cast<uint64_t>(merged_files.size()),
                                   std::memory_order_release);
           }
@@ -2754,6 +4814,11 @@ extern "C" __declspec(dllexport) bool omni_start_indexing(
           if (IsIndexingCancelled(request_token)) {
             return;
           }
+          const size_t drive_file_count = merged_files.size();
+          if (!AppendNetworkRootFiles(include_directories, request_token, &merged_files)) {
+            return;
+          }
+          has_success = has_success || merged_files.size() > drive_file_count;
 
           if (!has_success) {
             g_is_ready.store(false, std::memory_order_release);
@@ -2761,10 +4826,19 @@ extern "C" __declspec(dllexport) bool omni_start_indexing(
             SetLastErrorText(combined_error.empty() ? "Unknown indexing error."
                                                     : combined_error);
           } else {
-            const uint64_t indexed_count = static_cast<uint64_t>(merged_files.size());
+            uint64_t indexed_count = 0;
             {
               std::unique_lock<std::shared_mutex> lock(g_index_mutex);
               ApplyIndexedFilesOnlyLocked(std::move(merged_files));
+              const bool trimmed = EnforceIndexMemoryBudgetLocked();
+              g_index_trimmed.store(trimmed || any_trimmed.load(std::memory_order_acquire),
+                                    std::memory_order_release);
+              indexed_count = static_cast<uint64_t>(g_indexed_files.size());
+            }
+            for (size_t index = 0; index < target_drives.size(); ++index) {
+              if (succeeded[index]) {
+                RecordDriveIndexResult(target_drives[index], true, drive_counts[index], "");
+              }
             }
             g_indexed_count.store(indexed_count, std::memory_order_release);
             g_is_ready.store(true, std::memory_order_release);
@@ -2772,11 +4846,13 @@ extern "C" __declspec(dllexport) bool omni_start_indexing(
           }
 
           if (!IsIndexingCancelled(request_token)) {
+            FinishIndexRun();
             g_is_indexing.store(false, std::memory_order_release);
           }
           return;
         }
 
+        ResetDriveIndexStates(WithNetworkRoots({drive_letter}));
         ScanSnapshot snapshot;
         std::string error;
         bool cancelled = false;
@@ -2784,20 +4860,37 @@ extern "C" __declspec(dllexport) bool omni_start_indexing(
         const bool ok = can_use_accelerated
                             ? scan_mft_internal(drive_letter, &snapshot,
                                                 include_directories, request_token,
-                                                &cancelled, &error)
+                                                thread_count, &cancelled, &error)
                             : scan_fallback_internal(drive_letter, &snapshot,
                                                      include_directories, request_token,
                                                      &cancelled, &error);
+        if (ok && follow_mount_points &&
+            !AppendMountedVolumeFiles(drive_letter, include_directories, false, request_token,
+                                      &snapshot)) {
+          cancelled = true;
+        }
+        RemoveSystemArtifacts(&snapshot.files);
+        const bool scan_trimmed = TrimScannedFilesToBudget(&snapshot.files);
+        const size_t drive_file_count = snapshot.files.size();
+        if (ok && !cancelled &&
+            !AppendNetworkRootFiles(include_directories, request_token, &snapshot.files)) {
+          cancelled = true;
+        }
         if (cancelled || IsIndexingCancelled(request_token)) {
           return;
         }
 
         if (ok) {
-          const uint64_t indexed_count = static_cast<uint64_t>(snapshot.files.size());
+          uint64_t indexed_count = 0;
           {
             std::unique_lock<std::shared_mutex> lock(g_index_mutex);
             ApplyScanSnapshotLocked(&snapshot);
+            const bool trimmed = EnforceIndexMemoryBudgetLocked();
+            g_index_trimmed.store(trimmed || scan_trimmed, std::memory_order_release);
+            indexed_count = static_cast<uint64_t>(g_indexed_files.size());
           }
+          RecordDriveIndexResult(drive_letter, true, static_cast<uint64_t>(drive_file_count),
+                                 "");
           g_indexed_count.store(indexed_count, std::memory_order_release);
           g_is_ready.store(true, std::memory_order_release);
           SetLastErrorText("");
@@ -2806,12 +4899,14 @@ extern "C" __declspec(dllexport) bool omni_start_indexing(
                                 snapshot.journal_next_usn);
           }
         } else {
+          RecordDriveIndexResult(drive_letter, false, 0, error);
           g_is_ready.store(false, std::memory_order_release);
           g_indexed_count.store(0, std::memory_order_release);
           SetLastErrorText(error.empty() ? "Unknown indexing error." : error);
         }
 
         if (!IsIndexingCancelled(request_token)) {
+          FinishIndexRun();
           g_is_indexing.store(false, std::memory_order_release);
         }
   }).detach();
@@ -2819,6 +4914,38 @@ extern "C" __declspec(dllexport) bool omni_start_indexing(
   return true;
 }
 
+extern "C" __declspec(dllexport) bool omni_start_indexing(
+    const char* drive_utf8, const bool include_directories,
+    const bool scan_all_drives) {
+  OmniIndexingOptions options{};
+  options.drive_utf8 = drive_utf8;
+  options.include_directories = include_directories;
+  options.scan_all_drives = scan_all_drives;
+  options.threads = 0;
+  options.follow_mount_points = false;
+  options.include_system_files = false;
+  options.low_priority = false;
+  return omni_start_indexing_with_options(&options);
+}
+
+// Moves the live-update counters into the out parameters and resets them; returns whether
+// anything changed since the previous call.
+extern "C" __declspec(dllexport) bool omni_take_index_update_counts(uint64_t* out_added,
+                                                                    uint64_t* out_removed,
+                                                                    uint64_t* out_modified) {
+  if (out_added == nullptr || out_removed == nullptr || out_modified == nullptr) {
+    return false;
+  }
+  *out_added = g_index_added_count.exchange(0, std::memory_order_relaxed);
+  *out_removed = g_index_removed_count.exchange(0, std::memory_order_relaxed);
+  *out_modified = g_index_modified_count.exchange(0, std::memory_order_relaxed);
+  return *out_added != 0 || *out_removed != 0 || *out_modified != 0;
+}
+
+extern "C" __declspec(dllexport) uint32_t omni_indexing_thread_count() {
+  return g_indexing_threads.load(std::memory_order_acquire);
+}
+
 extern "C" __declspec(dllexport) bool omni_is_indexing() {
   return g_is_indexing.load(std::memory_order_acquire);
 }
@@ -2831,342 +4958,4634 @@ extern "C" __declspec(dllexport) uint64_t omni_indexed_file_count() {
   return g_indexed_count.load(std::memory_order_acquire);
 }
 
-extern "C" __declspec(dllexport) const char* omni_last_error() {
-  thread_local std::string error_cache;
-  error_cache = ReadLastErrorText();
-  return error_cache.c_str();
+// Estimated heap use of the index as of the last index run or budget change.
+extern "C" __declspec(dllexport) uint64_t omni_index_memory_bytes() {
+  return g_index_memory_bytes.load(std::memory_order_acquire);
 }
 
-extern "C" __declspec(dllexport) char* omni_list_drives_json() {
-  const std::vector<DriveInfo> rows = list_drives_internal();
-  const std::string json = DriveRowsToJson(rows);
+// Whether entries were dropped from the current index to stay under the memory budget.
+extern "C" __declspec(dllexport) bool omni_index_trimmed() {
+  return g_index_trimmed.load(std::memory_order_acquire);
+}
+
+// Sets the index memory budget in bytes (zero removes it) and trims the current index to fit.
+// Trimmed entries come back with the next index run under a larger budget.
+extern "C" __declspec(dllexport) void omni_set_index_memory_budget(const uint64_t budget_bytes) {
+  g_index_memory_budget.store(budget_bytes, std::memory_order_release);
+  std::unique_lock<std::shared_mutex> lock(g_index_mutex);
+  if (EnforceIndexMemoryBudgetLocked()) {
+    g_index_trimmed.store(true, std::memory_order_release);
+  }
+}
+
+// Reports when the last successful indexing run completed and how long it took; false until
+// one has.
+extern "C" __declspec(dllexport) bool omni_last_index_run(int64_t* out_completed_unix,
+                                                          uint64_t* out_duration_ms) {
+  const int64_t completed_unix = g_last_index_completed_unix.load(std::memory_order_acquire);
+  if (completed_unix == 0 || out_completed_unix == nullptr || out_duration_ms == nullptr) {
+    return false;
+  }
+  *out_completed_unix = completed_unix;
+  *out_duration_ms = g_last_index_duration_ms.load(std::memory_order_acquire);
+  return true;
+}
+
+// Reports how much MFT data the latest indexing run has read and how many records it parsed.
+// Rates are measured between consecutive calls while indexing (the run average on the first
+// call or once the run is over), so polling once a second gives a current read speed.
+extern "C" __declspec(dllexport) char* omni_index_diagnostics_json() {
+  struct DiagnosticsSample {
+    uint64_t tick_ms = 0;
+    uint64_t bytes_read = 0;
+    uint64_t records_processed = 0;
+  };
+  static std::mutex sample_mutex;
+  static DiagnosticsSample last_sample;
+
+  const bool indexing = g_is_indexing.load(std::memory_order_acquire);
+  const uint64_t bytes_read = g_index_bytes_read.load(std::memory_order_acquire);
+  const uint64_t records_processed = g_index_records_processed.load(std::memory_order_acquire);
+  const uint64_t started_ms = g_index_run_started_ms.load(std::memory_order_acquire);
+  const uint64_t finished_ms = g_index_run_finished_ms.load(std::memory_order_acquire);
+  const uint64_t now_ms = GetTickCount64();
+  const uint64_t end_ms = finished_ms != 0 ? finished_ms : now_ms;
+  const uint64_t elapsed_ms = started_ms == 0 || end_ms < started_ms ? 0 : end_ms - started_ms;
+
+  double bytes_per_sec = 0.0;
+  double records_per_sec = 0.0;
+  if (elapsed_ms > 0) {
+    bytes_per_sec = static_cast<double>(bytes_read) * 1000.0 / static_cast<double>(elapsed_ms);
+    records_per_sec =
+        static_cast<double>(records_processed) * 1000.0 / static_cast<double>(elapsed_ms);
+  }
+  {
+    std::lock_guard<std::mutex> lock(sample_mutex);
+    const bool same_run = last_sample.tick_ms >= started_ms &&
+                          last_sample.bytes_read <= bytes_read &&
+                          last_sample.records_processed <= records_processed;
+    if (indexing && same_run && now_ms > last_sample.tick_ms) {
+      const double window_ms = static_cast<double>(now_ms - last_sample.tick_ms);
+      bytes_per_sec =
+          static_cast<double>(bytes_read - last_sample.bytes_read) * 1000.0 / window_ms;
+      records_per_sec = static_cast<double>(records_processed - last_sample.records_processed) *
+                        1000.0 / window_ms;
+    }
+    last_sample = DiagnosticsSample{now_ms, bytes_read, records_processed};
+  }
+
+  std::string json;
+  json.reserve(192);
+  json.append("{\"indexing\":");
+  json.append(indexing ? "true" : "false");
+  json.append(",\"bytesRead\":");
+  json.append(std::to_string(bytes_read));
+  json.append(",\"recordsProcessed\":");
+  json.append(std::to_string(records_processed));
+  json.append(",\"elapsedMs\":");
+  json.append(std::to_string(elapsed_ms));
+  json.append(",\"bytesPerSec\":");
+  json.append(std::to_string(static_cast<uint64_t>(bytes_per_sec)));
+  json.append(",\"recordsPerSec\":");
+  json.append(std::to_string(static_cast<uint64_t>(records_per_sec)));
+  json.push_back('}');
+
   char* out = HeapCopyString(json);
   if (out == nullptr) {
-    SetLastErrorText("Failed to allocate drives result buffer.");
+    SetLastErrorText("Failed to allocate index diagnostics buffer.");
   }
   return out;
 }
 
-extern "C" __declspec(dllexport) char* omni_search_files_json(
-    const char* query_utf8, const char* extension_utf8, uint64_t min_size,
-    uint64_t max_size, int64_t min_created_unix, int64_t max_created_unix,
-    uint32_t requested_limit) {
-  const uint64_t request_token =
-      g_search_request_token.fetch_add(1, std::memory_order_acq_rel) + 1;
-  const uint32_t limit =
-      (requested_limit == 0) ? 200 : std::min<uint32_t>(requested_limit, 5000);
-  const ParsedSearchQuery parsed_query =
-      ParseSearchQuery(Utf8ToWide(query_utf8 == nullptr ? "" : query_utf8));
-  const std::wstring& query = parsed_query.path_query_lower;
-  const std::wstring extension_filter = NormalizeExtensionFilter(extension_utf8);
-  std::unordered_set<std::wstring> extension_set;
-  if (!extension_filter.empty()) {
-    size_t start = 0;
-    while (start <= extension_filter.size()) {
-      const size_t delimiter = extension_filter.find(L',', start);
-      const size_t end =
-          delimiter == std::wstring::npos ? extension_filter.size() : delimiter;
-      std::wstring normalized = NormalizeExtensionValue(extension_filter.substr(start, end - start));
-      if (!normalized.empty()) {
-        extension_set.insert(std::move(normalized));
+extern "C" __declspec(dllexport) char* omni_index_counts_json() {
+  std::vector<DriveIndexState> states;
+  {
+    std::lock_guard<std::mutex> lock(g_drive_state_mutex);
+    states = g_drive_index_states;
+  }
+
+  // Ready drives report their live count, so USN updates since the scan are included.
+  if (g_is_ready.load(std::memory_order_acquire)) {
+    uint64_t counts_by_letter[26] = {};
+    {
+      std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+      for (const IndexedFile& file : g_indexed_files) {
+        if (file.path.empty()) {
+          continue;
+        }
+        const wchar_t letter = static_cast<wchar_t>(std::towupper(file.path[0]));
+        if (letter >= L'A' && letter <= L'Z') {
+          ++counts_by_letter[letter - L'A'];
+        }
       }
-      if (delimiter == std::wstring::npos) {
-        break;
+    }
+    for (DriveIndexState& state : states) {
+      if (state.ready && !state.drive.empty() && state.drive[0] >= L'A' &&
+          state.drive[0] <= L'Z') {
+        state.indexed_count = counts_by_letter[state.drive[0] - L'A'];
       }
-      start = delimiter + 1;
     }
   }
-  const bool has_extension_filter = !extension_set.empty();
-  const bool extension_targets_directories =
-      extension_filter == L"folder" || extension_filter == L"folders" ||
-      extension_filter == L"dir" || extension_filter == L"directory";
-  const bool has_size_filter =
-      min_size > 0 || max_size < std::numeric_limits<uint64_t>::max();
-  const bool has_date_filter =
-      min_created_unix > std::numeric_limits<int64_t>::min() ||
-      max_created_unix < std::numeric_limits<int64_t>::max();
-  const bool requires_metadata = has_size_filter || has_date_filter;
-  const bool distribute_across_drives =
-      g_scan_all_drives_mode.load(std::memory_order_acquire) && limit > 1 &&
-      query.empty() && !parsed_query.has_content_filter &&
-      (has_extension_filter || has_size_filter || has_date_filter);
 
+  std::string json;
+  json.reserve(states.size() * 96 + 2);
+  json.push_back('[');
+  for (size_t i = 0; i < states.size(); ++i) {
+    if (i > 0) {
+      json.push_back(',');
+    }
+    json.append("{\"drive\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(states[i].drive));
+    json.append("\",\"indexedCount\":");
+    json.append(std::to_string(states[i].indexed_count));
+    json.append(",\"ready\":");
+    json.append(states[i].ready ? "true" : "false");
+    json.append(",\"error\":");
+    if (states[i].error.empty()) {
+      json.append("null");
+    } else {
+      json.push_back('"');
+      AppendEscapedJsonString(&json, states[i].error);
+      json.push_back('"');
+    }
+    json.push_back('}');
+  }
+  json.push_back(']');
 
-  std::vector<SearchRow> rows;
-  rows.reserve(limit);
-  std::unordered_map<wchar_t, std::vector<SearchRow>> drive_buckets;
-  std::vector<wchar_t> drive_order;
-
-  if (distribute_across_drives) {
-    drive_buckets.reserve(16);
-    drive_order.reserve(16);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate index counts buffer.");
   }
+  return out;
+}
 
+// File and folder counts and summed file sizes of the index per drive letter.
+extern "C" __declspec(dllexport) char* omni_index_totals_json() {
+  uint64_t files_by_letter[26] = {};
+  uint64_t directories_by_letter[26] = {};
+  uint64_t bytes_by_letter[26] = {};
   {
     std::shared_lock<std::shared_mutex> lock(g_index_mutex);
     for (const IndexedFile& file : g_indexed_files) {
-      if (IsSearchCancelled(request_token)) {
-        return HeapCopyString("[]");
-      }
-      if (!ContainsCaseInsensitive(file.path, query)) {
-        continue;
-      }
-      if (!MatchesQueryExtensionFilters(file, parsed_query.extension_filters)) {
+      const wchar_t letter = DriveBucketKeyFromPath(file.path);
+      if (letter < L'A' || letter > L'Z') {
         continue;
       }
-      if (has_extension_filter) {
-        if (extension_targets_directories) {
-          if (!file.is_directory) {
-            continue;
-          }
-        } else if (file.is_directory ||
-                   extension_set.find(IndexedFileExtensionLower(file)) == extension_set.end()) {
-          continue;
-        }
+      if (file.is_directory) {
+        ++directories_by_letter[letter - L'A'];
+      } else {
+        ++files_by_letter[letter - L'A'];
+        bytes_by_letter[letter - L'A'] += file.size;
       }
+    }
+  }
 
-      uint64_t size = 0;
-      int64_t created = 0;
-      int64_t modified = 0;
-      bool metadata_loaded = false;
+  uint64_t total_files = 0;
+  uint64_t total_directories = 0;
+  uint64_t total_bytes = 0;
+  std::string drives_json;
+  drives_json.push_back('[');
+  for (int letter = 0; letter < 26; ++letter) {
+    if (files_by_letter[letter] == 0 && directories_by_letter[letter] == 0) {
+      continue;
+    }
+    total_files += files_by_letter[letter];
+    total_directories += directories_by_letter[letter];
+    total_bytes += bytes_by_letter[letter];
 
-      if (requires_metadata) {
-        metadata_loaded = ReadFileMetadata(file.path, &size, &created, &modified);
-        if (!metadata_loaded && IsPathMissingError(GetLastError())) {
-          // Skip stale entries for files that were deleted or moved.
-          continue;
-        }
-        if (!metadata_loaded) {
-          continue;
-        }
-        if (size < min_size || size > max_size) {
-          continue;
-        }
-        if (created < min_created_unix || created > max_created_unix) {
-          continue;
-        }
-      }
+    if (drives_json.size() > 1) {
+      drives_json.push_back(',');
+    }
+    drives_json.append("{\"drive\":\"");
+    drives_json.push_back(static_cast<char>('A' + letter));
+    drives_json.append(":\",\"fileCount\":");
+    drives_json.append(std::to_string(files_by_letter[letter]));
+    drives_json.append(",\"directoryCount\":");
+    drives_json.append(std::to_string(directories_by_letter[letter]));
+    drives_json.append(",\"totalBytes\":");
+    drives_json.append(std::to_string(bytes_by_letter[letter]));
+    drives_json.push_back('}');
+  }
+  drives_json.push_back(']');
 
-      if (parsed_query.has_content_filter) {
-        if (file.is_directory) {
-          continue;
-        }
-        if (!SearchFileContent(file.path, parsed_query.content_query_lower,
-                               parsed_query.content_mode, request_token)) {
-          if (IsSearchCancelled(request_token)) {
-            return HeapCopyString("[]");
-          }
-          continue;
-        }
-      }
+  std::string json;
+  json.reserve(drives_json.size() + 96);
+  json.append("{\"fileCount\":");
+  json.append(std::to_string(total_files));
+  json.append(",\"directoryCount\":");
+  json.append(std::to_string(total_directories));
+  json.append(",\"totalBytes\":");
+  json.append(std::to_string(total_bytes));
+  json.append(",\"drives\":");
+  json.append(drives_json);
+  json.push_back('}');
 
-      if (!metadata_loaded) {
-        metadata_loaded = ReadFileMetadata(file.path, &size, &created, &modified);
-        if (!metadata_loaded && IsPathMissingError(GetLastError())) {
-          continue;
-        }
-      }
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate index totals buffer.");
+  }
+  return out;
+}
 
-      if (!metadata_loaded) {
-        size = 0;
-        created = 0;
-        modified = 0;
-      }
+// Distinct lowercase extensions of the indexed files under `within_path` (the whole index
+// when empty), most frequent first.
+extern "C" __declspec(dllexport) char* omni_present_extensions_json(const char* within_path_utf8) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
 
-      SearchRow row{
-          IndexedFileName(file),
-          file.path,
-          IndexedFileExtensionLower(file),
-          size,
-          created,
-          modified,
-          file.is_directory,
-      };
-if (distribute_across_drives) {
-  
-        const wchar_t bucket_key = DriveBucketKeyFromPath(file.path);
-        auto bucket_it = drive_buckets.find(bucket_key);
-        if (bucket_it == drive_buckets.end()) {
-          drive_order.push_back(bucket_key);
-          bucket_it = drive_buckets.emplace(bucket_key, std::vector<SearchRow>{}).first;
-          bucket_it->second.reserve(128);
-        }
-        bucket_it->second.push_back(std::move(row));
-      } else {
-        rows.push_back(std::move(row));
-        if (rows.size() >= limit) {
-          break;
-        }
+  const std::wstring scope = NormalizeScopePath(
+      Utf8ToWide(within_path_utf8 == nullptr ? "" : within_path_utf8));
+  const PathScope within_scope = BuildPathScope(scope);
+  std::unordered_map<std::wstring, uint64_t> counts;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (file.is_directory) {
+        continue;
+      }
+      if (!scope.empty() && !IsPathWithinPathScope(file.path, within_scope)) {
+        continue;
+      }
+      std::wstring extension = IndexedFileExtensionLower(file);
+      if (!extension.empty()) {
+        ++counts[std::move(extension)];
       }
     }
   }
-  if (distribute_across_drives) {
 
-    rows.clear();
-    rows.reserve(limit);
-    std::vector<size_t> bucket_offsets(drive_order.size(), 0);
-    bool appended = true;
-    while (rows.size() < limit && appended) {
-      if (IsSearchCancelled(request_token)) {
-        return HeapCopyString("[]");
-      }
-      appended = false;
-      for (size_t i = 0; i < drive_order.size(); ++i) {
-        std::vector<SearchRow>& bucket = drive_buckets[drive_order[i]];
-        size_t& offset = bucket_offsets[i];
-        if (offset >= bucket.size()) {
-          continue;
-        }
-        rows.push_back(std::move(bucket[offset]));
-        ++offset;
-        appended = true;
-        if (rows.size() >= limit) {
-          break;
-        }
-      }
+  std::vector<std::pair<std::wstring, uint64_t>> ranked(counts.begin(), counts.end());
+  std::sort(ranked.begin(), ranked.end(), [](const auto& left, const auto& right) {
+    if (left.second != right.second) {
+      return left.second > right.second;
+    }
+    return left.first < right.first;
+  });
+
+  std::string json;
+  json.reserve(ranked.size() * 8 + 2);
+  json.push_back('[');
+  for (size_t i = 0; i < ranked.size(); ++i) {
+    if (i > 0) {
+      json.push_back(',');
     }
+    json.push_back('"');
+    AppendEscapedJsonString(&json, WideToUtf8(ranked[i].first));
+    json.push_back('"');
   }
+  json.push_back(']');
 
-  const std::string json = SearchRowsToJson(rows);
   char* out = HeapCopyString(json);
   if (out == nullptr) {
-    SetLastErrorText("Failed to allocate result buffer.");
+    SetLastErrorText("Failed to allocate extensions buffer.");
   }
   return out;
 }
 
-extern "C" __declspec(dllexport) bool omni_cancel_search() {
-  g_search_request_token.fetch_add(1, std::memory_order_acq_rel);
-  return true;
-}
-
-extern "C" __declspec(dllexport) char* omni_find_duplicates_json(
-    uint64_t min_size, uint32_t requested_max_groups,
-    uint32_t requested_max_files_per_group) {
+// Indexed folders whose path starts with `prefix_utf8` (case-insensitive, matching partial
+// names), for completing a typed path. Folders come from directory entries and from the
+// parents of indexed files, so they are found even when directories are not indexed.
+// Shallower folders come first, then busier ones (more indexed children), then shorter
+// paths, and at most `max_rows` are returned.
+extern "C" __declspec(dllexport) char* omni_path_autocomplete_json(const char* prefix_utf8,
+                                                                   const uint32_t max_rows) {
   if (!g_is_ready.load(std::memory_order_acquire)) {
     SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
     return nullptr;
   }
 
-  const bool already_running =
-      g_duplicate_scan_running.exchange(true, std::memory_order_acq_rel);
-  if (already_running) {
-    SetLastErrorText("Duplicate scan is already running.");
-    return nullptr;
+  std::wstring prefix = Utf8ToWide(prefix_utf8 == nullptr ? "" : prefix_utf8);
+  for (wchar_t& ch : prefix) {
+    if (ch == L'/') {
+      ch = L'\\';
+    }
+  }
+  if (prefix.empty()) {
+    SetLastErrorText("Path completion failed: empty prefix.");
+    return nullptr;
+  }
+  const auto starts_with_prefix = [&](const std::wstring& path) {
+    return path.size() >= prefix.size() &&
+           CompareStringOrdinal(path.c_str(), static_cast<int>(prefix.size()),
+                                prefix.c_str(), static_cast<int>(prefix.size()),
+                                TRUE) == CSTR_EQUAL;
+  };
+
+  std::unordered_map<std::wstring, uint64_t> child_counts;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (!starts_with_prefix(file.path)) {
+        continue;
+      }
+      if (file.is_directory) {
+        child_counts.try_emplace(file.path, 0);
+      }
+      std::wstring parent = ParentPathOf(file.path);
+      if (starts_with_prefix(parent)) {
+        ++child_counts[std::move(parent)];
+      }
+    }
+  }
+
+  struct Completion {
+    std::wstring path;
+    size_t depth;
+    uint64_t children;
+  };
+  std::vector<Completion> ranked;
+  ranked.reserve(child_counts.size());
+  for (auto& [path, children] : child_counts) {
+    const size_t depth = CountPathSegments(path);
+    ranked.push_back(Completion{path, depth, children});
+  }
+  std::sort(ranked.begin(), ranked.end(), [](const Completion& left, const Completion& right) {
+    if (left.depth != right.depth) {
+      return left.depth < right.depth;
+    }
+    if (left.children != right.children) {
+      return left.children > right.children;
+    }
+    if (left.path.size() != right.path.size()) {
+      return left.path.size() < right.path.size();
+    }
+    return left.path < right.path;
+  });
+  if (ranked.size() > max_rows) {
+    ranked.resize(max_rows);
+  }
+
+  std::string json;
+  json.reserve(ranked.size() * 48 + 2);
+  json.push_back('[');
+  for (size_t i = 0; i < ranked.size(); ++i) {
+    if (i > 0) {
+      json.push_back(',');
+    }
+    json.push_back('"');
+    AppendEscapedJsonString(&json, WideToUtf8(ranked[i].path));
+    json.push_back('"');
+  }
+  json.push_back(']');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate path completion buffer.");
+  }
+  return out;
+}
+
+// Reports how the index covers `path`: the drive or network root it falls under, whether
+// that root is indexed, when it was last scanned, and whether live USN updates are running.
+extern "C" __declspec(dllexport) char* omni_path_index_status_json(const char* path_utf8) {
+  const std::wstring path =
+      NormalizeScopePath(Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8));
+  if (path.empty()) {
+    SetLastErrorText("Index status failed: empty path.");
+    return nullptr;
+  }
+
+  std::wstring drive;
+  if (path.size() >= 2 && path[1] == L':') {
+    drive.assign(1, static_cast<wchar_t>(std::towupper(path[0])));
+  }
+  DriveIndexState matched{};
+  bool found = false;
+  {
+    std::lock_guard<std::mutex> lock(g_drive_state_mutex);
+    for (const DriveIndexState& state : g_drive_index_states) {
+      const bool is_network_root = state.drive.rfind(L"\\\\", 0) == 0;
+      if (is_network_root ? IsPathWithinScope(path, state.drive) ||
+                                ToLower(path) == ToLower(state.drive)
+                          : !drive.empty() && state.drive == drive) {
+        matched = state;
+        found = true;
+        break;
+      }
+    }
+  }
+  if (found) {
+    drive = matched.drive;
+  }
+
+  std::string json;
+  json.reserve(256);
+  json.append("{\"path\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(path));
+  json.append("\",\"drive\":");
+  if (drive.empty()) {
+    json.append("null");
+  } else {
+    json.push_back('"');
+    AppendEscapedJsonString(&json, WideToUtf8(drive));
+    json.push_back('"');
+  }
+  json.append(",\"indexed\":");
+  json.append(found && matched.ready && g_is_ready.load(std::memory_order_acquire) ? "true"
+                                                                                    : "false");
+  json.append(",\"indexing\":");
+  json.append(g_is_indexing.load(std::memory_order_acquire) ? "true" : "false");
+  json.append(",\"indexedCount\":");
+  json.append(std::to_string(found ? matched.indexed_count : 0));
+  json.append(",\"indexedUnix\":");
+  json.append(found && matched.indexed_unix > 0 ? std::to_string(matched.indexed_unix)
+                                                : "null");
+  json.append(",\"liveUpdates\":");
+  json.append(found && matched.live_updates ? "true" : "false");
+  json.append(",\"error\":");
+  if (!found || matched.error.empty()) {
+    json.append("null");
+  } else {
+    json.push_back('"');
+    AppendEscapedJsonString(&json, matched.error);
+    json.push_back('"');
+  }
+  json.push_back('}');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate index status buffer.");
+  }
+  return out;
+}
+
+namespace {
+
+// Bumped whenever the exported API changes shape, so callers can tell scanner builds apart.
+constexpr uint32_t kScannerApiVersion = 1;
+constexpr const char* kScannerCapabilities[] = {
+    "usn_journal", "fallback_walk", "content_search", "content_hash", "name_prefix_index",
+    "fuzzy_match", "network_roots", "mount_points", "folder_watch", "lock_probe", "clipboard",
+    "index_snapshot", "path_lookup", "video_frames", "media_metadata", "short_names",
+};
+
+}  // namespace
+
+extern "C" __declspec(dllexport) char* omni_scanner_info_json() {
+  std::string json = "{\"version\":";
+  json.append(std::to_string(kScannerApiVersion));
+  json.append(",\"capabilities\":[");
+  for (size_t i = 0; i < std::size(kScannerCapabilities); ++i) {
+    if (i > 0) {
+      json.push_back(',');
+    }
+    json.push_back('"');
+    json.append(kScannerCapabilities[i]);
+    json.push_back('"');
+  }
+  json.append("]}");
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate scanner info buffer.");
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) const char* omni_last_error() {
+  thread_local std::string error_cache;
+  error_cache = ReadLastErrorText();
+  return error_cache.c_str();
+}
+
+// Replaces the registered network roots (newline-separated `\\server\share` paths). They are
+// walked on the next indexing run.
+extern "C" __declspec(dllexport) bool omni_set_network_roots(const char* roots_utf8) {
+  const std::wstring raw = Utf8ToWide(roots_utf8 == nullptr ? "" : roots_utf8);
+  std::vector<std::wstring> roots;
+  size_t start = 0;
+  while (start <= raw.size()) {
+    const size_t delimiter = raw.find(L'\n', start);
+    const size_t end = delimiter == std::wstring::npos ? raw.size() : delimiter;
+    std::wstring root = NormalizeScopePath(TrimWhitespace(raw.substr(start, end - start)));
+    if (root.rfind(L"\\\\", 0) == 0) {
+      roots.push_back(std::move(root));
+    }
+    if (delimiter == std::wstring::npos) {
+      break;
+    }
+    start = delimiter + 1;
+  }
+
+  std::lock_guard<std::mutex> lock(g_network_roots_mutex);
+  g_network_roots = std::move(roots);
+  return true;
+}
+
+extern "C" __declspec(dllexport) char* omni_list_drives_json() {
+  std::vector<DriveInfo> rows = list_drives_internal();
+  for (const std::wstring& root : RegisteredNetworkRoots()) {
+    wchar_t filesystem_buffer[MAX_PATH] = L"";
+    DWORD serial_number = 0;
+    const std::wstring volume_root = root + L"\\";
+    const BOOL has_fs = GetVolumeInformationW(volume_root.c_str(), nullptr, 0, &serial_number,
+                                              nullptr, nullptr, filesystem_buffer, MAX_PATH);
+    rows.push_back(DriveInfo{L"", root, has_fs ? filesystem_buffer : L"", L"network", false,
+                             false, L"", has_fs ? static_cast<uint32_t>(serial_number) : 0,
+                             root, L"walk"});
+  }
+  const std::string json = DriveRowsToJson(rows);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate drives result buffer.");
+  }
+  return out;
+}
+
+namespace {
+
+constexpr BYTE kSmartReallocatedSectors = 5;
+constexpr BYTE kSmartAirflowTemperature = 190;
+constexpr BYTE kSmartTemperature = 194;
+constexpr BYTE kSmartPendingSectors = 197;
+// A SMART attribute table holds up to 30 entries of 12 bytes after a 2-byte revision.
+constexpr size_t kSmartAttributeCount = 30;
+constexpr size_t kSmartAttributeSize = 12;
+
+struct DriveHealthReport {
+  bool smart_supported = false;
+  bool threshold_exceeded = false;
+  int64_t temperature_celsius = -1;
+  int64_t reallocated_sectors = -1;
+  int64_t pending_sectors = -1;
+};
+
+// Issues one SMART command (`feature`) to the disk and returns its output buffer, whose
+// `bBuffer` holds the returned sector or registers.
+bool SendSmartCommand(HANDLE disk, const DWORD ioctl, const BYTE feature,
+                      std::vector<BYTE>* out) {
+  SENDCMDINPARAMS in{};
+  in.cBufferSize = ioctl == SMART_RCV_DRIVE_DATA ? READ_ATTRIBUTE_BUFFER_SIZE : 0;
+  in.irDriveRegs.bFeaturesReg = feature;
+  in.irDriveRegs.bSectorCountReg = 1;
+  in.irDriveRegs.bSectorNumberReg = 1;
+  in.irDriveRegs.bCylLowReg = SMART_CYL_LOW;
+  in.irDriveRegs.bCylHighReg = SMART_CYL_HI;
+  in.irDriveRegs.bDriveHeadReg = DRIVE_HEAD_REG;
+  in.irDriveRegs.bCommandReg = SMART_CMD;
+  out->assign(sizeof(SENDCMDOUTPARAMS) + READ_ATTRIBUTE_BUFFER_SIZE, 0);
+  DWORD returned = 0;
+  return DeviceIoControl(disk, ioctl, &in, sizeof(SENDCMDINPARAMS) - 1, out->data(),
+                         static_cast<DWORD>(out->size()), &returned, nullptr) != FALSE;
+}
+
+// Reads the SMART status and attributes of the physical disk behind `drive_letter`. Drives
+// behind USB bridges and NVMe drives usually refuse the ATA pass-through, which leaves
+// `smart_supported` false.
+DriveHealthReport ReadDriveHealth(const std::wstring& drive_letter) {
+  DriveHealthReport report;
+  const std::wstring volume_path = L"\\\\.\\" + drive_letter + L":";
+  HANDLE volume = CreateFileW(volume_path.c_str(), 0, FILE_SHARE_READ | FILE_SHARE_WRITE,
+                              nullptr, OPEN_EXISTING, 0, nullptr);
+  if (volume == INVALID_HANDLE_VALUE) {
+    return report;
+  }
+  STORAGE_DEVICE_NUMBER device_number{};
+  DWORD returned = 0;
+  const BOOL has_number =
+      DeviceIoControl(volume, IOCTL_STORAGE_GET_DEVICE_NUMBER, nullptr, 0, &device_number,
+                      sizeof(device_number), &returned, nullptr);
+  CloseHandle(volume);
+  if (!has_number) {
+    return report;
+  }
+
+  const std::wstring disk_path =
+      L"\\\\.\\PhysicalDrive" + std::to_wstring(device_number.DeviceNumber);
+  HANDLE disk = CreateFileW(disk_path.c_str(), GENERIC_READ | GENERIC_WRITE,
+                            FILE_SHARE_READ | FILE_SHARE_WRITE, nullptr, OPEN_EXISTING, 0,
+                            nullptr);
+  if (disk == INVALID_HANDLE_VALUE) {
+    return report;
+  }
+
+  GETVERSIONINPARAMS version{};
+  std::vector<BYTE> status;
+  if (DeviceIoControl(disk, SMART_GET_VERSION, nullptr, 0, &version, sizeof(version),
+                      &returned, nullptr) &&
+      (version.fCapabilities & CAP_SMART_CMD) != 0 &&
+      SendSmartCommand(disk, SMART_SEND_DRIVE_COMMAND, RETURN_SMART_STATUS, &status)) {
+    report.smart_supported = true;
+    // The drive answers 0x2CF4 in the cylinder registers once a threshold is exceeded.
+    const auto* registers = reinterpret_cast<const IDEREGS*>(
+        reinterpret_cast<const SENDCMDOUTPARAMS*>(status.data())->bBuffer);
+    report.threshold_exceeded =
+        registers->bCylLowReg == 0xF4 && registers->bCylHighReg == 0x2C;
+
+    std::vector<BYTE> attributes;
+    std::vector<BYTE> thresholds;
+    if (SendSmartCommand(disk, SMART_RCV_DRIVE_DATA, READ_ATTRIBUTES, &attributes)) {
+      const bool has_thresholds =
+          SendSmartCommand(disk, SMART_RCV_DRIVE_DATA, READ_THRESHOLDS, &thresholds);
+      const BYTE* table = reinterpret_cast<const SENDCMDOUTPARAMS*>(attributes.data())->bBuffer;
+      const BYTE* limits =
+          has_thresholds ? reinterpret_cast<const SENDCMDOUTPARAMS*>(thresholds.data())->bBuffer
+                         : nullptr;
+      for (size_t i = 0; i < kSmartAttributeCount; ++i) {
+        const BYTE* entry = table + 2 + i * kSmartAttributeSize;
+        const BYTE id = entry[0];
+        if (id == 0) {
+          continue;
+        }
+        const BYTE current = entry[3];
+        uint64_t raw = 0;
+        for (int byte = 5; byte >= 0; --byte) {
+          raw = (raw << 8) | entry[5 + byte];
+        }
+        if (limits != nullptr) {
+          for (size_t j = 0; j < kSmartAttributeCount; ++j) {
+            const BYTE* limit = limits + 2 + j * kSmartAttributeSize;
+            if (limit[0] == id && limit[1] != 0 && current <= limit[1]) {
+              report.threshold_exceeded = true;
+            }
+          }
+        }
+        if (id == kSmartReallocatedSectors) {
+          report.reallocated_sectors = static_cast<int64_t>(raw);
+        } else if (id == kSmartPendingSectors) {
+          report.pending_sectors = static_cast<int64_t>(raw);
+        } else if (id == kSmartTemperature ||
+                   (id == kSmartAirflowTemperature && report.temperature_celsius < 0)) {
+          // The lowest raw byte is the current temperature; the others hold min/max values.
+          report.temperature_celsius = static_cast<int64_t>(raw & 0xFF);
+        }
+      }
+    }
+  }
+  CloseHandle(disk);
+  return report;
+}
+
+void AppendOptionalCount(std::string* json, const int64_t value) {
+  json->append(value < 0 ? "null" : std::to_string(value));
+}
+
+}  // namespace
+
+// SMART health of the disk holding `drive_utf8`: "failing" once the drive reports an
+// exceeded threshold, "warning" with reallocated or pending sectors, "healthy" otherwise,
+// and "unknown" when the disk does not expose SMART.
+extern "C" __declspec(dllexport) char* omni_drive_health_json(const char* drive_utf8) {
+  const std::wstring drive_letter = NormalizeDriveLetter(drive_utf8);
+  const DriveHealthReport report = ReadDriveHealth(drive_letter);
+  const char* status = "unknown";
+  if (report.smart_supported) {
+    if (report.threshold_exceeded) {
+      status = "failing";
+    } else if (report.reallocated_sectors > 0 || report.pending_sectors > 0) {
+      status = "warning";
+    } else {
+      status = "healthy";
+    }
+  }
+
+  std::string json;
+  json.reserve(192);
+  json.append("{\"drive\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(drive_letter));
+  json.append(":\",\"smartSupported\":");
+  json.append(report.smart_supported ? "true" : "false");
+  json.append(",\"status\":\"");
+  json.append(status);
+  json.append("\",\"temperatureCelsius\":");
+  AppendOptionalCount(&json, report.temperature_celsius);
+  json.append(",\"reallocatedSectors\":");
+  AppendOptionalCount(&json, report.reallocated_sectors);
+  json.append(",\"pendingSectors\":");
+  AppendOptionalCount(&json, report.pending_sectors);
+  json.push_back('}');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate drive health buffer.");
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) char* omni_search_files_with_options_json(
+    const OmniSearchOptions* options) {
+  if (options == nullptr) {
+    SetLastErrorText("Search failed: missing options.");
+    return nullptr;
+  }
+  uint32_t resume_at = std::numeric_limits<uint32_t>::max();
+  if (options->out_resume_at != nullptr) {
+    *options->out_resume_at = resume_at;
+  }
+
+  constexpr uint32_t kDefaultLimitCeiling = 5000;
+  constexpr uint32_t kMaxLimitCeiling = 50000;
+  // A zero token is never cancelled.
+  const uint64_t request_token =
+      options->background ? 0
+                          : g_search_request_token.fetch_add(1, std::memory_order_acq_rel) + 1;
+  if (!options->background) {
+    std::lock_guard<std::mutex> lock(g_search_id_mutex);
+    const auto cancelled = std::find(g_cancelled_search_ids.begin(),
+                                     g_cancelled_search_ids.end(), options->search_id);
+    if (options->search_id != 0 && cancelled != g_cancelled_search_ids.end()) {
+      g_cancelled_search_ids.erase(cancelled);
+      return HeapCopyString("[]");
+    }
+    g_active_search_id = options->search_id;
+  }
+  const uint32_t limit_ceiling =
+      options->max_limit == 0
+          ? kDefaultLimitCeiling
+          : std::min<uint32_t>(options->max_limit, kMaxLimitCeiling);
+  const uint32_t limit = (options->limit == 0)
+                             ? 200
+                             : std::min<uint32_t>(options->limit, limit_ceiling);
+  const char* query_utf8 = options->query_utf8;
+  const char* extension_utf8 = options->extension_utf8;
+  const uint64_t min_size = options->min_size;
+  const uint64_t max_size = options->max_size;
+  const int64_t min_created_unix = options->min_created_unix;
+  const int64_t max_created_unix = options->max_created_unix;
+  const int64_t min_modified_unix = options->min_modified_unix;
+  const int64_t max_modified_unix = options->max_modified_unix;
+  const std::wstring within_path = NormalizeScopePath(
+      Utf8ToWide(options->within_path_utf8 == nullptr ? "" : options->within_path_utf8));
+  const PathScope within_scope = BuildPathScope(within_path);
+  const uint32_t max_depth = options->max_depth;
+  const size_t within_segments = CountPathSegments(within_path);
+  const ParsedSearchQuery parsed_query =
+      ParseSearchQuery(Utf8ToWide(query_utf8 == nullptr ? "" : query_utf8));
+  const std::wstring& query = parsed_query.path_query_lower;
+  // The parsed query has its whitespace collapsed, so single spaces separate the tokens.
+  // A one-token query matches the same way in either mode.
+  std::vector<std::wstring> query_tokens;
+  if (options->match_mode == static_cast<uint32_t>(QueryMatchMode::Tokens)) {
+    size_t start = 0;
+    while (start < query.size()) {
+      const size_t delimiter = query.find(L' ', start);
+      const size_t end = delimiter == std::wstring::npos ? query.size() : delimiter;
+      if (end > start) {
+        query_tokens.push_back(query.substr(start, end - start));
+      }
+      if (delimiter == std::wstring::npos) {
+        break;
+      }
+      start = delimiter + 1;
+    }
+  }
+  const MatchPosition match_position = static_cast<MatchPosition>(options->match_position);
+  const bool match_anchored = !query.empty() && (match_position == MatchPosition::Prefix ||
+                                                 match_position == MatchPosition::Suffix);
+  const bool match_tokens = query_tokens.size() > 1 && !match_anchored;
+  const auto matches_query = [&](const std::wstring& text) {
+    return match_tokens ? ContainsAllTokens(text, query_tokens)
+                        : ContainsCaseInsensitive(text, query);
+  };
+  const std::wstring extension_filter = NormalizeExtensionFilter(extension_utf8);
+  std::unordered_set<std::wstring> extension_set;
+  if (!extension_filter.empty()) {
+    size_t start = 0;
+    while (start <= extension_filter.size()) {
+      const size_t delimiter = extension_filter.find(L',', start);
+      const size_t end =
+          delimiter == std::wstring::npos ? extension_filter.size() : delimiter;
+      std::wstring normalized = NormalizeExtensionValue(extension_filter.substr(start, end - start));
+      if (!normalized.empty()) {
+        extension_set.insert(std::move(normalized));
+      }
+      if (delimiter == std::wstring::npos) {
+        break;
+      }
+      start = delimiter + 1;
+    }
+  }
+  const bool has_extension_filter = !extension_set.empty();
+  const bool no_extension = options->no_extension;
+  const bool extension_targets_directories =
+      extension_filter == L"folder" || extension_filter == L"folders" ||
+      extension_filter == L"dir" || extension_filter == L"directory";
+  const bool has_size_filter =
+      min_size > 0 || max_size < std::numeric_limits<uint64_t>::max();
+  const bool has_date_filter =
+      min_created_unix > std::numeric_limits<int64_t>::min() ||
+      max_created_unix < std::numeric_limits<int64_t>::max() ||
+      min_modified_unix > std::numeric_limits<int64_t>::min() ||
+      max_modified_unix < std::numeric_limits<int64_t>::max();
+  const bool requires_metadata = has_size_filter || has_date_filter;
+  // Lightweight rows only carry the name, path and directory flag, so unless a filter needs
+  // the metadata, nothing else is read from disk or computed for them.
+  const bool skip_row_details = options->lightweight && !requires_metadata;
+  const uint32_t attributes_mask = options->attributes_mask;
+  const uint32_t attributes_value = options->attributes_value & attributes_mask;
+  const bool has_attribute_filter = attributes_mask != 0;
+  const bool match_name_only =
+      options->search_in == static_cast<uint32_t>(SearchScope::Name);
+  constexpr uint32_t kRelevanceCandidateCeiling = 20000;
+  const bool sort_by_relevance =
+      options->sort_order == static_cast<uint32_t>(SearchSortOrder::RelevanceRecency);
+  const size_t collect_limit =
+      sort_by_relevance ? std::max<size_t>(limit, kRelevanceCandidateCeiling) : limit;
+  const bool distribute_across_drives =
+      !sort_by_relevance && g_scan_all_drives_mode.load(std::memory_order_acquire) && limit > 1 &&
+      query.empty() && !parsed_query.has_content_filter &&
+      (has_extension_filter || no_extension || has_size_filter || has_date_filter ||
+       has_attribute_filter);
+  // Short queries matched against names are served from the name bigram index instead of a
+  // full scan; its candidates are still matched below, so the semantics do not change. Path
+  // queries also match folder names and drive prefixes, so they keep the scan, which stops
+  // once `limit` rows are found. Short names are looked up per entry, so queries aimed at
+  // them need the full scan (with its own pre-check); the bigram index only knows long names.
+  const bool match_short_names = options->match_short_names && !match_anchored &&
+                                 query.find(L'~') != std::wstring::npos;
+  const bool use_bigram_index = !query.empty() && query.size() <= kShortQueryMaxLength &&
+                                (match_name_only || match_anchored) &&
+                                !parsed_query.has_content_filter && !match_short_names;
+  // Fuzzy matching compares names only, and needs a query long enough that a typo still
+  // leaves most of it intact.
+  constexpr uint32_t kFuzzyMaxDistanceCeiling = 3;
+  const bool fuzzy_applicable = !match_anchored && query.size() > kShortQueryMaxLength &&
+                                query.find(L'\\') == std::wstring::npos;
+  const uint32_t fuzzy_max_distance =
+      fuzzy_applicable
+          ? std::min<uint32_t>({options->fuzzy_max_distance, kFuzzyMaxDistanceCeiling,
+                                static_cast<uint32_t>(query.size() - 1)})
+          : 0;
+  const double fuzzy_min_score = std::clamp(options->fuzzy_min_score, 0.0, 1.0);
+  // Owner lookups hit the security descriptor of each file, so they run last, on entries
+  // that passed every other filter.
+  const std::wstring owner_filter = ToLower(
+      Utf8ToWide(options->owner_utf8 == nullptr ? "" : options->owner_utf8));
+  std::unordered_map<std::wstring, std::wstring> owner_cache;
+  std::vector<std::wstring> path_set;
+  {
+    const std::wstring raw =
+        Utf8ToWide(options->path_set_utf8 == nullptr ? "" : options->path_set_utf8);
+    size_t start = 0;
+    while (start < raw.size()) {
+      const size_t delimiter = raw.find(L'\n', start);
+      const size_t end = delimiter == std::wstring::npos ? raw.size() : delimiter;
+      std::wstring path = NormalizeScopePath(TrimWhitespace(raw.substr(start, end - start)));
+      if (!path.empty()) {
+        path_set.push_back(std::move(path));
+      }
+      if (delimiter == std::wstring::npos) {
+        break;
+      }
+      start = delimiter + 1;
+    }
+  }
+  const bool use_path_set = !path_set.empty();
+
+  std::vector<SearchRow> rows;
+  rows.reserve(limit);
+  std::unordered_map<wchar_t, std::vector<SearchRow>> drive_buckets;
+  std::vector<wchar_t> drive_order;
+
+  if (distribute_across_drives) {
+    drive_buckets.reserve(16);
+    drive_order.reserve(16);
+  }
+
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    if (options->index_generation != nullptr) {
+      const uint64_t generation = g_index_generation.load(std::memory_order_acquire);
+      if (options->resume_from != 0 && *options->index_generation != generation) {
+        SetLastErrorText("Search cursor has expired; run the search again.");
+        return nullptr;
+      }
+      *options->index_generation = generation;
+    }
+    // A path set is usually tiny, so resolving it beats any scan; otherwise short queries
+    // narrow the scan through the name bigram index.
+    const bool use_candidates = use_path_set || use_bigram_index;
+    std::vector<uint32_t> candidates;
+    if (use_path_set) {
+      candidates.reserve(path_set.size());
+      for (const std::wstring& path : path_set) {
+        uint32_t position = 0;
+        if (FindIndexedPathLocked(path, &position)) {
+          candidates.push_back(position);
+        }
+      }
+      std::sort(candidates.begin(), candidates.end());
+      candidates.erase(std::unique(candidates.begin(), candidates.end()), candidates.end());
+    } else if (use_bigram_index) {
+      candidates = LookupNameBigramsLocked(query);
+    }
+    const size_t candidate_count = use_candidates ? candidates.size() : g_indexed_files.size();
+    const bool can_resume = !sort_by_relevance && !distribute_across_drives;
+    const size_t first_candidate = can_resume ? options->resume_from : 0;
+    for (size_t candidate = first_candidate; candidate < candidate_count; ++candidate) {
+      const IndexedFile& file =
+          g_indexed_files[use_candidates ? candidates[candidate] : candidate];
+      if (IsSearchCancelled(request_token)) {
+        return HeapCopyString("[]");
+      }
+      std::wstring short_name;
+      MatchRange anchored_range{};
+      bool query_matched = false;
+      if (match_anchored) {
+        query_matched =
+            MatchesNamePosition(IndexedFileName(file), query, match_position, &anchored_range);
+      } else {
+        query_matched = match_name_only ? matches_query(IndexedFileName(file))
+                                        : matches_query(file.path);
+      }
+      if (!query_matched && match_short_names &&
+          MayMatchShortName(IndexedFileName(file), query)) {
+        short_name = ReadShortName(file.path);
+        query_matched = !short_name.empty() && ContainsCaseInsensitive(short_name, query);
+      }
+      if (!query_matched) {
+        if (fuzzy_max_distance == 0) {
+          continue;
+        }
+        const std::wstring name = IndexedFileName(file);
+        if (name.size() + fuzzy_max_distance < query.size()) {
+          continue;
+        }
+        const uint32_t distance = ApproximateSubstringDistance(name, query);
+        const double score =
+            1.0 - static_cast<double>(distance) / static_cast<double>(query.size());
+        if (distance > fuzzy_max_distance || score < fuzzy_min_score) {
+          continue;
+        }
+      }
+      if (!within_path.empty() && !IsPathWithinPathScope(file.path, within_scope)) {
+        continue;
+      }
+      if (max_depth != 0) {
+        // Without a scope, depth counts from the drive root or the `\\server\share` root.
+        const size_t base_segments =
+            !within_path.empty() ? within_segments
+                                 : (file.path.rfind(L"\\\\", 0) == 0 ? 2 : 1);
+        if (CountPathSegments(file.path) > base_segments + max_depth) {
+          continue;
+        }
+      }
+      if (has_attribute_filter && (file.attributes & attributes_mask) != attributes_value) {
+        continue;
+      }
+      if (!MatchesQueryExtensionFilters(file, parsed_query.extension_filters)) {
+        continue;
+      }
+      // With an extension filter as well, extensionless files match in addition to it.
+      const bool matches_no_extension =
+          no_extension && !file.is_directory && IndexedFileExtensionLower(file).empty();
+      if (no_extension && !has_extension_filter && !matches_no_extension) {
+        continue;
+      }
+      if (has_extension_filter && !matches_no_extension) {
+        if (extension_targets_directories) {
+          if (!file.is_directory) {
+            continue;
+          }
+        } else if (!ExtensionSetMatchesFile(file, extension_set)) {
+          continue;
+        }
+      }
+
+      uint64_t size = 0;
+      int64_t created = 0;
+      int64_t modified = 0;
+      bool metadata_loaded = false;
+
+      if (requires_metadata) {
+        metadata_loaded = ReadFileMetadata(file.path, &size, &created, &modified);
+        if (!metadata_loaded && IsPathMissingError(GetLastError())) {
+          // Skip stale entries for files that were deleted or moved.
+          continue;
+        }
+        if (!metadata_loaded) {
+          continue;
+        }
+        if (size < min_size || size > max_size) {
+          continue;
+        }
+        if (created < min_created_unix || created > max_created_unix) {
+          continue;
+        }
+        if (modified < min_modified_unix || modified > max_modified_unix) {
+          continue;
+        }
+      }
+
+      if (parsed_query.has_content_filter) {
+        if (file.is_directory) {
+          continue;
+        }
+        if (!SearchFileContent(file.path, parsed_query.content_query_lower,
+                               parsed_query.content_mode, request_token)) {
+          if (IsSearchCancelled(request_token)) {
+            return HeapCopyString("[]");
+          }
+          continue;
+        }
+      }
+
+      if (!metadata_loaded && !skip_row_details) {
+        metadata_loaded = ReadFileMetadata(file.path, &size, &created, &modified);
+        if (!metadata_loaded && IsPathMissingError(GetLastError())) {
+          continue;
+        }
+      }
+
+      if (!metadata_loaded) {
+        size = 0;
+        created = 0;
+        modified = 0;
+      }
+
+      std::wstring owner;
+      if (!owner_filter.empty()) {
+        owner = ResolveFileOwner(file.path, &owner_cache);
+        if (!OwnerMatchesFilter(owner, owner_filter)) {
+          continue;
+        }
+      }
+
+      const bool is_reparse_point = IsReparsePoint(file);
+      SearchRow row{
+          IndexedFileName(file),
+          file.path,
+          IndexedFileExtensionLower(file),
+          size,
+          created,
+          modified,
+          file.is_directory,
+          is_reparse_point,
+          // Filled in once the returned rows are known; see below.
+          std::wstring(),
+      };
+      row.is_cloud_placeholder = IsCloudPlaceholder(file);
+      row.file_id = file.frn;
+      // Ranges are not serialized for lightweight rows.
+      if (!skip_row_details) {
+        if (match_anchored) {
+          row.name_ranges.push_back(anchored_range);
+        } else {
+          row.name_ranges = match_tokens ? FindTokenMatchRanges(row.name, query_tokens)
+                                         : FindMatchRanges(row.name, query);
+        }
+        if (match_name_only || match_anchored) {
+          // Only the name part of the path matched, so shift the name ranges onto it.
+          const uint32_t name_offset =
+              static_cast<uint32_t>(row.path.size() - std::min(row.path.size(), row.name.size()));
+          row.path_ranges.reserve(row.name_ranges.size());
+          for (const MatchRange& range : row.name_ranges) {
+            row.path_ranges.push_back(
+                MatchRange{range.start + name_offset, range.end + name_offset});
+          }
+        } else {
+          row.path_ranges = match_tokens ? FindTokenMatchRanges(row.path, query_tokens)
+                                         : FindMatchRanges(row.path, query);
+        }
+      }
+      row.owner = std::move(owner);
+      if (short_name.empty() && options->match_short_names && !skip_row_details) {
+        short_name = ReadShortName(file.path);
+      }
+      row.short_name = std::move(short_name);
+      if (sort_by_relevance) {
+        row.score = NameRelevance(row.name, query, query_tokens, query_matched);
+      }
+if (distribute_across_drives) {
+  
+        const wchar_t bucket_key = DriveBucketKeyFromPath(file.path);
+        auto bucket_it = drive_buckets.find(bucket_key);
+        if (bucket_it == drive_buckets.end()) {
+          drive_order.push_back(bucket_key);
+          bucket_it = drive_buckets.emplace(bucket_key, std::vector<SearchRow>{}).first;
+          bucket_it->second.reserve(128);
+        }
+        bucket_it->second.push_back(std::move(row));
+      } else {
+        rows.push_back(std::move(row));
+        if (rows.size() >= collect_limit) {
+          if (can_resume && candidate + 1 < candidate_count) {
+            resume_at = static_cast<uint32_t>(candidate + 1);
+          }
+          break;
+        }
+      }
+    }
+  }
+  if (sort_by_relevance) {
+    std::stable_sort(rows.begin(), rows.end(), [](const SearchRow& a, const SearchRow& b) {
+      if (a.score != b.score) {
+        return a.score > b.score;
+      }
+      return a.modified_unix > b.modified_unix;
+    });
+    if (rows.size() > limit) {
+      rows.resize(limit);
+    }
+  }
+  if (distribute_across_drives) {
+
+    rows.clear();
+    rows.reserve(limit);
+    std::vector<size_t> bucket_offsets(drive_order.size(), 0);
+    bool appended = true;
+    while (rows.size() < limit && appended) {
+      if (IsSearchCancelled(request_token)) {
+        return HeapCopyString("[]");
+      }
+      appended = false;
+      for (size_t i = 0; i < drive_order.size(); ++i) {
+        std::vector<SearchRow>& bucket = drive_buckets[drive_order[i]];
+        size_t& offset = bucket_offsets[i];
+        if (offset >= bucket.size()) {
+          continue;
+        }
+        rows.push_back(std::move(bucket[offset]));
+        ++offset;
+        appended = true;
+        if (rows.size() >= limit) {
+          break;
+        }
+      }
+    }
+  }
+
+  // Resolving a link target opens the link, so only the rows that are returned pay for it.
+  if (!skip_row_details) {
+    for (SearchRow& row : rows) {
+      if (row.is_reparse_point) {
+        row.target_path = ResolveReparseTarget(row.path);
+      }
+    }
+  }
+
+  const std::string json = SearchRowsToJson(rows, options->lightweight);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate result buffer.");
+  } else if (options->out_resume_at != nullptr) {
+    *options->out_resume_at = resume_at;
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) char* omni_search_files_json(
+    const char* query_utf8, const char* extension_utf8, uint64_t min_size,
+    uint64_t max_size, int64_t min_created_unix, int64_t max_created_unix,
+    uint32_t requested_limit) {
+  OmniSearchOptions options{};
+  options.query_utf8 = query_utf8;
+  options.extension_utf8 = extension_utf8;
+  options.min_size = min_size;
+  options.max_size = max_size;
+  options.min_created_unix = min_created_unix;
+  options.max_created_unix = max_created_unix;
+  options.limit = requested_limit;
+  options.max_limit = 5000;
+  options.min_modified_unix = std::numeric_limits<int64_t>::min();
+  options.max_modified_unix = std::numeric_limits<int64_t>::max();
+  options.fuzzy_max_distance = 0;
+  options.fuzzy_min_score = 0.0;
+  options.owner_utf8 = nullptr;
+  options.path_set_utf8 = nullptr;
+  options.match_short_names = false;
+  options.no_extension = false;
+  options.match_mode = static_cast<uint32_t>(QueryMatchMode::Phrase);
+  options.sort_order = static_cast<uint32_t>(SearchSortOrder::Index);
+  options.match_position = static_cast<uint32_t>(MatchPosition::Anywhere);
+  options.resume_from = 0;
+  options.out_resume_at = nullptr;
+  options.index_generation = nullptr;
+  options.max_depth = 0;
+  options.search_id = 0;
+  options.lightweight = false;
+  options.background = false;
+  return omni_search_files_with_options_json(&options);
+}
+
+extern "C" __declspec(dllexport) bool omni_cancel_search() {
+  g_search_request_token.fetch_add(1, std::memory_order_acq_rel);
+  return true;
+}
+
+// Stops the search started with `search_id`. Only the newest search holds a live request
+// token, so a search that is running but not newest has already been superseded; one that
+// has not reached the scanner yet returns no rows once it does. Returns false for zero.
+extern "C" __declspec(dllexport) bool omni_cancel_search_id(const uint64_t search_id) {
+  if (search_id == 0) {
+    return false;
+  }
+  std::lock_guard<std::mutex> lock(g_search_id_mutex);
+  if (g_active_search_id == search_id) {
+    g_search_request_token.fetch_add(1, std::memory_order_acq_rel);
+    g_active_search_id = 0;
+  } else {
+    // Each pending cancel is consumed by the search it names; the oldest is dropped once
+    // too many searches were cancelled without ever starting.
+    if (std::find(g_cancelled_search_ids.begin(), g_cancelled_search_ids.end(), search_id) ==
+        g_cancelled_search_ids.end()) {
+      if (g_cancelled_search_ids.size() >= kMaxPendingSearchCancels) {
+        g_cancelled_search_ids.erase(g_cancelled_search_ids.begin());
+      }
+      g_cancelled_search_ids.push_back(search_id);
+    }
+  }
+  return true;
+}
+
+extern "C" __declspec(dllexport) char* omni_find_duplicates_with_options_json(
+    const OmniDuplicateScanOptions* options) {
+  if (options == nullptr) {
+    SetLastErrorText("Duplicate scan failed: missing options.");
+    return nullptr;
+  }
+
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+
+  const bool already_running =
+      g_duplicate_scan_running.exchange(true, std::memory_order_acq_rel);
+  if (already_running) {
+    SetLastErrorText("Duplicate scan is already running.");
+    return nullptr;
+  }
+
+  g_duplicate_cancel_requested.store(false, std::memory_order_release);
+  ResetDuplicateProgress();
+  // Name grouping stats only files sharing a name, so it needs no size floor.
+  const bool name_mode =
+      options->group_by == static_cast<uint32_t>(DuplicateGroupMode::Name);
+  const uint64_t effective_min_size =
+      options->min_size == 0 && !name_mode ? 1ULL * 1024ULL * 1024ULL : options->min_size;
+  const uint32_t max_groups = std::clamp<uint32_t>(options->max_groups, 1, 1000);
+  const uint32_t max_files_per_group =
+      std::clamp<uint32_t>(options->max_files_per_group, 2, 400);
+  g_duplicate_io_concurrency.store(options->io_concurrency, std::memory_order_release);
+  g_duplicate_throttle_bytes_per_sec.store(options->throttle_bytes_per_sec,
+                                           std::memory_order_release);
+
+  const std::vector<DuplicateGroupRow> groups = find_duplicates_internal(
+      effective_min_size, max_groups, max_files_per_group,
+      options->skip_reparse_points,
+      static_cast<DuplicateGroupMode>(
+          std::min<uint32_t>(options->group_by,
+                             static_cast<uint32_t>(DuplicateGroupMode::Partial))));
+  const bool cancelled = IsDuplicateScanCancelRequested();
+  g_duplicate_io_concurrency.store(0, std::memory_order_release);
+  g_duplicate_throttle_bytes_per_sec.store(0, std::memory_order_release);
+  g_duplicate_scan_running.store(false, std::memory_order_release);
+
+  if (cancelled) {
+    g_duplicate_cancel_requested.store(false, std::memory_order_release);
+    SetLastErrorText("Duplicate scan cancelled.");
+    return nullptr;
+  }
+
+  const std::string json = DuplicateGroupsToJson(groups);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate duplicate results buffer.");
+  }
+  g_duplicate_cancel_requested.store(false, std::memory_order_release);
+  return out;
+}
+
+// Totals over every duplicate group in the index without returning the groups: with
+// `verify_content` files are grouped by full content hash, otherwise by size alone. Shares
+// the duplicate scan's progress, cancellation and single-run guard.
+extern "C" __declspec(dllexport) char* omni_duplicate_overview_json(
+    const uint64_t min_size, const bool verify_content, const bool skip_reparse_points) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+  if (g_duplicate_scan_running.exchange(true, std::memory_order_acq_rel)) {
+    SetLastErrorText("Duplicate scan is already running.");
+    return nullptr;
+  }
+
+  g_duplicate_cancel_requested.store(false, std::memory_order_release);
+  ResetDuplicateProgress();
+  // Two files per group are enough to keep; the counts come from `file_count`.
+  const std::vector<DuplicateGroupRow> groups = find_duplicates_internal(
+      min_size == 0 ? 1ULL * 1024ULL * 1024ULL : min_size,
+      std::numeric_limits<uint32_t>::max(), 2, skip_reparse_points,
+      verify_content ? DuplicateGroupMode::Content : DuplicateGroupMode::Size);
+  {
+    // Nobody streams an overview, so its groups are not left queued for the next scan.
+    std::lock_guard<std::mutex> lock(g_duplicate_pending_mutex);
+    g_duplicate_pending_groups.clear();
+  }
+  const bool cancelled = IsDuplicateScanCancelRequested();
+  g_duplicate_scan_running.store(false, std::memory_order_release);
+  g_duplicate_cancel_requested.store(false, std::memory_order_release);
+  if (cancelled) {
+    SetLastErrorText("Duplicate scan cancelled.");
+    return nullptr;
+  }
+
+  uint64_t file_count = 0;
+  uint64_t redundant_files = 0;
+  uint64_t total_bytes = 0;
+  uint64_t reclaimable_bytes = 0;
+  for (const DuplicateGroupRow& group : groups) {
+    file_count += group.file_count;
+    redundant_files += group.file_count - 1;
+    total_bytes += group.total_bytes;
+    reclaimable_bytes += group.size * (group.file_count - 1);
+  }
+
+  std::string json = "{\"groupCount\":";
+  json.append(std::to_string(groups.size()));
+  json.append(",\"fileCount\":");
+  json.append(std::to_string(file_count));
+  json.append(",\"redundantFiles\":");
+  json.append(std::to_string(redundant_files));
+  json.append(",\"totalBytes\":");
+  json.append(std::to_string(total_bytes));
+  json.append(",\"reclaimableBytes\":");
+  json.append(std::to_string(reclaimable_bytes));
+  json.push_back('}');
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate duplicate overview buffer.");
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) char* omni_find_duplicates_json(
+    uint64_t min_size, uint32_t requested_max_groups,
+    uint32_t requested_max_files_per_group) {
+  OmniDuplicateScanOptions options{};
+  options.min_size = min_size;
+  options.max_groups = requested_max_groups;
+  options.max_files_per_group = requested_max_files_per_group;
+  options.skip_reparse_points = false;
+  options.io_concurrency = 0;
+  options.throttle_bytes_per_sec = 0;
+  return omni_find_duplicates_with_options_json(&options);
+}
+
+extern "C" __declspec(dllexport) bool omni_cancel_duplicate_scan() {
+  if (!g_duplicate_scan_running.load(std::memory_order_acquire)) {
+    return false;
+  }
+  g_duplicate_cancel_requested.store(true, std::memory_order_release);
+  return true;
+}
+
+extern "C" __declspec(dllexport) char* omni_duplicate_scan_status_json() {
+  const std::string json = DuplicateScanStatusToJson();
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate duplicate status buffer.");
+  }
+  return out;
+}
+
+// Hands over the groups confirmed by the running duplicate scan since the previous call, as
+// they were found (before the final reclaimable-size sort), and clears the queue.
+extern "C" __declspec(dllexport) char* omni_take_duplicate_groups_json() {
+  std::vector<DuplicateGroupRow> groups;
+  {
+    std::lock_guard<std::mutex> lock(g_duplicate_pending_mutex);
+    groups.swap(g_duplicate_pending_groups);
+  }
+  const std::string json = DuplicateGroupsToJson(groups);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate duplicate groups buffer.");
+  }
+  return out;
+}
+
+namespace {
+
+// One folder in a duplicate-folder scan. `entries` holds a hash per direct child (file name,
+// size and optionally content; or subfolder name and signature), combined order-independently.
+struct FolderSignatureNode {
+  std::wstring path;
+  size_t depth = 0;
+  std::vector<uint64_t> entries;
+  uint64_t signature = 0;
+  uint64_t total_bytes = 0;
+  uint32_t file_count = 0;
+};
+
+std::vector<DuplicateGroupRow> find_duplicate_folders_internal(const std::wstring& scope,
+                                                               const bool compare_content,
+                                                               const uint32_t max_groups,
+                                                               const uint32_t max_files_per_group) {
+  std::vector<std::wstring> file_paths;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (!file.is_directory && !IsReparsePoint(file) && IsPathWithinScope(file.path, scope)) {
+        file_paths.push_back(file.path);
+      }
+    }
+  }
+
+  std::vector<uint64_t> sizes(file_paths.size(), 0);
+  std::vector<uint64_t> content_hashes(file_paths.size(), 0);
+  std::vector<uint8_t> read_ok(file_paths.size(), 0);
+  AddDuplicateProgressTotal(static_cast<uint64_t>(file_paths.size()));
+  const size_t worker_count = ComputeDuplicateWorkerCount(file_paths.size());
+  std::atomic<size_t> next_index{0};
+  std::vector<std::thread> workers;
+  workers.reserve(worker_count);
+  for (size_t worker = 0; worker < worker_count; ++worker) {
+    workers.emplace_back([&]() {
+      while (!IsDuplicateScanCancelRequested()) {
+        const size_t index = next_index.fetch_add(1, std::memory_order_relaxed);
+        if (index >= file_paths.size()) {
+          return;
+        }
+        int64_t created = 0;
+        int64_t modified = 0;
+        bool ok = ReadFileMetadata(file_paths[index], &sizes[index], &created, &modified);
+        if (ok && compare_content && sizes[index] > 0) {
+          ok = HashFileFNV1a64(file_paths[index], &content_hashes[index]);
+        }
+        read_ok[index] = ok ? 1 : 0;
+        AddDuplicateProgressDone(1);
+      }
+    });
+  }
+  for (std::thread& worker : workers) {
+    worker.join();
+  }
+  if (IsDuplicateScanCancelRequested()) {
+    return {};
+  }
+
+  // Every folder strictly inside the scope that holds an indexed file, keyed by lowercase path.
+  std::unordered_map<std::wstring, FolderSignatureNode> nodes;
+  const auto is_candidate_folder = [&](const std::wstring& path) {
+    return !path.empty() && IsPathWithinScope(path, scope);
+  };
+  for (size_t index = 0; index < file_paths.size(); ++index) {
+    if (!read_ok[index]) {
+      continue;
+    }
+    const std::wstring parent = ParentPathOf(file_paths[index]);
+    if (!is_candidate_folder(parent)) {
+      continue;
+    }
+    uint64_t entry = kFNVOffsetBasis;
+    FNV1aMixU64(HashWideString(ToLower(ExtractFileNameFromPath(file_paths[index]))), &entry);
+    FNV1aMixU64(sizes[index], &entry);
+    FNV1aMixU64(content_hashes[index], &entry);
+
+    std::wstring folder = parent;
+    bool direct_parent = true;
+    while (is_candidate_folder(folder)) {
+      const auto [found, inserted] = nodes.try_emplace(ToLower(folder));
+      if (inserted) {
+        found->second.path = folder;
+        found->second.depth =
+            static_cast<size_t>(std::count(folder.begin(), folder.end(), L'\\'));
+      }
+      if (direct_parent) {
+        found->second.entries.push_back(entry);
+        direct_parent = false;
+      }
+      found->second.file_count += 1;
+      found->second.total_bytes += sizes[index];
+      const std::wstring next = ParentPathOf(folder);
+      if (next.size() >= folder.size()) {
+        break;
+      }
+      folder = next;
+    }
+  }
+
+  // Deepest folders first, so each subfolder signature is final before its parent uses it.
+  std::vector<FolderSignatureNode*> ordered;
+  ordered.reserve(nodes.size());
+  for (auto& [key, node] : nodes) {
+    ordered.push_back(&node);
+  }
+  std::sort(ordered.begin(), ordered.end(),
+            [](const FolderSignatureNode* left, const FolderSignatureNode* right) {
+              return left->depth > right->depth;
+            });
+  std::unordered_map<uint64_t, std::vector<const FolderSignatureNode*>> by_signature;
+  for (FolderSignatureNode* node : ordered) {
+    std::sort(node->entries.begin(), node->entries.end());
+    uint64_t signature = kFNVOffsetBasis;
+    for (const uint64_t entry : node->entries) {
+      FNV1aMixU64(entry, &signature);
+    }
+    node->signature = signature;
+    if (node->total_bytes > 0) {
+      by_signature[signature].push_back(node);
+    }
+
+    const std::wstring parent = ParentPathOf(node->path);
+    if (parent.size() < node->path.size() && is_candidate_folder(parent)) {
+      const auto found = nodes.find(ToLower(parent));
+      if (found != nodes.end()) {
+        uint64_t entry = kFNVOffsetBasis;
+        FNV1aMixU64(1, &entry);
+        FNV1aMixU64(HashWideString(ToLower(ExtractFileNameFromPath(node->path))), &entry);
+        FNV1aMixU64(signature, &entry);
+        found->second.entries.push_back(entry);
+      }
+    }
+  }
+
+  std::unordered_map<const FolderSignatureNode*, uint64_t> group_of;
+  for (const auto& [signature, members] : by_signature) {
+    if (members.size() >= 2) {
+      for (const FolderSignatureNode* member : members) {
+        group_of[member] = signature;
+      }
+    }
+  }
+
+  std::vector<std::pair<uint64_t, const std::vector<const FolderSignatureNode*>*>> matches;
+  for (const auto& [signature, members] : by_signature) {
+    if (members.size() < 2) {
+      continue;
+    }
+    // Matching folders whose parents also all match are implied by the parent group.
+    bool implied = true;
+    uint64_t parent_group = 0;
+    for (size_t i = 0; i < members.size() && implied; ++i) {
+      const auto parent = nodes.find(ToLower(ParentPathOf(members[i]->path)));
+      const auto parent_match = parent == nodes.end() ? group_of.end()
+                                                      : group_of.find(&parent->second);
+      if (parent_match == group_of.end() || (i > 0 && parent_match->second != parent_group)) {
+        implied = false;
+      } else {
+        parent_group = parent_match->second;
+      }
+    }
+    if (!implied) {
+      matches.emplace_back(signature, &members);
+    }
+  }
+
+  std::sort(matches.begin(), matches.end(), [](const auto& left, const auto& right) {
+    const uint64_t left_wasted = left.second->front()->total_bytes * (left.second->size() - 1);
+    const uint64_t right_wasted =
+        right.second->front()->total_bytes * (right.second->size() - 1);
+    return left_wasted > right_wasted;
+  });
+  if (matches.size() > max_groups) {
+    matches.resize(max_groups);
+  }
+
+  std::vector<DuplicateGroupRow> groups;
+  groups.reserve(matches.size());
+  uint32_t serial = 0;
+  for (const auto& [signature, members] : matches) {
+    DuplicateGroupRow row{};
+    row.size = members->front()->total_bytes;
+    row.group_id = BuildDuplicateGroupId(row.size, signature, serial++);
+    row.file_count = static_cast<uint32_t>(members->size());
+    row.total_bytes = row.size * members->size();
+    std::vector<const FolderSignatureNode*> sorted = *members;
+    std::sort(sorted.begin(), sorted.end(),
+              [](const FolderSignatureNode* left, const FolderSignatureNode* right) {
+                return ToLower(left->path) < ToLower(right->path);
+              });
+    const size_t render_count = std::min<size_t>(sorted.size(), max_files_per_group);
+    for (size_t i = 0; i < render_count; ++i) {
+      DuplicateFileRow folder{};
+      folder.name = ExtractFileNameFromPath(sorted[i]->path);
+      folder.path = sorted[i]->path;
+      uint64_t ignored_size = 0;
+      ReadFileMetadata(folder.path, &ignored_size, &folder.created_unix, &folder.modified_unix);
+      folder.size = row.size;
+      row.files.push_back(std::move(folder));
+    }
+    groups.push_back(std::move(row));
+  }
+  g_duplicate_groups_found.store(static_cast<uint64_t>(groups.size()),
+                                 std::memory_order_release);
+  return groups;
+}
+
+}  // namespace
+
+// Groups folders whose subtrees match: same relative file names and sizes, and the same
+// content when `compare_content` is set. Shares the duplicate scan's busy flag and cancel.
+extern "C" __declspec(dllexport) char* omni_find_duplicate_folders_json(
+    const char* within_path_utf8, const bool compare_content, const uint32_t max_groups,
+    const uint32_t max_files_per_group) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+
+  std::wstring scope = Utf8ToWide(within_path_utf8 == nullptr ? "" : within_path_utf8);
+  while (scope.size() > 3 && (scope.back() == L'\\' || scope.back() == L'/')) {
+    scope.pop_back();
+  }
+
+  const bool already_running =
+      g_duplicate_scan_running.exchange(true, std::memory_order_acq_rel);
+  if (already_running) {
+    SetLastErrorText("Duplicate scan is already running.");
+    return nullptr;
+  }
+
+  g_duplicate_cancel_requested.store(false, std::memory_order_release);
+  ResetDuplicateProgress();
+  const std::vector<DuplicateGroupRow> groups = find_duplicate_folders_internal(
+      scope, compare_content, std::clamp<uint32_t>(max_groups, 1, 1000),
+      std::clamp<uint32_t>(max_files_per_group, 2, 400));
+  const bool cancelled = IsDuplicateScanCancelRequested();
+  g_duplicate_scan_running.store(false, std::memory_order_release);
+  g_duplicate_cancel_requested.store(false, std::memory_order_release);
+  if (cancelled) {
+    SetLastErrorText("Duplicate scan cancelled.");
+    return nullptr;
+  }
+
+  const std::string json = DuplicateGroupsToJson(groups);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate duplicate folder results buffer.");
+  }
+  return out;
+}
+
+// Sizes up a content duplicate scan without hashing anything: counts indexed files of at
+// least `min_size` within the scope, and those sharing their size with another candidate,
+// which are the ones a scan would actually hash. Only file metadata is read.
+extern "C" __declspec(dllexport) char* omni_estimate_duplicate_scan_json(
+    const uint64_t min_size, const char* within_path_utf8, const bool skip_reparse_points) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+
+  std::wstring scope = Utf8ToWide(within_path_utf8 == nullptr ? "" : within_path_utf8);
+  while (scope.size() > 3 && (scope.back() == L'\\' || scope.back() == L'/')) {
+    scope.pop_back();
+  }
+  const uint64_t effective_min_size = min_size == 0 ? 1ULL * 1024ULL * 1024ULL : min_size;
+
+  std::vector<std::wstring> file_paths;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (file.is_directory || (skip_reparse_points && IsReparsePoint(file)) ||
+          IsCloudPlaceholder(file) || !IsPathWithinScope(file.path, scope)) {
+        continue;
+      }
+      file_paths.push_back(file.path);
+    }
+  }
+
+  std::vector<uint64_t> sizes(file_paths.size(), 0);
+  const size_t worker_count = ComputeDuplicateWorkerCount(file_paths.size());
+  std::atomic<size_t> next_index{0};
+  std::vector<std::thread> workers;
+  workers.reserve(worker_count);
+  for (size_t worker = 0; worker < worker_count; ++worker) {
+    workers.emplace_back([&]() {
+      while (true) {
+        const size_t index = next_index.fetch_add(1, std::memory_order_relaxed);
+        if (index >= file_paths.size()) {
+          return;
+        }
+        uint64_t size = 0;
+        int64_t created = 0;
+        int64_t modified = 0;
+        if (ReadFileMetadata(file_paths[index], &size, &created, &modified)) {
+          sizes[index] = size;
+        }
+      }
+    });
+  }
+  for (std::thread& worker : workers) {
+    worker.join();
+  }
+
+  uint64_t candidate_files = 0;
+  uint64_t candidate_bytes = 0;
+  std::unordered_map<uint64_t, uint64_t> files_by_size;
+  for (const uint64_t size : sizes) {
+    if (size < effective_min_size) {
+      continue;
+    }
+    ++candidate_files;
+    candidate_bytes += size;
+    ++files_by_size[size];
+  }
+  uint64_t files_to_hash = 0;
+  uint64_t bytes_to_hash = 0;
+  for (const auto& [size, count] : files_by_size) {
+    if (count >= 2) {
+      files_to_hash += count;
+      bytes_to_hash += size * count;
+    }
+  }
+
+  std::string json;
+  json.reserve(160);
+  json.append("{\"candidateFiles\":");
+  json.append(std::to_string(candidate_files));
+  json.append(",\"candidateBytes\":");
+  json.append(std::to_string(candidate_bytes));
+  json.append(",\"filesToHash\":");
+  json.append(std::to_string(files_to_hash));
+  json.append(",\"bytesToHash\":");
+  json.append(std::to_string(bytes_to_hash));
+  json.push_back('}');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate duplicate estimate buffer.");
+  }
+  return out;
+}
+
+bool DeletePathWithShell(const std::wstring& path, bool recycle_bin) {
+  // The shell rejects `\\?\` paths, so it gets the plain form.
+  const std::wstring plain_path = StripExtendedPathPrefix(path);
+  std::vector<wchar_t> shell_path(plain_path.begin(), plain_path.end());
+  shell_path.push_back(L'\0');
+  shell_path.push_back(L'\0');
+
+  SHFILEOPSTRUCTW operation{};
+  operation.wFunc = FO_DELETE;
+  operation.pFrom = shell_path.data();
+  operation.fFlags = FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT;
+  if (recycle_bin) {
+    operation.fFlags |= FOF_ALLOWUNDO;
+  }
+
+  const int result = SHFileOperationW(&operation);
+  if (result != 0) {
+    SetLastErrorText(BuildWin32ErrorText(
+        recycle_bin ? "Failed to move item to the Recycle Bin."
+                    : "Failed to delete item.",
+        static_cast<DWORD>(result)));
+    return false;
+  }
+
+  if (operation.fAnyOperationsAborted) {
+    SetLastErrorText(recycle_bin ? "Move to Recycle Bin cancelled."
+                                 : "Delete cancelled.");
+    return false;
+  }
+
+  return true;
+}
+
+extern "C" __declspec(dllexport) bool omni_delete_path(const char* path_utf8,
+                                                       bool recycle_bin) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Delete failed: empty path.");
+    return false;
+  }
+
+  const DWORD attributes = GetFileAttributesW(ToExtendedLengthPath(path).c_str());
+  if (attributes == INVALID_FILE_ATTRIBUTES) {
+    SetLastErrorText(BuildWin32ErrorText("Delete failed: path not found.",
+                                         GetLastError()));
+    return false;
+  }
+
+  if (!DeletePathWithShell(path, recycle_bin)) {
+    return false;
+  }
+
+  {
+    std::unique_lock<std::shared_mutex> lock(g_index_mutex);
+    RemoveIndexedFileByPathLocked(path);
+    g_indexed_count.store(static_cast<uint64_t>(g_indexed_files.size()),
+                          std::memory_order_release);
+  }
+
+  SetLastErrorText("");
+  return true;
+}
+
+// Sets the timestamps whose pointers are non-null and leaves the others untouched. Folders
+// are supported.
+extern "C" __declspec(dllexport) bool omni_set_file_times(const char* path_utf8,
+                                                          const int64_t* created_unix,
+                                                          const int64_t* modified_unix,
+                                                          const int64_t* accessed_unix) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Setting timestamps failed: empty path.");
+    return false;
+  }
+
+  FILETIME created{};
+  FILETIME modified{};
+  FILETIME accessed{};
+  if ((created_unix != nullptr && !UnixSecondsToFileTime(*created_unix, &created)) ||
+      (modified_unix != nullptr && !UnixSecondsToFileTime(*modified_unix, &modified)) ||
+      (accessed_unix != nullptr && !UnixSecondsToFileTime(*accessed_unix, &accessed))) {
+    SetLastErrorText("Setting timestamps failed: time is out of range.");
+    return false;
+  }
+
+  HANDLE handle = CreateFileW(ToExtendedLengthPath(path).c_str(), FILE_WRITE_ATTRIBUTES,
+                              FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr,
+                              OPEN_EXISTING, FILE_FLAG_BACKUP_SEMANTICS, nullptr);
+  if (handle == INVALID_HANDLE_VALUE) {
+    SetLastErrorText(BuildWin32ErrorText("Setting timestamps failed: could not open the path.",
+                                         GetLastError()));
+    return false;
+  }
+  const BOOL ok = SetFileTime(handle, created_unix != nullptr ? &created : nullptr,
+                              accessed_unix != nullptr ? &accessed : nullptr,
+                              modified_unix != nullptr ? &modified : nullptr);
+  const DWORD error_code = ok ? ERROR_SUCCESS : GetLastError();
+  CloseHandle(handle);
+  if (!ok) {
+    SetLastErrorText(BuildWin32ErrorText("Setting timestamps failed.", error_code));
+    return false;
+  }
+
+  SetLastErrorText("");
+  return true;
+}
+
+namespace {
+
+struct LockingProcess {
+  DWORD pid = 0;
+  std::wstring name;
+};
+
+// Asks the Restart Manager which processes hold `path` open. Only files can be registered,
+// so directories always report no lockers.
+bool QueryLockingProcesses(const std::wstring& path, std::vector<LockingProcess>* out) {
+  DWORD session = 0;
+  WCHAR session_key[CCH_RM_SESSION_KEY + 1] = {};
+  if (RmStartSession(&session, 0, session_key) != ERROR_SUCCESS) {
+    return false;
+  }
+
+  bool ok = false;
+  LPCWSTR resources[] = {path.c_str()};
+  if (RmRegisterResources(session, 1, resources, 0, nullptr, 0, nullptr) == ERROR_SUCCESS) {
+    std::vector<RM_PROCESS_INFO> infos;
+    UINT needed = 0;
+    UINT count = 0;
+    DWORD reasons = RmRebootReasonNone;
+    DWORD result = RmGetList(session, &needed, &count, nullptr, &reasons);
+    while (result == ERROR_MORE_DATA) {
+      infos.resize(needed);
+      count = needed;
+      result = RmGetList(session, &needed, &count, infos.data(), &reasons);
+    }
+    if (result == ERROR_SUCCESS) {
+      ok = true;
+      for (UINT i = 0; i < count && i < infos.size(); ++i) {
+        out->push_back(LockingProcess{infos[i].Process.dwProcessId, infos[i].strAppName});
+      }
+    }
+  }
+  RmEndSession(session);
+  return ok;
+}
+
+void AppendLockingProcessesJson(std::string* json, const std::vector<LockingProcess>& processes) {
+  json->push_back('[');
+  for (size_t i = 0; i < processes.size(); ++i) {
+    if (i > 0) {
+      json->push_back(',');
+    }
+    json->append("{\"pid\":");
+    json->append(std::to_string(processes[i].pid));
+    json->append(",\"name\":\"");
+    AppendEscapedJsonString(json, WideToUtf8(processes[i].name));
+    json->append("\"}");
+  }
+  json->push_back(']');
+}
+
+}  // namespace
+
+// Lists the processes that have `path` open, whether or not they allow sharing, as reported
+// by the Restart Manager.
+extern "C" __declspec(dllexport) char* omni_who_locks_json(const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Lock lookup failed: empty path.");
+    return nullptr;
+  }
+  const DWORD attributes = GetFileAttributesW(ToExtendedLengthPath(path).c_str());
+  if (attributes == INVALID_FILE_ATTRIBUTES) {
+    SetLastErrorText(BuildWin32ErrorText("Lock lookup failed: path not found.", GetLastError()));
+    return nullptr;
+  }
+
+  std::vector<LockingProcess> processes;
+  if ((attributes & FILE_ATTRIBUTE_DIRECTORY) == 0 && !QueryLockingProcesses(path, &processes)) {
+    SetLastErrorText("Lock lookup failed: the Restart Manager could not inspect the file.");
+    return nullptr;
+  }
+
+  std::string json;
+  json.reserve(16 + processes.size() * 64);
+  AppendLockingProcessesJson(&json, processes);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate lock lookup buffer.");
+  }
+  return out;
+}
+
+// Checks whether `path` could be deleted right now: opens it for DELETE with full sharing
+// and, when that hits a sharing violation, lists the processes holding it.
+extern "C" __declspec(dllexport) char* omni_probe_delete_json(const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Delete probe failed: empty path.");
+    return nullptr;
+  }
+
+  const std::wstring extended_path = ToExtendedLengthPath(path);
+  const DWORD attributes = GetFileAttributesW(extended_path.c_str());
+  const bool exists = attributes != INVALID_FILE_ATTRIBUTES;
+  const bool is_directory = exists && (attributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
+  bool locked = false;
+  std::string error;
+  std::vector<LockingProcess> processes;
+  if (!exists) {
+    error = BuildWin32ErrorText("Path not found.", GetLastError());
+  } else {
+    HANDLE handle = CreateFileW(extended_path.c_str(), DELETE,
+                                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr,
+                                OPEN_EXISTING,
+                                FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_OPEN_REPARSE_POINT,
+                                nullptr);
+    if (handle == INVALID_HANDLE_VALUE) {
+      const DWORD open_error = GetLastError();
+      locked = open_error == ERROR_SHARING_VIOLATION || open_error == ERROR_LOCK_VIOLATION;
+      error = BuildWin32ErrorText(locked ? "File is in use." : "File cannot be deleted.",
+                                  open_error);
+    } else {
+      CloseHandle(handle);
+    }
+    if (locked && !is_directory) {
+      QueryLockingProcesses(path, &processes);
+    }
+  }
+
+  std::string json;
+  json.reserve(256 + processes.size() * 64);
+  json.append("{\"path\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(path));
+  json.append("\",\"exists\":");
+  json.append(exists ? "true" : "false");
+  json.append(",\"isDirectory\":");
+  json.append(is_directory ? "true" : "false");
+  json.append(",\"locked\":");
+  json.append(locked ? "true" : "false");
+  json.append(",\"canDelete\":");
+  json.append(exists && !locked && error.empty() ? "true" : "false");
+  json.append(",\"processes\":");
+  AppendLockingProcessesJson(&json, processes);
+  json.append(",\"error\":");
+  if (error.empty()) {
+    json.append("null");
+  } else {
+    json.push_back('"');
+    AppendEscapedJsonString(&json, error);
+    json.push_back('"');
+  }
+  json.push_back('}');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate delete probe buffer.");
+  }
+  return out;
+}
+
+namespace {
+
+struct RecycledItemInfo {
+  std::wstring original_path;
+  int64_t deleted_unix = 0;
+};
+
+std::wstring CurrentUserSidString() {
+  HANDLE token = nullptr;
+  if (!OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &token)) {
+    return L"";
+  }
+  DWORD length = 0;
+  GetTokenInformation(token, TokenUser, nullptr, 0, &length);
+  std::vector<uint8_t> buffer(length);
+  std::wstring sid_text;
+  if (length > 0 &&
+      GetTokenInformation(token, TokenUser, buffer.data(), length, &length)) {
+    LPWSTR raw_sid = nullptr;
+    if (ConvertSidToStringSidW(reinterpret_cast<TOKEN_USER*>(buffer.data())->User.Sid,
+                               &raw_sid)) {
+      sid_text = raw_sid;
+      LocalFree(raw_sid);
+    }
+  }
+  CloseHandle(token);
+  return sid_text;
+}
+
+// Parses a Recycle Bin "$I" record: version, size, deletion FILETIME, then the original
+// path (a fixed MAX_PATH buffer in version 1, length-prefixed in version 2).
+bool ReadRecycleInfoFile(const std::wstring& info_path, RecycledItemInfo* out) {
+  FILE* file = _wfopen(info_path.c_str(), L"rb");
+  if (file == nullptr) {
+    return false;
+  }
+  std::vector<uint8_t> content(28 + 2 * 32768);
+  const size_t read = std::fread(content.data(), 1, content.size(), file);
+  std::fclose(file);
+  if (read < 24) {
+    return false;
+  }
+
+  int64_t version = 0;
+  FILETIME deleted_time{};
+  std::memcpy(&version, content.data(), sizeof(version));
+  std::memcpy(&deleted_time, content.data() + 16, sizeof(deleted_time));
+  size_t path_offset = 24;
+  size_t path_chars = 0;
+  if (version == 1) {
+    path_chars = MAX_PATH;
+  } else if (version == 2 && read >= 28) {
+    uint32_t length = 0;
+    std::memcpy(&length, content.data() + 24, sizeof(length));
+    path_offset = 28;
+    path_chars = length;
+  } else {
+    return false;
+  }
+  path_chars = std::min(path_chars, (read - path_offset) / sizeof(wchar_t));
+
+  std::wstring path(path_chars, L'\0');
+  std::memcpy(path.data(), content.data() + path_offset, path_chars * sizeof(wchar_t));
+  const size_t terminator = path.find(L'\0');
+  if (terminator != std::wstring::npos) {
+    path.resize(terminator);
+  }
+  if (path.empty()) {
+    return false;
+  }
+  out->original_path = std::move(path);
+  out->deleted_unix = FileTimeToUnixSeconds(deleted_time);
+  return true;
+}
+
+}  // namespace
+
+// Moves the newest Recycle Bin entry for `original_path` deleted at or after
+// `deleted_after_unix` back to where it came from.
+extern "C" __declspec(dllexport) bool omni_restore_recycled_path(const char* original_path_utf8,
+                                                                 int64_t deleted_after_unix) {
+  const std::wstring path = Utf8ToWide(original_path_utf8 == nullptr ? "" : original_path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Restore failed: empty path.");
+    return false;
+  }
+  if (path.size() < 3 || path[1] != L':') {
+    SetLastErrorText("Restore failed: only items from local drives can be restored.");
+    return false;
+  }
+  const std::wstring extended_path = ToExtendedLengthPath(path);
+  if (GetFileAttributesW(extended_path.c_str()) != INVALID_FILE_ATTRIBUTES) {
+    SetLastErrorText("Restore failed: an item already exists at the original location.");
+    return false;
+  }
+
+  const std::wstring sid = CurrentUserSidString();
+  if (sid.empty()) {
+    SetLastErrorText("Restore failed: could not resolve the current user.");
+    return false;
+  }
+  const std::wstring bin_folder = path.substr(0, 2) + L"\\$Recycle.Bin\\" + sid;
+
+  WIN32_FIND_DATAW data{};
+  HANDLE find = FindFirstFileW((bin_folder + L"\\$I*").c_str(), &data);
+  if (find == INVALID_HANDLE_VALUE) {
+    SetLastErrorText("Restore failed: the item is no longer in the Recycle Bin.");
+    return false;
+  }
+  std::wstring best_info_name;
+  int64_t best_deleted_unix = std::numeric_limits<int64_t>::min();
+  do {
+    if ((data.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0) {
+      continue;
+    }
+    RecycledItemInfo info;
+    if (!ReadRecycleInfoFile(bin_folder + L"\\" + data.cFileName, &info)) {
+      continue;
+    }
+    if (info.deleted_unix >= deleted_after_unix && info.deleted_unix >= best_deleted_unix &&
+        PathEqualsInsensitive(info.original_path, path)) {
+      best_info_name = data.cFileName;
+      best_deleted_unix = info.deleted_unix;
+    }
+  } while (FindNextFileW(find, &data));
+  FindClose(find);
+
+  if (best_info_name.size() < 3) {
+    SetLastErrorText("Restore failed: the item is no longer in the Recycle Bin.");
+    return false;
+  }
+
+  const std::wstring info_path = bin_folder + L"\\" + best_info_name;
+  const std::wstring data_path = bin_folder + L"\\$R" + best_info_name.substr(2);
+  const std::wstring parent = ParentPathOf(path);
+  if (!parent.empty() &&
+      GetFileAttributesW(ToExtendedLengthPath(parent).c_str()) == INVALID_FILE_ATTRIBUTES) {
+    SHCreateDirectoryExW(nullptr, parent.c_str(), nullptr);
+  }
+  if (!MoveFileExW(ToExtendedLengthPath(data_path).c_str(), extended_path.c_str(),
+                   MOVEFILE_COPY_ALLOWED)) {
+    SetLastErrorText(BuildWin32ErrorText("Restore failed.", GetLastError()));
+    return false;
+  }
+  DeleteFileW(ToExtendedLengthPath(info_path).c_str());
+
+  SetLastErrorText("");
+  return true;
+}
+
+namespace {
+
+std::wstring QueryAssociationString(const std::wstring& extension, const ASSOCSTR kind) {
+  DWORD length = 0;
+  HRESULT result = AssocQueryStringW(ASSOCF_INIT_IGNOREUNKNOWN, kind, extension.c_str(),
+                                     nullptr, nullptr, &length);
+  if (result != S_FALSE || length == 0) {
+    return L"";
+  }
+  std::wstring value(length, L'\0');
+  result = AssocQueryStringW(ASSOCF_INIT_IGNOREUNKNOWN, kind, extension.c_str(), nullptr,
+                             value.data(), &length);
+  if (FAILED(result)) {
+    return L"";
+  }
+  value.resize(wcsnlen(value.c_str(), value.size()));
+  return value;
+}
+
+}  // namespace
+
+extern "C" __declspec(dllexport) char* omni_default_app_json(const char* extension_utf8) {
+  std::wstring extension = Utf8ToWide(extension_utf8 == nullptr ? "" : extension_utf8);
+  while (!extension.empty() && extension.front() == L'.') {
+    extension.erase(extension.begin());
+  }
+  if (extension.empty()) {
+    SetLastErrorText("Default app lookup failed: empty extension.");
+    return nullptr;
+  }
+  extension.insert(extension.begin(), L'.');
+
+  const std::wstring executable = QueryAssociationString(extension, ASSOCSTR_EXECUTABLE);
+  const std::wstring name = QueryAssociationString(extension, ASSOCSTR_FRIENDLYAPPNAME);
+  if (executable.empty() && name.empty()) {
+    SetLastErrorText("No default app is associated with this file type.");
+    return nullptr;
+  }
+
+  std::string json;
+  json.reserve(256);
+  json.append("{\"extension\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(ToLower(extension)));
+  json.append("\",\"name\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(name.empty() ? executable : name));
+  json.append("\",\"path\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(executable));
+  json.append("\"}");
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate default app buffer.");
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) bool omni_open_elevated(const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Open elevated failed: empty path.");
+    return false;
+  }
+
+  const size_t slash = path.find_last_of(L"\\/");
+  const std::wstring directory =
+      slash == std::wstring::npos ? std::wstring() : path.substr(0, slash);
+
+  SHELLEXECUTEINFOW info{};
+  info.cbSize = sizeof(info);
+  info.fMask = SEE_MASK_NOASYNC;
+  info.lpVerb = L"runas";
+  info.lpFile = path.c_str();
+  info.lpDirectory = directory.empty() ? nullptr : directory.c_str();
+  info.nShow = SW_SHOWNORMAL;
+  if (!ShellExecuteExW(&info)) {
+    const DWORD error_code = GetLastError();
+    if (error_code == ERROR_CANCELLED) {
+      SetLastErrorText("Elevation was cancelled.");
+    } else {
+      SetLastErrorText(BuildWin32ErrorText("Open elevated failed.", error_code));
+    }
+    return false;
+  }
+
+  SetLastErrorText("");
+  return true;
+}
+
+extern "C" __declspec(dllexport) bool omni_show_properties(const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Show properties failed: empty path.");
+    return false;
+  }
+
+  // The "properties" verb is an IDList verb, which needs COM on the calling thread.
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  SHELLEXECUTEINFOW info{};
+  info.cbSize = sizeof(info);
+  info.fMask = SEE_MASK_INVOKEIDLIST | SEE_MASK_NOASYNC;
+  info.lpVerb = L"properties";
+  info.lpFile = path.c_str();
+  info.nShow = SW_SHOWNORMAL;
+  const BOOL shown = ShellExecuteExW(&info);
+  const DWORD error_code = shown ? ERROR_SUCCESS : GetLastError();
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (!shown) {
+    SetLastErrorText(BuildWin32ErrorText("Show properties failed.", error_code));
+    return false;
+  }
+
+  SetLastErrorText("");
+  return true;
+}
+
+namespace {
+
+// Selects `path` in the Explorer window behind `dispatch` when that window shows `folder`,
+// then brings the window to the front.
+bool SelectInShellWindow(IDispatch* dispatch, const std::wstring& folder,
+                         const std::wstring& path) {
+  IServiceProvider* provider = nullptr;
+  if (FAILED(dispatch->QueryInterface(IID_IServiceProvider,
+                                      reinterpret_cast<void**>(&provider)))) {
+    return false;
+  }
+  IShellBrowser* browser = nullptr;
+  HRESULT result = provider->QueryService(SID_STopLevelBrowser, IID_IShellBrowser,
+                                          reinterpret_cast<void**>(&browser));
+  provider->Release();
+  if (FAILED(result)) {
+    return false;
+  }
+  IShellView* view = nullptr;
+  result = browser->QueryActiveShellView(&view);
+  browser->Release();
+  if (FAILED(result)) {
+    return false;
+  }
+
+  bool selected = false;
+  IFolderView* folder_view = nullptr;
+  IPersistFolder2* persist = nullptr;
+  PIDLIST_ABSOLUTE current_pidl = nullptr;
+  PWSTR current_path = nullptr;
+  if (SUCCEEDED(view->QueryInterface(IID_IFolderView, reinterpret_cast<void**>(&folder_view))) &&
+      SUCCEEDED(folder_view->GetFolder(IID_IPersistFolder2, reinterpret_cast<void**>(&persist))) &&
+      SUCCEEDED(persist->GetCurFolder(&current_pidl)) &&
+      SUCCEEDED(SHGetNameFromIDList(current_pidl, SIGDN_FILESYSPATH, &current_path)) &&
+      _wcsicmp(NormalizeScopePath(current_path).c_str(), folder.c_str()) == 0) {
+    PIDLIST_ABSOLUTE item_pidl = nullptr;
+    if (SUCCEEDED(SHParseDisplayName(path.c_str(), nullptr, &item_pidl, 0, nullptr))) {
+      selected = SUCCEEDED(view->SelectItem(ILFindLastID(item_pidl),
+                                            SVSI_SELECT | SVSI_DESELECTOTHERS |
+                                                SVSI_ENSUREVISIBLE | SVSI_FOCUSED));
+      CoTaskMemFree(item_pidl);
+    }
+  }
+  if (current_path != nullptr) {
+    CoTaskMemFree(current_path);
+  }
+  if (current_pidl != nullptr) {
+    CoTaskMemFree(current_pidl);
+  }
+  if (persist != nullptr) {
+    persist->Release();
+  }
+  if (folder_view != nullptr) {
+    folder_view->Release();
+  }
+  view->Release();
+
+  if (selected) {
+    IWebBrowserApp* browser_app = nullptr;
+    if (SUCCEEDED(dispatch->QueryInterface(IID_IWebBrowserApp,
+                                           reinterpret_cast<void**>(&browser_app)))) {
+      SHANDLE_PTR handle = 0;
+      if (SUCCEEDED(browser_app->get_HWND(&handle))) {
+        const HWND window = reinterpret_cast<HWND>(handle);
+        if (IsIconic(window)) {
+          ShowWindow(window, SW_RESTORE);
+        }
+        SetForegroundWindow(window);
+      }
+      browser_app->Release();
+    }
+  }
+  return selected;
+}
+
+}  // namespace
+
+// Selects `path` in an Explorer window that already shows its folder, found through the
+// Shell windows collection. False when no open window shows the folder, so the caller can
+// open a new one.
+extern "C" __declspec(dllexport) bool omni_reveal_in_open_window(const char* path_utf8) {
+  const std::wstring path = NormalizeScopePath(Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8));
+  const std::wstring folder = NormalizeScopePath(ParentPathOf(path));
+  if (path.empty() || folder.empty()) {
+    return false;
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  bool revealed = false;
+  IShellWindows* shell_windows = nullptr;
+  if (SUCCEEDED(CoCreateInstance(CLSID_ShellWindows, nullptr, CLSCTX_ALL, IID_IShellWindows,
+                                 reinterpret_cast<void**>(&shell_windows)))) {
+    long count = 0;
+    if (FAILED(shell_windows->get_Count(&count))) {
+      count = 0;
+    }
+    for (long i = 0; i < count && !revealed; ++i) {
+      VARIANT index;
+      VariantInit(&index);
+      index.vt = VT_I4;
+      index.lVal = i;
+      IDispatch* dispatch = nullptr;
+      if (FAILED(shell_windows->Item(index, &dispatch)) || dispatch == nullptr) {
+        continue;
+      }
+      revealed = SelectInShellWindow(dispatch, folder, path);
+      dispatch->Release();
+    }
+    shell_windows->Release();
+  }
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  return revealed;
+}
+
+// Opens `folder` in Explorer once with every item in `items_utf8` (newline-separated full
+// paths inside that folder) selected.
+extern "C" __declspec(dllexport) bool omni_reveal_items(const char* folder_utf8,
+                                                        const char* items_utf8) {
+  const std::wstring folder = Utf8ToWide(folder_utf8 == nullptr ? "" : folder_utf8);
+  const std::wstring raw_items = Utf8ToWide(items_utf8 == nullptr ? "" : items_utf8);
+  if (folder.empty()) {
+    SetLastErrorText("Reveal failed: empty folder path.");
+    return false;
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  PIDLIST_ABSOLUTE folder_pidl = nullptr;
+  HRESULT result = SHParseDisplayName(folder.c_str(), nullptr, &folder_pidl, 0, nullptr);
+  std::vector<PIDLIST_ABSOLUTE> item_pidls;
+  if (SUCCEEDED(result)) {
+    size_t start = 0;
+    while (start <= raw_items.size()) {
+      const size_t delimiter = raw_items.find(L'\n', start);
+      const size_t end = delimiter == std::wstring::npos ? raw_items.size() : delimiter;
+      const std::wstring item = raw_items.substr(start, end - start);
+      PIDLIST_ABSOLUTE item_pidl = nullptr;
+      if (!item.empty() &&
+          SUCCEEDED(SHParseDisplayName(item.c_str(), nullptr, &item_pidl, 0, nullptr))) {
+        item_pidls.push_back(item_pidl);
+      }
+      if (delimiter == std::wstring::npos) {
+        break;
+      }
+      start = delimiter + 1;
+    }
+
+    // The selection is given as child IDs relative to the folder.
+    std::vector<PCUITEMID_CHILD> children;
+    children.reserve(item_pidls.size());
+    for (PIDLIST_ABSOLUTE item_pidl : item_pidls) {
+      children.push_back(ILFindLastID(item_pidl));
+    }
+    result = SHOpenFolderAndSelectItems(folder_pidl, static_cast<UINT>(children.size()),
+                                        children.empty() ? nullptr : children.data(), 0);
+  }
+
+  for (PIDLIST_ABSOLUTE item_pidl : item_pidls) {
+    CoTaskMemFree(item_pidl);
+  }
+  if (folder_pidl != nullptr) {
+    CoTaskMemFree(folder_pidl);
+  }
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (FAILED(result)) {
+    SetLastErrorText(BuildWin32ErrorText("Reveal failed.", static_cast<DWORD>(result)));
+    return false;
+  }
+
+  SetLastErrorText("");
+  return true;
+}
+
+namespace {
+
+// Replaces the clipboard contents with one format. The clipboard takes ownership of the
+// memory only when SetClipboardData succeeds.
+bool SetClipboardBytes(HWND owner, const UINT format, const void* data, const size_t size,
+                       const char* context) {
+  HGLOBAL memory = GlobalAlloc(GMEM_MOVEABLE, size);
+  if (memory == nullptr) {
+    SetLastErrorText(BuildWin32ErrorText(context, GetLastError()));
+    return false;
+  }
+  void* target = GlobalLock(memory);
+  if (target == nullptr) {
+    SetLastErrorText(BuildWin32ErrorText(context, GetLastError()));
+    GlobalFree(memory);
+    return false;
+  }
+  std::memcpy(target, data, size);
+  GlobalUnlock(memory);
+
+  if (!OpenClipboard(owner)) {
+    SetLastErrorText(BuildWin32ErrorText(context, GetLastError()));
+    GlobalFree(memory);
+    return false;
+  }
+  EmptyClipboard();
+  const bool stored = SetClipboardData(format, memory) != nullptr;
+  const DWORD error = stored ? ERROR_SUCCESS : GetLastError();
+  CloseClipboard();
+  if (!stored) {
+    GlobalFree(memory);
+    SetLastErrorText(BuildWin32ErrorText(context, error));
+    return false;
+  }
+  return true;
+}
+
+// Converts the first frame of `decoder` into straight-alpha BGRA rows, top-down.
+HRESULT ReadFirstFrameBgra(IWICBitmapDecoder* decoder, UINT* width, UINT* height,
+                           std::vector<BYTE>* pixels) {
+  IWICBitmapFrameDecode* frame = nullptr;
+  IWICBitmapSource* converted = nullptr;
+  HRESULT result = decoder->GetFrame(0, &frame);
+  if (SUCCEEDED(result)) {
+    result = WICConvertBitmapSource(GUID_WICPixelFormat32bppBGRA, frame, &converted);
+  }
+  if (SUCCEEDED(result)) {
+    result = converted->GetSize(width, height);
+  }
+  if (SUCCEEDED(result)) {
+    const uint64_t byte_count = static_cast<uint64_t>(*width) * *height * 4;
+    if (*width == 0 || *height == 0 || byte_count > 512ULL * 1024ULL * 1024ULL) {
+      result = E_INVALIDARG;
+    } else {
+      pixels->resize(static_cast<size_t>(byte_count));
+      result = converted->CopyPixels(nullptr, *width * 4, static_cast<UINT>(byte_count),
+                                     pixels->data());
+    }
+  }
+
+  if (converted != nullptr) {
+    converted->Release();
+  }
+  if (frame != nullptr) {
+    frame->Release();
+  }
+  return result;
+}
+
+// Decodes the first frame of an image file with WIC into straight-alpha BGRA rows, top-down.
+HRESULT DecodeImageBgra(const std::wstring& path, UINT* width, UINT* height,
+                        std::vector<BYTE>* pixels) {
+  IWICImagingFactory* factory = nullptr;
+  IWICBitmapDecoder* decoder = nullptr;
+  HRESULT result = CoCreateInstance(CLSID_WICImagingFactory, nullptr, CLSCTX_INPROC_SERVER,
+                                    IID_PPV_ARGS(&factory));
+  if (SUCCEEDED(result)) {
+    result = factory->CreateDecoderFromFilename(path.c_str(), nullptr, GENERIC_READ,
+                                                WICDecodeMetadataCacheOnDemand, &decoder);
+  }
+  if (SUCCEEDED(result)) {
+    result = ReadFirstFrameBgra(decoder, width, height, pixels);
+  }
+
+  if (decoder != nullptr) {
+    decoder->Release();
+  }
+  if (factory != nullptr) {
+    factory->Release();
+  }
+  return result;
+}
+
+// Same as DecodeImageBgra for an encoded image already in memory.
+HRESULT DecodeImageBytesBgra(const uint8_t* data, const size_t size, UINT* width,
+                             UINT* height, std::vector<BYTE>* pixels) {
+  if (size == 0 || size > std::numeric_limits<DWORD>::max()) {
+    return E_INVALIDARG;
+  }
+  IWICImagingFactory* factory = nullptr;
+  IWICStream* stream = nullptr;
+  IWICBitmapDecoder* decoder = nullptr;
+  HRESULT result = CoCreateInstance(CLSID_WICImagingFactory, nullptr, CLSCTX_INPROC_SERVER,
+                                    IID_PPV_ARGS(&factory));
+  if (SUCCEEDED(result)) {
+    result = factory->CreateStream(&stream);
+  }
+  if (SUCCEEDED(result)) {
+    result = stream->InitializeFromMemory(const_cast<BYTE*>(data), static_cast<DWORD>(size));
+  }
+  if (SUCCEEDED(result)) {
+    result = factory->CreateDecoderFromStream(stream, nullptr, WICDecodeMetadataCacheOnDemand,
+                                              &decoder);
+  }
+  if (SUCCEEDED(result)) {
+    result = ReadFirstFrameBgra(decoder, width, height, pixels);
+  }
+
+  if (decoder != nullptr) {
+    decoder->Release();
+  }
+  if (stream != nullptr) {
+    stream->Release();
+  }
+  if (factory != nullptr) {
+    factory->Release();
+  }
+  return result;
+}
+
+}  // namespace
+
+extern "C" __declspec(dllexport) bool omni_set_clipboard_text(void* owner_hwnd,
+                                                              const char* text_utf8) {
+  const std::wstring text = Utf8ToWide(text_utf8 == nullptr ? "" : text_utf8);
+  if (!SetClipboardBytes(static_cast<HWND>(owner_hwnd), CF_UNICODETEXT, text.c_str(),
+                         (text.size() + 1) * sizeof(wchar_t), "Copy to clipboard failed.")) {
+    return false;
+  }
+  SetLastErrorText("");
+  return true;
+}
+
+// Places the decoded image on the clipboard as CF_DIBV5 with alpha; Windows synthesizes
+// CF_DIB and CF_BITMAP from it for applications that ask for those.
+extern "C" __declspec(dllexport) bool omni_set_clipboard_image(void* owner_hwnd,
+                                                               const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Copy to clipboard failed: empty path.");
+    return false;
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  UINT width = 0;
+  UINT height = 0;
+  std::vector<BYTE> pixels;
+  const HRESULT decoded = DecodeImageBgra(path, &width, &height, &pixels);
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (FAILED(decoded)) {
+    SetLastErrorText(BuildWin32ErrorText("Copy to clipboard failed: image could not be decoded.",
+                                         static_cast<DWORD>(decoded)));
+    return false;
+  }
+
+  BITMAPV5HEADER header{};
+  header.bV5Size = sizeof(header);
+  header.bV5Width = static_cast<LONG>(width);
+  // Positive height: rows are stored bottom-up.
+  header.bV5Height = static_cast<LONG>(height);
+  header.bV5Planes = 1;
+  header.bV5BitCount = 32;
+  header.bV5Compression = BI_BITFIELDS;
+  header.bV5SizeImage = static_cast<DWORD>(pixels.size());
+  header.bV5RedMask = 0x00FF0000;
+  header.bV5GreenMask = 0x0000FF00;
+  header.bV5BlueMask = 0x000000FF;
+  header.bV5AlphaMask = 0xFF000000;
+  header.bV5CSType = LCS_sRGB;
+  header.bV5Intent = LCS_GM_IMAGES;
+
+  const size_t row_bytes = static_cast<size_t>(width) * 4;
+  std::vector<BYTE> dib(sizeof(header) + pixels.size());
+  std::memcpy(dib.data(), &header, sizeof(header));
+  for (UINT row = 0; row < height; ++row) {
+    std::memcpy(dib.data() + sizeof(header) + static_cast<size_t>(row) * row_bytes,
+                pixels.data() + static_cast<size_t>(height - 1 - row) * row_bytes, row_bytes);
+  }
+  if (!SetClipboardBytes(static_cast<HWND>(owner_hwnd), CF_DIBV5, dib.data(), dib.size(),
+                         "Copy to clipboard failed.")) {
+    return false;
+  }
+  SetLastErrorText("");
+  return true;
+}
+
+// Changes whenever any application replaces the clipboard contents; no clipboard access needed.
+extern "C" __declspec(dllexport) uint32_t omni_clipboard_sequence() {
+  return static_cast<uint32_t>(GetClipboardSequenceNumber());
+}
+
+// Returns the paths of files copied in Explorer (CF_HDROP) as a JSON array, empty when the
+// clipboard holds something else.
+extern "C" __declspec(dllexport) char* omni_get_clipboard_paths_json() {
+  std::vector<std::wstring> paths;
+  if (IsClipboardFormatAvailable(CF_HDROP) && OpenClipboard(nullptr)) {
+    HANDLE data = GetClipboardData(CF_HDROP);
+    HDROP drop = data == nullptr ? nullptr : static_cast<HDROP>(GlobalLock(data));
+    if (drop != nullptr) {
+      const UINT count = DragQueryFileW(drop, 0xFFFFFFFF, nullptr, 0);
+      for (UINT index = 0; index < count; ++index) {
+        const UINT length = DragQueryFileW(drop, index, nullptr, 0);
+        std::wstring path(length + 1, L'\0');
+        DragQueryFileW(drop, index, path.data(), length + 1);
+        path.resize(length);
+        paths.push_back(std::move(path));
+      }
+      GlobalUnlock(data);
+    }
+    CloseClipboard();
+  }
+
+  std::string json = "[";
+  for (size_t i = 0; i < paths.size(); ++i) {
+    if (i > 0) {
+      json.push_back(',');
+    }
+    json.push_back('"');
+    AppendEscapedJsonString(&json, WideToUtf8(paths[i]));
+    json.push_back('"');
+  }
+  json.push_back(']');
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate clipboard path buffer.");
+  }
+  return out;
+}
+
+namespace {
+
+// Decodes the video frame nearest to `fraction` of the way through `path` into top-down,
+// opaque BGRA rows. Seeks land on the preceding key frame, so frames are decoded forward
+// until the target time (within a bounded number of samples).
+HRESULT DecodeVideoFrameBgra(const std::wstring& path, const double fraction, UINT32* width,
+                             UINT32* height, std::vector<BYTE>* pixels) {
+  constexpr DWORD kVideoStream = static_cast<DWORD>(MF_SOURCE_READER_FIRST_VIDEO_STREAM);
+  constexpr int kMaxSamplesAfterSeek = 240;
+  IMFAttributes* attributes = nullptr;
+  IMFSourceReader* reader = nullptr;
+  IMFMediaType* requested_type = nullptr;
+  IMFMediaType* current_type = nullptr;
+  IMFSample* sample = nullptr;
+  IMFMediaBuffer* buffer = nullptr;
+  LONG stride = 0;
+
+  HRESULT result = MFCreateAttributes(&attributes, 1);
+  if (SUCCEEDED(result)) {
+    // Lets the reader convert whatever the decoder produces into RGB32.
+    result = attributes->SetUINT32(MF_SOURCE_READER_ENABLE_VIDEO_PROCESSING, TRUE);
+  }
+  if (SUCCEEDED(result)) {
+    result = MFCreateSourceReaderFromURL(path.c_str(), attributes, &reader);
+  }
+  if (SUCCEEDED(result)) {
+    result = reader->SetStreamSelection(static_cast<DWORD>(MF_SOURCE_READER_ALL_STREAMS), FALSE);
+  }
+  if (SUCCEEDED(result)) {
+    result = reader->SetStreamSelection(kVideoStream, TRUE);
+  }
+  if (SUCCEEDED(result)) {
+    result = MFCreateMediaType(&requested_type);
+  }
+  if (SUCCEEDED(result)) {
+    result = requested_type->SetGUID(MF_MT_MAJOR_TYPE, MFMediaType_Video);
+  }
+  if (SUCCEEDED(result)) {
+    result = requested_type->SetGUID(MF_MT_SUBTYPE, MFVideoFormat_RGB32);
+  }
+  if (SUCCEEDED(result)) {
+    result = reader->SetCurrentMediaType(kVideoStream, nullptr, requested_type);
+  }
+  if (SUCCEEDED(result)) {
+    result = reader->GetCurrentMediaType(kVideoStream, &current_type);
+  }
+  if (SUCCEEDED(result)) {
+    result = MFGetAttributeSize(current_type, MF_MT_FRAME_SIZE, width, height);
+  }
+  if (SUCCEEDED(result)) {
+    if (*width == 0 || *height == 0 ||
+        static_cast<uint64_t>(*width) * *height * 4 > 512ULL * 1024ULL * 1024ULL) {
+      result = E_INVALIDARG;
+    } else {
+      UINT32 raw_stride = 0;
+      stride = SUCCEEDED(current_type->GetUINT32(MF_MT_DEFAULT_STRIDE, &raw_stride))
+                   ? static_cast<LONG>(raw_stride)
+                   : static_cast<LONG>(*width * 4);
+    }
+  }
+
+  LONGLONG target = 0;
+  if (SUCCEEDED(result)) {
+    PROPVARIANT duration;
+    PropVariantInit(&duration);
+    if (SUCCEEDED(reader->GetPresentationAttribute(
+            static_cast<DWORD>(MF_SOURCE_READER_MEDIASOURCE), MF_PD_DURATION, &duration)) &&
+        duration.vt == VT_UI8) {
+      target = static_cast<LONGLONG>(static_cast<double>(duration.uhVal.QuadPart) *
+                                     std::clamp(fraction, 0.0, 1.0));
+    }
+    PropVariantClear(&duration);
+    if (target > 0) {
+      PROPVARIANT position;
+      PropVariantInit(&position);
+      position.vt = VT_I8;
+      position.hVal.QuadPart = target;
+      // Unseekable sources simply decode from the start.
+      if (FAILED(reader->SetCurrentPosition(GUID_NULL, position))) {
+        target = 0;
+      }
+      PropVariantClear(&position);
+    }
+  }
+
+  for (int attempt = 0; SUCCEEDED(result) && attempt < kMaxSamplesAfterSeek; ++attempt) {
+    DWORD stream_flags = 0;
+    LONGLONG timestamp = 0;
+    IMFSample* next = nullptr;
+    result = reader->ReadSample(kVideoStream, 0, nullptr, &stream_flags, &timestamp, &next);
+    if (FAILED(result)) {
+      break;
+    }
+    if (next != nullptr) {
+      if (sample != nullptr) {
+        sample->Release();
+      }
+      sample = next;
+      if (timestamp >= target) {
+        break;
+      }
+    }
+    if ((stream_flags & MF_SOURCE_READERF_ENDOFSTREAM) != 0) {
+      break;
+    }
+  }
+  if (SUCCEEDED(result) && sample == nullptr) {
+    result = MF_E_END_OF_STREAM;
+  }
+  if (SUCCEEDED(result)) {
+    result = sample->ConvertToContiguousBuffer(&buffer);
+  }
+  if (SUCCEEDED(result)) {
+    BYTE* data = nullptr;
+    DWORD length = 0;
+    result = buffer->Lock(&data, nullptr, &length);
+    if (SUCCEEDED(result)) {
+      const size_t row_bytes = static_cast<size_t>(*width) * 4;
+      const size_t abs_stride = static_cast<size_t>(stride < 0 ? -stride : stride);
+      if (abs_stride < row_bytes || static_cast<size_t>(length) < abs_stride * *height) {
+        result = E_UNEXPECTED;
+      } else {
+        pixels->resize(row_bytes * *height);
+        for (UINT32 row = 0; row < *height; ++row) {
+          // A negative stride means the buffer holds the rows bottom-up.
+          const UINT32 source_row = stride < 0 ? *height - 1 - row : row;
+          BYTE* target_row = pixels->data() + static_cast<size_t>(row) * row_bytes;
+          std::memcpy(target_row, data + static_cast<size_t>(source_row) * abs_stride,
+                      row_bytes);
+          for (size_t alpha = 3; alpha < row_bytes; alpha += 4) {
+            target_row[alpha] = 0xFF;
+          }
+        }
+      }
+      buffer->Unlock();
+    }
+  }
+
+  if (buffer != nullptr) {
+    buffer->Release();
+  }
+  if (sample != nullptr) {
+    sample->Release();
+  }
+  if (current_type != nullptr) {
+    current_type->Release();
+  }
+  if (requested_type != nullptr) {
+    requested_type->Release();
+  }
+  if (reader != nullptr) {
+    reader->Release();
+  }
+  if (attributes != nullptr) {
+    attributes->Release();
+  }
+  return result;
+}
+
+}  // namespace
+
+// Decodes one video frame at `fraction` (0..1) of the duration and returns its opaque BGRA
+// pixels, top-down, as a malloc'd buffer of `width * height * 4` bytes. Release it with
+// `omni_free_string`.
+extern "C" __declspec(dllexport) uint8_t* omni_read_video_frame(const char* path_utf8,
+                                                                double fraction,
+                                                                uint32_t* out_width,
+                                                                uint32_t* out_height) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty() || out_width == nullptr || out_height == nullptr) {
+    SetLastErrorText("Video frame failed: empty path.");
+    return nullptr;
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  const HRESULT startup = MFStartup(MF_VERSION, MFSTARTUP_LITE);
+  UINT32 width = 0;
+  UINT32 height = 0;
+  std::vector<BYTE> pixels;
+  const HRESULT decoded = SUCCEEDED(startup)
+                              ? DecodeVideoFrameBgra(path, fraction, &width, &height, &pixels)
+                              : startup;
+  if (SUCCEEDED(startup)) {
+    MFShutdown();
+  }
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (FAILED(decoded)) {
+    SetLastErrorText(BuildWin32ErrorText("Video frame failed: video could not be decoded.",
+                                         static_cast<DWORD>(decoded)));
+    return nullptr;
+  }
+
+  auto* out = static_cast<uint8_t*>(std::malloc(pixels.size()));
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate video frame buffer.");
+    return nullptr;
+  }
+  std::memcpy(out, pixels.data(), pixels.size());
+  *out_width = width;
+  *out_height = height;
+  SetLastErrorText("");
+  return out;
+}
+
+// Decodes an encoded image held in memory (any format WIC supports) into BGRA pixels,
+// returned the same way as omni_read_video_frame.
+extern "C" __declspec(dllexport) uint8_t* omni_decode_image_bytes(const uint8_t* data,
+                                                                  size_t size,
+                                                                  uint32_t* out_width,
+                                                                  uint32_t* out_height) {
+  if (data == nullptr || size == 0 || out_width == nullptr || out_height == nullptr) {
+    SetLastErrorText("Image decode failed: no image data.");
+    return nullptr;
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  UINT width = 0;
+  UINT height = 0;
+  std::vector<BYTE> pixels;
+  const HRESULT decoded = DecodeImageBytesBgra(data, size, &width, &height, &pixels);
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (FAILED(decoded)) {
+    SetLastErrorText(BuildWin32ErrorText("Image decode failed: image could not be decoded.",
+                                         static_cast<DWORD>(decoded)));
+    return nullptr;
+  }
+
+  auto* out = static_cast<uint8_t*>(std::malloc(pixels.size()));
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate image buffer.");
+    return nullptr;
+  }
+  std::memcpy(out, pixels.data(), pixels.size());
+  *out_width = width;
+  *out_height = height;
+  SetLastErrorText("");
+  return out;
+}
+
+extern "C" __declspec(dllexport) char* omni_resolve_shortcut_json(const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Resolve shortcut failed: empty path.");
+    return nullptr;
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  IShellLinkW* link = nullptr;
+  IPersistFile* persist = nullptr;
+  HRESULT result = CoCreateInstance(CLSID_ShellLink, nullptr, CLSCTX_INPROC_SERVER,
+                                    IID_IShellLinkW, reinterpret_cast<void**>(&link));
+  if (SUCCEEDED(result)) {
+    result = link->QueryInterface(IID_IPersistFile, reinterpret_cast<void**>(&persist));
+  }
+  if (SUCCEEDED(result)) {
+    result = persist->Load(path.c_str(), STGM_READ);
+  }
+
+  std::string json;
+  if (SUCCEEDED(result)) {
+    wchar_t target[MAX_PATH * 4] = L"";
+    wchar_t arguments[INFOTIPSIZE] = L"";
+    wchar_t working_directory[MAX_PATH * 4] = L"";
+    wchar_t icon_location[MAX_PATH * 4] = L"";
+    wchar_t description[INFOTIPSIZE] = L"";
+    int icon_index = 0;
+    // Shortcuts to shell items (Control Panel, etc.) have no file system target.
+    if (link->GetPath(target, ARRAYSIZE(target), nullptr, 0) != S_OK) {
+      target[0] = L'\0';
+    }
+    link->GetArguments(arguments, ARRAYSIZE(arguments));
+    link->GetWorkingDirectory(working_directory, ARRAYSIZE(working_directory));
+    link->GetIconLocation(icon_location, ARRAYSIZE(icon_location), &icon_index);
+    link->GetDescription(description, ARRAYSIZE(description));
+
+    json.reserve(512);
+    json.append("{\"path\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(path));
+    json.append("\",\"targetPath\":");
+    if (target[0] == L'\0') {
+      json.append("null");
+    } else {
+      json.push_back('"');
+      AppendEscapedJsonString(&json, WideToUtf8(target));
+      json.push_back('"');
+    }
+    json.append(",\"arguments\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(arguments));
+    json.append("\",\"workingDirectory\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(working_directory));
+    json.append("\",\"iconLocation\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(icon_location));
+    json.append("\",\"iconIndex\":");
+    json.append(std::to_string(icon_index));
+    json.append(",\"description\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(description));
+    json.append("\"}");
+  }
+
+  if (persist != nullptr) {
+    persist->Release();
+  }
+  if (link != nullptr) {
+    link->Release();
+  }
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (FAILED(result)) {
+    SetLastErrorText(BuildWin32ErrorText("Resolve shortcut failed.",
+                                         static_cast<DWORD>(result)));
+    return nullptr;
+  }
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate shortcut buffer.");
+  }
+  return out;
+}
+
+// Finds indexed .lnk files (within the scope when given) whose file system target is gone.
+// Only a not-found answer counts as broken: targets on unplugged drives or unreachable shares
+// are left out, as are shortcuts to shell items that have no file system target.
+extern "C" __declspec(dllexport) char* omni_find_broken_shortcuts_json(
+    const char* within_path_utf8) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+
+  std::wstring scope = Utf8ToWide(within_path_utf8 == nullptr ? "" : within_path_utf8);
+  while (scope.size() > 3 && (scope.back() == L'\\' || scope.back() == L'/')) {
+    scope.pop_back();
+  }
+
+  std::vector<std::wstring> shortcut_paths;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (file.is_directory || IndexedFileExtensionLower(file) != L"lnk" ||
+          !IsPathWithinScope(file.path, scope)) {
+        continue;
+      }
+      shortcut_paths.push_back(file.path);
+    }
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  IShellLinkW* link = nullptr;
+  IPersistFile* persist = nullptr;
+  HRESULT result = CoCreateInstance(CLSID_ShellLink, nullptr, CLSCTX_INPROC_SERVER,
+                                    IID_IShellLinkW, reinterpret_cast<void**>(&link));
+  if (SUCCEEDED(result)) {
+    result = link->QueryInterface(IID_IPersistFile, reinterpret_cast<void**>(&persist));
+  }
+
+  std::string json;
+  json.push_back('[');
+  bool first = true;
+  if (SUCCEEDED(result)) {
+    for (const std::wstring& shortcut_path : shortcut_paths) {
+      if (FAILED(persist->Load(shortcut_path.c_str(), STGM_READ))) {
+        continue;
+      }
+      wchar_t target[MAX_PATH * 4] = L"";
+      if (link->GetPath(target, ARRAYSIZE(target), nullptr, 0) != S_OK || target[0] == L'\0') {
+        continue;
+      }
+      if (GetFileAttributesW(target) != INVALID_FILE_ATTRIBUTES) {
+        continue;
+      }
+      const DWORD error = GetLastError();
+      if (error != ERROR_FILE_NOT_FOUND && error != ERROR_PATH_NOT_FOUND) {
+        continue;
+      }
+
+      if (!first) {
+        json.push_back(',');
+      }
+      first = false;
+      json.append("{\"name\":\"");
+      AppendEscapedJsonString(&json, WideToUtf8(ExtractFileNameFromPath(shortcut_path)));
+      json.append("\",\"path\":\"");
+      AppendEscapedJsonString(&json, WideToUtf8(shortcut_path));
+      json.append("\",\"targetPath\":\"");
+      AppendEscapedJsonString(&json, WideToUtf8(target));
+      json.append("\"}");
+    }
+  }
+  json.push_back(']');
+
+  if (persist != nullptr) {
+    persist->Release();
+  }
+  if (link != nullptr) {
+    link->Release();
+  }
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (FAILED(result)) {
+    SetLastErrorText(BuildWin32ErrorText("Broken shortcut scan failed.",
+                                         static_cast<DWORD>(result)));
+    return nullptr;
+  }
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate broken shortcuts buffer.");
+  }
+  return out;
+}
+
+namespace {
+
+// Reads an unsigned property from a shell property store; false when it is absent or empty.
+bool ReadPropertyUInt64(IPropertyStore* store, const PROPERTYKEY& key, uint64_t* out) {
+  PROPVARIANT value;
+  PropVariantInit(&value);
+  bool found = false;
+  if (SUCCEEDED(store->GetValue(key, &value))) {
+    switch (value.vt) {
+      case VT_UI4:
+        *out = value.ulVal;
+        found = true;
+        break;
+      case VT_UI8:
+        *out = value.uhVal.QuadPart;
+        found = true;
+        break;
+      default:
+        break;
+    }
+  }
+  PropVariantClear(&value);
+  return found;
+}
+
+void AppendOptionalNumberJson(std::string* json, const char* key, const bool present,
+                              const std::string& value) {
+  json->append(",\"");
+  json->append(key);
+  json->append("\":");
+  json->append(present ? value : "null");
+}
+
+}  // namespace
+
+// Reads the media properties Explorer shows through the shell property system: duration,
+// pixel dimensions and the camera "date taken". Properties a file does not carry are null.
+extern "C" __declspec(dllexport) char* omni_media_metadata_json(const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Media metadata failed: empty path.");
+    return nullptr;
+  }
+
+  const HRESULT com_result = CoInitializeEx(nullptr, COINIT_APARTMENTTHREADED);
+  IPropertyStore* store = nullptr;
+  const HRESULT result =
+      SHGetPropertyStoreFromParsingName(path.c_str(), nullptr, GPS_DEFAULT, IID_PPV_ARGS(&store));
+  std::string json;
+  if (SUCCEEDED(result)) {
+    // Duration is in 100-nanosecond units.
+    uint64_t duration = 0;
+    const bool has_duration = ReadPropertyUInt64(store, PKEY_Media_Duration, &duration);
+    uint64_t width = 0;
+    uint64_t height = 0;
+    bool has_size = ReadPropertyUInt64(store, PKEY_Image_HorizontalSize, &width) &&
+                    ReadPropertyUInt64(store, PKEY_Image_VerticalSize, &height);
+    if (!has_size) {
+      has_size = ReadPropertyUInt64(store, PKEY_Video_FrameWidth, &width) &&
+                 ReadPropertyUInt64(store, PKEY_Video_FrameHeight, &height);
+    }
+    int64_t date_taken = 0;
+    PROPVARIANT taken;
+    PropVariantInit(&taken);
+    if (SUCCEEDED(store->GetValue(PKEY_Photo_DateTaken, &taken)) && taken.vt == VT_FILETIME) {
+      date_taken = FileTimeToUnixSeconds(taken.filetime);
+    }
+    PropVariantClear(&taken);
+    store->Release();
+
+    json.reserve(256);
+    json.append("{\"path\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(path));
+    json.push_back('"');
+    AppendOptionalNumberJson(&json, "durationMs", has_duration,
+                             std::to_string(duration / 10000));
+    AppendOptionalNumberJson(&json, "width", has_size && width > 0, std::to_string(width));
+    AppendOptionalNumberJson(&json, "height", has_size && height > 0, std::to_string(height));
+    AppendOptionalNumberJson(&json, "dateTakenUnix", date_taken > 0,
+                             std::to_string(date_taken));
+    json.push_back('}');
+  }
+  if (SUCCEEDED(com_result)) {
+    CoUninitialize();
+  }
+  if (FAILED(result)) {
+    SetLastErrorText(BuildWin32ErrorText("Media metadata failed.", static_cast<DWORD>(result)));
+    return nullptr;
+  }
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate media metadata buffer.");
+  }
+  return out;
+}
+
+namespace {
+
+// Reads one `\StringFileInfo\<language><codepage>\<name>` value, trying each translation
+// the resource lists and then US English. Returns an empty string when none carries it.
+std::wstring QueryVersionString(const std::vector<BYTE>& block, const wchar_t* name) {
+  struct Translation {
+    WORD language;
+    WORD codepage;
+  };
+  std::vector<Translation> translations;
+  Translation* listed = nullptr;
+  UINT listed_bytes = 0;
+  if (VerQueryValueW(block.data(), L"\\VarFileInfo\\Translation",
+                     reinterpret_cast<void**>(&listed), &listed_bytes) &&
+      listed != nullptr) {
+    translations.assign(listed, listed + listed_bytes / sizeof(Translation));
+  }
+  translations.push_back(Translation{0x0409, 1200});
+  translations.push_back(Translation{0x0409, 1252});
+
+  for (const Translation& translation : translations) {
+    wchar_t sub_block[96];
+    std::swprintf(sub_block, std::size(sub_block), L"\\StringFileInfo\\%04x%04x\\%ls",
+                  translation.language, translation.codepage, name);
+    wchar_t* value = nullptr;
+    UINT value_chars = 0;
+    if (VerQueryValueW(block.data(), sub_block, reinterpret_cast<void**>(&value),
+                       &value_chars) &&
+        value != nullptr && value_chars > 1) {
+      return TrimWhitespace(std::wstring(value, value_chars - 1));
+    }
+  }
+  return std::wstring();
+}
+
+void AppendOptionalStringJson(std::string* json, const char* key, const std::wstring& value) {
+  json->append(",\"");
+  json->append(key);
+  json->append("\":");
+  if (value.empty()) {
+    json->append("null");
+    return;
+  }
+  json->push_back('"');
+  AppendEscapedJsonString(json, WideToUtf8(value));
+  json->push_back('"');
+}
+
+}  // namespace
+
+// Reads the version resource of an executable or DLL: product and company names, the file
+// description and both version numbers. The file version falls back to the numeric
+// VS_FIXEDFILEINFO value when no string is present. Fields the resource lacks are null.
+extern "C" __declspec(dllexport) char* omni_file_version_info_json(const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Version info failed: empty path.");
+    return nullptr;
+  }
+
+  DWORD handle = 0;
+  const DWORD size = GetFileVersionInfoSizeW(path.c_str(), &handle);
+  if (size == 0) {
+    SetLastErrorText(BuildWin32ErrorText("Version info failed: no version resource.",
+                                         GetLastError()));
+    return nullptr;
+  }
+  std::vector<BYTE> block(size);
+  if (!GetFileVersionInfoW(path.c_str(), 0, size, block.data())) {
+    SetLastErrorText(BuildWin32ErrorText("Version info failed.", GetLastError()));
+    return nullptr;
+  }
+
+  std::wstring file_version = QueryVersionString(block, L"FileVersion");
+  VS_FIXEDFILEINFO* fixed = nullptr;
+  UINT fixed_bytes = 0;
+  if (file_version.empty() &&
+      VerQueryValueW(block.data(), L"\\", reinterpret_cast<void**>(&fixed), &fixed_bytes) &&
+      fixed != nullptr && fixed_bytes >= sizeof(VS_FIXEDFILEINFO)) {
+    file_version = std::to_wstring(HIWORD(fixed->dwFileVersionMS)) + L"." +
+                   std::to_wstring(LOWORD(fixed->dwFileVersionMS)) + L"." +
+                   std::to_wstring(HIWORD(fixed->dwFileVersionLS)) + L"." +
+                   std::to_wstring(LOWORD(fixed->dwFileVersionLS));
+  }
+
+  std::string json;
+  json.reserve(256);
+  json.append("{\"path\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(path));
+  json.push_back('"');
+  AppendOptionalStringJson(&json, "productName", QueryVersionString(block, L"ProductName"));
+  AppendOptionalStringJson(&json, "productVersion",
+                           QueryVersionString(block, L"ProductVersion"));
+  AppendOptionalStringJson(&json, "fileVersion", file_version);
+  AppendOptionalStringJson(&json, "companyName", QueryVersionString(block, L"CompanyName"));
+  AppendOptionalStringJson(&json, "fileDescription",
+                           QueryVersionString(block, L"FileDescription"));
+  json.push_back('}');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate version info buffer.");
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) bool omni_set_file_attributes(const char* path_utf8,
+                                                               uint32_t mask,
+                                                               uint32_t value) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Set attributes failed: empty path.");
+    return false;
+  }
+
+  const std::wstring extended_path = ToExtendedLengthPath(path);
+  const DWORD current = GetFileAttributesW(extended_path.c_str());
+  if (current == INVALID_FILE_ATTRIBUTES) {
+    SetLastErrorText(BuildWin32ErrorText("Set attributes failed: path not found.",
+                                         GetLastError()));
+    return false;
+  }
+
+  const DWORD next = (current & ~mask) | (value & mask);
+  if (next != current) {
+    // FILE_ATTRIBUTE_NORMAL is only valid on its own, when every other bit is cleared.
+    const DWORD applied = next == 0 ? FILE_ATTRIBUTE_NORMAL : next;
+    if (!SetFileAttributesW(extended_path.c_str(), applied)) {
+      SetLastErrorText(BuildWin32ErrorText("Set attributes failed.", GetLastError()));
+      return false;
+    }
+
+    std::unique_lock<std::shared_mutex> lock(g_index_mutex);
+    UpdateIndexedAttributesByPathLocked(path, static_cast<uint32_t>(next));
+  }
+
+  SetLastErrorText("");
+  return true;
+}
+
+namespace {
+
+constexpr DWORD kFolderWatchBufferBytes = 64 * 1024;
+constexpr DWORD kFolderWatchFilter = FILE_NOTIFY_CHANGE_FILE_NAME | FILE_NOTIFY_CHANGE_DIR_NAME |
+                                     FILE_NOTIFY_CHANGE_SIZE | FILE_NOTIFY_CHANGE_LAST_WRITE;
+
+// One open directory handle with at most one outstanding overlapped read. Each watch is
+// drained by a single waiter; the handles close once the last reference is dropped.
+struct FolderWatch {
+  std::wstring root;
+  bool recursive = true;
+  HANDLE directory = INVALID_HANDLE_VALUE;
+  HANDLE event = nullptr;
+  OVERLAPPED overlapped{};
+  std::vector<DWORD> buffer = std::vector<DWORD>(kFolderWatchBufferBytes / sizeof(DWORD));
+  bool pending = false;
+
+  ~FolderWatch() {
+    if (directory != INVALID_HANDLE_VALUE) {
+      if (pending) {
+        // The kernel writes into `buffer` until the cancelled read completes.
+        CancelIoEx(directory, &overlapped);
+        DWORD ignored = 0;
+        GetOverlappedResult(directory, &overlapped, &ignored, TRUE);
+      }
+      CloseHandle(directory);
+    }
+    if (event != nullptr) {
+      CloseHandle(event);
+    }
+  }
+};
+
+std::mutex g_folder_watch_mutex;
+std::unordered_map<uint32_t, std::shared_ptr<FolderWatch>> g_folder_watches;
+std::atomic<uint32_t> g_next_folder_watch_id{1};
+
+const char* FolderChangeLabel(DWORD action) {
+  switch (action) {
+    case FILE_ACTION_ADDED:
+      return "added";
+    case FILE_ACTION_REMOVED:
+      return "removed";
+    case FILE_ACTION_RENAMED_OLD_NAME:
+      return "renamedFrom";
+    case FILE_ACTION_RENAMED_NEW_NAME:
+      return "renamedTo";
+    default:
+      return "modified";
+  }
+}
+
+void AppendFolderChangeJson(std::string* json, bool* first, const char* change,
+                            const std::wstring& path) {
+  if (!*first) {
+    json->push_back(',');
+  }
+  *first = false;
+  json->append("{\"change\":\"");
+  json->append(change);
+  json->append("\",\"path\":\"");
+  AppendEscapedJsonString(json, WideToUtf8(path));
+  json->append("\"}");
+}
+
+}  // namespace
+
+extern "C" __declspec(dllexport) uint32_t omni_watch_folder(const char* path_utf8,
+                                                            bool recursive) {
+  std::wstring root = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  while (root.size() > 3 && (root.back() == L'\\' || root.back() == L'/')) {
+    root.pop_back();
+  }
+  if (root.empty()) {
+    SetLastErrorText("Watch folder failed: empty path.");
+    return 0;
+  }
+
+  auto watch = std::make_shared<FolderWatch>();
+  watch->root = root;
+  watch->recursive = recursive;
+  watch->directory = CreateFileW(root.c_str(), FILE_LIST_DIRECTORY,
+                                 FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, nullptr,
+                                 OPEN_EXISTING, FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_OVERLAPPED,
+                                 nullptr);
+  if (watch->directory == INVALID_HANDLE_VALUE) {
+    SetLastErrorText(BuildWin32ErrorText("Watch folder failed.", GetLastError()));
+    return 0;
+  }
+  watch->event = CreateEventW(nullptr, TRUE, FALSE, nullptr);
+  if (watch->event == nullptr) {
+    SetLastErrorText(BuildWin32ErrorText("Watch folder failed.", GetLastError()));
+    return 0;
+  }
+
+  const uint32_t watch_id = g_next_folder_watch_id.fetch_add(1);
+  {
+    std::lock_guard<std::mutex> lock(g_folder_watch_mutex);
+    g_folder_watches[watch_id] = std::move(watch);
+  }
+  SetLastErrorText("");
+  return watch_id;
+}
+
+// Waits up to `timeout_ms` for the next batch of changes and returns them as a JSON array,
+// empty on timeout. Returns null once the watch is stopped or the directory becomes unreadable.
+extern "C" __declspec(dllexport) char* omni_wait_folder_changes_json(uint32_t watch_id,
+                                                                     uint32_t timeout_ms) {
+  std::shared_ptr<FolderWatch> watch;
+  {
+    std::lock_guard<std::mutex> lock(g_folder_watch_mutex);
+    const auto found = g_folder_watches.find(watch_id);
+    if (found != g_folder_watches.end()) {
+      watch = found->second;
+    }
+  }
+  if (!watch) {
+    SetLastErrorText("Folder watch is not active.");
+    return nullptr;
+  }
+
+  if (!watch->pending) {
+    ResetEvent(watch->event);
+    watch->overlapped = OVERLAPPED{};
+    watch->overlapped.hEvent = watch->event;
+    if (!ReadDirectoryChangesW(watch->directory, watch->buffer.data(),
+                               static_cast<DWORD>(watch->buffer.size() * sizeof(DWORD)),
+                               watch->recursive ? TRUE : FALSE, kFolderWatchFilter, nullptr,
+                               &watch->overlapped, nullptr)) {
+      SetLastErrorText(BuildWin32ErrorText("Watch folder failed.", GetLastError()));
+      return nullptr;
+    }
+    watch->pending = true;
+  }
+
+  std::string json = "[";
+  bool first = true;
+  if (WaitForSingleObject(watch->event, timeout_ms) == WAIT_OBJECT_0) {
+    DWORD bytes = 0;
+    const BOOL ok = GetOverlappedResult(watch->directory, &watch->overlapped, &bytes, FALSE);
+    watch->pending = false;
+    if (!ok) {
+      const DWORD error = GetLastError();
+      SetLastErrorText(error == ERROR_OPERATION_ABORTED
+                           ? std::string("Folder watch stopped.")
+                           : BuildWin32ErrorText("Watch folder failed.", error));
+      return nullptr;
+    }
+
+    if (bytes == 0) {
+      // The change buffer overflowed; callers should rescan the whole folder.
+      AppendFolderChangeJson(&json, &first, "overflow", watch->root);
+    } else {
+      const uint8_t* cursor = reinterpret_cast<const uint8_t*>(watch->buffer.data());
+      while (true) {
+        const auto* info = reinterpret_cast<const FILE_NOTIFY_INFORMATION*>(cursor);
+        const std::wstring name(info->FileName, info->FileNameLength / sizeof(wchar_t));
+        std::wstring path = watch->root;
+        if (!path.empty() && path.back() != L'\\') {
+          path.push_back(L'\\');
+        }
+        path.append(name);
+        AppendFolderChangeJson(&json, &first, FolderChangeLabel(info->Action), path);
+        if (info->NextEntryOffset == 0) {
+          break;
+        }
+        cursor += info->NextEntryOffset;
+      }
+    }
+  }
+  json.push_back(']');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate folder change buffer.");
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) bool omni_unwatch_folder(uint32_t watch_id) {
+  std::shared_ptr<FolderWatch> watch;
+  {
+    std::lock_guard<std::mutex> lock(g_folder_watch_mutex);
+    const auto found = g_folder_watches.find(watch_id);
+    if (found == g_folder_watches.end()) {
+      SetLastErrorText("Folder watch is not active.");
+      return false;
+    }
+    watch = std::move(found->second);
+    g_folder_watches.erase(found);
+  }
+
+  // Wakes a waiter blocked on this watch; it sees the aborted read and stops.
+  CancelIoEx(watch->directory, nullptr);
+  SetLastErrorText("");
+  return true;
+}
+
+namespace {
+
+struct SnapshotEntry {
+  std::wstring path;
+  uint64_t size = 0;
+  int64_t modified_unix = 0;
+};
+
+constexpr char kSnapshotHeader[] = "OMNI-SNAPSHOT 2";
+constexpr char kSnapshotHeaderV1[] = "OMNI-SNAPSHOT 1";
+constexpr char kSnapshotVolumePrefix[] = "volume\t";
+
+// Reads size and modification time for every indexed file, in parallel.
+std::vector<SnapshotEntry> CollectIndexSnapshot() {
+  std::vector<std::wstring> file_paths;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    file_paths.reserve(g_indexed_files.size());
+    for (const IndexedFile& file : g_indexed_files) {
+      if (!file.is_directory) {
+        file_paths.push_back(file.path);
+      }
+    }
+  }
+
+  std::vector<SnapshotEntry> entries(file_paths.size());
+  std::vector<uint8_t> entry_ok(file_paths.size(), 0);
+  const size_t worker_count = ComputeDuplicateWorkerCount(file_paths.size());
+  std::atomic<size_t> next_index{0};
+  std::vector<std::thread> workers;
+  workers.reserve(worker_count);
+  for (size_t worker = 0; worker < worker_count; ++worker) {
+    workers.emplace_back([&]() {
+      while (true) {
+        const size_t index = next_index.fetch_add(1, std::memory_order_relaxed);
+        if (index >= file_paths.size()) {
+          return;
+        }
+        int64_t created = 0;
+        SnapshotEntry& entry = entries[index];
+        if (ReadFileMetadata(file_paths[index], &entry.size, &created,
+                             &entry.modified_unix)) {
+          entry.path = file_paths[index];
+          entry_ok[index] = 1;
+        }
+      }
+    });
+  }
+  for (std::thread& worker : workers) {
+    worker.join();
+  }
+
+  std::vector<SnapshotEntry> loaded;
+  loaded.reserve(entries.size());
+  for (size_t i = 0; i < entries.size(); ++i) {
+    if (entry_ok[i]) {
+      loaded.push_back(std::move(entries[i]));
+    }
+  }
+  return loaded;
+}
+
+bool ReadWholeFile(const std::wstring& path, std::string* out, std::string* out_error) {
+  FILE* file = _wfopen(path.c_str(), L"rb");
+  if (file == nullptr) {
+    *out_error = "Failed to open the index snapshot.";
+    return false;
+  }
+  char buffer[64 * 1024];
+  size_t read = 0;
+  while ((read = std::fread(buffer, 1, sizeof(buffer), file)) > 0) {
+    out->append(buffer, read);
+  }
+  const bool failed = std::ferror(file) != 0;
+  std::fclose(file);
+  if (failed) {
+    *out_error = "Failed to read the index snapshot.";
+    return false;
+  }
+  return true;
+}
+
+// Snapshot lines are "modified<TAB>size<TAB>path", after a version header line.
+// Version 2 adds "volume<TAB>letter<TAB>guid" lines recording which volume each letter was.
+bool ParseIndexSnapshot(const std::string& content,
+                        std::unordered_map<std::wstring, SnapshotEntry>* out_entries,
+                        std::unordered_map<wchar_t, std::wstring>* out_volumes,
+                        std::string* out_error) {
+  size_t line_start = 0;
+  bool header_seen = false;
+  while (line_start < content.size()) {
+    size_t line_end = content.find('\n', line_start);
+    if (line_end == std::string::npos) {
+      line_end = content.size();
+    }
+    std::string line = content.substr(line_start, line_end - line_start);
+    line_start = line_end + 1;
+    if (!line.empty() && line.back() == '\r') {
+      line.pop_back();
+    }
+    if (!header_seen) {
+      if (line != kSnapshotHeader && line != kSnapshotHeaderV1) {
+        *out_error = "File is not an OmniSearch index snapshot.";
+        return false;
+      }
+      header_seen = true;
+      continue;
+    }
+    if (line.rfind(kSnapshotVolumePrefix, 0) == 0) {
+      const std::wstring volume = Utf8ToWide(line.substr(sizeof(kSnapshotVolumePrefix) - 1));
+      const size_t tab = volume.find(L'\t');
+      if (tab == 1 && volume.size() > 2) {
+        (*out_volumes)[static_cast<wchar_t>(std::towupper(volume[0]))] = volume.substr(2);
+      }
+      continue;
+    }
+    const size_t first_tab = line.find('\t');
+    const size_t second_tab =
+        first_tab == std::string::npos ? std::string::npos : line.find('\t', first_tab + 1);
+    if (second_tab == std::string::npos) {
+      continue;
+    }
+    SnapshotEntry entry;
+    entry.modified_unix = _strtoi64(line.substr(0, first_tab).c_str(), nullptr, 10);
+    entry.size =
+        _strtoui64(line.substr(first_tab + 1, second_tab - first_tab - 1).c_str(), nullptr, 10);
+    entry.path = Utf8ToWide(line.substr(second_tab + 1));
+    if (!entry.path.empty()) {
+      std::wstring key = ToLower(entry.path);
+      out_entries->insert_or_assign(std::move(key), std::move(entry));
+    }
+  }
+  if (!header_seen) {
+    *out_error = "File is not an OmniSearch index snapshot.";
+    return false;
+  }
+  return true;
+}
+
+// Rewrites snapshot paths whose volume now has a different letter, matching on volume GUID.
+// Letters whose GUID is unknown or no longer mounted are kept as they were.
+void RemapSnapshotDriveLetters(const std::unordered_map<wchar_t, std::wstring>& volumes,
+                               std::unordered_map<std::wstring, SnapshotEntry>* entries) {
+  if (volumes.empty()) {
+    return;
+  }
+  std::unordered_map<std::wstring, wchar_t> current_letters;
+  for (const DriveInfo& drive : list_drives_internal()) {
+    if (!drive.volume_guid.empty()) {
+      current_letters[ToLower(drive.volume_guid)] = drive.letter[0];
+    }
+  }
+  std::unordered_map<wchar_t, wchar_t> letter_map;
+  for (const auto& volume : volumes) {
+    const auto current = current_letters.find(ToLower(volume.second));
+    if (current != current_letters.end() && current->second != volume.first) {
+      letter_map[volume.first] = current->second;
+    }
+  }
+  if (letter_map.empty()) {
+    return;
+  }
+
+  std::unordered_map<std::wstring, SnapshotEntry> remapped;
+  remapped.reserve(entries->size());
+  for (auto& item : *entries) {
+    SnapshotEntry entry = std::move(item.second);
+    if (!entry.path.empty()) {
+      const auto mapped =
+          letter_map.find(static_cast<wchar_t>(std::towupper(entry.path[0])));
+      if (mapped != letter_map.end()) {
+        entry.path[0] = mapped->second;
+      }
+    }
+    std::wstring key = ToLower(entry.path);
+    remapped.insert_or_assign(std::move(key), std::move(entry));
+  }
+  *entries = std::move(remapped);
+}
+
+void AppendJsonPathArray(std::string* json, const std::vector<std::wstring>& paths) {
+  json->push_back('[');
+  for (size_t i = 0; i < paths.size(); ++i) {
+    if (i > 0) {
+      json->push_back(',');
+    }
+    json->push_back('"');
+    AppendEscapedJsonString(json, WideToUtf8(paths[i]));
+    json->push_back('"');
+  }
+  json->push_back(']');
+}
+
+// Size of `root`, summed from the sizes the index keeps for the files beneath it, so no file
+// is read from disk.
+DirectorySizeSummary ComputeDirectorySize(const std::wstring& root) {
+  DirectorySizeSummary summary;
+  std::unordered_set<std::wstring> subdirectories;
+  std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+  for (const IndexedFile& file : g_indexed_files) {
+    if (!IsPathWithinScope(file.path, root)) {
+      continue;
+    }
+    if (file.is_directory) {
+      subdirectories.insert(ToLower(file.path));
+      continue;
+    }
+    summary.total_bytes += file.size;
+    ++summary.file_count;
+    // Folders are only indexed when requested, so also derive them from file parents.
+    std::wstring parent = ParentPathOf(file.path);
+    while (IsPathWithinScope(parent, root)) {
+      if (!subdirectories.insert(ToLower(parent)).second) {
+        break;
+      }
+      parent = ParentPathOf(parent);
+    }
+  }
+  summary.subdir_count = static_cast<uint64_t>(subdirectories.size());
+  return summary;
+}
+
+struct ExtensionTotals {
+  uint64_t file_count = 0;
+  uint64_t total_bytes = 0;
+};
+
+struct FolderSummary {
+  DirectorySizeSummary totals;
+  std::unordered_map<std::wstring, ExtensionTotals> extensions;
+};
+
+FolderSummary ComputeFolderSummary(const std::wstring& root) {
+  std::vector<std::wstring> file_paths;
+  std::unordered_set<std::wstring> subdirectories;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (!IsPathWithinScope(file.path, root)) {
+        continue;
+      }
+      if (file.is_directory) {
+        subdirectories.insert(ToLower(file.path));
+        continue;
+      }
+      file_paths.push_back(file.path);
+    }
+  }
+  for (const std::wstring& path : file_paths) {
+    std::wstring parent = ParentPathOf(path);
+    while (IsPathWithinScope(parent, root)) {
+      if (!subdirectories.insert(ToLower(parent)).second) {
+        break;
+      }
+      parent = ParentPathOf(parent);
+    }
+  }
+
+  const size_t worker_count = ComputeDuplicateWorkerCount(file_paths.size());
+  std::vector<std::unordered_map<std::wstring, ExtensionTotals>> partials(worker_count);
+  std::atomic<size_t> next_index{0};
+  std::vector<std::thread> workers;
+  workers.reserve(worker_count);
+  for (size_t worker = 0; worker < worker_count; ++worker) {
+    workers.emplace_back([&, worker]() {
+      std::unordered_map<std::wstring, ExtensionTotals>& partial = partials[worker];
+      while (true) {
+        const size_t index = next_index.fetch_add(1, std::memory_order_relaxed);
+        if (index >= file_paths.size()) {
+          return;
+        }
+        uint64_t size = 0;
+        int64_t created = 0;
+        int64_t modified = 0;
+        if (!ReadFileMetadata(file_paths[index], &size, &created, &modified)) {
+          continue;
+        }
+        ExtensionTotals& totals =
+            partial[ExtractExtensionLower(ExtractFileNameFromPath(file_paths[index]))];
+        ++totals.file_count;
+        totals.total_bytes += size;
+      }
+    });
+  }
+  for (std::thread& worker : workers) {
+    worker.join();
+  }
+
+  FolderSummary summary;
+  for (const auto& partial : partials) {
+    for (const auto& [extension, totals] : partial) {
+      ExtensionTotals& merged = summary.extensions[extension];
+      merged.file_count += totals.file_count;
+      merged.total_bytes += totals.total_bytes;
+      summary.totals.file_count += totals.file_count;
+      summary.totals.total_bytes += totals.total_bytes;
+    }
+  }
+  summary.totals.subdir_count = static_cast<uint64_t>(subdirectories.size());
+  return summary;
+}
+
+void AppendExtensionTotalsJson(
+    std::string* json,
+    const std::vector<std::pair<std::wstring, ExtensionTotals>>& entries) {
+  json->push_back('[');
+  for (size_t i = 0; i < entries.size(); ++i) {
+    if (i > 0) {
+      json->push_back(',');
+    }
+    json->append("{\"extension\":\"");
+    AppendEscapedJsonString(json, WideToUtf8(entries[i].first));
+    json->append("\",\"fileCount\":");
+    json->append(std::to_string(entries[i].second.file_count));
+    json->append(",\"totalBytes\":");
+    json->append(std::to_string(entries[i].second.total_bytes));
+    json->push_back('}');
+  }
+  json->push_back(']');
+}
+
+struct AlternateStreamRow {
+  std::wstring name;
+  uint64_t size;
+};
+
+std::wstring TrimStreamName(const std::wstring& raw_name) {
+  // FindFirstStreamW reports names as ":<name>:<type>", e.g. ":Zone.Identifier:$DATA".
+  std::wstring name = raw_name;
+  if (!name.empty() && name.front() == L':') {
+    name.erase(name.begin());
+  }
+  const size_t type_separator = name.rfind(L':');
+  if (type_separator != std::wstring::npos) {
+    name.resize(type_separator);
+  }
+  return name;
+}
+
+std::string AlternateStreamRowsToJson(const std::vector<AlternateStreamRow>& rows) {
+  std::string json;
+  json.reserve(rows.size() * 64);
+  json.push_back('[');
+  for (size_t i = 0; i < rows.size(); ++i) {
+    if (i > 0) {
+      json.push_back(',');
+    }
+    json.append("{\"name\":\"");
+    AppendEscapedJsonString(&json, WideToUtf8(rows[i].name));
+    json.append("\",\"size\":");
+    json.append(std::to_string(rows[i].size));
+    json.push_back('}');
+  }
+  json.push_back(']');
+  return json;
+}
+
+}  // namespace
+
+extern "C" __declspec(dllexport) char* omni_list_alternate_streams_json(
+    const char* path_utf8) {
+  const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
+  if (path.empty()) {
+    SetLastErrorText("Stream listing failed: empty path.");
+    return nullptr;
+  }
+
+  std::vector<AlternateStreamRow> rows;
+  WIN32_FIND_STREAM_DATA stream{};
+  HANDLE find_handle =
+      FindFirstStreamW(path.c_str(), FindStreamInfoStandard, &stream, 0);
+  if (find_handle == INVALID_HANDLE_VALUE) {
+    const DWORD error = GetLastError();
+    if (error != ERROR_HANDLE_EOF) {
+      SetLastErrorText(
+          BuildWin32ErrorText("Failed to enumerate alternate data streams.", error));
+      return nullptr;
+    }
+  } else {
+    do {
+      // The unnamed "::$DATA" stream is the file's main content, not an ADS.
+      std::wstring name = TrimStreamName(stream.cStreamName);
+      if (!name.empty()) {
+        rows.push_back(AlternateStreamRow{
+            std::move(name),
+            static_cast<uint64_t>(stream.StreamSize.QuadPart),
+        });
+      }
+    } while (FindNextStreamW(find_handle, &stream) != FALSE);
+    FindClose(find_handle);
+  }
+
+  const std::string json = AlternateStreamRowsToJson(rows);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate stream list buffer.");
+  }
+  return out;
+}
+
+// Samples random index entries and checks them against the disk. An entry is "mismatched"
+// when its file/folder kind no longer agrees or, for a file, its size or modified time does.
+extern "C" __declspec(dllexport) char* omni_verify_index_json(uint32_t sample_size) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+
+  std::vector<IndexedFile> sample;
+  uint64_t indexed_total = 0;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    indexed_total = static_cast<uint64_t>(g_indexed_files.size());
+    const size_t wanted =
+        std::min<size_t>(sample_size == 0 ? 500 : sample_size, g_indexed_files.size());
+    sample.reserve(wanted);
+    std::mt19937_64 generator{std::random_device{}()};
+    std::uniform_int_distribution<size_t> pick(0, g_indexed_files.empty()
+                                                       ? 0
+                                                       : g_indexed_files.size() - 1);
+    std::unordered_set<size_t> chosen;
+    chosen.reserve(wanted * 2 + 1);
+    while (sample.size() < wanted) {
+      const size_t index = pick(generator);
+      if (chosen.insert(index).second) {
+        sample.push_back(g_indexed_files[index]);
+      }
+    }
+  }
+
+  std::atomic<uint64_t> missing{0};
+  std::atomic<uint64_t> mismatched{0};
+  const size_t worker_count = ComputeDuplicateWorkerCount(sample.size());
+  std::atomic<size_t> next_index{0};
+  std::vector<std::thread> workers;
+  workers.reserve(worker_count);
+  for (size_t worker = 0; worker < worker_count; ++worker) {
+    workers.emplace_back([&]() {
+      while (true) {
+        const size_t index = next_index.fetch_add(1, std::memory_order_relaxed);
+        if (index >= sample.size()) {
+          return;
+        }
+        const IndexedFile& file = sample[index];
+        WIN32_FILE_ATTRIBUTE_DATA data{};
+        if (!GetFileAttributesExW(ToExtendedLengthPath(file.path).c_str(), GetFileExInfoStandard,
+                                  &data)) {
+          if (IsPathMissingError(GetLastError())) {
+            missing.fetch_add(1, std::memory_order_relaxed);
+          }
+          continue;
+        }
+        const bool is_directory = (data.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
+        const uint64_t size = (static_cast<uint64_t>(data.nFileSizeHigh) << 32) | data.nFileSizeLow;
+        if (is_directory != file.is_directory ||
+            (!is_directory && (size != file.size ||
+                               FileTimeToUnixSeconds(data.ftLastWriteTime) !=
+                                   file.modified_unix))) {
+          mismatched.fetch_add(1, std::memory_order_relaxed);
+        }
+      }
+    });
+  }
+  for (std::thread& worker : workers) {
+    worker.join();
+  }
+
+  std::string json;
+  json.reserve(128);
+  json.append("{\"indexedCount\":");
+  json.append(std::to_string(indexed_total));
+  json.append(",\"sampled\":");
+  json.append(std::to_string(sample.size()));
+  json.append(",\"missing\":");
+  json.append(std::to_string(missing.load()));
+  json.append(",\"mismatched\":");
+  json.append(std::to_string(mismatched.load()));
+  json.push_back('}');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate index health buffer.");
+  }
+  return out;
+}
+
+// Total size, file count and folder count beneath `path`, from the in-memory index.
+extern "C" __declspec(dllexport) char* omni_directory_size_json(const char* path_utf8) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+
+  const std::wstring root =
+      NormalizeScopePath(Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8));
+  if (root.empty()) {
+    SetLastErrorText("Directory size failed: empty path.");
+    return nullptr;
   }
 
-  g_duplicate_cancel_requested.store(false, std::memory_order_release);
-  ResetDuplicateProgress();
-  const uint64_t effective_min_size =
-      min_size == 0 ? 1ULL * 1024ULL * 1024ULL : min_size;
-  const uint32_t max_groups = std::clamp<uint32_t>(requested_max_groups, 1, 1000);
-  const uint32_t max_files_per_group =
-      std::clamp<uint32_t>(requested_max_files_per_group, 2, 400);
+  const DirectorySizeSummary summary = ComputeDirectorySize(root);
+  std::string json;
+  json.reserve(128);
+  json.append("{\"path\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(root));
+  json.append("\",\"totalBytes\":");
+  json.append(std::to_string(summary.total_bytes));
+  json.append(",\"fileCount\":");
+  json.append(std::to_string(summary.file_count));
+  json.append(",\"subdirCount\":");
+  json.append(std::to_string(summary.subdir_count));
+  json.push_back('}');
 
-  const std::vector<DuplicateGroupRow> groups = find_duplicates_internal(
-      effective_min_size, max_groups, max_files_per_group);
-  const bool cancelled = IsDuplicateScanCancelRequested();
-  g_duplicate_scan_running.store(false, std::memory_order_release);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate directory size buffer.");
+  }
+  return out;
+}
 
-  if (cancelled) {
-    g_duplicate_cancel_requested.store(false, std::memory_order_release);
-    SetLastErrorText("Duplicate scan cancelled.");
+extern "C" __declspec(dllexport) char* omni_folder_summary_json(const char* path_utf8,
+                                                               uint32_t top_count) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
     return nullptr;
   }
 
-  const std::string json = DuplicateGroupsToJson(groups);
+  const std::wstring root =
+      NormalizeScopePath(Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8));
+  if (root.empty()) {
+    SetLastErrorText("Folder summary failed: empty path.");
+    return nullptr;
+  }
+  const size_t top = top_count == 0 ? 10 : std::min<uint32_t>(top_count, 100);
+
+  const FolderSummary summary = ComputeFolderSummary(root);
+  std::vector<std::pair<std::wstring, ExtensionTotals>> by_count(summary.extensions.begin(),
+                                                                 summary.extensions.end());
+  std::vector<std::pair<std::wstring, ExtensionTotals>> by_size = by_count;
+  std::sort(by_count.begin(), by_count.end(), [](const auto& left, const auto& right) {
+    if (left.second.file_count != right.second.file_count) {
+      return left.second.file_count > right.second.file_count;
+    }
+    return left.first < right.first;
+  });
+  std::sort(by_size.begin(), by_size.end(), [](const auto& left, const auto& right) {
+    if (left.second.total_bytes != right.second.total_bytes) {
+      return left.second.total_bytes > right.second.total_bytes;
+    }
+    return left.first < right.first;
+  });
+  by_count.resize(std::min(by_count.size(), top));
+  by_size.resize(std::min(by_size.size(), top));
+
+  std::string json;
+  json.reserve(256 + (by_count.size() + by_size.size()) * 64);
+  json.append("{\"path\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(root));
+  json.append("\",\"totalBytes\":");
+  json.append(std::to_string(summary.totals.total_bytes));
+  json.append(",\"fileCount\":");
+  json.append(std::to_string(summary.totals.file_count));
+  json.append(",\"subdirCount\":");
+  json.append(std::to_string(summary.totals.subdir_count));
+  json.append(",\"topByCount\":");
+  AppendExtensionTotalsJson(&json, by_count);
+  json.append(",\"topBySize\":");
+  AppendExtensionTotalsJson(&json, by_size);
+  json.push_back('}');
+
   char* out = HeapCopyString(json);
   if (out == nullptr) {
-    SetLastErrorText("Failed to allocate duplicate results buffer.");
+    SetLastErrorText("Failed to allocate folder summary buffer.");
   }
-  g_duplicate_cancel_requested.store(false, std::memory_order_release);
   return out;
 }
 
-extern "C" __declspec(dllexport) bool omni_cancel_duplicate_scan() {
-  if (!g_duplicate_scan_running.load(std::memory_order_acquire)) {
-    return false;
+namespace {
+
+constexpr uint64_t kRefreshedEntryFrnBase = (1ULL << 63) | (1ULL << 61);
+std::atomic<uint64_t> g_next_refreshed_frn{kRefreshedEntryFrnBase};
+
+}  // namespace
+
+// Re-walks one folder and replaces the index entries beneath it: entries still on disk keep
+// their FRNs (so live USN updates keep applying), new ones get synthetic FRNs, and missing
+// ones are dropped. Every entry takes the size and modified time the walk just read.
+extern "C" __declspec(dllexport) char* omni_refresh_path_json(const char* path_utf8) {
+  const std::wstring root =
+      NormalizeScopePath(Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8));
+  if (root.empty()) {
+    SetLastErrorText("Refresh failed: empty path.");
+    return nullptr;
   }
-  g_duplicate_cancel_requested.store(true, std::memory_order_release);
-  return true;
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+  const DWORD attributes = GetFileAttributesW(ToExtendedLengthPath(root).c_str());
+  if (attributes == INVALID_FILE_ATTRIBUTES || (attributes & FILE_ATTRIBUTE_DIRECTORY) == 0) {
+    SetLastErrorText("Refresh failed: folder does not exist on disk.");
+    return nullptr;
+  }
+
+  const bool include_directories = g_include_directories.load(std::memory_order_acquire);
+  std::vector<IndexedFile> walked;
+  uint64_t unused_frn = 0;
+  // A new indexing run supersedes the refresh.
+  const uint64_t request_token = g_indexing_request_token.load(std::memory_order_acquire);
+  if (!WalkDirectoryTree(root, include_directories, request_token, &unused_frn, &walked,
+                         false)) {
+    SetLastErrorText("Refresh cancelled by a new indexing run.");
+    return nullptr;
+  }
+  RemoveSystemArtifacts(&walked);
+
+  uint64_t added = 0;
+  uint64_t removed = 0;
+  uint64_t kept = 0;
+  {
+    std::unique_lock<std::shared_mutex> lock(g_index_mutex);
+    if (IsIndexingCancelled(request_token)) {
+      SetLastErrorText("Refresh cancelled by a new indexing run.");
+      return nullptr;
+    }
+
+    std::unordered_map<std::wstring, IndexedFile> previous;
+    size_t write_index = 0;
+    for (size_t read_index = 0; read_index < g_indexed_files.size(); ++read_index) {
+      IndexedFile& file = g_indexed_files[read_index];
+      if (IsPathWithinScope(file.path, root)) {
+        std::wstring key = ToLower(file.path);
+        previous.emplace(std::move(key), std::move(file));
+        continue;
+      }
+      if (write_index != read_index) {
+        g_indexed_files[write_index] = std::move(file);
+      }
+      ++write_index;
+    }
+    g_indexed_files.resize(write_index);
+
+    for (IndexedFile& file : walked) {
+      const auto found = previous.find(ToLower(file.path));
+      if (found != previous.end()) {
+        file.frn = found->second.frn;
+        file.parent_frn = found->second.parent_frn;
+        previous.erase(found);
+        ++kept;
+      } else {
+        file.frn = g_next_refreshed_frn.fetch_add(1, std::memory_order_relaxed);
+        ++added;
+      }
+      g_indexed_files.push_back(std::move(file));
+    }
+    removed = static_cast<uint64_t>(previous.size());
+    RebuildFilePositionLookupLocked();
+    g_indexed_count.store(static_cast<uint64_t>(g_indexed_files.size()),
+                          std::memory_order_release);
+  }
+
+  std::string json;
+  json.reserve(192);
+  json.append("{\"path\":\"");
+  AppendEscapedJsonString(&json, WideToUtf8(root));
+  json.append("\",\"added\":");
+  json.append(std::to_string(added));
+  json.append(",\"removed\":");
+  json.append(std::to_string(removed));
+  json.append(",\"kept\":");
+  json.append(std::to_string(kept));
+  json.push_back('}');
+
+  SetLastErrorText("");
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate refresh result buffer.");
+  }
+  return out;
 }
 
-extern "C" __declspec(dllexport) char* omni_duplicate_scan_status_json() {
-  const std::string json = DuplicateScanStatusToJson();
+namespace {
+
+// Builds the search row for a single looked-up entry, reading its metadata from disk.
+SearchRow BuildLookupRow(const IndexedFile& match) {
+  uint64_t size = 0;
+  int64_t created = 0;
+  int64_t modified = 0;
+  if (!ReadFileMetadata(match.path, &size, &created, &modified)) {
+    size = 0;
+    created = 0;
+    modified = 0;
+  }
+  const bool is_reparse_point = IsReparsePoint(match);
+  SearchRow row{
+      IndexedFileName(match),
+      match.path,
+      IndexedFileExtensionLower(match),
+      size,
+      created,
+      modified,
+      match.is_directory,
+      is_reparse_point,
+      is_reparse_point ? ResolveReparseTarget(match.path) : std::wstring(),
+  };
+  row.is_cloud_placeholder = IsCloudPlaceholder(match);
+  row.file_id = match.frn;
+  return row;
+}
+
+}  // namespace
+
+// Returns the indexed entry for exactly `path` as a one-row search result array, or an empty
+// array when the path is not indexed. Candidates come from the name bigram index, so only
+// entries sharing the rarest bigram of the name are compared.
+extern "C" __declspec(dllexport) char* omni_lookup_path_json(const char* path_utf8) {
+  const std::wstring path =
+      NormalizeScopePath(Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8));
+  const size_t separator = path.find_last_of(L'\\');
+  if (separator == std::wstring::npos || separator + 1 >= path.size()) {
+    SetLastErrorText("Lookup failed: expected a file or folder path.");
+    return nullptr;
+  }
+
+  std::vector<SearchRow> rows;
+  bool found = false;
+  IndexedFile match{};
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    uint32_t position = 0;
+    found = FindIndexedPathLocked(path, &position);
+    if (found) {
+      match = g_indexed_files[position];
+    }
+  }
+
+  if (found) {
+    rows.push_back(BuildLookupRow(match));
+  }
+
+  const std::string json = SearchRowsToJson(rows);
   char* out = HeapCopyString(json);
   if (out == nullptr) {
-    SetLastErrorText("Failed to allocate duplicate status buffer.");
+    SetLastErrorText("Failed to allocate lookup result buffer.");
   }
   return out;
 }
 
-bool DeletePathWithShell(const std::wstring& path, bool recycle_bin) {
-  std::vector<wchar_t> shell_path(path.begin(), path.end());
-  shell_path.push_back(L'\0');
-  shell_path.push_back(L'\0');
+// Returns the indexed entry on `drive_root` (e.g. `C:\`) whose file id is `file_id`, as a
+// one-row search result array, or an empty array when no entry matches. Ids repeat across
+// volumes, so the drive picks which volume's entry is meant.
+extern "C" __declspec(dllexport) char* omni_lookup_file_id_json(const char* drive_root_utf8,
+                                                                 const uint64_t file_id) {
+  const std::wstring drive_root = Utf8ToWide(drive_root_utf8 == nullptr ? "" : drive_root_utf8);
+  if (drive_root.empty() || file_id == 0) {
+    SetLastErrorText("Lookup failed: expected a drive and a file id.");
+    return nullptr;
+  }
 
-  SHFILEOPSTRUCTW operation{};
-  operation.wFunc = FO_DELETE;
-  operation.pFrom = shell_path.data();
-  operation.fFlags = FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT;
-  if (recycle_bin) {
-    operation.fFlags |= FOF_ALLOWUNDO;
+  std::vector<SearchRow> rows;
+  bool found = false;
+  IndexedFile match{};
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (file.frn == file_id && IsPathWithinScope(file.path, drive_root)) {
+        match = file;
+        found = true;
+        break;
+      }
+    }
   }
 
-  const int result = SHFileOperationW(&operation);
-  if (result != 0) {
-    SetLastErrorText(BuildWin32ErrorText(
-        recycle_bin ? "Failed to move item to the Recycle Bin."
-                    : "Failed to delete item.",
-        static_cast<DWORD>(result)));
-    return false;
+  if (found) {
+    rows.push_back(BuildLookupRow(match));
   }
 
-  if (operation.fAnyOperationsAborted) {
-    SetLastErrorText(recycle_bin ? "Move to Recycle Bin cancelled."
-                                 : "Delete cancelled.");
-    return false;
+  const std::string json = SearchRowsToJson(rows);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate lookup result buffer.");
   }
+  return out;
+}
 
-  return true;
+// Finds every indexed entry named exactly (case-insensitively) one of the newline-separated
+// `names_utf8`, in one pass over the index. `drives_utf8` ("C,D") limits the search to those
+// drives; empty searches everything. Rows are ordered by the position of their name in the
+// list, then by index order, and stop at `max_rows`.
+extern "C" __declspec(dllexport) char* omni_find_by_names_json(const char* names_utf8,
+                                                               const char* drives_utf8,
+                                                               const uint32_t max_rows) {
+  std::unordered_map<std::wstring, size_t> name_order;
+  const std::wstring raw_names = Utf8ToWide(names_utf8 == nullptr ? "" : names_utf8);
+  size_t start = 0;
+  while (start <= raw_names.size()) {
+    const size_t delimiter = raw_names.find(L'\n', start);
+    const size_t end = delimiter == std::wstring::npos ? raw_names.size() : delimiter;
+    std::wstring name = raw_names.substr(start, end - start);
+    while (!name.empty() && (name.back() == L'\r' || name.back() == L' ')) {
+      name.pop_back();
+    }
+    const size_t first = name.find_first_not_of(L' ');
+    if (first != std::wstring::npos) {
+      name_order.emplace(ToLower(name.substr(first)), name_order.size());
+    }
+    if (delimiter == std::wstring::npos) {
+      break;
+    }
+    start = delimiter + 1;
+  }
+  if (name_order.empty()) {
+    SetLastErrorText("Lookup failed: no file names given.");
+    return nullptr;
+  }
+  const std::vector<std::wstring> drives = ParseDriveList(drives_utf8);
+
+  std::vector<std::pair<size_t, IndexedFile>> matches;
+  {
+    std::shared_lock<std::shared_mutex> lock(g_index_mutex);
+    for (const IndexedFile& file : g_indexed_files) {
+      if (!drives.empty()) {
+        if (file.path.size() < 2 || file.path[1] != L':' ||
+            std::find(drives.begin(), drives.end(),
+                      std::wstring(1, static_cast<wchar_t>(std::towupper(file.path[0])))) ==
+                drives.end()) {
+          continue;
+        }
+      }
+      const auto order_it = name_order.find(ToLower(IndexedFileName(file)));
+      if (order_it != name_order.end()) {
+        matches.emplace_back(order_it->second, file);
+      }
+    }
+  }
+  std::stable_sort(matches.begin(), matches.end(),
+                   [](const auto& left, const auto& right) { return left.first < right.first; });
+  if (matches.size() > max_rows) {
+    matches.resize(max_rows);
+  }
+
+  std::vector<SearchRow> rows;
+  rows.reserve(matches.size());
+  for (const auto& match : matches) {
+    rows.push_back(BuildLookupRow(match.second));
+  }
+
+  const std::string json = SearchRowsToJson(rows);
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate lookup result buffer.");
+  }
+  return out;
 }
 
-extern "C" __declspec(dllexport) bool omni_delete_path(const char* path_utf8,
-                                                       bool recycle_bin) {
+namespace {
+
+// Every path that hard-links to the same file as `path`, `path` itself included. Returns an
+// empty list when the names cannot be enumerated.
+std::vector<std::wstring> ListHardLinkPaths(const std::wstring& path) {
+  std::vector<std::wstring> links;
+  wchar_t volume_root[MAX_PATH] = L"";
+  if (!GetVolumePathNameW(path.c_str(), volume_root, MAX_PATH)) {
+    return links;
+  }
+  std::wstring root = volume_root;
+  while (!root.empty() && root.back() == L'\\') {
+    root.pop_back();
+  }
+
+  std::vector<wchar_t> name(MAX_PATH);
+  DWORD name_length = static_cast<DWORD>(name.size());
+  HANDLE find = FindFirstFileNameW(path.c_str(), 0, &name_length, name.data());
+  if (find == INVALID_HANDLE_VALUE && GetLastError() == ERROR_MORE_DATA) {
+    name.resize(name_length);
+    find = FindFirstFileNameW(path.c_str(), 0, &name_length, name.data());
+  }
+  if (find == INVALID_HANDLE_VALUE) {
+    return links;
+  }
+  while (true) {
+    links.push_back(root + name.data());
+    name_length = static_cast<DWORD>(name.size());
+    BOOL found_next = FindNextFileNameW(find, &name_length, name.data());
+    if (!found_next && GetLastError() == ERROR_MORE_DATA) {
+      name.resize(name_length);
+      found_next = FindNextFileNameW(find, &name_length, name.data());
+    }
+    if (!found_next) {
+      break;
+    }
+  }
+  FindClose(find);
+  return links;
+}
+
+}  // namespace
+
+// For each newline-separated path, reports `{"key": ..., "links": [...]}` in input order. Files
+// with more than one hard link get a key built from the volume serial and file id, shared by
+// every link, plus the paths of all links; other entries get an empty key and no links.
+extern "C" __declspec(dllexport) char* omni_hardlink_info_json(const char* paths_utf8) {
+  const std::wstring raw = Utf8ToWide(paths_utf8 == nullptr ? "" : paths_utf8);
+  std::string json = "[";
+  size_t start = 0;
+  bool first = true;
+  while (start < raw.size()) {
+    const size_t delimiter = raw.find(L'\n', start);
+    const size_t end = delimiter == std::wstring::npos ? raw.size() : delimiter;
+    const std::wstring path = raw.substr(start, end - start);
+    start = delimiter == std::wstring::npos ? raw.size() : delimiter + 1;
+
+    std::string key;
+    std::vector<std::wstring> links;
+    HANDLE handle = CreateFileW(ToExtendedLengthPath(path).c_str(), FILE_READ_ATTRIBUTES,
+                                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                                nullptr, OPEN_EXISTING, FILE_FLAG_BACKUP_SEMANTICS, nullptr);
+    if (handle != INVALID_HANDLE_VALUE) {
+      BY_HANDLE_FILE_INFORMATION info{};
+      if (GetFileInformationByHandle(handle, &info) && info.nNumberOfLinks > 1 &&
+          (info.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) == 0) {
+        char buffer[40];
+        std::snprintf(buffer, sizeof(buffer), "%08lx-%08lx%08lx", info.dwVolumeSerialNumber,
+                      info.nFileIndexHigh, info.nFileIndexLow);
+        key = buffer;
+        links = ListHardLinkPaths(path);
+      }
+      CloseHandle(handle);
+    }
+
+    if (!first) {
+      json.push_back(',');
+    }
+    first = false;
+    json.append("{\"key\":\"");
+    AppendEscapedJsonString(&json, key);
+    json.append("\",\"links\":[");
+    for (size_t i = 0; i < links.size(); ++i) {
+      if (i > 0) {
+        json.push_back(',');
+      }
+      json.push_back('"');
+      AppendEscapedJsonString(&json, WideToUtf8(links[i]));
+      json.push_back('"');
+    }
+    json.append("]}");
+  }
+  json.push_back(']');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate hard link buffer.");
+  }
+  return out;
+}
+
+extern "C" __declspec(dllexport) bool omni_save_index_snapshot(const char* path_utf8) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return false;
+  }
   const std::wstring path = Utf8ToWide(path_utf8 == nullptr ? "" : path_utf8);
   if (path.empty()) {
-    SetLastErrorText("Delete failed: empty path.");
+    SetLastErrorText("Snapshot failed: empty path.");
     return false;
   }
 
-  const DWORD attributes = GetFileAttributesW(path.c_str());
-  if (attributes == INVALID_FILE_ATTRIBUTES) {
-    SetLastErrorText(BuildWin32ErrorText("Delete failed: path not found.",
-                                         GetLastError()));
+  const std::vector<SnapshotEntry> entries = CollectIndexSnapshot();
+  FILE* file = _wfopen(path.c_str(), L"wb");
+  if (file == nullptr) {
+    SetLastErrorText("Snapshot failed: could not create the snapshot file.");
     return false;
   }
-
-  if (!DeletePathWithShell(path, recycle_bin)) {
+  std::string line;
+  line.reserve(512);
+  line.assign(kSnapshotHeader);
+  line.push_back('\n');
+  for (const DriveInfo& drive : list_drives_internal()) {
+    if (drive.volume_guid.empty()) {
+      continue;
+    }
+    line.append(kSnapshotVolumePrefix);
+    line.append(WideToUtf8(drive.letter));
+    line.push_back('\t');
+    line.append(WideToUtf8(drive.volume_guid));
+    line.push_back('\n');
+  }
+  bool ok = std::fwrite(line.data(), 1, line.size(), file) == line.size();
+  for (const SnapshotEntry& entry : entries) {
+    if (!ok) {
+      break;
+    }
+    line.assign(std::to_string(entry.modified_unix));
+    line.push_back('\t');
+    line.append(std::to_string(entry.size));
+    line.push_back('\t');
+    line.append(WideToUtf8(entry.path));
+    line.push_back('\n');
+    ok = std::fwrite(line.data(), 1, line.size(), file) == line.size();
+  }
+  ok = std::fclose(file) == 0 && ok;
+  if (!ok) {
+    SetLastErrorText("Snapshot failed: could not write the snapshot file.");
     return false;
   }
 
-  {
-    std::unique_lock<std::shared_mutex> lock(g_index_mutex);
-    RemoveIndexedFileByPathLocked(path);
-    g_indexed_count.store(static_cast<uint64_t>(g_indexed_files.size()),
-                          std::memory_order_release);
-  }
-
   SetLastErrorText("");
   return true;
 }
 
+extern "C" __declspec(dllexport) char* omni_diff_index_json(const char* snapshot_path_utf8,
+                                                            uint32_t max_entries) {
+  if (!g_is_ready.load(std::memory_order_acquire)) {
+    SetLastErrorText("Index is not ready yet. Wait for indexing to finish.");
+    return nullptr;
+  }
+  const std::wstring snapshot_path =
+      Utf8ToWide(snapshot_path_utf8 == nullptr ? "" : snapshot_path_utf8);
+  std::string content;
+  std::string error;
+  std::unordered_map<std::wstring, SnapshotEntry> previous;
+  std::unordered_map<wchar_t, std::wstring> previous_volumes;
+  if (!ReadWholeFile(snapshot_path, &content, &error) ||
+      !ParseIndexSnapshot(content, &previous, &previous_volumes, &error)) {
+    SetLastErrorText(error);
+    return nullptr;
+  }
+  content.clear();
+  RemapSnapshotDriveLetters(previous_volumes, &previous);
+
+  const size_t limit = max_entries == 0 ? 5000 : max_entries;
+  std::vector<std::wstring> added;
+  std::vector<std::wstring> modified;
+  std::vector<std::wstring> removed;
+  bool truncated = false;
+  const auto push_limited = [&](std::vector<std::wstring>* list, const std::wstring& path) {
+    if (list->size() >= limit) {
+      truncated = true;
+      return;
+    }
+    list->push_back(path);
+  };
+
+  for (const SnapshotEntry& entry : CollectIndexSnapshot()) {
+    const auto previous_it = previous.find(ToLower(entry.path));
+    if (previous_it == previous.end()) {
+      push_limited(&added, entry.path);
+      continue;
+    }
+    if (previous_it->second.size != entry.size ||
+        previous_it->second.modified_unix != entry.modified_unix) {
+      push_limited(&modified, entry.path);
+    }
+    previous.erase(previous_it);
+  }
+  for (const auto& leftover : previous) {
+    push_limited(&removed, leftover.second.path);
+  }
+
+  std::string json;
+  json.reserve(256 + (added.size() + modified.size() + removed.size()) * 96);
+  json.append("{\"added\":");
+  AppendJsonPathArray(&json, added);
+  json.append(",\"removed\":");
+  AppendJsonPathArray(&json, removed);
+  json.append(",\"modified\":");
+  AppendJsonPathArray(&json, modified);
+  json.append(",\"truncated\":");
+  json.append(truncated ? "true" : "false");
+  json.push_back('}');
+
+  char* out = HeapCopyString(json);
+  if (out == nullptr) {
+    SetLastErrorText("Failed to allocate index diff buffer.");
+  }
+  return out;
+}
+
 extern "C" __declspec(dllexport) char* scan_mft(const char* drive_utf8) {
   ScanSnapshot snapshot;
   std::string error;
   bool cancelled = false;
-  const bool ok = scan_mft_internal(
-      NormalizeDriveLetter(drive_utf8), &snapshot, false, 0, &cancelled, &error);
+  const bool ok = scan_mft_internal(NormalizeDriveLetter(drive_utf8), &snapshot, false,
+                                    0, ResolveIndexingThreadCount(0), &cancelled, &error);
   if (!ok) {
     SetLastErrorText(error.empty() ? "scan_mft failed." : error);
     return nullptr;
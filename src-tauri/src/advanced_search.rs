@@ -0,0 +1,339 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+#[cfg(target_os = "windows")]
+use std::ffi::CString;
+
+/// One set of criteria for `advanced_search`. Every criterion given must hold for a result to
+/// match the group; results matching any group are returned.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FilterGroup {
+    /// Extensions without the dot, compared case-insensitively.
+    pub extensions: Option<Vec<String>>,
+    /// Extension categories, as on search results (`image`, `video`...).
+    pub categories: Option<Vec<String>>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub min_created_unix: Option<i64>,
+    pub max_created_unix: Option<i64>,
+    pub min_modified_unix: Option<i64>,
+    pub max_modified_unix: Option<i64>,
+    /// `true` for folders only, `false` for files only.
+    pub is_directory: Option<bool>,
+}
+
+fn normalized_list(values: &Option<Vec<String>>) -> Option<Vec<String>> {
+    values.as_ref().map(|values| {
+        values
+            .iter()
+            .map(|value| value.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|value| !value.is_empty())
+            .collect()
+    })
+}
+
+/// Lowercases and trims `group`'s lists and rejects groups that would match everything or
+/// nothing. `number` is the 1-based group position used in error messages.
+fn validate_group(number: usize, mut group: FilterGroup) -> Result<FilterGroup, String> {
+    group.extensions = normalized_list(&group.extensions);
+    group.categories = normalized_list(&group.categories);
+    if group
+        .extensions
+        .as_ref()
+        .is_some_and(|extensions| extensions.is_empty())
+    {
+        return Err(format!(
+            "Filter group {number} has an empty extension list."
+        ));
+    }
+    if group
+        .categories
+        .as_ref()
+        .is_some_and(|categories| categories.is_empty())
+    {
+        return Err(format!("Filter group {number} has an empty category list."));
+    }
+    if let (Some(min), Some(max)) = (group.min_size, group.max_size) {
+        if min > max {
+            return Err(format!(
+                "Filter group {number}: minimum size is above the maximum."
+            ));
+        }
+    }
+    if let (Some(min), Some(max)) = (group.min_created_unix, group.max_created_unix) {
+        if min > max {
+            return Err(format!("Filter group {number}: created range is reversed."));
+        }
+    }
+    if let (Some(min), Some(max)) = (group.min_modified_unix, group.max_modified_unix) {
+        if min > max {
+            return Err(format!(
+                "Filter group {number}: modified range is reversed."
+            ));
+        }
+    }
+    if group.is_directory == Some(true)
+        && (group.extensions.is_some() || group.min_size.is_some() || group.max_size.is_some())
+    {
+        return Err(format!(
+            "Filter group {number}: folders have no extension or size to filter on."
+        ));
+    }
+    let has_criteria = group.extensions.is_some()
+        || group.categories.is_some()
+        || group.min_size.is_some()
+        || group.max_size.is_some()
+        || group.min_created_unix.is_some()
+        || group.max_created_unix.is_some()
+        || group.min_modified_unix.is_some()
+        || group.max_modified_unix.is_some()
+        || group.is_directory.is_some();
+    if !has_criteria {
+        return Err(format!("Filter group {number} has no criteria."));
+    }
+    Ok(group)
+}
+
+#[cfg(target_os = "windows")]
+fn group_matches(group: &FilterGroup, result: &crate::SearchResult) -> bool {
+    let in_list = |values: &Option<Vec<String>>, value: &str| {
+        values.as_ref().is_none_or(|values| {
+            values
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(value))
+        })
+    };
+    in_list(&group.extensions, &result.extension)
+        && in_list(&group.categories, &result.category)
+        && group.min_size.is_none_or(|min| result.size >= min)
+        && group.max_size.is_none_or(|max| result.size <= max)
+        && group
+            .min_created_unix
+            .is_none_or(|min| result.created_unix >= min)
+        && group
+            .max_created_unix
+            .is_none_or(|max| result.created_unix <= max)
+        && group
+            .min_modified_unix
+            .is_none_or(|min| result.modified_unix >= min)
+        && group
+            .max_modified_unix
+            .is_none_or(|max| result.modified_unix <= max)
+        && group
+            .is_directory
+            .is_none_or(|is_directory| result.is_directory == is_directory)
+}
+
+/// The loosest bound over all groups: `Some` only when every group sets it, so narrowing the
+/// scanner query by it cannot drop a match.
+#[cfg(target_os = "windows")]
+fn shared_bound<T: Copy>(
+    groups: &[FilterGroup],
+    bound: impl Fn(&FilterGroup) -> Option<T>,
+    loosest: impl Fn(T, T) -> T,
+) -> Option<T> {
+    let mut values = groups.iter().map(&bound);
+    let first = values.next()??;
+    values.try_fold(first, |current, value| Some(loosest(current, value?)))
+}
+
+/// Extensions that can narrow the scanner for `group`: its own list, or else the extensions
+/// of its categories. `None` when the group allows any extension, or names a category such
+/// as `folder` or `other` that no extension list describes.
+#[cfg(target_os = "windows")]
+fn group_extensions(group: &FilterGroup) -> Option<Vec<&str>> {
+    if let Some(extensions) = &group.extensions {
+        return Some(extensions.iter().map(String::as_str).collect());
+    }
+    let mut extensions = Vec::new();
+    for category in group.categories.as_ref()? {
+        extensions.extend_from_slice(crate::category::category_extensions(category)?);
+    }
+    Some(extensions)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdvancedSearchResponse {
+    pub results: Vec<crate::SearchResult>,
+    /// More matches exist past `limit`.
+    pub truncated: bool,
+}
+
+/// Searches with OR-ed filter groups, e.g. "(images over 5 MB) or (videos over 100 MB)",
+/// beyond what the flat `search_files` parameters can express. The scanner finds candidates
+/// for `query` within `within_path`, narrowed by whatever every group has in common, and
+/// each candidate is kept when it matches at least one group; the scan pages through the
+/// index until `limit` matches are found or nothing is left. Empty or contradictory groups
+/// are rejected. `limit` defaults to 200.
+#[tauri::command]
+pub async fn advanced_search(
+    app: AppHandle<tauri::Wry>,
+    query: Option<String>,
+    within_path: Option<String>,
+    groups: Vec<FilterGroup>,
+    limit: Option<u32>,
+) -> Result<AdvancedSearchResponse, String> {
+    if groups.is_empty() {
+        return Err("Add at least one filter group.".to_string());
+    }
+    let groups = groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, group)| validate_group(index + 1, group))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let limit = limit
+            .unwrap_or(200)
+            .clamp(1, crate::DEFAULT_RESULT_LIMIT_CEILING) as usize;
+        // Only extensions known for every group can narrow the scanner by extension.
+        let extension_filter = groups
+            .iter()
+            .map(group_extensions)
+            .collect::<Option<Vec<_>>>()
+            .map(|lists| {
+                let mut extensions = lists.concat();
+                extensions.sort_unstable();
+                extensions.dedup();
+                extensions.join(",")
+            })
+            .unwrap_or_default();
+        let c_query = CString::new(query.unwrap_or_default().trim())
+            .map_err(|_| "Invalid query".to_string())?;
+        let c_extension =
+            CString::new(extension_filter).map_err(|_| "Invalid extension".to_string())?;
+        let c_within_path = CString::new(within_path.unwrap_or_default())
+            .map_err(|_| "Invalid path parameter".to_string())?;
+
+        let mut response = tauri::async_runtime::spawn_blocking(move || {
+            let mut options = crate::SearchOptions {
+                min_size: shared_bound(&groups, |group| group.min_size, u64::min).unwrap_or(0),
+                max_size: shared_bound(&groups, |group| group.max_size, u64::max)
+                    .unwrap_or(u64::MAX),
+                min_created_unix: shared_bound(&groups, |group| group.min_created_unix, i64::min)
+                    .unwrap_or(i64::MIN),
+                max_created_unix: shared_bound(&groups, |group| group.max_created_unix, i64::max)
+                    .unwrap_or(i64::MAX),
+                min_modified_unix: shared_bound(&groups, |group| group.min_modified_unix, i64::min)
+                    .unwrap_or(i64::MIN),
+                max_modified_unix: shared_bound(&groups, |group| group.max_modified_unix, i64::max)
+                    .unwrap_or(i64::MAX),
+                ..crate::SearchOptions::background(&c_query, &c_extension, &c_within_path)
+            };
+            let mut resume_at = u32::MAX;
+            let mut index_generation = 0_u64;
+            options.out_resume_at = &mut resume_at;
+            options.index_generation = &mut index_generation;
+            let mut results = Vec::new();
+            let truncated = loop {
+                let page = crate::run_search(&options)?;
+                let page_full = page.len() >= options.limit as usize;
+                results.extend(
+                    page.into_iter()
+                        .filter(|result| groups.iter().any(|group| group_matches(group, result))),
+                );
+                if results.len() >= limit {
+                    break results.len() > limit || resume_at != u32::MAX;
+                }
+                if resume_at == u32::MAX {
+                    // Searches the scanner cannot resume end after one full page.
+                    break page_full;
+                }
+                options.resume_from = resume_at;
+            };
+            results.truncate(limit);
+            Ok::<_, String>(AdvancedSearchResponse { results, truncated })
+        })
+        .await
+        .map_err(|err| format!("Advanced search task failed: {err}"))??;
+        crate::attach_tags(&app, &mut response.results);
+        Ok(response)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, query, within_path, groups, limit);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(extensions: &[&str]) -> FilterGroup {
+        FilterGroup {
+            extensions: Some(extensions.iter().map(|value| value.to_string()).collect()),
+            ..FilterGroup::default()
+        }
+    }
+
+    #[test]
+    fn validate_group_normalizes_lists() {
+        let group = validate_group(1, group(&[" .JPG", "png", " "])).unwrap();
+        assert_eq!(
+            group.extensions,
+            Some(vec!["jpg".to_string(), "png".to_string()])
+        );
+    }
+
+    #[test]
+    fn validate_group_rejects_empty_and_contradictory_groups() {
+        let error = validate_group(2, FilterGroup::default()).unwrap_err();
+        assert_eq!(error, "Filter group 2 has no criteria.");
+        let error = validate_group(1, group(&[" ", "."])).unwrap_err();
+        assert_eq!(error, "Filter group 1 has an empty extension list.");
+        let reversed = FilterGroup {
+            min_size: Some(10),
+            max_size: Some(5),
+            ..FilterGroup::default()
+        };
+        assert!(validate_group(1, reversed).is_err());
+        let folders_by_size = FilterGroup {
+            is_directory: Some(true),
+            min_size: Some(1),
+            ..FilterGroup::default()
+        };
+        assert!(validate_group(1, folders_by_size).is_err());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn shared_bound_needs_every_group() {
+        let sized = |min_size| FilterGroup {
+            min_size,
+            ..FilterGroup::default()
+        };
+        let groups = [sized(Some(5)), sized(Some(100))];
+        assert_eq!(
+            shared_bound(&groups, |group| group.min_size, u64::min),
+            Some(5)
+        );
+        let groups = [sized(Some(5)), sized(None)];
+        assert_eq!(
+            shared_bound(&groups, |group| group.min_size, u64::min),
+            None
+        );
+        assert_eq!(shared_bound(&[], |group| group.min_size, u64::min), None);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn group_extensions_expands_categories() {
+        assert_eq!(group_extensions(&group(&["rs"])), Some(vec!["rs"]));
+        let images = FilterGroup {
+            categories: Some(vec!["image".to_string()]),
+            ..FilterGroup::default()
+        };
+        assert!(group_extensions(&images).unwrap().contains(&"png"));
+        let folders = FilterGroup {
+            categories: Some(vec!["folder".to_string()]),
+            ..FilterGroup::default()
+        };
+        assert_eq!(group_extensions(&folders), None);
+        assert_eq!(group_extensions(&FilterGroup::default()), None);
+    }
+}
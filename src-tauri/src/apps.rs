@@ -8,26 +8,36 @@ use std::{
 };
 
 #[cfg(windows)]
-use image::{DynamicImage, ImageFormat, RgbaImage};
-#[cfg(windows)]
-use std::{mem::size_of, os::windows::process::CommandExt, path::PathBuf};
+use image::{imageops::FilterType, DynamicImage, ImageFormat, RgbaImage};
 #[cfg(windows)]
 use std::time::{SystemTime, UNIX_EPOCH};
 #[cfg(windows)]
+use std::{
+    ffi::{CStr, CString},
+    mem::size_of,
+    os::windows::process::CommandExt,
+    path::PathBuf,
+};
+#[cfg(windows)]
 use tauri_plugin_opener::OpenerExt;
 #[cfg(windows)]
 use windows::{
     core::PCWSTR,
     Win32::{
-        Foundation::{SIZE, RPC_E_CHANGED_MODE},
+        Foundation::{RPC_E_CHANGED_MODE, SIZE},
         Graphics::Gdi::{
-            BI_RGB, BITMAP, BITMAPINFO, BITMAPINFOHEADER, CreateCompatibleDC, DIB_RGB_COLORS,
-            DeleteDC, DeleteObject, GetDIBits, GetObjectW, HBITMAP,
+            CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO,
+            BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP,
         },
+        Storage::FileSystem::{FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL},
         System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED},
-        UI::Shell::{
-            IShellItem, IShellItemImageFactory, SHCreateItemFromParsingName, SIIGBF,
-            SIIGBF_BIGGERSIZEOK, SIIGBF_ICONONLY,
+        UI::{
+            Shell::{
+                IShellItem, IShellItemImageFactory, SHCreateItemFromParsingName, SHGetFileInfoW,
+                SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_SMALLICON, SHGFI_USEFILEATTRIBUTES,
+                SIIGBF_BIGGERSIZEOK, SIIGBF_ICONONLY, SIIGBF_THUMBNAILONLY,
+            },
+            WindowsAndMessaging::{DestroyIcon, GetIconInfo, ICONINFO},
         },
     },
 };
@@ -35,14 +45,25 @@ use windows::{
 use windows_core::{Error as WindowsError, Interface};
 
 static APP_ICON_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+#[cfg(windows)]
+static EXTENSION_ICON_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
 
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+#[cfg(windows)]
+const EXTENSION_ICON_MIN_SIZE: u32 = 16;
+#[cfg(windows)]
+const EXTENSION_ICON_MAX_SIZE: u32 = 256;
 
 fn app_icon_cache() -> &'static Mutex<HashMap<String, String>> {
     APP_ICON_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+#[cfg(windows)]
+fn extension_icon_cache() -> &'static Mutex<HashMap<String, String>> {
+    EXTENSION_ICON_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InstalledApp {
@@ -57,6 +78,14 @@ pub struct InstalledApp {
     pub modified_unix: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    pub extension: String,
+    pub name: String,
+    pub path: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct RawInstalledApp {
@@ -66,7 +95,7 @@ struct RawInstalledApp {
 }
 
 #[cfg(windows)]
-struct ComInitGuard {
+pub(crate) struct ComInitGuard {
     should_uninitialize: bool,
 }
 
@@ -82,7 +111,7 @@ impl Drop for ComInitGuard {
 }
 
 #[cfg(windows)]
-fn ensure_com_initialized() -> Result<ComInitGuard, String> {
+pub(crate) fn ensure_com_initialized() -> Result<ComInitGuard, String> {
     unsafe {
         let result = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
         if result.is_ok() {
@@ -274,6 +303,19 @@ fn resolve_shell_parsing_name(launch_target: &str, reveal_path: Option<&str>, is
 
 #[cfg(windows)]
 fn hbitmap_to_png_data_url(bitmap_handle: HBITMAP) -> Result<String, String> {
+    let rgba = hbitmap_to_rgba_image(bitmap_handle)?;
+
+    let mut png_bytes = Vec::new();
+    DynamicImage::ImageRgba8(rgba)
+        .write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
+        .map_err(|err| format!("Failed to encode installed app icon: {err}"))?;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png_bytes);
+    Ok(format!("data:image/png;base64,{encoded}"))
+}
+
+#[cfg(windows)]
+fn hbitmap_to_rgba_image(bitmap_handle: HBITMAP) -> Result<RgbaImage, String> {
     let mut bitmap = BITMAP::default();
     let object_read = unsafe {
         GetObjectW(
@@ -343,16 +385,8 @@ fn hbitmap_to_png_data_url(bitmap_handle: HBITMAP) -> Result<String, String> {
         pixel.swap(0, 2);
     }
 
-    let rgba = RgbaImage::from_raw(width as u32, height as u32, pixels)
-        .ok_or_else(|| "Installed app icon pixel buffer was invalid.".to_string())?;
-
-    let mut png_bytes = Vec::new();
-    DynamicImage::ImageRgba8(rgba)
-        .write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
-        .map_err(|err| format!("Failed to encode installed app icon: {err}"))?;
-
-    let encoded = base64::engine::general_purpose::STANDARD.encode(png_bytes);
-    Ok(format!("data:image/png;base64,{encoded}"))
+    RgbaImage::from_raw(width as u32, height as u32, pixels)
+        .ok_or_else(|| "Installed app icon pixel buffer was invalid.".to_string())
 }
 
 #[cfg(windows)]
@@ -382,10 +416,7 @@ fn load_shell_icon_data_url(
 
     let bitmap = unsafe {
         image_factory
-            .GetImage(
-                SIZE { cx: 64, cy: 64 },
-                SIIGBF_ICONONLY | SIIGBF_BIGGERSIZEOK | SIIGBF(0),
-            )
+            .GetImage(SIZE { cx: 64, cy: 64 }, SIIGBF_ICONONLY | SIIGBF_BIGGERSIZEOK)
             .map_err(|err| format!("Failed to render the installed app icon: {err}"))?
     };
     let data_url = hbitmap_to_png_data_url(bitmap)?;
@@ -397,6 +428,130 @@ fn load_shell_icon_data_url(
     Ok(data_url)
 }
 
+/// Renders a file through the shell thumbnail providers (WIC codecs such as HEIF)
+/// and returns it as a PNG data URL.
+#[cfg(windows)]
+pub(crate) fn load_shell_thumbnail_data_url(path: &str, size: i32) -> Result<String, String> {
+    hbitmap_to_png_data_url(load_shell_thumbnail_bitmap(path, size)?)
+}
+
+/// Same as `load_shell_thumbnail_data_url`, but hands back the decoded pixels.
+#[cfg(windows)]
+pub(crate) fn load_shell_thumbnail_image(path: &str, size: i32) -> Result<RgbaImage, String> {
+    hbitmap_to_rgba_image(load_shell_thumbnail_bitmap(path, size)?)
+}
+
+#[cfg(windows)]
+fn load_shell_thumbnail_bitmap(path: &str, size: i32) -> Result<HBITMAP, String> {
+    let _com_guard = ensure_com_initialized()?;
+    let wide_path = to_wide_null_terminated(path);
+    let shell_item: IShellItem = unsafe {
+        SHCreateItemFromParsingName(PCWSTR(wide_path.as_ptr()), None)
+            .map_err(|err| format!("Failed to access the preview shell item: {err}"))?
+    };
+    let image_factory: IShellItemImageFactory = shell_item
+        .cast()
+        .map_err(|err| format!("Failed to read the preview image provider: {err}"))?;
+
+    let bitmap = unsafe {
+        image_factory
+            .GetImage(SIZE { cx: size, cy: size }, SIIGBF_THUMBNAILONLY)
+            .map_err(|err| format!("Failed to decode preview image: {err}"))?
+    };
+    Ok(bitmap)
+}
+
+/// Looks up the generic shell icon registered for an extension without touching any file.
+/// "folder" returns the folder icon; an empty extension returns the unknown-file icon.
+#[cfg(windows)]
+pub(crate) fn load_extension_icon_data_url(extension: &str, size: u32) -> Result<String, String> {
+    let extension = extension
+        .trim()
+        .trim_start_matches('.')
+        .to_ascii_lowercase();
+    let size = size.clamp(EXTENSION_ICON_MIN_SIZE, EXTENSION_ICON_MAX_SIZE);
+    let cache_key = format!("{extension}:{size}");
+
+    if let Ok(cache) = extension_icon_cache().lock() {
+        if let Some(cached) = cache.get(&cache_key) {
+            return Ok(cached.clone());
+        }
+    }
+
+    let is_folder = matches!(
+        extension.as_str(),
+        "folder" | "folders" | "dir" | "directory"
+    );
+    let (file_name, attributes) = if is_folder {
+        ("folder".to_string(), FILE_ATTRIBUTE_DIRECTORY)
+    } else if extension.is_empty() {
+        ("file".to_string(), FILE_ATTRIBUTE_NORMAL)
+    } else {
+        (format!("file.{extension}"), FILE_ATTRIBUTE_NORMAL)
+    };
+    let icon_size_flag = if size <= EXTENSION_ICON_MIN_SIZE {
+        SHGFI_SMALLICON
+    } else {
+        SHGFI_LARGEICON
+    };
+
+    let _com_guard = ensure_com_initialized()?;
+    let wide_name = to_wide_null_terminated(&file_name);
+    let mut file_info = SHFILEINFOW::default();
+    // SAFETY: `wide_name` is NUL-terminated and `file_info` is a valid out buffer of the
+    // advertised size. SHGFI_USEFILEATTRIBUTES keeps the shell from touching the disk.
+    let found = unsafe {
+        SHGetFileInfoW(
+            PCWSTR(wide_name.as_ptr()),
+            attributes,
+            Some(&mut file_info),
+            size_of::<SHFILEINFOW>() as u32,
+            SHGFI_ICON | SHGFI_USEFILEATTRIBUTES | icon_size_flag,
+        )
+    };
+    if found == 0 || file_info.hIcon.is_invalid() {
+        return Err("No icon is registered for this extension.".to_string());
+    }
+
+    let mut icon_info = ICONINFO::default();
+    // SAFETY: `hIcon` is a valid icon handle owned by this call until `DestroyIcon`.
+    let icon_read = unsafe { GetIconInfo(file_info.hIcon, &mut icon_info) };
+    // SAFETY: the icon is no longer needed once its bitmaps have been copied out.
+    unsafe {
+        let _ = DestroyIcon(file_info.hIcon);
+    }
+    icon_read.map_err(|err| format!("Failed to read the extension icon: {err}"))?;
+    if !icon_info.hbmMask.is_invalid() {
+        // SAFETY: `GetIconInfo` hands ownership of the mask bitmap to the caller.
+        unsafe {
+            let _ = DeleteObject(icon_info.hbmMask.into());
+        }
+    }
+    if icon_info.hbmColor.is_invalid() {
+        return Err("Extension icon has no color bitmap.".to_string());
+    }
+
+    let mut rgba = hbitmap_to_rgba_image(icon_info.hbmColor)?;
+    if rgba.width() != size || rgba.height() != size {
+        rgba = DynamicImage::ImageRgba8(rgba)
+            .resize_exact(size, size, FilterType::Lanczos3)
+            .to_rgba8();
+    }
+
+    let mut png_bytes = Vec::new();
+    DynamicImage::ImageRgba8(rgba)
+        .write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
+        .map_err(|err| format!("Failed to encode extension icon: {err}"))?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png_bytes);
+    let data_url = format!("data:image/png;base64,{encoded}");
+
+    if let Ok(mut cache) = extension_icon_cache().lock() {
+        cache.insert(cache_key, data_url.clone());
+    }
+
+    Ok(data_url)
+}
+
 #[tauri::command]
 pub async fn list_installed_apps() -> Result<Vec<InstalledApp>, String> {
     #[cfg(windows)]
@@ -491,3 +646,47 @@ pub fn load_installed_app_icon_data_url(
         Err("Installed app icons are only supported on Windows.".to_string())
     }
 }
+
+#[tauri::command]
+pub fn default_app_for(extension: String) -> Result<AppInfo, String> {
+    #[cfg(windows)]
+    {
+        let c_extension =
+            CString::new(extension).map_err(|_| "Invalid extension parameter".to_string())?;
+        // SAFETY: `c_extension` lives long enough for this synchronous call.
+        let raw_json = unsafe { crate::omni_default_app_json(c_extension.as_ptr()) };
+        if raw_json.is_null() {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to look up the default app.".to_string()));
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { crate::omni_free_string(raw_json) };
+
+        let parsed: AppInfo = serde_json::from_str(&json)
+            .map_err(|err| format!("Invalid default app payload: {err}"))?;
+        Ok(parsed)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = extension;
+        Err("Default app lookup is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn extension_icon(extension: String, size: u32) -> Result<String, String> {
+    #[cfg(windows)]
+    {
+        load_extension_icon_data_url(&extension, size)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (extension, size);
+        Err("Extension icons are only supported on Windows.".to_string())
+    }
+}
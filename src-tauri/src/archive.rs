@@ -0,0 +1,594 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, BufWriter, Seek, Write},
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{AppHandle, Emitter};
+use zip::{write::SimpleFileOptions, CompressionMethod, DateTime, ZipWriter};
+
+#[cfg(target_os = "windows")]
+use std::io::Read;
+#[cfg(target_os = "windows")]
+use zip::ZipArchive;
+
+const ZIP_PROGRESS_EVENT: &str = "omni://zip-progress";
+/// Entries from this size on need Zip64 size fields.
+const ZIP64_THRESHOLD_BYTES: u64 = u32::MAX as u64;
+#[cfg(target_os = "windows")]
+const ARCHIVE_COVER_EXTENSIONS: &[&str] =
+    &["jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff"];
+/// Longest edge of an archive cover thumbnail, in pixels.
+#[cfg(target_os = "windows")]
+const ARCHIVE_COVER_EDGE: u32 = 320;
+/// Covers are inflated into memory, so oversized entries are refused.
+#[cfg(target_os = "windows")]
+const ARCHIVE_COVER_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipReport {
+    pub output_path: String,
+    pub added: u32,
+    /// Uncompressed bytes of the added files.
+    pub total_bytes: u64,
+    /// Size of the finished archive.
+    pub archive_bytes: u64,
+    pub failed: Vec<ZipFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ZipProgress {
+    done: u32,
+    total: u32,
+    path: String,
+}
+
+struct ZipSource {
+    path: PathBuf,
+    entry_name: String,
+}
+
+/// Converts a modification time to a zip timestamp (UTC, stored in 2-second steps). Times
+/// zip cannot store, before 1980 or after 2107, become 1980-01-01.
+fn zip_date_time(modified: SystemTime) -> DateTime {
+    let seconds = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let days = (seconds / 86_400) as i64;
+    let day_seconds = seconds % 86_400;
+
+    // Civil-from-days (Howard Hinnant), valid for the whole zip date range.
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    u16::try_from(year)
+        .ok()
+        .and_then(|year| {
+            DateTime::from_date_and_time(
+                year,
+                month as u8,
+                day as u8,
+                (day_seconds / 3_600) as u8,
+                ((day_seconds % 3_600) / 60) as u8,
+                (day_seconds % 60) as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// Lowest folder that contains every path, or `None` when they share no root (other drives).
+fn common_parent(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut common = paths.first()?.parent()?.to_path_buf();
+    for path in &paths[1..] {
+        let parent = path.parent()?;
+        while !parent.starts_with(&common) {
+            common = common.parent()?.to_path_buf();
+        }
+    }
+    Some(common)
+}
+
+/// Archive name of `path` relative to `base`, using `/` separators; a drive prefix becomes a
+/// top-level folder when there is no shared base.
+fn relative_entry_name(path: &Path, base: Option<&Path>) -> String {
+    let relative = base
+        .and_then(|base| path.strip_prefix(base).ok())
+        .unwrap_or(path);
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Prefix(prefix) => Some(
+                prefix
+                    .as_os_str()
+                    .to_string_lossy()
+                    .trim_end_matches(':')
+                    .trim_start_matches(['\\', '?', '.'])
+                    .replace('\\', "/"),
+            ),
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Appends " (2)", " (3)", ... before the extension until `name` is unused.
+fn unique_entry_name(name: String, used: &mut HashSet<String>) -> String {
+    if used.insert(name.to_lowercase()) {
+        return name;
+    }
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > name.rfind('/').map_or(0, |slash| slash + 1) => {
+            (&name[..dot], &name[dot..])
+        }
+        _ => (name.as_str(), ""),
+    };
+    let mut counter = 2;
+    loop {
+        let candidate = format!("{stem} ({counter}){extension}");
+        if used.insert(candidate.to_lowercase()) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+/// Expands folders into their files and assigns each file its name inside the archive.
+fn collect_sources(
+    paths: &[PathBuf],
+    preserve_structure: bool,
+    output_path: &Path,
+    failed: &mut Vec<ZipFailure>,
+) -> Vec<ZipSource> {
+    let base = preserve_structure.then(|| common_parent(paths)).flatten();
+    let mut used_names = HashSet::new();
+    let mut sources = Vec::new();
+    let mut pending: Vec<(PathBuf, String)> = Vec::new();
+
+    for path in paths {
+        let top_name = if preserve_structure {
+            relative_entry_name(path, base.as_deref())
+        } else {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        pending.push((path.clone(), top_name));
+        while let Some((current, name)) = pending.pop() {
+            let metadata = match fs::symlink_metadata(&current) {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    failed.push(ZipFailure {
+                        path: current.to_string_lossy().into_owned(),
+                        error: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            if metadata.is_dir() {
+                let entries = match fs::read_dir(&current) {
+                    Ok(entries) => entries,
+                    Err(err) => {
+                        failed.push(ZipFailure {
+                            path: current.to_string_lossy().into_owned(),
+                            error: err.to_string(),
+                        });
+                        continue;
+                    }
+                };
+                for entry in entries.flatten() {
+                    let child_name = format!("{name}/{}", entry.file_name().to_string_lossy());
+                    pending.push((entry.path(), child_name));
+                }
+            } else if metadata.is_file() && current != output_path {
+                let entry_name = if preserve_structure {
+                    name
+                } else {
+                    // Flattening keeps only the file name, even for files found in folders.
+                    current
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or(name)
+                };
+                sources.push(ZipSource {
+                    path: current,
+                    entry_name: unique_entry_name(entry_name, &mut used_names),
+                });
+            }
+        }
+    }
+    sources
+}
+
+/// Streams one file into `zip` as a deflated entry and returns its uncompressed size. A file
+/// that fails part way is taken back out, so the archive stays valid.
+fn add_file(zip: &mut ZipWriter<BufWriter<File>>, source: &ZipSource) -> Result<u64, String> {
+    let mut file = File::open(&source.path).map_err(|err| err.to_string())?;
+    let metadata = file.metadata().map_err(|err| err.to_string())?;
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(zip_date_time(metadata.modified().unwrap_or(UNIX_EPOCH)))
+        .large_file(metadata.len() >= ZIP64_THRESHOLD_BYTES);
+    zip.start_file(source.entry_name.as_str(), options)
+        .map_err(|err| err.to_string())?;
+    match io::copy(&mut file, zip) {
+        Ok(size) => Ok(size),
+        Err(err) => {
+            zip.abort_file()
+                .map_err(|abort_err| format!("{err} ({abort_err})"))?;
+            Err(err.to_string())
+        }
+    }
+}
+
+fn write_zip(
+    app: &AppHandle<tauri::Wry>,
+    paths: Vec<String>,
+    output_path: PathBuf,
+    preserve_structure: bool,
+) -> Result<ZipReport, String> {
+    let mut failed = Vec::new();
+    let paths = paths
+        .iter()
+        .map(|path| path.trim())
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    let sources = collect_sources(&paths, preserve_structure, &output_path, &mut failed);
+    if sources.is_empty() {
+        return Err(failed
+            .first()
+            .map(|failure| format!("Nothing to add: {}", failure.error))
+            .unwrap_or_else(|| "Nothing to add to the archive.".to_string()));
+    }
+    let file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(&output_path)
+        .map_err(|err| format!("Failed to create the archive: {err}"))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let mut added = 0_u32;
+    let mut total_bytes = 0_u64;
+    let total = sources.len() as u32;
+
+    for (index, source) in sources.iter().enumerate() {
+        let source_path = source.path.to_string_lossy().into_owned();
+        match add_file(&mut zip, source) {
+            Ok(size) => {
+                added += 1;
+                total_bytes += size;
+            }
+            Err(error) => failed.push(ZipFailure {
+                path: source_path.clone(),
+                error,
+            }),
+        }
+        let _ = app.emit(
+            ZIP_PROGRESS_EVENT,
+            ZipProgress {
+                done: index as u32 + 1,
+                total,
+                path: source_path,
+            },
+        );
+    }
+
+    if added == 0 {
+        drop(zip);
+        let _ = fs::remove_file(&output_path);
+        return Err(failed
+            .first()
+            .map(|failure| format!("No file could be added: {}", failure.error))
+            .unwrap_or_else(|| "No file could be added.".to_string()));
+    }
+    let finished = zip.finish().map_err(io::Error::from).and_then(|mut inner| {
+        inner.flush()?;
+        inner.stream_position()
+    });
+    let archive_bytes = match finished {
+        Ok(archive_bytes) => archive_bytes,
+        Err(err) => {
+            let _ = fs::remove_file(&output_path);
+            return Err(format!("Failed to write the archive: {err}"));
+        }
+    };
+    Ok(ZipReport {
+        output_path: output_path.to_string_lossy().into_owned(),
+        added,
+        total_bytes,
+        archive_bytes,
+        failed,
+    })
+}
+
+/// Writes the given files and folders into a new zip at `output_path`, emitting
+/// `omni://zip-progress` after each file. Entries are flattened to file names unless
+/// `preserve_structure` is set, which keeps paths relative to the selection's common folder.
+/// Clashing names get a " (2)" suffix, and files that cannot be read are reported in `failed`
+/// instead of failing the archive.
+#[tauri::command]
+pub async fn create_zip(
+    app: AppHandle<tauri::Wry>,
+    paths: Vec<String>,
+    output_path: String,
+    preserve_structure: Option<bool>,
+) -> Result<ZipReport, String> {
+    let output_path = PathBuf::from(output_path.trim());
+    if output_path.as_os_str().is_empty() {
+        return Err("Output path is empty.".to_string());
+    }
+    if output_path.exists() {
+        return Err("Output file already exists.".to_string());
+    }
+    if !output_path.parent().is_some_and(Path::is_dir) {
+        return Err("Output folder does not exist.".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        write_zip(
+            &app,
+            paths,
+            output_path,
+            preserve_structure.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|err| format!("Zip task failed: {err}"))?
+}
+
+/// Decodes an encoded image with WIC and returns it as a PNG data URL scaled to fit
+/// `ARCHIVE_COVER_EDGE`.
+#[cfg(target_os = "windows")]
+fn image_bytes_data_url(bytes: &[u8]) -> Result<String, String> {
+    use base64::Engine;
+    use image::{DynamicImage, ImageFormat, RgbaImage};
+
+    let mut width = 0_u32;
+    let mut height = 0_u32;
+    // SAFETY: `bytes` is valid for `bytes.len()` bytes and the out pointers are valid for the
+    // call.
+    let raw_pixels = unsafe {
+        crate::omni_decode_image_bytes(bytes.as_ptr(), bytes.len(), &mut width, &mut height)
+    };
+    if raw_pixels.is_null() {
+        return Err(
+            crate::read_last_error().unwrap_or_else(|| "Failed to decode the image.".to_string())
+        );
+    }
+
+    let byte_count = width as usize * height as usize * 4;
+    // SAFETY: C++ allocated `width * height * 4` bytes at `raw_pixels`.
+    let mut pixels = unsafe { std::slice::from_raw_parts(raw_pixels, byte_count).to_vec() };
+    // SAFETY: `raw_pixels` was allocated by C++ and must be released by C++.
+    unsafe { crate::omni_free_string(raw_pixels.cast()) };
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let cover = RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| "Image has an unexpected size.".to_string())?;
+    let cover = DynamicImage::ImageRgba8(cover).thumbnail(ARCHIVE_COVER_EDGE, ARCHIVE_COVER_EDGE);
+
+    let mut png_bytes = Vec::new();
+    cover
+        .write_to(&mut io::Cursor::new(&mut png_bytes), ImageFormat::Png)
+        .map_err(|err| format!("Failed to encode the cover: {err}"))?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png_bytes);
+    Ok(format!("data:image/png;base64,{encoded}"))
+}
+
+/// Returns a thumbnail of the first image in a zip archive (`.cbz` comics, photo zips) as a
+/// PNG data URL, without extracting anything to disk. Images are ordered by their path in
+/// the archive, ignoring case; hidden entries and `__MACOSX` metadata are skipped.
+#[tauri::command]
+pub async fn archive_cover(path: String) -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !Path::new(&path).is_file() {
+            return Err("File does not exist on disk.".to_string());
+        }
+        tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+            let file =
+                File::open(&path).map_err(|err| format!("Failed to open the archive: {err}"))?;
+            let mut archive = ZipArchive::new(file)
+                .map_err(|err| format!("Failed to read the archive: {err}"))?;
+            let mut images = archive
+                .file_names()
+                .filter(|name| {
+                    let hidden = name
+                        .split('/')
+                        .any(|part| part.starts_with('.') || part.eq_ignore_ascii_case("__MACOSX"));
+                    let extension = Path::new(name)
+                        .extension()
+                        .map(|value| value.to_string_lossy().to_ascii_lowercase())
+                        .unwrap_or_default();
+                    !hidden && ARCHIVE_COVER_EXTENSIONS.contains(&extension.as_str())
+                })
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            images.sort_by_cached_key(|name| name.to_lowercase());
+
+            let cover = images
+                .first()
+                .ok_or_else(|| "Archive contains no images.".to_string())?;
+            let mut entry = archive
+                .by_name(cover)
+                .map_err(|err| format!("Failed to read the archive entry: {err}"))?;
+            if entry.size() > ARCHIVE_COVER_MAX_BYTES {
+                return Err("Cover image is too large to preview.".to_string());
+            }
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            // Capped again while inflating, in case the recorded size understates the data.
+            (&mut entry)
+                .take(ARCHIVE_COVER_MAX_BYTES)
+                .read_to_end(&mut bytes)
+                .map_err(|err| format!("Failed to inflate the archive entry: {err}"))?;
+            image_bytes_data_url(&bytes)
+        })
+        .await
+        .map_err(|err| format!("Archive cover task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("Archive covers are only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Read, time::Duration};
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("omni-search-archive-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn zip_date_time_encodes_utc_fields() {
+        // 2000-02-29 12:34:56 UTC; seconds are stored in 2-second steps.
+        let modified = UNIX_EPOCH + Duration::from_secs(951_827_696);
+        let date_time = zip_date_time(modified);
+        assert_eq!(date_time.timepart(), (12 << 11) | (34 << 5) | 28);
+        assert_eq!(date_time.datepart(), (20 << 9) | (2 << 5) | 29);
+    }
+
+    #[test]
+    fn zip_date_time_clamps_to_the_dos_epoch() {
+        let parts = |date_time: DateTime| (date_time.timepart(), date_time.datepart());
+        assert_eq!(parts(zip_date_time(UNIX_EPOCH)), (0, (1 << 5) | 1));
+        // 1979-12-31 23:59:59 UTC is still before the first zip date.
+        let modified = UNIX_EPOCH + Duration::from_secs(315_532_799);
+        assert_eq!(parts(zip_date_time(modified)), (0, (1 << 5) | 1));
+        // Midnight on 1980-01-01 is the first date zip can store, with the same fields.
+        let modified = UNIX_EPOCH + Duration::from_secs(315_532_800);
+        assert_eq!(parts(zip_date_time(modified)), (0, (1 << 5) | 1));
+    }
+
+    #[test]
+    fn unique_entry_name_numbers_clashes_before_the_extension() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_entry_name("a.txt".to_string(), &mut used), "a.txt");
+        assert_eq!(
+            unique_entry_name("A.TXT".to_string(), &mut used),
+            "A (2).TXT"
+        );
+        assert_eq!(
+            unique_entry_name("a.txt".to_string(), &mut used),
+            "a (3).txt"
+        );
+        assert_eq!(
+            unique_entry_name("notes.d/readme".to_string(), &mut used),
+            "notes.d/readme"
+        );
+        // A dot in a folder name or at the start of the name is not an extension.
+        assert_eq!(
+            unique_entry_name("notes.d/readme".to_string(), &mut used),
+            "notes.d/readme (2)"
+        );
+        assert_eq!(unique_entry_name(".env".to_string(), &mut used), ".env");
+        assert_eq!(unique_entry_name(".env".to_string(), &mut used), ".env (2)");
+    }
+
+    #[test]
+    fn relative_entry_name_strips_the_shared_base() {
+        let base = Path::new("photos");
+        assert_eq!(
+            relative_entry_name(Path::new("photos/2023/trip/a.jpg"), Some(base)),
+            "2023/trip/a.jpg"
+        );
+        assert_eq!(
+            relative_entry_name(Path::new("music/b.mp3"), Some(base)),
+            "music/b.mp3"
+        );
+        assert_eq!(
+            relative_entry_name(Path::new("docs/./c.txt"), None),
+            "docs/c.txt"
+        );
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn relative_entry_name_turns_a_drive_into_a_folder() {
+        assert_eq!(
+            relative_entry_name(Path::new(r"C:\Users\a.txt"), None),
+            "C/Users/a.txt"
+        );
+        assert_eq!(
+            relative_entry_name(Path::new(r"\\?\D:\data\b.bin"), None),
+            "D/data/b.bin"
+        );
+    }
+
+    #[test]
+    fn added_files_read_back() {
+        let dir = test_dir("round-trip");
+        let contents: [(&str, Vec<u8>); 2] = [
+            ("readme.txt", b"hello archive".to_vec()),
+            (
+                "data/values.bin",
+                (0..50_000_u32).map(|i| (i % 251) as u8).collect(),
+            ),
+        ];
+        let archive_path = dir.join("test.zip");
+        let mut zip = ZipWriter::new(BufWriter::new(File::create(&archive_path).unwrap()));
+        for (index, (name, data)) in contents.iter().enumerate() {
+            let path = dir.join(format!("source-{index}"));
+            fs::write(&path, data).unwrap();
+            let source = ZipSource {
+                path,
+                entry_name: name.to_string(),
+            };
+            assert_eq!(add_file(&mut zip, &source).unwrap(), data.len() as u64);
+        }
+        let missing = ZipSource {
+            path: dir.join("missing"),
+            entry_name: "missing".to_string(),
+        };
+        assert!(add_file(&mut zip, &missing).is_err());
+        zip.finish().unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&archive_path).unwrap()).unwrap();
+        assert_eq!(archive.len(), contents.len());
+        for (name, data) in &contents {
+            let mut entry = archive.by_name(name).unwrap();
+            assert_eq!(entry.compression(), CompressionMethod::Deflated);
+            let mut read = Vec::new();
+            entry.read_to_end(&mut read).unwrap();
+            assert_eq!(read, *data);
+        }
+        drop(archive);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,110 @@
+/// Built-in extension table behind `SearchResult::category`, so the UI does not keep its own
+/// mapping that drifts from the backend.
+const CATEGORY_EXTENSIONS: &[(&str, &[&str])] = &[
+    (
+        "image",
+        &[
+            "jpg", "jpeg", "png", "gif", "bmp", "webp", "ico", "svg", "tif", "tiff", "heic",
+            "heif", "avif", "raw", "cr2", "nef", "arw", "dng", "psd",
+        ],
+    ),
+    (
+        "video",
+        &[
+            "mp4", "mkv", "avi", "mov", "wmv", "webm", "m4v", "mpg", "mpeg", "flv", "3gp",
+        ],
+    ),
+    (
+        "audio",
+        &[
+            "mp3", "wav", "flac", "aac", "ogg", "m4a", "wma", "opus", "aiff", "mid", "midi",
+        ],
+    ),
+    (
+        "document",
+        &[
+            "pdf", "doc", "docx", "ppt", "pptx", "xls", "xlsx", "odt", "ods", "odp", "rtf", "txt",
+            "md", "csv", "epub", "log",
+        ],
+    ),
+    (
+        "archive",
+        &[
+            "zip", "rar", "7z", "tar", "gz", "tgz", "bz2", "xz", "zst", "iso", "cab",
+        ],
+    ),
+    (
+        "code",
+        &[
+            "rs", "c", "cpp", "cc", "h", "hpp", "cs", "java", "kt", "go", "py", "rb", "php", "js",
+            "jsx", "ts", "tsx", "html", "htm", "css", "scss", "json", "xml", "yaml", "yml", "toml",
+            "ini", "sql", "sh", "ps1", "bat", "cmd", "lua", "swift",
+        ],
+    ),
+];
+
+/// Classifies a result by its lowercase extension: "image", "video", "audio", "document",
+/// "archive" or "code". Directories are "folder" and anything unmapped is "other".
+pub(crate) fn category_for(extension: &str, is_directory: bool) -> &'static str {
+    if is_directory {
+        return "folder";
+    }
+    CATEGORY_EXTENSIONS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension))
+        .map_or("other", |(category, _)| category)
+}
+
+/// Extensions of a category, accepting the singular or plural name ("image" or "images").
+pub(crate) fn category_extensions(category: &str) -> Option<&'static [&'static str]> {
+    let category = category.trim().to_ascii_lowercase();
+    let singular = category.strip_suffix('s').unwrap_or(&category);
+    CATEGORY_EXTENSIONS
+        .iter()
+        .find(|(name, _)| *name == category || *name == singular)
+        .map(|(_, extensions)| *extensions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_for_maps_known_extensions() {
+        assert_eq!(category_for("jpg", false), "image");
+        assert_eq!(category_for("mkv", false), "video");
+        assert_eq!(category_for("flac", false), "audio");
+        assert_eq!(category_for("pdf", false), "document");
+        assert_eq!(category_for("7z", false), "archive");
+        assert_eq!(category_for("rs", false), "code");
+    }
+
+    #[test]
+    fn category_for_falls_back_to_folder_and_other() {
+        assert_eq!(category_for("jpg", true), "folder");
+        assert_eq!(category_for("", true), "folder");
+        assert_eq!(category_for("", false), "other");
+        assert_eq!(category_for("xyz", false), "other");
+    }
+
+    #[test]
+    fn every_extension_has_one_category() {
+        let mut seen = std::collections::HashSet::new();
+        for (_, extensions) in CATEGORY_EXTENSIONS {
+            for extension in *extensions {
+                assert_eq!(*extension, extension.to_ascii_lowercase());
+                assert!(seen.insert(*extension), "{extension} is listed twice");
+            }
+        }
+    }
+
+    #[test]
+    fn category_extensions_accepts_plurals_and_case() {
+        assert!(category_extensions("image").unwrap().contains(&"jpg"));
+        assert!(category_extensions(" Images ").unwrap().contains(&"png"));
+        assert!(category_extensions("DOCUMENTS").unwrap().contains(&"pdf"));
+        assert!(category_extensions("other").is_none());
+        assert!(category_extensions("folder").is_none());
+        assert!(category_extensions("").is_none());
+    }
+}
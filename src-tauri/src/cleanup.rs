@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+#[cfg(target_os = "windows")]
+use std::{
+    ffi::CString,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Temp and cache locations checked by `find_cleanup_candidates`, relative to the system
+/// drive. A `*` segment matches any single folder, so every user profile is covered.
+#[cfg(target_os = "windows")]
+const CLEANUP_LOCATIONS: &[(&str, &str)] = &[
+    ("User temp files", r"Users\*\AppData\Local\Temp"),
+    ("Windows temp files", r"Windows\Temp"),
+    (
+        "Internet cache",
+        r"Users\*\AppData\Local\Microsoft\Windows\INetCache",
+    ),
+    (
+        "Chrome cache",
+        r"Users\*\AppData\Local\Google\Chrome\User Data\*\Cache",
+    ),
+    (
+        "Edge cache",
+        r"Users\*\AppData\Local\Microsoft\Edge\User Data\*\Cache",
+    ),
+    (
+        "Firefox cache",
+        r"Users\*\AppData\Local\Mozilla\Firefox\Profiles\*\cache2",
+    ),
+    ("DirectX shader cache", r"Users\*\AppData\Local\D3DSCache"),
+    ("Crash dumps", r"Users\*\AppData\Local\CrashDumps"),
+    (
+        "Windows Update downloads",
+        r"Windows\SoftwareDistribution\Download",
+    ),
+];
+
+/// Files from one temp or cache location, largest first.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupGroup {
+    pub label: String,
+    /// The searched location, with `*` for the folders that vary (`C:\Users\*\...`).
+    pub location: String,
+    pub file_count: u32,
+    pub total_bytes: u64,
+    /// The search stopped at its result limit, so the location holds more files than
+    /// `file_count` and `total_bytes` cover.
+    pub truncated: bool,
+    pub files: Vec<crate::SearchResult>,
+}
+
+/// Finds files in known temp and cache folders (user and Windows temp, browser and shader
+/// caches, crash dumps, Windows Update downloads) last modified more than `older_than_days`
+/// days ago, using the index. Files come back grouped by location, largest groups first, for
+/// review before anything is deleted; locations with no such files are left out.
+#[tauri::command]
+pub async fn find_cleanup_candidates(
+    app: AppHandle<tauri::Wry>,
+    older_than_days: u32,
+) -> Result<Vec<CleanupGroup>, String> {
+    if older_than_days == 0 {
+        return Err("older_than_days must be at least 1.".to_string());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let system_drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        let cutoff = now.saturating_sub(i64::from(older_than_days) * 86_400);
+
+        let mut groups = tauri::async_runtime::spawn_blocking(move || {
+            let c_query = CString::default();
+            let c_extension = CString::default();
+            let mut groups = Vec::new();
+            for (label, relative) in CLEANUP_LOCATIONS {
+                let location = format!("{}\\{relative}", system_drive.trim_end_matches('\\'));
+                let c_within_path = CString::new(location.as_str())
+                    .map_err(|_| "Invalid path parameter".to_string())?;
+                let options = crate::SearchOptions {
+                    // Online-only placeholders take no local space to reclaim.
+                    attributes_mask: crate::CLOUD_PLACEHOLDER_ATTRIBUTES,
+                    max_modified_unix: cutoff,
+                    ..crate::SearchOptions::background(&c_query, &c_extension, &c_within_path)
+                };
+                let found = crate::run_search(&options)?;
+                let truncated = found.len() >= options.limit as usize;
+                let mut files = found
+                    .into_iter()
+                    .filter(|file| !file.is_directory)
+                    .collect::<Vec<_>>();
+                if files.is_empty() {
+                    continue;
+                }
+                files.sort_by(|a, b| b.size.cmp(&a.size));
+                groups.push(CleanupGroup {
+                    label: label.to_string(),
+                    location,
+                    file_count: files.len() as u32,
+                    total_bytes: files.iter().map(|file| file.size).sum(),
+                    truncated,
+                    files,
+                });
+            }
+            groups.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes));
+            Ok::<_, String>(groups)
+        })
+        .await
+        .map_err(|err| format!("Cleanup scan task failed: {err}"))??;
+        for group in &mut groups {
+            crate::attach_tags(&app, &mut group.files);
+        }
+        Ok(groups)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
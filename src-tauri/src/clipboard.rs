@@ -0,0 +1,192 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use tauri::{AppHandle, WebviewWindow};
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+#[cfg(target_os = "windows")]
+use std::{
+    ffi::{CStr, CString},
+    fs,
+    path::Path,
+    time::Duration,
+};
+#[cfg(target_os = "windows")]
+use tauri::Emitter;
+
+#[cfg(target_os = "windows")]
+const CLIPBOARD_PATH_EVENT: &str = "omni://clipboard-path";
+#[cfg(target_os = "windows")]
+const CLIPBOARD_WATCH_POLL_MS: u64 = 500;
+
+/// Whether the user turned the clipboard watch on; it never starts by itself.
+static CLIPBOARD_WATCH_ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Bumped on every start and stop so a watcher thread from an earlier start exits.
+static CLIPBOARD_WATCH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Larger text files are refused rather than truncated, so a paste is never silently partial.
+#[cfg(target_os = "windows")]
+const CLIPBOARD_TEXT_MAX_BYTES: u64 = 8 * 1024 * 1024;
+#[cfg(target_os = "windows")]
+const CLIPBOARD_IMAGE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+#[cfg(target_os = "windows")]
+const CLIPBOARD_IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "jfif", "bmp", "gif", "tif", "tiff", "ico", "webp",
+];
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClipboardPathEvent {
+    paths: Vec<String>,
+}
+
+#[cfg(target_os = "windows")]
+fn read_clipboard_paths() -> Vec<String> {
+    // SAFETY: FFI call only reads the clipboard; returns an allocated C string or null.
+    let raw_json = unsafe { crate::omni_get_clipboard_paths_json() };
+    if raw_json.is_null() {
+        return Vec::new();
+    }
+
+    // SAFETY: `raw_json` points to a C string allocated by C++.
+    let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+    // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+    unsafe { crate::omni_free_string(raw_json) };
+    serde_json::from_str(&json).unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn decode_clipboard_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        return String::from_utf16_lossy(&units);
+    }
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[cfg(target_os = "windows")]
+fn copy_file_content(owner: usize, path: &Path) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let size = fs::metadata(path)
+        .map_err(|err| format!("Copy to clipboard failed: {err}"))?
+        .len();
+
+    if crate::preview::TEXT_PREVIEW_EXTENSIONS.contains(&extension.as_str()) {
+        if size > CLIPBOARD_TEXT_MAX_BYTES {
+            return Err("File is too large to copy as text.".to_string());
+        }
+        let bytes = fs::read(path).map_err(|err| format!("Copy to clipboard failed: {err}"))?;
+        let c_text = CString::new(decode_clipboard_text(&bytes))
+            .map_err(|_| "File contains binary data and cannot be copied as text.".to_string())?;
+        // SAFETY: `owner` is the live main window handle; `c_text` outlives the call.
+        let ok = unsafe { crate::omni_set_clipboard_text(owner as *mut _, c_text.as_ptr()) };
+        if !ok {
+            return Err(
+                crate::read_last_error().unwrap_or_else(|| "Copy to clipboard failed.".to_string())
+            );
+        }
+        return Ok(());
+    }
+
+    if CLIPBOARD_IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        if size > CLIPBOARD_IMAGE_MAX_BYTES {
+            return Err("Image is too large to copy.".to_string());
+        }
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `owner` is the live main window handle; `c_path` outlives the call.
+        let ok = unsafe { crate::omni_set_clipboard_image(owner as *mut _, c_path.as_ptr()) };
+        if !ok {
+            return Err(
+                crate::read_last_error().unwrap_or_else(|| "Copy to clipboard failed.".to_string())
+            );
+        }
+        return Ok(());
+    }
+
+    Err(format!(
+        "Only text and image files can be copied as content (got .{extension})."
+    ))
+}
+
+/// Copies a file's content rather than its path: text files as Unicode text, images as a
+/// bitmap. Other types are rejected.
+#[tauri::command]
+pub async fn copy_content_to_clipboard(window: WebviewWindow, path: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let file_path = std::path::PathBuf::from(path);
+        if !file_path.is_file() {
+            return Err("Copy target is not a file.".to_string());
+        }
+        let owner = window
+            .hwnd()
+            .map_err(|err| format!("Failed to access the native window handle: {err}"))?
+            .0 as usize;
+        tauri::async_runtime::spawn_blocking(move || copy_file_content(owner, &file_path))
+            .await
+            .map_err(|err| format!("Clipboard task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (window, path);
+        Err("Copying file content is only supported on Windows.".to_string())
+    }
+}
+
+/// Opt-in: watches the clipboard and emits `omni://clipboard-path` with the copied paths each
+/// time files are copied in Explorer, so the UI can locate them. Whatever was on the
+/// clipboard before the call is not reported, and other clipboard content is never read past
+/// its format. Starting an active watch does nothing.
+#[tauri::command]
+pub fn start_clipboard_watch(app: AppHandle<tauri::Wry>) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        if CLIPBOARD_WATCH_ACTIVE.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+        let generation = CLIPBOARD_WATCH_GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
+        std::thread::spawn(move || {
+            // SAFETY: FFI call reads a counter and touches no memory.
+            let mut last_sequence = unsafe { crate::omni_clipboard_sequence() };
+            while CLIPBOARD_WATCH_GENERATION.load(Ordering::Acquire) == generation {
+                std::thread::sleep(Duration::from_millis(CLIPBOARD_WATCH_POLL_MS));
+                // SAFETY: as above.
+                let sequence = unsafe { crate::omni_clipboard_sequence() };
+                if sequence == last_sequence {
+                    continue;
+                }
+                last_sequence = sequence;
+                let paths = read_clipboard_paths();
+                if !paths.is_empty()
+                    && CLIPBOARD_WATCH_GENERATION.load(Ordering::Acquire) == generation
+                {
+                    let _ = app.emit(CLIPBOARD_PATH_EVENT, ClipboardPathEvent { paths });
+                }
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app;
+        Err("Clipboard watching is only supported on Windows.".to_string())
+    }
+}
+
+/// Stops the watch started by `start_clipboard_watch`; stopping an inactive watch does nothing.
+#[tauri::command]
+pub fn stop_clipboard_watch() {
+    if CLIPBOARD_WATCH_ACTIVE.swap(false, Ordering::AcqRel) {
+        CLIPBOARD_WATCH_GENERATION.fetch_add(1, Ordering::AcqRel);
+    }
+}
@@ -45,6 +45,7 @@ const QUICK_WINDOW_HEIGHT: f64 = 730.0;
 const QUICK_WINDOW_MIN_WIDTH: f64 = 960.0;
 const QUICK_WINDOW_MIN_HEIGHT: f64 = 620.0;
 pub const WINDOW_MODE_EVENT: &str = "omni-search://window-mode";
+pub const ACTIVATE_EVENT: &str = "omni://activate";
 
 static WINDOW_STATE_SAVE_ENABLED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
 
@@ -131,6 +132,7 @@ pub struct DesktopRuntimeState {
     settings: Mutex<DesktopSettings>,
     last_full_window_layout: Mutex<Option<FullWindowLayoutSnapshot>>,
     active_shortcut: Mutex<Option<String>>,
+    activate_hotkey: Mutex<Option<String>>,
     window_state_save_enabled: Arc<AtomicBool>,
     pending_full_restore_from_quick: AtomicBool,
     is_quitting: AtomicBool,
@@ -143,6 +145,7 @@ impl Default for DesktopRuntimeState {
             settings: Mutex::new(DesktopSettings::default()),
             last_full_window_layout: Mutex::new(None),
             active_shortcut: Mutex::new(None),
+            activate_hotkey: Mutex::new(None),
             window_state_save_enabled: shared_window_state_save_enabled(),
             pending_full_restore_from_quick: AtomicBool::new(false),
             is_quitting: AtomicBool::new(false),
@@ -919,6 +922,30 @@ fn register_app_shortcut<R: Runtime>(
     Ok(normalized_shortcut)
 }
 
+fn unregister_activate_hotkey<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = desktop_state(app);
+    let mut activate_hotkey = state
+        .activate_hotkey
+        .lock()
+        .map_err(|_| "Failed to lock the activate hotkey".to_string())?;
+
+    if let Some(previous_hotkey) = activate_hotkey.clone() {
+        if let Ok(previous_parsed_hotkey) = previous_hotkey.parse::<Shortcut>() {
+            if app
+                .global_shortcut()
+                .is_registered(previous_parsed_hotkey.clone())
+            {
+                app.global_shortcut()
+                    .unregister(previous_parsed_hotkey)
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+    }
+
+    *activate_hotkey = None;
+    Ok(())
+}
+
 fn quit_background_app<R: Runtime>(app: &AppHandle<R>) {
     if let Err(err) = persist_full_window_state_snapshot(app) {
         log_desktop_error("persist the desktop window layout before quitting", &err);
@@ -1003,6 +1030,57 @@ pub fn get_desktop_settings(app: AppHandle<tauri::Wry>) -> Result<DesktopSetting
     current_desktop_settings(&app)
 }
 
+#[tauri::command]
+pub fn register_global_hotkey(
+    app: AppHandle<tauri::Wry>,
+    accelerator: String,
+) -> Result<String, String> {
+    let normalized_hotkey = normalize_shortcut(&accelerator)?;
+    let parsed_hotkey = normalized_hotkey
+        .parse::<Shortcut>()
+        .map_err(|err| err.to_string())?;
+
+    let conflicts_with_app_shortcut = desktop_state(&app)
+        .active_shortcut
+        .lock()
+        .map_err(|_| "Failed to lock the active shortcut".to_string())?
+        .as_deref()
+        .map(|registered| registered.eq_ignore_ascii_case(&normalized_hotkey))
+        .unwrap_or(false);
+    if conflicts_with_app_shortcut {
+        return Err(format!(
+            "{normalized_hotkey} already toggles the quick window. Choose another shortcut."
+        ));
+    }
+
+    unregister_activate_hotkey(&app)?;
+    app.global_shortcut()
+        .on_shortcut(parsed_hotkey, |app, _, event| {
+            if event.state == ShortcutState::Pressed {
+                if let Err(err) = app.emit(ACTIVATE_EVENT, ()) {
+                    log_desktop_error("emit the activate event", &err.to_string());
+                }
+            }
+        })
+        .map_err(|err| {
+            format!(
+                "{normalized_hotkey} could not be registered; it may already be in use by another app ({err})."
+            )
+        })?;
+
+    *desktop_state(&app)
+        .activate_hotkey
+        .lock()
+        .map_err(|_| "Failed to lock the activate hotkey".to_string())? =
+        Some(normalized_hotkey.clone());
+    Ok(normalized_hotkey)
+}
+
+#[tauri::command]
+pub fn unregister_global_hotkey(app: AppHandle<tauri::Wry>) -> Result<(), String> {
+    unregister_activate_hotkey(&app)
+}
+
 #[tauri::command]
 pub fn open_full_window_command(app: AppHandle<tauri::Wry>) -> Result<(), String> {
     open_full_window(&app)
@@ -1083,19 +1161,28 @@ pub fn update_desktop_settings(
         shortcut,
     };
 
-    let normalized_shortcut = register_app_shortcut(&app, &settings)?;
+    apply_desktop_settings(&app, settings)
+}
+
+/// Registers the shortcut from `settings`, makes them current and persists them, returning
+/// them with the shortcut normalized. Used by `update_desktop_settings` and settings import.
+pub fn apply_desktop_settings<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: DesktopSettings,
+) -> Result<DesktopSettings, String> {
+    let normalized_shortcut = register_app_shortcut(app, &settings)?;
     let next_settings = DesktopSettings {
         shortcut: normalized_shortcut,
         ..settings
     };
 
-    set_desktop_settings(&app, &next_settings)?;
-    sync_window_state_save_behavior(&app);
-    if next_settings.remember_window_bounds && matches!(current_window_mode(&app), WindowMode::Full)
+    set_desktop_settings(app, &next_settings)?;
+    sync_window_state_save_behavior(app);
+    if next_settings.remember_window_bounds && matches!(current_window_mode(app), WindowMode::Full)
     {
-        persist_full_window_state_snapshot(&app)?;
+        persist_full_window_state_snapshot(app)?;
     } else {
-        persist_desktop_state(&app)?;
+        persist_desktop_state(app)?;
     }
     Ok(next_settings)
 }
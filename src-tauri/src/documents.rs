@@ -0,0 +1,458 @@
+#[cfg(target_os = "windows")]
+use crate::{DuplicateFile, DuplicateGroup, SearchResult};
+#[cfg(target_os = "windows")]
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fs::File,
+    io::Read,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+#[cfg(target_os = "windows")]
+const SIMILAR_DOCUMENT_EXTENSIONS: &str =
+    "txt,md,markdown,rst,log,csv,tsv,htm,html,xml,rtf,tex,docx,odt,pptx,odp";
+#[cfg(target_os = "windows")]
+const SIMILAR_DOCUMENT_SCAN_LIMIT: u32 = 20_000;
+/// Larger files are skipped rather than read partially.
+#[cfg(target_os = "windows")]
+const SIMILAR_DOCUMENT_MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+/// Extracted text beyond this many bytes is ignored.
+#[cfg(target_os = "windows")]
+const SIMILAR_DOCUMENT_MAX_TEXT_BYTES: usize = 2 * 1024 * 1024;
+/// Uncompressed size above which an office document part is skipped.
+#[cfg(target_os = "windows")]
+const SIMILAR_DOCUMENT_MAX_PART_BYTES: u64 = 64 * 1024 * 1024;
+#[cfg(target_os = "windows")]
+const SIMILAR_DOCUMENT_MAX_FILES_PER_GROUP: usize = 400;
+/// Words per shingle; documents with fewer words are left out.
+#[cfg(target_os = "windows")]
+const SHINGLE_WORDS: usize = 5;
+#[cfg(target_os = "windows")]
+const MINHASH_BANDS: usize = 32;
+#[cfg(target_os = "windows")]
+const MINHASH_ROWS_PER_BAND: usize = 4;
+#[cfg(target_os = "windows")]
+const MINHASH_LENGTH: usize = MINHASH_BANDS * MINHASH_ROWS_PER_BAND;
+
+#[cfg(target_os = "windows")]
+fn splitmix64(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+#[cfg(target_os = "windows")]
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Drops markup tags, keeping a space in their place so words on either side stay apart.
+#[cfg(target_os = "windows")]
+fn strip_markup(markup: &str) -> String {
+    let mut text = String::with_capacity(markup.len() / 2);
+    let mut in_tag = false;
+    for ch in markup.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Drops RTF control words and group braces, leaving the document's visible text.
+#[cfg(target_os = "windows")]
+fn strip_rtf(rtf: &str) -> String {
+    let mut text = String::with_capacity(rtf.len() / 2);
+    let mut chars = rtf.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' | '}' => text.push(' '),
+            '\\' => {
+                while chars.next_if(|next| next.is_ascii_alphabetic()).is_some() {}
+                while chars
+                    .next_if(|next| next.is_ascii_digit() || *next == '-')
+                    .is_some()
+                {}
+                chars.next_if_eq(&' ');
+                text.push(' ');
+            }
+            _ => text.push(ch),
+        }
+    }
+    text
+}
+
+#[cfg(target_os = "windows")]
+fn decode_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xff, 0xfe]) {
+        let units = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        return String::from_utf16_lossy(&units);
+    }
+    String::from_utf8_lossy(bytes.strip_prefix(&[0xef, 0xbb, 0xbf]).unwrap_or(bytes)).into_owned()
+}
+
+/// Text of an OOXML or OpenDocument file, from the archive parts holding its body.
+#[cfg(target_os = "windows")]
+fn office_document_text(path: &str, extension: &str) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(File::open(path).ok()?).ok()?;
+    let mut parts = archive
+        .file_names()
+        .filter(|name| match extension {
+            "docx" => *name == "word/document.xml",
+            "pptx" => name.starts_with("ppt/slides/slide") && name.ends_with(".xml"),
+            _ => *name == "content.xml",
+        })
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    parts.sort_by_cached_key(|name| (name.len(), name.clone()));
+
+    let mut text = String::new();
+    for part in parts {
+        if text.len() >= SIMILAR_DOCUMENT_MAX_TEXT_BYTES {
+            break;
+        }
+        let mut entry = archive.by_name(&part).ok()?;
+        if entry.size() > SIMILAR_DOCUMENT_MAX_PART_BYTES {
+            continue;
+        }
+        let mut xml = Vec::new();
+        (&mut entry)
+            .take(SIMILAR_DOCUMENT_MAX_PART_BYTES)
+            .read_to_end(&mut xml)
+            .ok()?;
+        text.push_str(&strip_markup(&String::from_utf8_lossy(&xml)));
+        text.push(' ');
+    }
+    Some(text)
+}
+
+/// The readable text of a candidate document, or `None` when it cannot be read.
+#[cfg(target_os = "windows")]
+fn document_text(path: &str) -> Option<String> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|value| value.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let mut text = match extension.as_str() {
+        "docx" | "pptx" | "odt" | "odp" => office_document_text(path, &extension)?,
+        _ => {
+            let mut bytes = Vec::new();
+            File::open(path)
+                .ok()?
+                .take(SIMILAR_DOCUMENT_MAX_TEXT_BYTES as u64)
+                .read_to_end(&mut bytes)
+                .ok()?;
+            let text = decode_text(&bytes);
+            match extension.as_str() {
+                "htm" | "html" | "xml" => strip_markup(&text),
+                "rtf" => strip_rtf(&text),
+                _ => text,
+            }
+        }
+    };
+    if text.len() > SIMILAR_DOCUMENT_MAX_TEXT_BYTES {
+        let mut cut = SIMILAR_DOCUMENT_MAX_TEXT_BYTES;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        text.truncate(cut);
+    }
+    Some(text)
+}
+
+/// MinHash signature over the document's word shingles, or `None` when it has too few words.
+/// Case and punctuation are ignored, so reflowed or recased copies still match.
+#[cfg(target_os = "windows")]
+fn minhash_signature(text: &str) -> Option<Vec<u64>> {
+    let words = text
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| fnv1a(&word.to_lowercase()))
+        .collect::<Vec<_>>();
+    if words.len() < SHINGLE_WORDS {
+        return None;
+    }
+    let seeds = (0..MINHASH_LENGTH as u64)
+        .map(splitmix64)
+        .collect::<Vec<_>>();
+    let mut signature = vec![u64::MAX; MINHASH_LENGTH];
+    for shingle in words.windows(SHINGLE_WORDS) {
+        let shingle_hash = shingle
+            .iter()
+            .fold(0_u64, |hash, word| splitmix64(hash ^ word));
+        for (slot, seed) in signature.iter_mut().zip(&seeds) {
+            *slot = (*slot).min(splitmix64(shingle_hash ^ seed));
+        }
+    }
+    Some(signature)
+}
+
+#[cfg(target_os = "windows")]
+fn estimated_similarity(left: &[u64], right: &[u64]) -> f64 {
+    let equal = left
+        .iter()
+        .zip(right)
+        .filter(|(left, right)| left == right)
+        .count();
+    equal as f64 / MINHASH_LENGTH as f64
+}
+
+#[cfg(target_os = "windows")]
+fn sign_documents_parallel(candidates: &[SearchResult]) -> Vec<Option<Vec<u64>>> {
+    let worker_count = thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(4)
+        .clamp(1, 8)
+        .min(candidates.len().max(1));
+    let next_index = AtomicUsize::new(0);
+    let mut signatures = vec![None; candidates.len()];
+
+    thread::scope(|scope| {
+        let workers = (0..worker_count)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut computed = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(candidate) = candidates.get(index) else {
+                            return computed;
+                        };
+                        let signature = (candidate.size <= SIMILAR_DOCUMENT_MAX_FILE_BYTES)
+                            .then(|| document_text(&candidate.path))
+                            .flatten()
+                            .and_then(|text| minhash_signature(&text));
+                        computed.push((index, signature));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            if let Ok(computed) = worker.join() {
+                for (index, signature) in computed {
+                    signatures[index] = signature;
+                }
+            }
+        }
+    });
+
+    signatures
+}
+
+#[cfg(target_os = "windows")]
+fn build_similar_document_group(
+    serial: u32,
+    signature: &[u64],
+    members: Vec<&SearchResult>,
+) -> DuplicateGroup {
+    let largest = members.iter().map(|file| file.size).max().unwrap_or(0);
+    let total_bytes = members.iter().map(|file| file.size).sum::<u64>();
+    let file_count = members.len() as u32;
+    let files = members
+        .into_iter()
+        .take(SIMILAR_DOCUMENT_MAX_FILES_PER_GROUP)
+        .map(|file| DuplicateFile {
+            name: file.name.clone(),
+            path: file.path.clone(),
+            size: file.size,
+            created_unix: file.created_unix,
+            modified_unix: file.modified_unix,
+        })
+        .collect();
+
+    DuplicateGroup {
+        group_id: format!("{largest:016x}-{:016x}-{serial:08x}", signature[0]),
+        size: largest,
+        total_bytes,
+        file_count,
+        files,
+        hash_mode: None,
+    }
+}
+
+/// Groups text and office documents (plain text, Markdown, HTML, RTF, DOCX, PPTX and
+/// OpenDocument) under `within_path` whose wording overlaps by at least `min_similarity`
+/// (0-1, e.g. 0.8), catching edited versions of the same document that exact duplicate
+/// detection misses. Similarity is estimated from MinHash signatures of five-word shingles;
+/// files over 16 MiB and documents with fewer than five words are skipped, and at most
+/// 20,000 documents are compared per run. Groups with the most reclaimable bytes come first.
+#[tauri::command]
+pub async fn find_similar_documents(
+    within_path: Option<String>,
+    min_similarity: f64,
+) -> Result<Vec<crate::DuplicateGroup>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !(min_similarity > 0.0 && min_similarity <= 1.0) {
+            return Err("min_similarity must be above 0 and at most 1.".to_string());
+        }
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<DuplicateGroup>, String> {
+            let c_query = CString::default();
+            let c_extension = CString::new(SIMILAR_DOCUMENT_EXTENSIONS)
+                .map_err(|_| "Invalid extension".to_string())?;
+            let c_within_path = CString::new(within_path.unwrap_or_default())
+                .map_err(|_| "Invalid path parameter".to_string())?;
+            let options = crate::SearchOptions {
+                min_size: 1,
+                max_size: SIMILAR_DOCUMENT_MAX_FILE_BYTES,
+                limit: SIMILAR_DOCUMENT_SCAN_LIMIT,
+                max_limit: SIMILAR_DOCUMENT_SCAN_LIMIT,
+                // Online-only placeholders would be downloaded just to be compared.
+                attributes_mask: crate::CLOUD_PLACEHOLDER_ATTRIBUTES,
+                ..crate::SearchOptions::background(&c_query, &c_extension, &c_within_path)
+            };
+            let candidates = crate::run_search(&options)?
+                .into_iter()
+                .filter(|file| !file.is_directory)
+                .collect::<Vec<_>>();
+            let signatures = sign_documents_parallel(&candidates);
+
+            // Locality-sensitive hashing: documents sharing any band of their signature become
+            // comparison candidates, so near-duplicates are found without comparing every pair.
+            let band_keys = |signature: &[u64]| {
+                signature
+                    .chunks_exact(MINHASH_ROWS_PER_BAND)
+                    .enumerate()
+                    .map(|(band, rows)| {
+                        (
+                            band,
+                            rows.iter().fold(0_u64, |hash, row| splitmix64(hash ^ row)),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let mut bands: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+            for (index, signature) in signatures.iter().enumerate() {
+                if let Some(signature) = signature {
+                    for key in band_keys(signature) {
+                        bands.entry(key).or_default().push(index);
+                    }
+                }
+            }
+
+            // Greedy clustering: each unassigned document seeds a group of its unassigned
+            // neighbours.
+            let mut assigned = vec![false; candidates.len()];
+            let mut groups = Vec::new();
+            for seed in 0..candidates.len() {
+                let Some(seed_signature) = signatures[seed].as_deref() else {
+                    continue;
+                };
+                if assigned[seed] {
+                    continue;
+                }
+                let mut others = band_keys(seed_signature)
+                    .iter()
+                    .filter_map(|key| bands.get(key))
+                    .flatten()
+                    .copied()
+                    .filter(|&other| other > seed && !assigned[other])
+                    .collect::<Vec<_>>();
+                others.sort_unstable();
+                others.dedup();
+                let mut members = vec![seed];
+                for other in others {
+                    if let Some(other_signature) = signatures[other].as_deref() {
+                        if estimated_similarity(seed_signature, other_signature) >= min_similarity {
+                            members.push(other);
+                        }
+                    }
+                }
+                if members.len() < 2 {
+                    continue;
+                }
+                for &member in &members {
+                    assigned[member] = true;
+                }
+                let serial = groups.len() as u32;
+                groups.push(build_similar_document_group(
+                    serial,
+                    seed_signature,
+                    members
+                        .into_iter()
+                        .map(|index| &candidates[index])
+                        .collect(),
+                ));
+            }
+
+            groups.sort_by(|left, right| {
+                let reclaimable =
+                    |group: &DuplicateGroup| group.total_bytes.saturating_sub(group.size);
+                reclaimable(right)
+                    .cmp(&reclaimable(left))
+                    .then(right.file_count.cmp(&left.file_count))
+            });
+            Ok(groups)
+        })
+        .await
+        .map_err(|err| format!("Similar document scan task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (within_path, min_similarity);
+        Err("Similar document search is only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog while the cat sleeps \
+        soundly in the warm afternoon sun beside the old stone wall";
+
+    #[test]
+    fn strip_rtf_keeps_visible_text() {
+        let text =
+            strip_rtf(r"{\rtf1\ansi\deff0 {\fonttbl {\f0 Arial;}}\f0\fs24 Hello \b world\b0 !}");
+        let words = text.split_whitespace().collect::<Vec<_>>();
+        assert_eq!(words, ["Arial;", "Hello", "world", "!"]);
+    }
+
+    #[test]
+    fn strip_rtf_drops_negative_parameters() {
+        let text = strip_rtf(r"\li-360 indented");
+        assert_eq!(text.trim(), "indented");
+    }
+
+    #[test]
+    fn minhash_signature_needs_enough_words() {
+        assert!(minhash_signature("only four short words").is_none());
+        assert!(minhash_signature("").is_none());
+        assert_eq!(minhash_signature(TEXT).unwrap().len(), MINHASH_LENGTH);
+    }
+
+    #[test]
+    fn minhash_signature_ignores_case_and_punctuation() {
+        let reflowed = TEXT.to_uppercase().replace(' ', ",\n");
+        assert_eq!(minhash_signature(TEXT), minhash_signature(&reflowed));
+    }
+
+    #[test]
+    fn minhash_signature_separates_unrelated_text() {
+        let left = minhash_signature(TEXT).unwrap();
+        let right = minhash_signature(
+            "Quarterly revenue figures improved across every region after the new \
+             pricing model launched in early spring",
+        )
+        .unwrap();
+        assert_eq!(estimated_similarity(&left, &left), 1.0);
+        assert!(estimated_similarity(&left, &right) < 0.2);
+    }
+}
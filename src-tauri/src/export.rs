@@ -0,0 +1,99 @@
+use crate::SearchResult;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_results_csv(writer: &mut impl Write, results: &[SearchResult]) -> std::io::Result<()> {
+    writer.write_all(b"name,path,size,created,modified\r\n")?;
+    for result in results {
+        write!(
+            writer,
+            "{},{},{},{},{}\r\n",
+            csv_field(&result.name),
+            csv_field(&result.path),
+            result.size,
+            result.created_unix,
+            result.modified_unix
+        )?;
+    }
+    Ok(())
+}
+
+fn write_results_txt(writer: &mut impl Write, results: &[SearchResult]) -> std::io::Result<()> {
+    for result in results {
+        write!(writer, "{}\r\n", result.path)?;
+    }
+    Ok(())
+}
+
+/// One compact JSON object per line, serialized straight into the writer so no intermediate
+/// document is built, however many results are exported.
+fn write_results_jsonl(writer: &mut impl Write, results: &[SearchResult]) -> std::io::Result<()> {
+    for result in results {
+        serde_json::to_writer(&mut *writer, result)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn export_results(
+    results: Vec<SearchResult>,
+    path: String,
+    format: String,
+) -> Result<(), String> {
+    let format = format.trim().to_ascii_lowercase();
+    if !matches!(format.as_str(), "csv" | "json" | "jsonl" | "txt") {
+        return Err(format!("Unsupported export format: {format}."));
+    }
+
+    let target = PathBuf::from(&path);
+    if target.is_dir() {
+        return Err("Export target is a folder.".to_string());
+    }
+
+    let file =
+        File::create(&target).map_err(|err| format!("Failed to create export file: {err}"))?;
+    let mut writer = BufWriter::new(file);
+    let written = match format.as_str() {
+        "csv" => write_results_csv(&mut writer, &results),
+        "txt" => write_results_txt(&mut writer, &results),
+        "jsonl" => write_results_jsonl(&mut writer, &results),
+        _ => serde_json::to_writer_pretty(&mut writer, &results).map_err(std::io::Error::from),
+    };
+    written
+        .and_then(|()| writer.flush())
+        .map_err(|err| format!("Failed to write export file: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_leaves_plain_values_alone() {
+        assert_eq!(
+            csv_field(r"C:\Users\me\notes.txt"),
+            r"C:\Users\me\notes.txt"
+        );
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn csv_field_quotes_separators_and_doubles_quotes() {
+        assert_eq!(csv_field("a,b.txt"), "\"a,b.txt\"");
+        assert_eq!(csv_field("say \"hi\".txt"), "\"say \"\"hi\"\".txt\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("return\r"), "\"return\r\"");
+    }
+}
@@ -0,0 +1,146 @@
+use crate::SearchResult;
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderNode {
+    /// Display name; chains of folders holding nothing but one subfolder are merged into a
+    /// single node named like `Users\me\Documents`.
+    pub name: String,
+    pub path: String,
+    /// Results directly inside this folder, in search order.
+    pub files: Vec<SearchResult>,
+    pub folders: Vec<FolderNode>,
+    /// Results anywhere beneath this folder.
+    pub result_count: u32,
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+struct FolderBuilder {
+    name: String,
+    path: String,
+    files: Vec<SearchResult>,
+    children: Vec<usize>,
+}
+
+/// Parent folder of `path`, keeping the trailing backslash on drive roots (`C:\`). `None` at
+/// a root.
+#[cfg(target_os = "windows")]
+fn parent_folder(path: &str) -> Option<String> {
+    let trimmed = path.trim_end_matches('\\');
+    let slash = trimmed.rfind('\\')?;
+    let parent = &trimmed[..slash];
+    if parent.ends_with(':') {
+        return Some(format!("{parent}\\"));
+    }
+    if parent.is_empty() || parent.ends_with('\\') {
+        return None;
+    }
+    Some(parent.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn folder_display_name(path: &str) -> String {
+    let trimmed = path.trim_end_matches('\\');
+    match trimmed.rfind('\\') {
+        Some(slash) if !trimmed.ends_with(':') => trimmed[slash + 1..].to_string(),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn build_node(builders: &mut [FolderBuilder], index: usize) -> FolderNode {
+    let children = std::mem::take(&mut builders[index].children);
+    let mut folders = children
+        .into_iter()
+        .map(|child| build_node(builders, child))
+        .collect::<Vec<_>>();
+    folders.sort_by_key(|folder| folder.name.to_lowercase());
+
+    let builder = &mut builders[index];
+    let files = std::mem::take(&mut builder.files);
+    let result_count = files.len() as u32
+        + folders
+            .iter()
+            .map(|folder| folder.result_count)
+            .sum::<u32>();
+    let mut node = FolderNode {
+        name: std::mem::take(&mut builder.name),
+        path: std::mem::take(&mut builder.path),
+        files,
+        folders,
+        result_count,
+    };
+    if node.files.is_empty() && node.folders.len() == 1 {
+        let child = node.folders.pop().expect("one child folder");
+        node = FolderNode {
+            name: format!("{}\\{}", node.name.trim_end_matches('\\'), child.name),
+            ..child
+        };
+    }
+    node
+}
+
+/// Nests results under their folders, roots first. Each result lands in its parent folder's
+/// `files`, including folder results, which do not become nodes of their own.
+#[cfg(target_os = "windows")]
+pub(crate) fn group_by_folder(results: Vec<SearchResult>) -> Vec<FolderNode> {
+    let mut builders: Vec<FolderBuilder> = Vec::new();
+    let mut index_by_path: HashMap<String, usize> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for result in results {
+        let Some(parent) = parent_folder(&result.path) else {
+            continue;
+        };
+        let mut folder = parent;
+        let mut pending_child: Option<usize> = None;
+        let mut leaf = None;
+        loop {
+            let key = folder.to_lowercase();
+            let (index, created) = match index_by_path.get(&key) {
+                Some(&index) => (index, false),
+                None => {
+                    builders.push(FolderBuilder {
+                        name: folder_display_name(&folder),
+                        path: folder.clone(),
+                        ..FolderBuilder::default()
+                    });
+                    index_by_path.insert(key, builders.len() - 1);
+                    (builders.len() - 1, true)
+                }
+            };
+            leaf.get_or_insert(index);
+            if let Some(child) = pending_child.take() {
+                builders[index].children.push(child);
+            }
+            if !created {
+                break;
+            }
+            match parent_folder(&folder) {
+                Some(next) => {
+                    pending_child = Some(index);
+                    folder = next;
+                }
+                None => {
+                    roots.push(index);
+                    break;
+                }
+            }
+        }
+        if let Some(leaf) = leaf {
+            builders[leaf].files.push(result);
+        }
+    }
+
+    let mut nodes = roots
+        .into_iter()
+        .map(|root| build_node(&mut builders, root))
+        .collect::<Vec<_>>();
+    nodes.sort_by_key(|node| node.name.to_lowercase());
+    nodes
+}
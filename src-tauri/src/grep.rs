@@ -0,0 +1,258 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::Read,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+const GREP_MAX_WORKERS: usize = 4;
+/// Files larger than this are skipped rather than read partially.
+const GREP_MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+/// A NUL byte in this many leading bytes marks the file as binary.
+const GREP_BINARY_SNIFF_BYTES: usize = 8 * 1024;
+const GREP_MAX_MATCHES_PER_FILE: usize = 100;
+const GREP_SNIPPET_MAX_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepLine {
+    /// 1-based line number.
+    pub line: u32,
+    /// The matching line, trimmed and cut to `GREP_SNIPPET_MAX_CHARS`.
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    pub path: String,
+    pub lines: Vec<GrepLine>,
+    /// The file had more matching lines than `GREP_MAX_MATCHES_PER_FILE`.
+    pub truncated: bool,
+}
+
+fn build_matcher(pattern: &str, regex: bool) -> Result<Regex, String> {
+    let source = if regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| format!("Invalid pattern: {err}"))
+}
+
+/// Checks a `*`/`?`/`[...]` wildcard pattern. Classes may be negated with `!` or `^` and
+/// hold ranges such as `a-z`; positions in errors are 1-based characters.
+fn validate_glob(pattern: &str) -> Result<(), String> {
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut index = 0;
+    while index < chars.len() {
+        match chars[index] {
+            ']' => {
+                return Err(format!(
+                    "Invalid pattern: unmatched `]` at position {}.",
+                    index + 1
+                ));
+            }
+            '[' => {
+                let open = index;
+                index += 1;
+                if matches!(chars.get(index), Some('!' | '^')) {
+                    index += 1;
+                }
+                // A `]` right after the opening bracket is a literal member.
+                let first_member = index;
+                while index < chars.len() && (chars[index] != ']' || index == first_member) {
+                    if chars.get(index + 1) == Some(&'-')
+                        && chars.get(index + 2).is_some_and(|end| *end != ']')
+                    {
+                        let (start, end) = (chars[index], chars[index + 2]);
+                        if start > end {
+                            return Err(format!(
+                                "Invalid pattern: range `{start}-{end}` at position {} is \
+                                 reversed.",
+                                index + 1
+                            ));
+                        }
+                        index += 3;
+                    } else {
+                        index += 1;
+                    }
+                }
+                if index >= chars.len() {
+                    return Err(format!(
+                        "Invalid pattern: `[` at position {} is never closed.",
+                        open + 1
+                    ));
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Checks `pattern` without running a search, so the search box can flag a typo as it is
+/// typed. `mode` is "regex" (compiled exactly as `grep_in_files` compiles it; errors point
+/// at the offending part) or "glob" (`*`, `?` and `[...]` classes).
+#[tauri::command]
+pub fn validate_pattern(pattern: String, mode: String) -> Result<(), String> {
+    if pattern.is_empty() {
+        return Err("Pattern is empty.".to_string());
+    }
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "regex" => build_matcher(&pattern, true).map(|_| ()),
+        "glob" => validate_glob(&pattern),
+        other => Err(format!("Unsupported pattern mode: {other}.")),
+    }
+}
+
+/// Matching lines of `path`, or `None` when the file is missing, too large, binary or
+/// has no match.
+fn grep_file(path: &str, matcher: &Regex) -> Option<GrepMatch> {
+    let mut file = File::open(path).ok()?;
+    let metadata = file.metadata().ok()?;
+    if !metadata.is_file() || metadata.len() > GREP_MAX_FILE_BYTES {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    file.read_to_end(&mut bytes).ok()?;
+    let sniffed = bytes.len().min(GREP_BINARY_SNIFF_BYTES);
+    if bytes[..sniffed].contains(&0) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines = Vec::new();
+    let mut truncated = false;
+    for (index, line) in text.lines().enumerate() {
+        if !matcher.is_match(line) {
+            continue;
+        }
+        if lines.len() == GREP_MAX_MATCHES_PER_FILE {
+            truncated = true;
+            break;
+        }
+        lines.push(GrepLine {
+            line: index as u32 + 1,
+            snippet: line.trim().chars().take(GREP_SNIPPET_MAX_CHARS).collect(),
+        });
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    Some(GrepMatch {
+        path: path.to_string(),
+        lines,
+        truncated,
+    })
+}
+
+/// Searches the contents of the given files (typically the current results) for `pattern`,
+/// case-insensitively, as a literal or with `regex` as a regular expression. Binary files
+/// and files over 16 MiB are skipped. Matches keep the input order and list at most 100
+/// lines per file.
+#[tauri::command]
+pub async fn grep_in_files(
+    paths: Vec<String>,
+    pattern: String,
+    regex: bool,
+) -> Result<Vec<GrepMatch>, String> {
+    if pattern.is_empty() {
+        return Err("Pattern is empty.".to_string());
+    }
+    let matcher = build_matcher(&pattern, regex)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let worker_count = GREP_MAX_WORKERS.min(paths.len()).max(1);
+        let next_index = AtomicUsize::new(0);
+        let mut matches: Vec<Option<GrepMatch>> = vec![None; paths.len()];
+
+        thread::scope(|scope| {
+            let workers = (0..worker_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut found = Vec::new();
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let Some(path) = paths.get(index) else {
+                                return found;
+                            };
+                            if let Some(hit) = grep_file(path, &matcher) {
+                                found.push((index, hit));
+                            }
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for worker in workers {
+                if let Ok(found) = worker.join() {
+                    for (index, hit) in found {
+                        matches[index] = Some(hit);
+                    }
+                }
+            }
+        });
+
+        matches.into_iter().flatten().collect()
+    })
+    .await
+    .map_err(|err| format!("Content search task failed: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_glob_accepts_wildcards_and_classes() {
+        for pattern in [
+            "*.rs",
+            "IMG_????.jpg",
+            "[a-z]*",
+            "[!0-9]x",
+            "[^.]*",
+            "[]]",
+            "[a-]",
+        ] {
+            assert!(validate_glob(pattern).is_ok(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn validate_glob_reports_unbalanced_brackets() {
+        assert_eq!(
+            validate_glob("ab]").unwrap_err(),
+            "Invalid pattern: unmatched `]` at position 3."
+        );
+        assert_eq!(
+            validate_glob("x[abc").unwrap_err(),
+            "Invalid pattern: `[` at position 2 is never closed."
+        );
+        assert_eq!(
+            validate_glob("[!]").unwrap_err(),
+            "Invalid pattern: `[` at position 1 is never closed."
+        );
+    }
+
+    #[test]
+    fn validate_glob_rejects_reversed_ranges() {
+        assert_eq!(
+            validate_glob("[z-a]").unwrap_err(),
+            "Invalid pattern: range `z-a` at position 2 is reversed."
+        );
+    }
+
+    #[test]
+    fn validate_pattern_checks_the_mode() {
+        assert!(validate_pattern("a(b".to_string(), "regex".to_string()).is_err());
+        assert!(validate_pattern("a(b".to_string(), "glob".to_string()).is_ok());
+        assert!(validate_pattern("".to_string(), "glob".to_string()).is_err());
+        assert!(validate_pattern("a".to_string(), "fuzzy".to_string()).is_err());
+    }
+}
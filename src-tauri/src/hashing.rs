@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+const HASH_READ_BUFFER_BYTES: usize = 1024 * 1024;
+const HASH_GROUP_MAX_WORKERS: usize = 4;
+/// Bytes read from each end of a file for `content_fingerprint`.
+const FINGERPRINT_CHUNK_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HashAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub(crate) fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("sha256") | Some("sha-256") => Ok(Self::Sha256),
+            Some("sha512") | Some("sha-512") => Ok(Self::Sha512),
+            Some(other) => Err(format!("Unsupported hash algorithm: {other}.")),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHash {
+    pub path: String,
+    pub algorithm: String,
+    pub size: u64,
+    /// Lowercase hex digest; `None` when the file could not be read.
+    pub digest: Option<String>,
+    pub error: Option<String>,
+}
+
+fn digest_reader<D: Digest>(reader: &mut impl Read) -> std::io::Result<(String, u64)> {
+    let mut hasher = D::new();
+    let mut buffer = vec![0_u8; HASH_READ_BUFFER_BYTES];
+    let mut total = 0_u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        total += read as u64;
+    }
+    let digest = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    Ok((digest, total))
+}
+
+/// Shared hashing core for single-file and group hashing.
+pub(crate) fn hash_file(path: &str, algorithm: HashAlgorithm) -> FileHash {
+    let result = File::open(path).and_then(|mut file| match algorithm {
+        HashAlgorithm::Sha256 => digest_reader::<Sha256>(&mut file),
+        HashAlgorithm::Sha512 => digest_reader::<Sha512>(&mut file),
+    });
+    let (digest, size, error) = match result {
+        Ok((digest, size)) => (Some(digest), size, None),
+        Err(err) => (None, 0, Some(format!("Failed to hash file: {err}"))),
+    };
+    FileHash {
+        path: path.to_string(),
+        algorithm: algorithm.label().to_string(),
+        size,
+        digest,
+        error,
+    }
+}
+
+#[tauri::command]
+pub async fn compute_file_hash(
+    path: String,
+    algorithm: Option<String>,
+) -> Result<FileHash, String> {
+    let algorithm = HashAlgorithm::parse(algorithm.as_deref())?;
+    let hashed = tauri::async_runtime::spawn_blocking(move || hash_file(&path, algorithm))
+        .await
+        .map_err(|err| format!("Hash task failed: {err}"))?;
+    match hashed.error {
+        Some(err) => Err(err),
+        None => Ok(hashed),
+    }
+}
+
+/// Reads until `buffer` is full or the reader is exhausted, returning the bytes read.
+fn fill_buffer(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let read = reader.read(&mut buffer[filled..])?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    Ok(filled)
+}
+
+fn compare_files(path_a: &str, path_b: &str) -> Result<bool, String> {
+    let open = |path: &str| File::open(path).map_err(|err| format!("Failed to open {path}: {err}"));
+    let mut file_a = open(path_a)?;
+    let mut file_b = open(path_b)?;
+    let size = |file: &File, path: &str| {
+        file.metadata()
+            .map(|metadata| metadata.len())
+            .map_err(|err| format!("Failed to read metadata of {path}: {err}"))
+    };
+    if size(&file_a, path_a)? != size(&file_b, path_b)? {
+        return Ok(false);
+    }
+
+    let mut buffer_a = vec![0_u8; HASH_READ_BUFFER_BYTES];
+    let mut buffer_b = vec![0_u8; HASH_READ_BUFFER_BYTES];
+    loop {
+        let read_a = fill_buffer(&mut file_a, &mut buffer_a)
+            .map_err(|err| format!("Failed to read {path_a}: {err}"))?;
+        let read_b = fill_buffer(&mut file_b, &mut buffer_b)
+            .map_err(|err| format!("Failed to read {path_b}: {err}"))?;
+        if read_a != read_b || buffer_a[..read_a] != buffer_b[..read_b] {
+            return Ok(false);
+        }
+        if read_a == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// SHA-256 over the size and the first and last 64 KiB, as hex. Cheap enough to key preview
+/// and thumbnail caches on, and unlike the modified time it stays put when a file is only
+/// touched.
+pub(crate) fn fingerprint_file(path: &str) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|err| format!("Failed to open {path}: {err}"))?;
+    let len = file
+        .metadata()
+        .map_err(|err| format!("Failed to read metadata of {path}: {err}"))?
+        .len();
+    let mut hasher = Sha256::new();
+    hasher.update(len.to_le_bytes());
+
+    let head_len = len.min(FINGERPRINT_CHUNK_BYTES);
+    let mut buffer = vec![0_u8; head_len as usize];
+    let read = fill_buffer(&mut file, &mut buffer)
+        .map_err(|err| format!("Failed to read {path}: {err}"))?;
+    hasher.update(&buffer[..read]);
+
+    let tail_len = (len - head_len).min(FINGERPRINT_CHUNK_BYTES);
+    if tail_len > 0 {
+        file.seek(SeekFrom::Start(len - tail_len))
+            .map_err(|err| format!("Failed to read {path}: {err}"))?;
+        buffer.resize(tail_len as usize, 0);
+        let read = fill_buffer(&mut file, &mut buffer)
+            .map_err(|err| format!("Failed to read {path}: {err}"))?;
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Returns the `fingerprint_file` key for `path`, the same one the preview cache checks, so
+/// frontend caches can tell whether a file's content actually changed.
+#[tauri::command]
+pub async fn content_fingerprint(path: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || fingerprint_file(&path))
+        .await
+        .map_err(|err| format!("Fingerprint task failed: {err}"))?
+}
+
+/// Compares two files byte-for-byte: sizes first, then both files in 1 MiB chunks, stopping
+/// at the first difference. This is the definitive check behind duplicate verification.
+#[tauri::command]
+pub async fn files_identical(path_a: String, path_b: String) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || compare_files(&path_a, &path_b))
+        .await
+        .map_err(|err| format!("Compare task failed: {err}"))?
+}
+
+/// Hashes every path with a small worker pool, so a size-matched duplicate group can be
+/// confirmed byte-identical. Results keep the input order; unreadable files carry an error.
+#[tauri::command]
+pub async fn hash_group(
+    paths: Vec<String>,
+    algorithm: Option<String>,
+) -> Result<Vec<FileHash>, String> {
+    let algorithm = HashAlgorithm::parse(algorithm.as_deref())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let worker_count = HASH_GROUP_MAX_WORKERS.min(paths.len()).max(1);
+        let next_index = AtomicUsize::new(0);
+        let mut hashes: Vec<Option<FileHash>> = vec![None; paths.len()];
+
+        thread::scope(|scope| {
+            let workers = (0..worker_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut computed = Vec::new();
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let Some(path) = paths.get(index) else {
+                                return computed;
+                            };
+                            computed.push((index, hash_file(path, algorithm)));
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for worker in workers {
+                if let Ok(computed) = worker.join() {
+                    for (index, hash) in computed {
+                        hashes[index] = Some(hash);
+                    }
+                }
+            }
+        });
+
+        hashes
+            .into_iter()
+            .zip(paths.iter())
+            .map(|(hash, path)| {
+                hash.unwrap_or_else(|| FileHash {
+                    path: path.clone(),
+                    algorithm: algorithm.label().to_string(),
+                    size: 0,
+                    digest: None,
+                    error: Some("Hash worker failed.".to_string()),
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|err| format!("Hash group task failed: {err}"))
+}
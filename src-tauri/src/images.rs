@@ -0,0 +1,393 @@
+#[cfg(target_os = "windows")]
+use crate::{DuplicateFile, DuplicateGroup, SearchResult};
+#[cfg(target_os = "windows")]
+use image::{imageops::FilterType, DynamicImage};
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fs,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::SystemTime,
+};
+
+#[cfg(target_os = "windows")]
+const SIMILAR_IMAGE_EXTENSIONS: &str = "jpg,jpeg,png,gif,bmp,webp,tif,tiff,heic,heif";
+#[cfg(target_os = "windows")]
+const SIMILAR_IMAGE_SCAN_LIMIT: u32 = 50_000;
+#[cfg(target_os = "windows")]
+const SIMILAR_IMAGE_MAX_FILES_PER_GROUP: usize = 400;
+#[cfg(target_os = "windows")]
+const THUMBNAIL_HASH_SIZE: i32 = 64;
+#[cfg(target_os = "windows")]
+const THUMBNAIL_MIN_SIZE: u32 = 16;
+#[cfg(target_os = "windows")]
+const THUMBNAIL_MAX_SIZE: u32 = 1024;
+/// Paths rendered by one `thumbnails` call; a results page is far below this.
+#[cfg(target_os = "windows")]
+const THUMBNAIL_BATCH_MAX_PATHS: usize = 500;
+#[cfg(target_os = "windows")]
+const THUMBNAIL_MAX_WORKERS: usize = 4;
+#[cfg(target_os = "windows")]
+const THUMBNAIL_CACHE_MAX_ENTRIES: usize = 2_048;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailResult {
+    pub path: String,
+    /// PNG data URL, absent when `error` is set.
+    pub data_url: Option<String>,
+    pub error: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+struct CachedThumbnail {
+    data_url: String,
+    modified: Option<SystemTime>,
+    len: u64,
+    last_used: u64,
+}
+
+/// LRU of rendered thumbnails keyed by lowercased path and edge size, dropped when the file's
+/// size or modified time moves.
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+struct ThumbnailCache {
+    entries: HashMap<String, CachedThumbnail>,
+    clock: u64,
+}
+
+#[cfg(target_os = "windows")]
+fn thumbnail_cache() -> &'static Mutex<ThumbnailCache> {
+    static THUMBNAIL_CACHE: OnceLock<Mutex<ThumbnailCache>> = OnceLock::new();
+    THUMBNAIL_CACHE.get_or_init(|| Mutex::new(ThumbnailCache::default()))
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn cached_thumbnail(path: &str, size: u32) -> Result<String, String> {
+    let metadata = fs::metadata(path).map_err(|_| "File does not exist on disk.".to_string())?;
+    let key = format!("{}|{size}", path.to_lowercase());
+    let modified = metadata.modified().ok();
+    if let Ok(mut cache) = thumbnail_cache().lock() {
+        cache.clock += 1;
+        let clock = cache.clock;
+        if let Some(entry) = cache.entries.get_mut(&key) {
+            if entry.modified == modified && entry.len == metadata.len() {
+                entry.last_used = clock;
+                return Ok(entry.data_url.clone());
+            }
+        }
+    }
+
+    let data_url = crate::apps::load_shell_thumbnail_data_url(path, size as i32)?;
+    if let Ok(mut cache) = thumbnail_cache().lock() {
+        cache.clock += 1;
+        let last_used = cache.clock;
+        cache.entries.insert(
+            key,
+            CachedThumbnail {
+                data_url: data_url.clone(),
+                modified,
+                len: metadata.len(),
+                last_used,
+            },
+        );
+        while cache.entries.len() > THUMBNAIL_CACHE_MAX_ENTRIES {
+            let Some(oldest_key) = cache
+                .entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            cache.entries.remove(&oldest_key);
+        }
+    }
+    Ok(data_url)
+}
+
+/// Difference hash (dHash) of a 9x8 grayscale downscale: one bit per horizontal gradient.
+#[cfg(target_os = "windows")]
+fn difference_hash(image: DynamicImage) -> u64 {
+    let gray = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0_u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if gray.get_pixel(x, y)[0] < gray.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+#[cfg(target_os = "windows")]
+fn hash_images_parallel(candidates: &[SearchResult]) -> Vec<Option<u64>> {
+    let worker_count = thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(4)
+        .clamp(1, 8)
+        .min(candidates.len().max(1));
+    let next_index = AtomicUsize::new(0);
+    let mut hashes = vec![None; candidates.len()];
+
+    thread::scope(|scope| {
+        let workers = (0..worker_count)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut computed = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(candidate) = candidates.get(index) else {
+                            return computed;
+                        };
+                        let hash = crate::apps::load_shell_thumbnail_image(
+                            &candidate.path,
+                            THUMBNAIL_HASH_SIZE,
+                        )
+                        .ok()
+                        .map(|pixels| difference_hash(DynamicImage::ImageRgba8(pixels)));
+                        computed.push((index, hash));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            if let Ok(computed) = worker.join() {
+                for (index, hash) in computed {
+                    hashes[index] = hash;
+                }
+            }
+        }
+    });
+
+    hashes
+}
+
+#[cfg(target_os = "windows")]
+fn build_similar_group(serial: u32, hash: u64, members: Vec<&SearchResult>) -> DuplicateGroup {
+    let largest = members.iter().map(|file| file.size).max().unwrap_or(0);
+    let total_bytes = members.iter().map(|file| file.size).sum::<u64>();
+    let file_count = members.len() as u32;
+    let files = members
+        .into_iter()
+        .take(SIMILAR_IMAGE_MAX_FILES_PER_GROUP)
+        .map(|file| DuplicateFile {
+            name: file.name.clone(),
+            path: file.path.clone(),
+            size: file.size,
+            created_unix: file.created_unix,
+            modified_unix: file.modified_unix,
+        })
+        .collect();
+
+    DuplicateGroup {
+        group_id: format!("{largest:016x}-{hash:016x}-{serial:08x}"),
+        size: largest,
+        total_bytes,
+        file_count,
+        files,
+        hash_mode: None,
+    }
+}
+
+#[tauri::command]
+pub async fn find_similar_images(
+    within_path: Option<String>,
+    threshold: u32,
+) -> Result<Vec<crate::DuplicateGroup>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let threshold = threshold.min(64);
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<DuplicateGroup>, String> {
+            let c_query = CString::default();
+            let c_extension = CString::new(SIMILAR_IMAGE_EXTENSIONS)
+                .map_err(|_| "Invalid extension".to_string())?;
+            let c_within_path = CString::new(within_path.unwrap_or_default())
+                .map_err(|_| "Invalid path parameter".to_string())?;
+            let options = crate::SearchOptions {
+                limit: SIMILAR_IMAGE_SCAN_LIMIT,
+                max_limit: SIMILAR_IMAGE_SCAN_LIMIT,
+                ..crate::SearchOptions::background(&c_query, &c_extension, &c_within_path)
+            };
+            let candidates = crate::run_search(&options)?
+                .into_iter()
+                .filter(|file| !file.is_directory)
+                .collect::<Vec<_>>();
+            let hashes = hash_images_parallel(&candidates);
+
+            // Greedy clustering: each unassigned image seeds a group of its unassigned neighbours.
+            let mut assigned = vec![false; candidates.len()];
+            let mut groups = Vec::new();
+            for seed in 0..candidates.len() {
+                let Some(seed_hash) = hashes[seed] else {
+                    continue;
+                };
+                if assigned[seed] {
+                    continue;
+                }
+                let mut members = vec![seed];
+                for other in (seed + 1)..candidates.len() {
+                    if assigned[other] {
+                        continue;
+                    }
+                    if let Some(other_hash) = hashes[other] {
+                        if (seed_hash ^ other_hash).count_ones() <= threshold {
+                            members.push(other);
+                        }
+                    }
+                }
+                if members.len() < 2 {
+                    continue;
+                }
+                for &member in &members {
+                    assigned[member] = true;
+                }
+                let serial = groups.len() as u32;
+                groups.push(build_similar_group(
+                    serial,
+                    seed_hash,
+                    members
+                        .into_iter()
+                        .map(|index| &candidates[index])
+                        .collect(),
+                ));
+            }
+
+            groups.sort_by(|left, right| {
+                let reclaimable =
+                    |group: &DuplicateGroup| group.total_bytes.saturating_sub(group.size);
+                reclaimable(right)
+                    .cmp(&reclaimable(left))
+                    .then(right.file_count.cmp(&left.file_count))
+            });
+            Ok(groups)
+        })
+        .await
+        .map_err(|err| format!("Similar image scan task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (within_path, threshold);
+        Err("Similar image search is only supported on Windows.".to_string())
+    }
+}
+
+/// Renders thumbnails for a whole results page in one call, so a gallery does not pay one
+/// invoke per tile. `size` is the longest edge in pixels (16-1024). Results keep the input
+/// order, and each path carries either its data URL or the reason it has none. Thumbnails
+/// are cached until the file changes; at most 500 paths are rendered per call.
+#[tauri::command]
+pub async fn thumbnails(paths: Vec<String>, size: u32) -> Result<Vec<ThumbnailResult>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if paths.len() > THUMBNAIL_BATCH_MAX_PATHS {
+            return Err(format!(
+                "At most {THUMBNAIL_BATCH_MAX_PATHS} thumbnails can be requested at once."
+            ));
+        }
+        let size = size.clamp(THUMBNAIL_MIN_SIZE, THUMBNAIL_MAX_SIZE);
+        tauri::async_runtime::spawn_blocking(move || {
+            let worker_count = THUMBNAIL_MAX_WORKERS.min(paths.len()).max(1);
+            let next_index = AtomicUsize::new(0);
+            let mut rendered: Vec<Option<Result<String, String>>> = vec![None; paths.len()];
+
+            thread::scope(|scope| {
+                let workers = (0..worker_count)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let mut computed = Vec::new();
+                            loop {
+                                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                                let Some(path) = paths.get(index) else {
+                                    return computed;
+                                };
+                                computed.push((index, cached_thumbnail(path, size)));
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                for worker in workers {
+                    if let Ok(computed) = worker.join() {
+                        for (index, thumbnail) in computed {
+                            rendered[index] = Some(thumbnail);
+                        }
+                    }
+                }
+            });
+
+            paths
+                .into_iter()
+                .zip(rendered)
+                .map(|(path, thumbnail)| {
+                    let thumbnail =
+                        thumbnail.unwrap_or_else(|| Err("Thumbnail worker failed.".to_string()));
+                    match thumbnail {
+                        Ok(data_url) => ThumbnailResult {
+                            path,
+                            data_url: Some(data_url),
+                            error: None,
+                        },
+                        Err(err) => ThumbnailResult {
+                            path,
+                            data_url: None,
+                            error: Some(err),
+                        },
+                    }
+                })
+                .collect()
+        })
+        .await
+        .map_err(|err| format!("Thumbnail task failed: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (paths, size);
+        Err("Thumbnails are only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn horizontal_gradient(width: u32, rising: bool) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, width, |x, _| {
+            let level = (x * 255 / (width - 1)) as u8;
+            let level = if rising { level } else { 255 - level };
+            Luma([level])
+        }))
+    }
+
+    #[test]
+    fn difference_hash_of_flat_image_is_zero() {
+        let flat = DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 64, Luma([128])));
+        assert_eq!(difference_hash(flat), 0);
+    }
+
+    #[test]
+    fn difference_hash_sets_a_bit_per_rising_gradient() {
+        assert_eq!(difference_hash(horizontal_gradient(64, true)), u64::MAX);
+        assert_eq!(difference_hash(horizontal_gradient(64, false)), 0);
+    }
+
+    #[test]
+    fn difference_hash_ignores_scale() {
+        assert_eq!(
+            difference_hash(horizontal_gradient(256, true)),
+            difference_hash(horizontal_gradient(32, true))
+        );
+    }
+}
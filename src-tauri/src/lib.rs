@@ -1,4 +1,3 @@
-use base64::Engine;
 use std::sync::Arc;
 mod sync_server;
 use serde::{Deserialize, Serialize};
@@ -9,14 +8,18 @@ use tauri_plugin_opener::OpenerExt;
 
 #[cfg(windows)]
 use std::{
+    ffi::c_void,
     os::windows::{ffi::OsStrExt, process::CommandExt},
     path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
 #[cfg(windows)]
 use windows::{
     core::{implement, PCWSTR},
     Win32::{
         Foundation::{DRAGDROP_S_CANCEL, DRAGDROP_S_DROP, DRAGDROP_S_USEDEFAULTCURSORS, S_OK},
+        Storage::FileSystem::GetShortPathNameW,
         System::{
             Com::IDataObject,
             Ole::{IDropSource, IDropSource_Impl, DROPEFFECT, DROPEFFECT_COPY},
@@ -29,10 +32,81 @@ use windows::{
     },
 };
 
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod advanced_search;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 mod apps;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod archive;
+#[cfg(target_os = "windows")]
+mod category;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod cleanup;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod clipboard;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
 mod desktop;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod documents;
+mod export;
+mod folder_tree;
+mod grep;
+mod hashing;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod images;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod network;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod pinned;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod preview;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod properties;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod quarantine;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod recent;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod recycle;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod rename;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod saved_searches;
+mod search_token;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod session;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod settings;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod store;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod tags;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod video;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod virtual_roots;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod watch;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackendInfo {
+    app_version: String,
+    /// `std::env::consts::OS` of the running build.
+    platform: String,
+    /// API version reported by the native scanner; `None` where the scanner is not built.
+    scanner_version: Option<u32>,
+    /// Feature flags such as `usn_journal`, `content_hash` or `perceptual_hash`. The UI should
+    /// only offer a feature whose flag is present.
+    capabilities: Vec<String>,
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Deserialize)]
+struct ScannerInfo {
+    version: u32,
+    capabilities: Vec<String>,
+}
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -40,7 +114,79 @@ struct IndexStatus {
     indexing: bool,
     ready: bool,
     indexed_count: u64,
+    indexing_threads: u32,
     last_error: Option<String>,
+    /// When the last successful indexing run completed; `None` until one has this session.
+    last_indexed_unix: Option<i64>,
+    /// How long that run took.
+    build_duration_ms: Option<u64>,
+    /// Estimated heap use of the index, as of the last index run or budget change.
+    memory_bytes: u64,
+    /// Entries were dropped to stay under the budget from `set_index_memory_budget`.
+    trimmed: bool,
+}
+
+/// Throughput of the latest indexing run, to tell a slow drive from a stuck scan.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexDiagnostics {
+    indexing: bool,
+    /// MFT enumeration data read from the volumes so far.
+    bytes_read: u64,
+    /// USN records parsed so far.
+    records_processed: u64,
+    /// Run time so far, or of the whole run once it finished.
+    elapsed_ms: u64,
+    /// Read speed since the previous call while indexing; the run average otherwise.
+    bytes_per_sec: u64,
+    records_per_sec: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveTotals {
+    drive: String,
+    file_count: u64,
+    directory_count: u64,
+    /// Summed size of the indexed files on the drive.
+    total_bytes: u64,
+}
+
+/// Scope of what is searchable, for "indexed 2.3M files totaling 1.4 TB".
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexTotals {
+    file_count: u64,
+    directory_count: u64,
+    /// Summed size of every indexed file.
+    total_bytes: u64,
+    drives: Vec<DriveTotals>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PathIndexStatus {
+    path: String,
+    /// Drive letter or network root that covers `path`, when one could be determined.
+    drive: Option<String>,
+    /// The covering drive finished its last scan, so results under `path` can be trusted.
+    indexed: bool,
+    indexing: bool,
+    indexed_count: u64,
+    /// When the covering drive was last scanned.
+    indexed_unix: Option<i64>,
+    /// USN journal monitoring is applying changes for the drive as they happen.
+    live_updates: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveIndexCount {
+    drive: String,
+    indexed_count: u64,
+    ready: bool,
+    error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,15 +194,141 @@ struct IndexStatus {
 struct SearchResult {
     name: String,
     path: String,
+    /// Lightweight searches leave this and the other metadata fields at their defaults.
+    #[serde(default)]
     extension: String,
+    #[serde(default)]
     size: u64,
+    #[serde(default)]
     created_unix: i64,
+    #[serde(default)]
     modified_unix: i64,
     is_directory: bool,
+    #[serde(default)]
+    is_reparse_point: bool,
+    target_path: Option<String>,
+    /// Half-open UTF-16 `[start, end)` offsets of query matches in `name`.
+    #[serde(default)]
+    name_ranges: Vec<[u32; 2]>,
+    /// Half-open UTF-16 `[start, end)` offsets of query matches in `path`.
+    #[serde(default)]
+    path_ranges: Vec<[u32; 2]>,
+    /// Extension-based category (see `category::category_for`), filled in after the scanner
+    /// returns.
+    #[serde(default)]
+    category: String,
+    /// Owner account of the file, only reported when the search filtered by `owner`.
+    #[serde(default)]
+    owner: Option<String>,
+    /// User tags attached through `set_tags`.
+    #[serde(default)]
+    tags: Vec<String>,
+    /// 8.3 short name, only reported with `match_short_names` and when it differs from `name`.
+    #[serde(default)]
+    short_name: Option<String>,
+    /// Name relevance in [0, 1], only reported when results are sorted by relevance.
+    #[serde(default)]
+    score: Option<f64>,
+    /// The path reaches the legacy 260-character limit; file commands handle it, but other
+    /// tools may not.
+    #[serde(default)]
+    long_path: bool,
+    /// Drive the entry lives on (`C:`), only reported by `cross_drive_name_matches`.
+    #[serde(default)]
+    drive: Option<String>,
+    /// Online-only cloud file (OneDrive and similar) whose content is downloaded on access.
+    #[serde(default)]
+    is_cloud_placeholder: bool,
+    /// NTFS file reference number, which survives renames and moves within the volume. Drives
+    /// indexed without the MFT get an id that only lasts until the next indexing run.
+    #[serde(default)]
+    file_id: u64,
+    /// Other hard links to the same file, only reported with `collapse_hardlinks`.
+    #[serde(default)]
+    aliases: Vec<String>,
+    /// Folder holding the entry (`C:\Users\me` for `C:\Users\me\a.txt`; `C:\` for top-level
+    /// entries), filled in after the scanner returns.
+    #[serde(default)]
+    parent_path: String,
+    /// Last segment of `parent_path` (`me`; the drive, `C:`, for top-level entries), for compact
+    /// "name - folder" rows.
+    #[serde(default)]
+    parent_name: String,
+    /// "user" (profile and library folders), "app" (Program Files, ProgramData, AppData),
+    /// "system" (the Windows folder and per-drive system folders) or "other", filled in after
+    /// the scanner returns.
+    #[serde(default)]
+    origin: String,
+    /// Virtual root the entry was found through, only reported by `search_virtual_root`.
+    #[serde(default)]
+    virtual_root: Option<String>,
+}
+
+/// A `lightweight` search match, for type-ahead lists that show nothing else.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchEntry {
+    name: String,
+    path: String,
+    is_directory: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResponse {
+    results: Vec<SearchResult>,
+    /// Set when the search was skipped because it had neither query text nor filters and
+    /// `require_query` was left on.
+    query_required: bool,
+    /// Set when the query text was shorter than `min_query_len`, so no search ran.
+    query_too_short: bool,
+    /// With `group_by_folder`, the results nested under their folders; `results` is then
+    /// left empty so rows are not sent twice.
+    #[serde(default)]
+    folders: Vec<folder_tree::FolderNode>,
+    /// With `lightweight`, the matches as names and paths only; `results` is then left empty.
+    #[serde(default)]
+    entries: Vec<SearchEntry>,
+    /// The scanner stopped at the effective limit, so more entries may match; the UI can
+    /// offer to load more.
+    #[serde(default)]
+    truncated: bool,
+    /// Continues this search with `next_page` when the scanner stopped at the limit and more
+    /// of the index is left to scan.
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+impl SearchResponse {
+    /// A response with no matches and no flags set, for searches that found nothing or did
+    /// not run; callers override the fields that apply.
+    fn empty() -> Self {
+        Self {
+            results: Vec::new(),
+            query_required: false,
+            query_too_short: false,
+            folders: Vec::new(),
+            entries: Vec::new(),
+            truncated: false,
+            cursor: None,
+        }
+    }
 }
 
 const SEND_TO_PHONE_ARG: &str = "--send-to-phone";
 const SEND_TO_PHONE_RESULT_EVENT: &str = "desktop-send-to-phone-result";
+#[cfg(target_os = "windows")]
+const DUPLICATE_GROUP_FOUND_EVENT: &str = "omni://duplicate-group-found";
+/// How often a running duplicate scan is asked for newly confirmed groups.
+#[cfg(target_os = "windows")]
+const DUPLICATE_GROUP_POLL_MS: u64 = 250;
+/// How often `await_index_ready` checks the scanner.
+#[cfg(target_os = "windows")]
+const INDEX_READY_POLL_MS: u64 = 100;
+/// How often `index_drive_with_progress` reports the scan's status.
+#[cfg(target_os = "windows")]
+const INDEX_PROGRESS_POLL_MS: u64 = 250;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -84,6 +356,21 @@ struct DuplicateGroup {
     total_bytes: u64,
     file_count: u32,
     files: Vec<DuplicateFile>,
+    /// What the files were matched on: "full", "partial", "size_only" or "name". Unset for
+    /// groups that are not about identical content, such as similar images.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hash_mode: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateScanEstimate {
+    /// Files at or above the minimum size.
+    candidate_files: u64,
+    candidate_bytes: u64,
+    /// Candidates sharing their size with another candidate; only these get hashed.
+    files_to_hash: u64,
+    bytes_to_hash: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -95,6 +382,26 @@ struct DuplicateScanStatus {
     total_files: u64,
     groups_found: u64,
     progress_percent: f64,
+    /// Files whose size or modified time changed between enumeration and hashing, so the
+    /// groups holding them may be unreliable.
+    #[serde(default)]
+    volatile_files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateOverview {
+    group_count: u64,
+    /// Every file that has at least one duplicate, originals included.
+    file_count: u64,
+    /// Copies beyond the first in each group: the files that could go.
+    redundant_files: u64,
+    total_bytes: u64,
+    /// Space freed by keeping one file per group.
+    reclaimable_bytes: u64,
+    /// Groups were confirmed by content hash rather than matched by size alone.
+    #[serde(default)]
+    verified: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -106,6 +413,116 @@ struct DriveInfo {
     drive_type: String,
     is_ntfs: bool,
     can_open_volume: bool,
+    volume_guid: String,
+    serial_number: u32,
+    /// `\\server\share` behind a mapped network drive or a registered network root. Network
+    /// drives report `drive_type` "network" and cannot use the fast NTFS (MFT) index path.
+    #[serde(default)]
+    unc_target: Option<String>,
+    /// How indexing reads the drive: "mft" (fast NTFS MFT enumeration with live updates),
+    /// "usn" (change-journal enumeration on other volumes such as ReFS), "walk" (recursive
+    /// directory walk: FAT/exFAT, network drives, or no administrator rights) or
+    /// "unsupported" (no readable filesystem).
+    #[serde(default)]
+    scan_mode: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveHealth {
+    drive: String,
+    /// The disk answered SMART queries; USB bridges and NVMe drives often do not.
+    smart_supported: bool,
+    /// "healthy", "warning" (reallocated or pending sectors), "failing" (a SMART threshold
+    /// is exceeded) or "unknown".
+    status: String,
+    temperature_celsius: Option<u32>,
+    reallocated_sectors: Option<u64>,
+    pending_sectors: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+    modified: Vec<String>,
+    truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexHealth {
+    indexed_count: u64,
+    sampled: u64,
+    /// Sampled entries that no longer exist on disk.
+    missing: u64,
+    /// Sampled entries whose file/folder kind changed on disk, or files whose size or
+    /// modified time no longer matches the index.
+    mismatched: u64,
+    #[serde(default)]
+    mismatch_ratio: f64,
+    /// Set when the mismatch ratio exceeds `INDEX_DRIFT_REINDEX_THRESHOLD`.
+    #[serde(default)]
+    needs_reindex: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DirSize {
+    path: String,
+    total_bytes: u64,
+    file_count: u64,
+    subdir_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtensionTotals {
+    /// Lowercase extension without the dot; empty for files without one.
+    extension: String,
+    file_count: u64,
+    total_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FolderSummary {
+    path: String,
+    total_bytes: u64,
+    file_count: u64,
+    subdir_count: u64,
+    top_by_count: Vec<ExtensionTotals>,
+    top_by_size: Vec<ExtensionTotals>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PathRefresh {
+    path: String,
+    /// Entries found on disk that were not indexed yet.
+    added: u64,
+    /// Indexed entries that no longer exist.
+    removed: u64,
+    kept: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Breadcrumb {
+    /// Last segment of `path`, or the root itself (`C:\`, `\\server\share\`).
+    name: String,
+    path: String,
+}
+
+/// Where `open_at` should land inside the opened file.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum OpenLocation {
+    /// One-based page of a document.
+    Page { page: u32 },
+    /// Playback position of a video or audio file.
+    Time { seconds: f64 },
 }
 
 #[derive(Debug, Serialize)]
@@ -114,6 +531,8 @@ struct TextPreviewPayload {
     text: String,
     truncated: bool,
     matched: bool,
+    /// Byte offset just past the last line read; pass it back as `start_offset` for the next page.
+    end_offset: u64,
 }
 
 #[cfg(windows)]
@@ -181,36 +600,272 @@ impl IDropSource_Impl for NativeFileDropSource_Impl {
     }
 }
 
+/// Highest `limit` accepted by `search_files` unless `allow_large_limit` is set.
+#[cfg(target_os = "windows")]
+const DEFAULT_RESULT_LIMIT_CEILING: u32 = 5_000;
+/// Ceiling used with `allow_large_limit`, meant for virtualized result lists.
+#[cfg(target_os = "windows")]
+const LARGE_RESULT_LIMIT_CEILING: u32 = 50_000;
+/// Share of sampled index entries that may be stale before a re-index is suggested.
+#[cfg(target_os = "windows")]
+const INDEX_DRIFT_REINDEX_THRESHOLD: f64 = 0.02;
+/// `SearchOptions::search_in` values, mirroring the scanner's `SearchScope`.
+#[cfg(target_os = "windows")]
+const SEARCH_IN_PATH: u32 = 0;
+#[cfg(target_os = "windows")]
+const SEARCH_IN_NAME: u32 = 1;
+/// `SearchOptions::match_mode` values, mirroring the scanner's `QueryMatchMode`.
+#[cfg(target_os = "windows")]
+const MATCH_MODE_TOKENS: u32 = 0;
+#[cfg(target_os = "windows")]
+const MATCH_MODE_PHRASE: u32 = 1;
+/// `SearchOptions::sort_order` values, mirroring the scanner's `SearchSortOrder`.
+#[cfg(target_os = "windows")]
+const SORT_ORDER_INDEX: u32 = 0;
+#[cfg(target_os = "windows")]
+const SORT_ORDER_RELEVANCE_RECENCY: u32 = 1;
+/// `SearchOptions::match_position` values, mirroring the scanner's `MatchPosition`.
+#[cfg(target_os = "windows")]
+const MATCH_POSITION_ANYWHERE: u32 = 0;
+#[cfg(target_os = "windows")]
+const MATCH_POSITION_PREFIX: u32 = 1;
+#[cfg(target_os = "windows")]
+const MATCH_POSITION_SUFFIX: u32 = 2;
+/// `SearchResult::origin` values.
+#[cfg(target_os = "windows")]
+const ORIGIN_USER: &str = "user";
+#[cfg(target_os = "windows")]
+const ORIGIN_APP: &str = "app";
+#[cfg(target_os = "windows")]
+const ORIGIN_SYSTEM: &str = "system";
+#[cfg(target_os = "windows")]
+const ORIGIN_OTHER: &str = "other";
+/// Window on either side of `near_time_unix` when the caller gives none: five minutes.
+#[cfg(target_os = "windows")]
+const NEAR_TIME_DEFAULT_WINDOW_SECONDS: i64 = 5 * 60;
+/// `FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS`,
+/// which cloud sync providers set on online-only files.
+#[cfg(target_os = "windows")]
+const CLOUD_PLACEHOLDER_ATTRIBUTES: u32 = 0x1000 | 0x4_0000 | 0x40_0000;
+/// Most entries `find_by_names` returns across all names.
+#[cfg(target_os = "windows")]
+const FIND_BY_NAMES_MAX_RESULTS: u32 = 10_000;
+#[cfg(target_os = "windows")]
+const PATH_AUTOCOMPLETE_DEFAULT_RESULTS: u32 = 20;
+#[cfg(target_os = "windows")]
+const PATH_AUTOCOMPLETE_MAX_RESULTS: u32 = 200;
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+struct IndexingOptions {
+    drive: *const c_char,
+    include_directories: bool,
+    scan_all_drives: bool,
+    threads: u32,
+    follow_mount_points: bool,
+    include_system_files: bool,
+    low_priority: bool,
+}
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+struct SearchOptions {
+    query: *const c_char,
+    extension: *const c_char,
+    within_path: *const c_char,
+    min_size: u64,
+    max_size: u64,
+    min_created_unix: i64,
+    max_created_unix: i64,
+    limit: u32,
+    max_limit: u32,
+    attributes_mask: u32,
+    attributes_value: u32,
+    search_in: u32,
+    min_modified_unix: i64,
+    max_modified_unix: i64,
+    fuzzy_max_distance: u32,
+    fuzzy_min_score: f64,
+    owner: *const c_char,
+    path_set: *const c_char,
+    match_short_names: bool,
+    no_extension: bool,
+    match_mode: u32,
+    sort_order: u32,
+    match_position: u32,
+    resume_from: u32,
+    out_resume_at: *mut u32,
+    /// Receives the index generation a page was read from; a resumed search fails when the
+    /// index has changed since the generation it points at.
+    index_generation: *mut u64,
+    max_depth: u32,
+    search_id: u64,
+    lightweight: bool,
+    /// Internal scans set this so they and the search box never cancel each other; the
+    /// scanner then skips its request-token bookkeeping for the call.
+    background: bool,
+}
+
+#[cfg(target_os = "windows")]
+impl SearchOptions {
+    /// Options for an internal scan matching `query` anywhere in the path, with every other
+    /// filter off and room for `LARGE_RESULT_LIMIT_CEILING` results in index order. Callers
+    /// override what they need with struct-update syntax; the strings must outlive the scan.
+    fn background(query: &CStr, extension: &CStr, within_path: &CStr) -> Self {
+        Self {
+            query: query.as_ptr(),
+            extension: extension.as_ptr(),
+            within_path: within_path.as_ptr(),
+            min_size: 0,
+            max_size: u64::MAX,
+            min_created_unix: i64::MIN,
+            max_created_unix: i64::MAX,
+            limit: LARGE_RESULT_LIMIT_CEILING,
+            max_limit: LARGE_RESULT_LIMIT_CEILING,
+            attributes_mask: 0,
+            attributes_value: 0,
+            search_in: SEARCH_IN_PATH,
+            min_modified_unix: i64::MIN,
+            max_modified_unix: i64::MAX,
+            fuzzy_max_distance: 0,
+            fuzzy_min_score: 0.0,
+            owner: std::ptr::null(),
+            path_set: std::ptr::null(),
+            match_short_names: false,
+            no_extension: false,
+            match_mode: MATCH_MODE_TOKENS,
+            sort_order: SORT_ORDER_INDEX,
+            match_position: MATCH_POSITION_ANYWHERE,
+            resume_from: 0,
+            out_resume_at: std::ptr::null_mut(),
+            index_generation: std::ptr::null_mut(),
+            max_depth: 0,
+            search_id: 0,
+            lightweight: false,
+            background: true,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+struct DuplicateScanOptions {
+    min_size: u64,
+    max_groups: u32,
+    max_files_per_group: u32,
+    skip_reparse_points: bool,
+    group_by: u32,
+    io_concurrency: u32,
+    throttle_bytes_per_sec: u64,
+}
+
 #[cfg(target_os = "windows")]
 unsafe extern "C" {
-    fn omni_start_indexing(
-        drive_utf8: *const c_char,
-        include_directories: bool,
-        scan_all_drives: bool,
+    fn omni_start_indexing_with_options(options: *const IndexingOptions) -> bool;
+    fn omni_indexing_thread_count() -> u32;
+    fn omni_take_index_update_counts(
+        out_added: *mut u64,
+        out_removed: *mut u64,
+        out_modified: *mut u64,
     ) -> bool;
     fn omni_is_indexing() -> bool;
     fn omni_is_index_ready() -> bool;
     fn omni_indexed_file_count() -> u64;
+    fn omni_last_index_run(out_completed_unix: *mut i64, out_duration_ms: *mut u64) -> bool;
+    fn omni_index_memory_bytes() -> u64;
+    fn omni_index_trimmed() -> bool;
+    fn omni_set_index_memory_budget(budget_bytes: u64);
+    fn omni_index_diagnostics_json() -> *mut c_char;
+    fn omni_index_counts_json() -> *mut c_char;
+    fn omni_index_totals_json() -> *mut c_char;
+    fn omni_present_extensions_json(within_path_utf8: *const c_char) -> *mut c_char;
+    fn omni_path_autocomplete_json(prefix_utf8: *const c_char, max_rows: u32) -> *mut c_char;
+    fn omni_path_index_status_json(path_utf8: *const c_char) -> *mut c_char;
     fn omni_last_error() -> *const c_char;
-    fn omni_search_files_json(
-        query_utf8: *const c_char,
-        extension_utf8: *const c_char,
-        min_size: u64,
-        max_size: u64,
-        min_created_unix: i64,
-        max_created_unix: i64,
-        limit: u32,
-    ) -> *mut c_char;
+    fn omni_scanner_info_json() -> *mut c_char;
+    fn omni_search_files_with_options_json(options: *const SearchOptions) -> *mut c_char;
     fn omni_cancel_search() -> bool;
-    fn omni_find_duplicates_json(
+    fn omni_cancel_search_id(search_id: u64) -> bool;
+    fn omni_find_duplicates_with_options_json(options: *const DuplicateScanOptions) -> *mut c_char;
+    fn omni_duplicate_overview_json(
         min_size: u64,
+        verify_content: bool,
+        skip_reparse_points: bool,
+    ) -> *mut c_char;
+    fn omni_find_duplicate_folders_json(
+        within_path_utf8: *const c_char,
+        compare_content: bool,
         max_groups: u32,
         max_files_per_group: u32,
     ) -> *mut c_char;
+    fn omni_estimate_duplicate_scan_json(
+        min_size: u64,
+        within_path_utf8: *const c_char,
+        skip_reparse_points: bool,
+    ) -> *mut c_char;
     fn omni_cancel_duplicate_scan() -> bool;
     fn omni_duplicate_scan_status_json() -> *mut c_char;
+    fn omni_take_duplicate_groups_json() -> *mut c_char;
     fn omni_list_drives_json() -> *mut c_char;
+    fn omni_drive_health_json(drive_utf8: *const c_char) -> *mut c_char;
+    fn omni_set_network_roots(roots_utf8: *const c_char) -> bool;
     fn omni_delete_path(path_utf8: *const c_char, recycle_bin: bool) -> bool;
+    fn omni_set_file_times(
+        path_utf8: *const c_char,
+        created_unix: *const i64,
+        modified_unix: *const i64,
+        accessed_unix: *const i64,
+    ) -> bool;
+    fn omni_who_locks_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_probe_delete_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_restore_recycled_path(
+        original_path_utf8: *const c_char,
+        deleted_after_unix: i64,
+    ) -> bool;
+    fn omni_list_alternate_streams_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_directory_size_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_folder_summary_json(path_utf8: *const c_char, top_count: u32) -> *mut c_char;
+    fn omni_refresh_path_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_lookup_path_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_lookup_file_id_json(drive_root_utf8: *const c_char, file_id: u64) -> *mut c_char;
+    fn omni_find_by_names_json(
+        names_utf8: *const c_char,
+        drives_utf8: *const c_char,
+        max_rows: u32,
+    ) -> *mut c_char;
+    fn omni_hardlink_info_json(paths_utf8: *const c_char) -> *mut c_char;
+    fn omni_read_video_frame(
+        path_utf8: *const c_char,
+        fraction: f64,
+        out_width: *mut u32,
+        out_height: *mut u32,
+    ) -> *mut u8;
+    fn omni_decode_image_bytes(
+        data: *const u8,
+        size: usize,
+        out_width: *mut u32,
+        out_height: *mut u32,
+    ) -> *mut u8;
+    fn omni_verify_index_json(sample_size: u32) -> *mut c_char;
+    fn omni_save_index_snapshot(path_utf8: *const c_char) -> bool;
+    fn omni_diff_index_json(snapshot_path_utf8: *const c_char, max_entries: u32) -> *mut c_char;
+    fn omni_open_elevated(path_utf8: *const c_char) -> bool;
+    fn omni_show_properties(path_utf8: *const c_char) -> bool;
+    fn omni_reveal_items(folder_utf8: *const c_char, items_utf8: *const c_char) -> bool;
+    fn omni_reveal_in_open_window(path_utf8: *const c_char) -> bool;
+    fn omni_resolve_shortcut_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_find_broken_shortcuts_json(within_path_utf8: *const c_char) -> *mut c_char;
+    fn omni_media_metadata_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_file_version_info_json(path_utf8: *const c_char) -> *mut c_char;
+    fn omni_set_clipboard_text(owner_hwnd: *mut c_void, text_utf8: *const c_char) -> bool;
+    fn omni_set_clipboard_image(owner_hwnd: *mut c_void, path_utf8: *const c_char) -> bool;
+    fn omni_clipboard_sequence() -> u32;
+    fn omni_get_clipboard_paths_json() -> *mut c_char;
+    fn omni_default_app_json(extension_utf8: *const c_char) -> *mut c_char;
+    fn omni_set_file_attributes(path_utf8: *const c_char, mask: u32, value: u32) -> bool;
+    fn omni_watch_folder(path_utf8: *const c_char, recursive: bool) -> u32;
+    fn omni_wait_folder_changes_json(watch_id: u32, timeout_ms: u32) -> *mut c_char;
+    fn omni_unwatch_folder(watch_id: u32) -> bool;
     fn omni_free_string(ptr: *mut c_char);
 }
 
@@ -238,11 +893,26 @@ fn current_status() -> IndexStatus {
     let ready = unsafe { omni_is_index_ready() };
     // SAFETY: FFI function reads atomic state only.
     let indexed_count = unsafe { omni_indexed_file_count() };
+    // SAFETY: FFI function reads atomic state only.
+    let indexing_threads = unsafe { omni_indexing_thread_count() };
+    let mut completed_unix = 0_i64;
+    let mut duration_ms = 0_u64;
+    // SAFETY: both out pointers are valid for the call; the function reads atomic state only.
+    let has_run = unsafe { omni_last_index_run(&mut completed_unix, &mut duration_ms) };
+    // SAFETY: FFI function reads atomic state only.
+    let memory_bytes = unsafe { omni_index_memory_bytes() };
+    // SAFETY: FFI function reads atomic state only.
+    let trimmed = unsafe { omni_index_trimmed() };
     IndexStatus {
         indexing,
         ready,
         indexed_count,
+        indexing_threads,
         last_error: read_last_error(),
+        last_indexed_unix: has_run.then_some(completed_unix),
+        build_duration_ms: has_run.then_some(duration_ms),
+        memory_bytes,
+        trimmed,
     }
 }
 
@@ -252,17 +922,29 @@ fn current_status() -> IndexStatus {
         indexing: false,
         ready: false,
         indexed_count: 0,
+        indexing_threads: 0,
         last_error: Some("OmniSearch scanner is only supported on Windows.".to_string()),
+        last_indexed_unix: None,
+        build_duration_ms: None,
+        memory_bytes: 0,
+        trimmed: false,
     }
 }
 
+/// Indexes `drive` (default C), or every NTFS drive with `include_all_drives`. `drive` may
+/// also list several letters (`"C,D"`) to index just those.
 #[tauri::command]
 fn start_indexing(
+    app: tauri::AppHandle,
     drive: Option<String>,
     include_folders: Option<bool>,
     #[allow(non_snake_case)] includeFolders: Option<bool>,
     include_all_drives: Option<bool>,
     #[allow(non_snake_case)] includeAllDrives: Option<bool>,
+    threads: Option<u32>,
+    follow_mount_points: Option<bool>,
+    include_system_files: Option<bool>,
+    low_priority: Option<bool>,
 ) -> Result<IndexStatus, String> {
     #[cfg(target_os = "windows")]
     {
@@ -270,9 +952,25 @@ fn start_indexing(
         let include_folders = include_folders.or(includeFolders).unwrap_or(false);
         let include_all_drives = include_all_drives.or(includeAllDrives).unwrap_or(false);
         let c_drive = CString::new(drive).map_err(|_| "Invalid drive parameter".to_string())?;
-        // SAFETY: `c_drive` lives long enough for this synchronous call.
-        let started =
-            unsafe { omni_start_indexing(c_drive.as_ptr(), include_folders, include_all_drives) };
+        // Registered network roots are walked alongside the selected drives.
+        network::sync_network_roots(&app)?;
+        let options = IndexingOptions {
+            drive: c_drive.as_ptr(),
+            include_directories: include_folders,
+            scan_all_drives: include_all_drives,
+            // Zero lets the scanner pick a default; it clamps to the logical CPU count.
+            threads: threads.unwrap_or(0),
+            // Off by default: volumes mounted into folders are only indexed when asked, and
+            // in all-drives mode only if they have no drive letter of their own.
+            follow_mount_points: follow_mount_points.unwrap_or(false),
+            // Off by default: `$MFT`, `$Recycle.Bin`, pagefile.sys and the like are skipped.
+            include_system_files: include_system_files.unwrap_or(false),
+            // Background processing mode lowers the scan's I/O and memory priority, so the
+            // machine stays responsive while the index builds, only more slowly.
+            low_priority: low_priority.unwrap_or(false),
+        };
+        // SAFETY: `options` and `c_drive` live long enough for this synchronous call.
+        let started = unsafe { omni_start_indexing_with_options(&options) };
         if !started {
             return Err(read_last_error().unwrap_or_else(|| "Failed to start indexing".to_string()));
         }
@@ -282,121 +980,1924 @@ fn start_indexing(
     #[cfg(not(target_os = "windows"))]
     {
         let _ = (
+            app,
             drive,
             include_folders,
             includeFolders,
             include_all_drives,
             includeAllDrives,
+            threads,
+            follow_mount_points,
+            include_system_files,
+            low_priority,
         );
         Err("OmniSearch scanner is only supported on Windows.".to_string())
     }
 }
 
+/// Indexes `drive` with the default options and streams `IndexStatus` to `on_progress`
+/// while the scan runs, resolving with the final status once it finishes. Errors when the
+/// scan ends without a ready index.
 #[tauri::command]
-fn index_status() -> IndexStatus {
-    current_status()
-}
-
-#[tauri::command]
-async fn search_files(
-    query: String,
-    extension: Option<String>,
-    min_size: Option<u64>,
-    max_size: Option<u64>,
-    min_created_unix: Option<i64>,
-    max_created_unix: Option<i64>,
-    limit: Option<u32>,
-) -> Result<Vec<SearchResult>, String> {
+async fn index_drive_with_progress(
+    app: tauri::AppHandle,
+    drive: String,
+    on_progress: tauri::ipc::Channel<IndexStatus>,
+) -> Result<IndexStatus, String> {
     #[cfg(target_os = "windows")]
     {
-        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<SearchResult>, String> {
-            let c_query = CString::new(query).map_err(|_| "Invalid query".to_string())?;
-            let c_extension = CString::new(extension.unwrap_or_default())
-                .map_err(|_| "Invalid extension".to_string())?;
+        start_indexing(
+            app,
+            Some(drive),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<IndexStatus, String> {
+            loop {
+                let status = current_status();
+                if !status.indexing {
+                    if status.ready {
+                        return Ok(status);
+                    }
+                    return Err(status
+                        .last_error
+                        .unwrap_or_else(|| "Indexing stopped before finishing.".to_string()));
+                }
+                // A closed channel only means the caller stopped listening; the scan goes on.
+                let _ = on_progress.send(status);
+                std::thread::sleep(Duration::from_millis(INDEX_PROGRESS_POLL_MS));
+            }
+        })
+        .await
+        .map_err(|err| format!("Indexing progress task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, drive, on_progress);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
 
-            let min_size = min_size.unwrap_or(0);
-            let max_size = max_size.unwrap_or(u64::MAX);
-            let min_created_unix = min_created_unix.unwrap_or(i64::MIN);
-            let max_created_unix = max_created_unix.unwrap_or(i64::MAX);
-            let limit = limit.unwrap_or(200).clamp(1, 5_000);
+/// Starts indexing the drives chosen with `set_autoindex_drives`, if any, so they are
+/// searchable without a manual start each session.
+#[cfg(target_os = "windows")]
+fn start_autoindex(app: &tauri::AppHandle) {
+    let drives = settings::load_autoindex_drives(app);
+    if drives.is_empty() {
+        return;
+    }
+    if let Err(err) = start_indexing(
+        app.clone(),
+        Some(drives.join(",")),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ) {
+        eprintln!("OmniSearch failed to start indexing on launch: {err}");
+    }
+}
 
-            // SAFETY: Inputs are valid null-terminated strings and primitive values.
-            let raw_json = unsafe {
-                omni_search_files_json(
-                    c_query.as_ptr(),
-                    c_extension.as_ptr(),
-                    min_size,
-                    max_size,
-                    min_created_unix,
-                    max_created_unix,
-                    limit,
-                )
-            };
-            if raw_json.is_null() {
-                return Err(read_last_error().unwrap_or_else(|| "Search failed".to_string()));
-            }
+/// Reports the app and scanner versions plus capability flags, so the UI can hide features
+/// this build or platform does not have.
+#[tauri::command]
+fn backend_info() -> BackendInfo {
+    let mut capabilities = vec!["sha_hash".to_string(), "mobile_sync".to_string()];
+    // Similar-image search hashes shell thumbnails, which only Windows provides.
+    if cfg!(target_os = "windows") {
+        capabilities.push("perceptual_hash".to_string());
+    }
 
+    #[cfg(target_os = "windows")]
+    let scanner_version = {
+        // SAFETY: No parameters, returns allocated C string or null.
+        let raw_json = unsafe { omni_scanner_info_json() };
+        let info = if raw_json.is_null() {
+            None
+        } else {
             // SAFETY: `raw_json` points to a C string allocated by C++.
             let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
             // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
             unsafe { omni_free_string(raw_json) };
-
-            let parsed: Vec<SearchResult> = serde_json::from_str(&json)
-                .map_err(|err| format!("Invalid search payload: {err}"))?;
-            Ok(parsed)
+            serde_json::from_str::<ScannerInfo>(&json).ok()
+        };
+        info.map(|info| {
+            capabilities.extend(info.capabilities);
+            info.version
         })
-        .await
-        .map_err(|err| format!("Search task failed: {err}"))?
-    }
-
+    };
     #[cfg(not(target_os = "windows"))]
-    {
-        let _ = (
-            query,
-            extension,
-            min_size,
-            max_size,
-            min_created_unix,
-            max_created_unix,
-            limit,
-        );
-        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    let scanner_version = None;
+
+    BackendInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: std::env::consts::OS.to_string(),
+        scanner_version,
+        capabilities,
     }
 }
 
 #[tauri::command]
-fn cancel_search() -> Result<bool, String> {
+fn index_status() -> IndexStatus {
+    current_status()
+}
+
+/// Caps the index's estimated memory at `bytes` (zero removes the cap) and trims the current
+/// index to fit; later index runs are trimmed the same way while each drive is scanned, and
+/// live updates that grow the index past the cap trim it again. Slack goes first, then hidden and
+/// system entries, then the most deeply nested ones, so low-RAM machines keep indexing big
+/// drives instead of running out of memory. `IndexStatus.trimmed` reports lost entries.
+#[tauri::command]
+async fn set_index_memory_budget(bytes: u64) -> Result<IndexStatus, String> {
     #[cfg(target_os = "windows")]
     {
-        // SAFETY: FFI call only advances the search cancellation token.
-        let cancelled = unsafe { omni_cancel_search() };
-        Ok(cancelled)
+        tauri::async_runtime::spawn_blocking(move || {
+            // SAFETY: FFI function takes a plain integer; it locks the index internally.
+            unsafe { omni_set_index_memory_budget(bytes) };
+            current_status()
+        })
+        .await
+        .map_err(|err| format!("Index trim task failed: {err}"))
     }
 
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = bytes;
         Err("OmniSearch scanner is only supported on Windows.".to_string())
     }
 }
 
+/// Resolves with the status once the index is ready, replacing a frontend polling loop. Errors
+/// when `timeout_ms` passes first; without a timeout it waits as long as indexing takes.
 #[tauri::command]
-async fn find_duplicate_groups(
-    min_size: Option<u64>,
-    max_groups: Option<u32>,
+async fn await_index_ready(timeout_ms: Option<u64>) -> Result<IndexStatus, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || -> Result<IndexStatus, String> {
+            let deadline = timeout_ms
+                .map(|timeout_ms| std::time::Instant::now() + Duration::from_millis(timeout_ms));
+            loop {
+                let status = current_status();
+                if status.ready {
+                    return Ok(status);
+                }
+                if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                    return Err("Timed out waiting for the index to become ready.".to_string());
+                }
+                std::thread::sleep(Duration::from_millis(INDEX_READY_POLL_MS));
+            }
+        })
+        .await
+        .map_err(|err| format!("Index wait task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = timeout_ms;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Samples the scanner's indexing counters. Poll it at a steady interval during a scan: each
+/// call measures the read speed since the previous one.
+#[tauri::command]
+fn index_diagnostics() -> Result<IndexDiagnostics, String> {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: FFI call only reads atomic counters; returns an allocated C string or null.
+        let raw_json = unsafe { omni_index_diagnostics_json() };
+        if raw_json.is_null() {
+            return Err(
+                read_last_error().unwrap_or_else(|| "Failed to read index diagnostics".to_string())
+            );
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { omni_free_string(raw_json) };
+
+        serde_json::from_str(&json)
+            .map_err(|err| format!("Invalid index diagnostics payload: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Tells whether search results under `path` are backed by the index: whether its drive is
+/// indexed, how old that scan is, and whether live updates keep it current.
+#[tauri::command]
+fn path_index_status(path: String) -> Result<PathIndexStatus, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let c_path = CString::new(path.trim()).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+        let raw_json = unsafe { omni_path_index_status_json(c_path.as_ptr()) };
+        if raw_json.is_null() {
+            return Err(read_last_error()
+                .unwrap_or_else(|| "Failed to read the index status for the path".to_string()));
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { omni_free_string(raw_json) };
+
+        serde_json::from_str(&json).map_err(|err| format!("Invalid index status payload: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+fn index_counts() -> Result<Vec<DriveIndexCount>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: No parameters, returns allocated C string or null.
+        let raw_json = unsafe { omni_index_counts_json() };
+        if raw_json.is_null() {
+            return Err(read_last_error()
+                .unwrap_or_else(|| "Failed to read per-drive index counts".to_string()));
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { omni_free_string(raw_json) };
+
+        let parsed: Vec<DriveIndexCount> = serde_json::from_str(&json)
+            .map_err(|err| format!("Invalid index counts payload: {err}"))?;
+        Ok(parsed)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Totals file and folder counts and file sizes from the in-memory index, per drive and
+/// overall.
+#[tauri::command]
+fn index_totals() -> Result<IndexTotals, String> {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: No parameters, returns allocated C string or null.
+        let raw_json = unsafe { omni_index_totals_json() };
+        if raw_json.is_null() {
+            return Err(
+                read_last_error().unwrap_or_else(|| "Failed to read index totals".to_string())
+            );
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { omni_free_string(raw_json) };
+
+        serde_json::from_str(&json).map_err(|err| format!("Invalid index totals payload: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Distinct extensions of the indexed files under `within_path`, most frequent first, so a
+/// type filter only offers extensions that will return something.
+#[tauri::command]
+async fn present_extensions(within_path: Option<String>) -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let c_within_path = CString::new(within_path.unwrap_or_default())
+            .map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<String>, String> {
+            // SAFETY: `c_within_path` lives long enough for this synchronous call.
+            let raw_json = unsafe { omni_present_extensions_json(c_within_path.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to list indexed extensions.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            serde_json::from_str(&json).map_err(|err| format!("Invalid extensions payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Extension listing task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = within_path;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Indexed folders whose path starts with `prefix`, for completing a `within_path` as it is
+/// typed. `C:\Us` offers `C:\Users`; a trailing separator lists the folders inside. Shallower
+/// and busier folders come first; `limit` defaults to 20 and is capped at 200.
+#[tauri::command]
+async fn path_autocomplete(prefix: String, limit: u32) -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if prefix.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let limit = if limit == 0 {
+            PATH_AUTOCOMPLETE_DEFAULT_RESULTS
+        } else {
+            limit.min(PATH_AUTOCOMPLETE_MAX_RESULTS)
+        };
+        let c_prefix =
+            CString::new(prefix.trim_start()).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<String>, String> {
+            // SAFETY: `c_prefix` lives long enough for this synchronous call.
+            let raw_json = unsafe { omni_path_autocomplete_json(c_prefix.as_ptr(), limit) };
+            if raw_json.is_null() {
+                return Err(
+                    read_last_error().unwrap_or_else(|| "Failed to complete the path.".to_string())
+                );
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid path completion payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Path completion task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (prefix, limit);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn run_search(options: &SearchOptions) -> Result<Vec<SearchResult>, String> {
+    // SAFETY: `options` and the strings it points to outlive the call.
+    let raw_json = unsafe { omni_search_files_with_options_json(options) };
+    if raw_json.is_null() {
+        return Err(read_last_error().unwrap_or_else(|| "Search failed".to_string()));
+    }
+
+    // SAFETY: `raw_json` points to a C string allocated by C++.
+    let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+    // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+    unsafe { omni_free_string(raw_json) };
+    parse_search_results(&json)
+}
+
+/// Runs one page of `options`, keeping only results from `origin` when one is given. The
+/// filter makes scanner pages come back short, so the scan resumes with the room left until
+/// `options.limit` results are kept or nothing is left to scan. Returns the kept results,
+/// whether more may follow, and where the next page starts (`u32::MAX` once the scan is done).
+/// `options.limit` is restored afterwards and `out_resume_at` left null.
+#[cfg(target_os = "windows")]
+fn run_search_page(
+    options: &mut SearchOptions,
+    origin: Option<&str>,
+) -> Result<(Vec<SearchResult>, bool, u32), String> {
+    let limit = options.limit;
+    let mut resume_at = u32::MAX;
+    let mut results = Vec::new();
+    let outcome = loop {
+        options.limit = limit - results.len() as u32;
+        options.out_resume_at = &mut resume_at;
+        let page = match run_search(options) {
+            Ok(page) => page,
+            Err(err) => break Err(err),
+        };
+        let page_full = page.len() >= options.limit as usize;
+        match origin {
+            Some(origin) => results.extend(page.into_iter().filter(|row| row.origin == origin)),
+            None => results.extend(page),
+        }
+        if results.len() >= limit as usize || resume_at == u32::MAX {
+            // Relevance-sorted searches cannot resume, so a full page of them may hide more.
+            break Ok(page_full);
+        }
+        options.resume_from = resume_at;
+    };
+    options.limit = limit;
+    options.out_resume_at = std::ptr::null_mut();
+    outcome.map(|truncated| (results, truncated, resume_at))
+}
+
+/// Paged searches kept for `next_page`; the oldest is dropped past this many.
+#[cfg(target_os = "windows")]
+const SEARCH_CURSOR_MAX: usize = 16;
+
+/// Scanner options of a paged search, with the strings they point into.
+#[cfg(target_os = "windows")]
+struct SearchCursor {
+    _query: CString,
+    _extension: CString,
+    _within_path: CString,
+    _owner: CString,
+    _path_set: CString,
+    /// `search_files`'s `origin_filter`, applied to every page.
+    origin_filter: Option<&'static str>,
+    /// `search_files`'s `collapse_hardlinks`, applied to every page.
+    collapse_hardlinks: bool,
+    /// Index generation `resume_from` belongs to; the cursor expires once the index changes.
+    index_generation: u64,
+    /// `resume_from` holds where the next page starts; `out_resume_at` and `index_generation`
+    /// are always null.
+    options: SearchOptions,
+}
+
+// SAFETY: the pointers in `options` only reference the heap buffers of the `CString`s owned
+// by the same cursor, which do not move when the cursor does.
+#[cfg(target_os = "windows")]
+unsafe impl Send for SearchCursor {}
+
+#[cfg(target_os = "windows")]
+static SEARCH_CURSORS: std::sync::Mutex<std::collections::BTreeMap<u64, SearchCursor>> =
+    std::sync::Mutex::new(std::collections::BTreeMap::new());
+#[cfg(target_os = "windows")]
+static NEXT_SEARCH_CURSOR_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+/// Keeps `cursor` and returns its opaque id.
+#[cfg(target_os = "windows")]
+fn store_search_cursor(cursor: SearchCursor) -> String {
+    let id = NEXT_SEARCH_CURSOR_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut cursors) = SEARCH_CURSORS.lock() {
+        cursors.insert(id, cursor);
+        while cursors.len() > SEARCH_CURSOR_MAX {
+            cursors.pop_first();
+        }
+    }
+    format!("{id:016x}")
+}
+
+#[cfg(target_os = "windows")]
+fn attach_tags(app: &tauri::AppHandle, results: &mut [SearchResult]) {
+    let tag_map = tags::tags_by_path(app);
+    if tag_map.is_empty() {
+        return;
+    }
+    for result in results {
+        if let Some(tags) = tag_map.get(&result.path.to_lowercase()) {
+            result.tags = tags.clone();
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Deserialize)]
+struct HardlinkInfo {
+    /// Volume serial and file id; empty unless the file has more than one link.
+    key: String,
+    links: Vec<String>,
+}
+
+/// Keeps the first result for each hard-linked file, listing its other links in `aliases`
+/// (including links the search did not return), and drops later results for the same file.
+#[cfg(target_os = "windows")]
+fn collapse_hardlinked_results(results: Vec<SearchResult>) -> Result<Vec<SearchResult>, String> {
+    let files = results
+        .iter()
+        .filter(|result| !result.is_directory)
+        .map(|result| result.path.as_str())
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return Ok(results);
+    }
+    let c_paths =
+        CString::new(files.join("\n")).map_err(|_| "Invalid path parameter".to_string())?;
+    // SAFETY: `c_paths` lives long enough for this synchronous call.
+    let raw_json = unsafe { omni_hardlink_info_json(c_paths.as_ptr()) };
+    if raw_json.is_null() {
+        return Err(read_last_error().unwrap_or_else(|| "Failed to read hard links.".to_string()));
+    }
+    // SAFETY: `raw_json` points to a C string allocated by C++.
+    let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+    // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+    unsafe { omni_free_string(raw_json) };
+    let infos: Vec<HardlinkInfo> =
+        serde_json::from_str(&json).map_err(|err| format!("Invalid hard link payload: {err}"))?;
+
+    let mut infos = infos.into_iter();
+    let mut seen = std::collections::HashSet::new();
+    let mut collapsed = Vec::with_capacity(results.len());
+    for mut result in results {
+        if result.is_directory {
+            collapsed.push(result);
+            continue;
+        }
+        let Some(info) = infos.next() else {
+            collapsed.push(result);
+            continue;
+        };
+        if info.key.is_empty() {
+            collapsed.push(result);
+            continue;
+        }
+        if !seen.insert(info.key) {
+            continue;
+        }
+        result.aliases = info
+            .links
+            .into_iter()
+            .filter(|link| !link.eq_ignore_ascii_case(&result.path))
+            .collect();
+        collapsed.push(result);
+    }
+    Ok(collapsed)
+}
+
+/// The folder holding `path` and that folder's own name. Drive roots keep their separator
+/// (`C:\`) and are named by the drive (`C:`); a path without separators has neither.
+#[cfg(target_os = "windows")]
+fn parent_path_and_name(path: &str) -> (String, String) {
+    let Some(separator) = path.rfind(['\\', '/']) else {
+        return (String::new(), String::new());
+    };
+    let parent = &path[..separator];
+    if parent.len() == 2 && parent.ends_with(':') {
+        return (format!("{parent}\\"), parent.to_string());
+    }
+    let name = parent
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    (parent.to_string(), name)
+}
+
+/// Top-level folders Windows keeps on every drive; anything under them is "system".
+#[cfg(target_os = "windows")]
+const SYSTEM_DRIVE_FOLDERS: &[&str] = &[
+    "$recycle.bin",
+    "system volume information",
+    "recovery",
+    "$windows.~bt",
+    "$windows.~ws",
+];
+
+/// Known-folder roots deciding a result's `origin`, lowercased without a trailing separator.
+/// Resolved once per session, since known folders only move when the user redirects them.
+#[cfg(target_os = "windows")]
+fn origin_roots() -> &'static [(String, &'static str)] {
+    static ORIGIN_ROOTS: std::sync::OnceLock<Vec<(String, &'static str)>> =
+        std::sync::OnceLock::new();
+    ORIGIN_ROOTS.get_or_init(|| {
+        use windows::Win32::UI::Shell::{
+            FOLDERID_Desktop, FOLDERID_Documents, FOLDERID_Downloads, FOLDERID_LocalAppData,
+            FOLDERID_LocalAppDataLow, FOLDERID_Music, FOLDERID_Pictures, FOLDERID_Profile,
+            FOLDERID_ProgramData, FOLDERID_ProgramFiles, FOLDERID_ProgramFilesX86, FOLDERID_Public,
+            FOLDERID_RoamingAppData, FOLDERID_SkyDrive, FOLDERID_UserProfiles, FOLDERID_Videos,
+            FOLDERID_Windows,
+        };
+
+        [
+            (FOLDERID_Windows, ORIGIN_SYSTEM),
+            (FOLDERID_ProgramFiles, ORIGIN_APP),
+            (FOLDERID_ProgramFilesX86, ORIGIN_APP),
+            (FOLDERID_ProgramData, ORIGIN_APP),
+            (FOLDERID_RoamingAppData, ORIGIN_APP),
+            (FOLDERID_LocalAppData, ORIGIN_APP),
+            (FOLDERID_LocalAppDataLow, ORIGIN_APP),
+            (FOLDERID_UserProfiles, ORIGIN_USER),
+            (FOLDERID_Profile, ORIGIN_USER),
+            (FOLDERID_Public, ORIGIN_USER),
+            (FOLDERID_Desktop, ORIGIN_USER),
+            (FOLDERID_Documents, ORIGIN_USER),
+            (FOLDERID_Downloads, ORIGIN_USER),
+            (FOLDERID_Pictures, ORIGIN_USER),
+            (FOLDERID_Music, ORIGIN_USER),
+            (FOLDERID_Videos, ORIGIN_USER),
+            (FOLDERID_SkyDrive, ORIGIN_USER),
+        ]
+        .iter()
+        .filter_map(|(folder_id, origin)| {
+            let path = known_folder_path(folder_id).ok()?;
+            let root = path.trim_end_matches(['\\', '/']).to_ascii_lowercase();
+            (!root.is_empty()).then_some((root, *origin))
+        })
+        .collect()
+    })
+}
+
+/// "user", "app" or "system" from the deepest known folder holding `path`, so AppData inside
+/// the profile counts as "app" while a Documents folder redirected elsewhere stays "user".
+/// Paths under no known folder, such as a `D:\Projects` tree, are "other".
+#[cfg(target_os = "windows")]
+fn path_origin(path: &str) -> &'static str {
+    let lower = path.replace('/', "\\").to_ascii_lowercase();
+    if lower.get(1..3) == Some(":\\") {
+        let top = lower[3..].split('\\').next().unwrap_or_default();
+        if SYSTEM_DRIVE_FOLDERS.contains(&top) {
+            return ORIGIN_SYSTEM;
+        }
+    }
+    origin_roots()
+        .iter()
+        .filter(|(root, _)| {
+            lower.starts_with(root.as_str())
+                && matches!(lower.as_bytes().get(root.len()), None | Some(b'\\'))
+        })
+        .max_by_key(|(root, _)| root.len())
+        .map_or(ORIGIN_OTHER, |(_, origin)| *origin)
+}
+
+#[cfg(target_os = "windows")]
+fn parse_origin_filter(value: Option<&str>) -> Result<Option<&'static str>, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some("user") => Ok(Some(ORIGIN_USER)),
+        Some("app") => Ok(Some(ORIGIN_APP)),
+        Some("system") => Ok(Some(ORIGIN_SYSTEM)),
+        Some("other") => Ok(Some(ORIGIN_OTHER)),
+        Some(other) => Err(format!("Unsupported origin filter: {other}.")),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn parse_search_results(json: &str) -> Result<Vec<SearchResult>, String> {
+    let mut results: Vec<SearchResult> =
+        serde_json::from_str(json).map_err(|err| format!("Invalid search payload: {err}"))?;
+    for result in &mut results {
+        result.category =
+            category::category_for(&result.extension, result.is_directory).to_string();
+        (result.parent_path, result.parent_name) = parent_path_and_name(&result.path);
+        result.origin = path_origin(&result.path).to_string();
+    }
+    Ok(results)
+}
+
+#[cfg(target_os = "windows")]
+fn parse_search_in(value: Option<&str>) -> Result<u32, String> {
+    // "path" and "both" match anywhere in the full path, which includes the name.
+    match value.map(str::trim) {
+        None | Some("") | Some("path") | Some("both") => Ok(SEARCH_IN_PATH),
+        Some("name") => Ok(SEARCH_IN_NAME),
+        Some(other) => Err(format!("Unsupported search_in value: {other}.")),
+    }
+}
+
+/// Inclusive byte bounds of a `size_bucket` chip. Buckets do not overlap: "tiny" starts at
+/// one byte and every upper bound stops just below the next bucket.
+#[cfg(target_os = "windows")]
+fn size_bucket_bounds(value: &str) -> Result<(u64, u64), String> {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+    match value.trim().to_ascii_lowercase().as_str() {
+        "empty" => Ok((0, 0)),
+        "tiny" => Ok((1, 100 * KB - 1)),
+        "small" => Ok((100 * KB, MB - 1)),
+        "medium" => Ok((MB, 100 * MB - 1)),
+        "large" => Ok((100 * MB, GB - 1)),
+        "huge" => Ok((GB, u64::MAX)),
+        other => Err(format!("Unsupported size bucket: {other}.")),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn parse_match_mode(value: Option<&str>) -> Result<u32, String> {
+    match value.map(str::trim) {
+        None | Some("") | Some("tokens") => Ok(MATCH_MODE_TOKENS),
+        Some("phrase") => Ok(MATCH_MODE_PHRASE),
+        Some(other) => Err(format!("Unsupported match mode: {other}.")),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn parse_sort_order(value: Option<&str>) -> Result<u32, String> {
+    match value.map(str::trim) {
+        None | Some("") | Some("index") => Ok(SORT_ORDER_INDEX),
+        Some("relevance_recency") => Ok(SORT_ORDER_RELEVANCE_RECENCY),
+        Some(other) => Err(format!("Unsupported sort order: {other}.")),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn parse_match_position(value: Option<&str>) -> Result<u32, String> {
+    match value.map(str::trim) {
+        None | Some("") | Some("anywhere") => Ok(MATCH_POSITION_ANYWHERE),
+        Some("prefix") => Ok(MATCH_POSITION_PREFIX),
+        Some("suffix") => Ok(MATCH_POSITION_SUFFIX),
+        Some(other) => Err(format!("Unsupported match position: {other}.")),
+    }
+}
+
+#[tauri::command]
+async fn search_files(
+    app: tauri::AppHandle,
+    params: search_token::SearchParams,
+    allow_large_limit: Option<bool>,
+    require_query: Option<bool>,
+    min_query_len: Option<u32>,
+    group_by_folder: Option<bool>,
+    auto_raise_limit: Option<bool>,
+    search_id: Option<u64>,
+    lightweight: Option<bool>,
+) -> Result<SearchResponse, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let search_token::SearchParams {
+            query,
+            extension,
+            within_path,
+            min_size,
+            max_size,
+            size_bucket,
+            min_created_unix,
+            max_created_unix,
+            limit,
+            attributes_mask,
+            attributes_value,
+            search_in,
+            fuzzy_max_distance,
+            fuzzy_min_score,
+            owner,
+            tags,
+            categories,
+            match_short_names,
+            no_extension,
+            mode,
+            sort,
+            match_position,
+            include_cloud_placeholders,
+            collapse_hardlinks,
+            near_time_unix,
+            near_time_window_seconds,
+            max_depth,
+            origin_filter,
+        } = params;
+        if max_depth == Some(0) {
+            return Err("max_depth must be at least 1.".to_string());
+        }
+        // Lightweight rows have no file id to collapse links by, and return no folder tree.
+        let lightweight = lightweight.unwrap_or(false);
+        if lightweight && (group_by_folder.unwrap_or(false) || collapse_hardlinks.unwrap_or(false))
+        {
+            return Err(
+                "Lightweight results cannot be grouped by folder or collapse hard links."
+                    .to_string(),
+            );
+        }
+        // Files touched together: modified within the window on either side of the reference.
+        let (min_modified_unix, max_modified_unix) = match near_time_unix {
+            Some(reference) => {
+                let window = near_time_window_seconds
+                    .unwrap_or(NEAR_TIME_DEFAULT_WINDOW_SECONDS)
+                    .max(0);
+                (
+                    reference.saturating_sub(window),
+                    reference.saturating_add(window),
+                )
+            }
+            None => (i64::MIN, i64::MAX),
+        };
+        // A bucket narrows any explicit bounds rather than replacing them.
+        let (min_size, max_size) = match size_bucket.as_deref().map(str::trim) {
+            None | Some("") => (min_size, max_size),
+            Some(bucket) => {
+                let (bucket_min, bucket_max) = size_bucket_bounds(bucket)?;
+                (
+                    Some(min_size.unwrap_or(0).max(bucket_min)),
+                    Some(max_size.unwrap_or(u64::MAX).min(bucket_max)),
+                )
+            }
+        };
+        // Categories widen the extension filter, so "images OR pdfs" is
+        // `categories: ["images"]` plus `extension: "pdf"`.
+        let mut extensions: Vec<String> = extension
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+        for category in categories.iter().flatten() {
+            if category.trim().is_empty() {
+                continue;
+            }
+            let category_extensions = category::category_extensions(category)
+                .ok_or_else(|| format!("Unsupported category: {}.", category.trim()))?;
+            extensions.extend(category_extensions.iter().map(|value| value.to_string()));
+        }
+        let extension = (!extensions.is_empty()).then(|| extensions.join(","));
+        let tags = tags.filter(|tags| tags.iter().any(|tag| !tag.trim().is_empty()));
+        // An unfiltered empty query would dump the whole index; callers must opt into that.
+        let has_criteria = !query.trim().is_empty()
+            || extension
+                .as_deref()
+                .is_some_and(|value| !value.trim().is_empty())
+            || min_size.is_some_and(|value| value > 0)
+            || max_size.is_some_and(|value| value < u64::MAX)
+            || size_bucket
+                .as_deref()
+                .is_some_and(|value| !value.trim().is_empty())
+            || min_created_unix.is_some()
+            || max_created_unix.is_some()
+            || near_time_unix.is_some()
+            || within_path
+                .as_deref()
+                .is_some_and(|value| !value.trim().is_empty())
+            || attributes_mask.is_some_and(|value| value != 0)
+            || owner
+                .as_deref()
+                .is_some_and(|value| !value.trim().is_empty())
+            || tags.is_some()
+            || no_extension.unwrap_or(false)
+            || max_depth.is_some()
+            || include_cloud_placeholders == Some(false);
+        let search_in = parse_search_in(search_in.as_deref())?;
+        let match_mode = parse_match_mode(mode.as_deref())?;
+        let sort_order = parse_sort_order(sort.as_deref())?;
+        let match_position = parse_match_position(match_position.as_deref())?;
+        // Applied to the scanner's rows, which `run_search_page` keeps fetching until the page
+        // is full.
+        let origin_filter = parse_origin_filter(origin_filter.as_deref())?;
+        let mut attributes_mask = attributes_mask.unwrap_or(0);
+        let mut attributes_value = attributes_value.unwrap_or(0);
+        if !include_cloud_placeholders.unwrap_or(true) {
+            attributes_mask |= CLOUD_PLACEHOLDER_ATTRIBUTES;
+            attributes_value &= !CLOUD_PLACEHOLDER_ATTRIBUTES;
+        }
+        if require_query.unwrap_or(true) && !has_criteria {
+            return Ok(SearchResponse {
+                query_required: true,
+                ..SearchResponse::empty()
+            });
+        }
+        // Lets type-as-you-search callers skip one-letter queries on huge indexes. Queries of
+        // up to two characters matched against names are served from the scanner's bigram index.
+        let query_len = query.trim().chars().count();
+        if query_len > 0 && query_len < min_query_len.unwrap_or(0) as usize {
+            return Ok(SearchResponse {
+                query_too_short: true,
+                ..SearchResponse::empty()
+            });
+        }
+
+        tauri::async_runtime::spawn_blocking(move || -> Result<SearchResponse, String> {
+            // Tagged files are few, so the tag filter becomes the set of paths to search.
+            let tagged_paths = match tags {
+                Some(tags) => {
+                    let paths = tags::tagged_paths(&app, tags)?;
+                    if paths.is_empty() {
+                        return Ok(SearchResponse::empty());
+                    }
+                    paths
+                }
+                None => Vec::new(),
+            };
+            let c_path_set = CString::new(tagged_paths.join("\n"))
+                .map_err(|_| "Invalid tagged path".to_string())?;
+            let c_query = CString::new(query).map_err(|_| "Invalid query".to_string())?;
+            let c_extension = CString::new(extension.unwrap_or_default())
+                .map_err(|_| "Invalid extension".to_string())?;
+            // A `*` segment matches any single folder, e.g. `C:\Users\*\Downloads`.
+            let c_within_path = CString::new(within_path.unwrap_or_default())
+                .map_err(|_| "Invalid path parameter".to_string())?;
+            let c_owner = CString::new(owner.unwrap_or_default().trim())
+                .map_err(|_| "Invalid owner".to_string())?;
+
+            let max_limit = if allow_large_limit.unwrap_or(false) {
+                LARGE_RESULT_LIMIT_CEILING
+            } else {
+                DEFAULT_RESULT_LIMIT_CEILING
+            };
+            // Extension and category filters bound the result set on their own, so a narrow
+            // filter can return every match instead of stopping at the default page.
+            let has_extension_filter =
+                !c_extension.as_bytes().is_empty() || no_extension.unwrap_or(false);
+            let limit = if auto_raise_limit.unwrap_or(false) && has_extension_filter {
+                max_limit
+            } else {
+                limit.unwrap_or(200).clamp(1, max_limit)
+            };
+            let mut options = SearchOptions {
+                query: c_query.as_ptr(),
+                extension: c_extension.as_ptr(),
+                within_path: c_within_path.as_ptr(),
+                min_size: min_size.unwrap_or(0),
+                max_size: max_size.unwrap_or(u64::MAX),
+                min_created_unix: min_created_unix.unwrap_or(i64::MIN),
+                max_created_unix: max_created_unix.unwrap_or(i64::MAX),
+                limit,
+                max_limit,
+                attributes_mask,
+                attributes_value,
+                search_in,
+                min_modified_unix,
+                max_modified_unix,
+                // Off unless asked for; the scanner caps the distance at 3 and below the
+                // query length.
+                fuzzy_max_distance: fuzzy_max_distance.unwrap_or(0),
+                fuzzy_min_score: fuzzy_min_score.unwrap_or(0.0),
+                // Resolved per candidate after every other filter, since each check reads the
+                // file's security descriptor.
+                owner: c_owner.as_ptr(),
+                path_set: c_path_set.as_ptr(),
+                // Only queries containing `~` are checked against short names, and only for
+                // entries whose long name could have produced them.
+                match_short_names: match_short_names.unwrap_or(false),
+                // Files such as `LICENSE`, `Makefile` or `.gitignore`; combined with
+                // `extension` or `categories`, either kind of file matches.
+                no_extension: no_extension.unwrap_or(false),
+                // "project report 2023" finds names containing all three words in any order;
+                // "phrase" keeps literal substring matching.
+                match_mode,
+                // "relevance_recency" ranks exact and prefix name matches first and breaks ties
+                // by modification time; the scanner sorts before cutting the list at `limit`.
+                sort_order,
+                // "prefix" finds `IMG*` names; "suffix" finds `*_final` before the extension (or
+                // at the very end of the name). Either matches the whole query against the name.
+                match_position,
+                resume_from: 0,
+                out_resume_at: std::ptr::null_mut(),
+                index_generation: std::ptr::null_mut(),
+                // 1 keeps only the immediate children of `within_path` (or of the drive root),
+                // which with a folders-only attribute filter lists one level like Explorer.
+                max_depth: max_depth.unwrap_or(0),
+                // Lets `cancel_search(search_id)` stop this search (and its later pages).
+                search_id: search_id.unwrap_or(0),
+                // Names, paths and folder flags only, returned in `entries` without a cursor.
+                lightweight,
+                background: false,
+            };
+
+            let mut index_generation = 0_u64;
+            options.index_generation = &mut index_generation;
+            let page = run_search_page(&mut options, origin_filter);
+            options.index_generation = std::ptr::null_mut();
+            let (mut results, truncated, resume_at) = page?;
+            if lightweight {
+                return Ok(SearchResponse {
+                    entries: results
+                        .into_iter()
+                        .map(|result| SearchEntry {
+                            name: result.name,
+                            path: result.path,
+                            is_directory: result.is_directory,
+                        })
+                        .collect(),
+                    truncated,
+                    ..SearchResponse::empty()
+                });
+            }
+            let collapse_hardlinks = collapse_hardlinks.unwrap_or(false);
+            if collapse_hardlinks {
+                results = collapse_hardlinked_results(results)?;
+            }
+            attach_tags(&app, &mut results);
+            if group_by_folder.unwrap_or(false) {
+                return Ok(SearchResponse {
+                    folders: folder_tree::group_by_folder(results),
+                    truncated,
+                    ..SearchResponse::empty()
+                });
+            }
+            let cursor = (resume_at != u32::MAX).then(|| {
+                options.resume_from = resume_at;
+                store_search_cursor(SearchCursor {
+                    _query: c_query,
+                    _extension: c_extension,
+                    _within_path: c_within_path,
+                    _owner: c_owner,
+                    _path_set: c_path_set,
+                    origin_filter,
+                    collapse_hardlinks,
+                    index_generation,
+                    options,
+                })
+            });
+            Ok(SearchResponse {
+                results,
+                truncated,
+                cursor,
+                ..SearchResponse::empty()
+            })
+        })
+        .await
+        .map_err(|err| format!("Search task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (
+            app,
+            params,
+            allow_large_limit,
+            require_query,
+            min_query_len,
+            group_by_folder,
+            auto_raise_limit,
+            search_id,
+            lightweight,
+        );
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchPage {
+    results: Vec<SearchResult>,
+    /// Pass back to `next_page` for the page after this one; `None` once the scan is done.
+    cursor: Option<String>,
+}
+
+/// Returns the next page of a search started by `search_files`, resuming the scan where the
+/// previous page stopped instead of re-scanning from the start. Pages keep the original
+/// limit. Only the most recent 16 paged searches are retained, and a cursor expires once the
+/// index changes (a live update, a rescan or a memory trim), since its position would no
+/// longer line up.
+#[tauri::command]
+async fn next_page(app: tauri::AppHandle, cursor: String) -> Result<SearchPage, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let id = u64::from_str_radix(cursor.trim(), 16)
+            .map_err(|_| "Invalid search cursor.".to_string())?;
+        let mut search = SEARCH_CURSORS
+            .lock()
+            .map_err(|_| "Search cursors are unavailable.".to_string())?
+            .remove(&id)
+            .ok_or_else(|| "Search cursor has expired; run the search again.".to_string())?;
+
+        tauri::async_runtime::spawn_blocking(move || -> Result<SearchPage, String> {
+            search.options.index_generation = &mut search.index_generation;
+            let page = run_search_page(&mut search.options, search.origin_filter);
+            search.options.index_generation = std::ptr::null_mut();
+            let (mut results, _, resume_at) = page?;
+            if search.collapse_hardlinks {
+                results = collapse_hardlinked_results(results)?;
+            }
+            attach_tags(&app, &mut results);
+            let cursor = (resume_at != u32::MAX).then(|| {
+                search.options.resume_from = resume_at;
+                store_search_cursor(search)
+            });
+            Ok(SearchPage { results, cursor })
+        })
+        .await
+        .map_err(|err| format!("Search task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, cursor);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Drive letter prefix of an indexed path, e.g. `C:` for `c:\Docs\a.txt`.
+#[cfg(target_os = "windows")]
+fn drive_of_path(path: &str) -> Option<String> {
+    let prefix = path.get(..2)?;
+    let mut chars = prefix.chars();
+    let letter = chars.next()?;
+    (letter.is_ascii_alphabetic() && chars.next() == Some(':'))
+        .then(|| format!("{}:", letter.to_ascii_uppercase()))
+}
+
+/// Finds entries whose name matches `name_query` and keeps only the names that exist on more
+/// than one indexed drive, e.g. a document copied between `C:` and a backup disk. Results are
+/// ordered by name and then drive so copies sit next to each other, and each carries `drive`.
+#[tauri::command]
+async fn cross_drive_name_matches(name_query: String) -> Result<Vec<SearchResult>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::collections::{HashMap, HashSet};
+
+        if name_query.trim().is_empty() {
+            return Err("Name query is empty.".to_string());
+        }
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<SearchResult>, String> {
+            let c_query =
+                CString::new(name_query.trim()).map_err(|_| "Invalid query".to_string())?;
+            let empty = CString::default();
+            let options = SearchOptions {
+                search_in: SEARCH_IN_NAME,
+                ..SearchOptions::background(&c_query, &empty, &empty)
+            };
+
+            let mut results = run_search(&options)?;
+            let mut drives_by_name: HashMap<String, HashSet<String>> = HashMap::new();
+            for result in &mut results {
+                result.drive = drive_of_path(&result.path);
+                if let Some(drive) = &result.drive {
+                    drives_by_name
+                        .entry(result.name.to_lowercase())
+                        .or_default()
+                        .insert(drive.clone());
+                }
+            }
+            results.retain(|result| {
+                drives_by_name
+                    .get(&result.name.to_lowercase())
+                    .is_some_and(|drives| drives.len() > 1)
+            });
+            results.sort_by(|a, b| {
+                a.name
+                    .to_lowercase()
+                    .cmp(&b.name.to_lowercase())
+                    .then_with(|| a.drive.cmp(&b.drive))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            Ok(results)
+        })
+        .await
+        .map_err(|err| format!("Cross-drive search task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = name_query;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Stops a running search so it returns no rows right away. With `search_id`, only the
+/// `search_files` call started with that id is stopped, even if the call has not reached the
+/// scanner yet; without it, whatever search is running is stopped.
+#[tauri::command]
+fn cancel_search(search_id: Option<u64>) -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let cancelled = match search_id {
+            // SAFETY: FFI call only updates the scanner's search id bookkeeping.
+            Some(search_id) => unsafe { omni_cancel_search_id(search_id) },
+            // SAFETY: FFI call only advances the search cancellation token.
+            None => unsafe { omni_cancel_search() },
+        };
+        Ok(cancelled)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = search_id;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Drains the groups the running duplicate scan has confirmed since the last call.
+#[cfg(target_os = "windows")]
+fn take_confirmed_duplicate_groups() -> Vec<DuplicateGroup> {
+    // SAFETY: FFI call only swaps out the scanner's pending group queue.
+    let raw_json = unsafe { omni_take_duplicate_groups_json() };
+    if raw_json.is_null() {
+        return Vec::new();
+    }
+
+    // SAFETY: `raw_json` points to a C string allocated by C++.
+    let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+    // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+    unsafe { omni_free_string(raw_json) };
+    serde_json::from_str(&json).unwrap_or_default()
+}
+
+/// Scans the index for duplicate files. Each group is also emitted as an
+/// `omni://duplicate-group-found` event once confirmed, so the UI can fill in while hashing
+/// continues; the returned list is complete and sorted by `sort_by`: "wasted_space"
+/// (default, reclaimable bytes), "group_size" (size of each copy) or "file_count".
+///
+/// With content grouping, `hash_mode` trades confidence for speed: "full" (default) hashes
+/// whole files and compares them byte for byte, "partial" stops at a hash of the first and
+/// last 64 KB, and "size_only" reads nothing. Every group reports the mode it was found with.
+/// Name grouping ("name") ignores the default minimum size, so small files such as many
+/// copies of `IMG_0001.JPG` are found; an explicit `min_size` still applies.
+#[tauri::command]
+async fn find_duplicate_groups(
+    app: tauri::AppHandle,
+    min_size: Option<u64>,
+    max_groups: Option<u32>,
+    max_files_per_group: Option<u32>,
+    skip_reparse_points: Option<bool>,
+    group_by: Option<String>,
+    io_concurrency: Option<u32>,
+    throttle_mb_per_sec: Option<u64>,
+    sort_by: Option<String>,
+    hash_mode: Option<String>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let sort_by = sort_by
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or("wasted_space")
+            .to_ascii_lowercase();
+        if !matches!(
+            sort_by.as_str(),
+            "" | "wasted_space" | "group_size" | "file_count"
+        ) {
+            return Err(format!("Unsupported duplicate sort order: {sort_by}."));
+        }
+        let normalize = |value: Option<String>, fallback: &str| {
+            let value = value.as_deref().map(str::trim).unwrap_or_default();
+            if value.is_empty() {
+                fallback.to_string()
+            } else {
+                value.to_ascii_lowercase()
+            }
+        };
+        let group_by = normalize(group_by, "content");
+        let hash_mode = hash_mode.map(|mode| normalize(Some(mode), "full"));
+        let (group_by, hash_mode) = match (group_by.as_str(), hash_mode.as_deref()) {
+            ("content", None | Some("full")) => (0, "full"),
+            ("content", Some("partial")) => (3, "partial"),
+            ("content" | "size", Some("size_only")) | ("size", None) => (1, "size_only"),
+            ("name", None) => (2, "name"),
+            ("content" | "size" | "name", Some(mode)) => {
+                return Err(format!(
+                    "Unsupported duplicate hash mode for {group_by} grouping: {mode}."
+                ))
+            }
+            (other, _) => return Err(format!("Unsupported duplicate grouping: {other}.")),
+        };
+        let defaults = settings::load_duplicate_defaults(&app);
+        let options = DuplicateScanOptions {
+            // Name grouping has no size floor unless one is asked for.
+            min_size: min_size.unwrap_or(if group_by == 2 { 0 } else { defaults.min_size }),
+            max_groups: max_groups.unwrap_or(defaults.max_groups).clamp(1, 1_000),
+            max_files_per_group: max_files_per_group
+                .unwrap_or(defaults.max_files_per_group)
+                .clamp(2, 400),
+            skip_reparse_points: skip_reparse_points.unwrap_or(false),
+            group_by,
+            // Fewer hashing workers and a read-rate cap keep a background scan from
+            // saturating the disk; zero leaves either limit off.
+            io_concurrency: io_concurrency.unwrap_or(0).min(64),
+            throttle_bytes_per_sec: throttle_mb_per_sec.unwrap_or(0).saturating_mul(1024 * 1024),
+        };
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<DuplicateGroup>, String> {
+            // Groups are streamed as they are confirmed; the final list is still returned, sorted.
+            let scan_finished = Arc::new(AtomicBool::new(false));
+            let streamer = {
+                let scan_finished = Arc::clone(&scan_finished);
+                std::thread::spawn(move || loop {
+                    let finished = scan_finished.load(Ordering::Acquire);
+                    for mut group in take_confirmed_duplicate_groups() {
+                        group.hash_mode = Some(hash_mode.to_string());
+                        let _ = app.emit(DUPLICATE_GROUP_FOUND_EVENT, group);
+                    }
+                    if finished {
+                        break;
+                    }
+                    std::thread::sleep(Duration::from_millis(DUPLICATE_GROUP_POLL_MS));
+                })
+            };
+
+            // SAFETY: `options` outlives the call; returns an allocated C string or null.
+            let raw_json = unsafe { omni_find_duplicates_with_options_json(&options) };
+            let scan_error = raw_json.is_null().then(read_last_error);
+            scan_finished.store(true, Ordering::Release);
+            let _ = streamer.join();
+            if let Some(err) = scan_error {
+                return Err(err.unwrap_or_else(|| "Failed to find duplicate files.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let mut parsed: Vec<DuplicateGroup> = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid duplicate payload: {err}"))?;
+            for group in &mut parsed {
+                group.hash_mode = Some(hash_mode.to_string());
+            }
+            let wasted_space =
+                |group: &DuplicateGroup| u64::from(group.file_count.saturating_sub(1)) * group.size;
+            parsed.sort_by(|left, right| {
+                let primary = match sort_by.as_str() {
+                    "group_size" => right.size.cmp(&left.size),
+                    "file_count" => right.file_count.cmp(&left.file_count),
+                    _ => std::cmp::Ordering::Equal,
+                };
+                primary.then_with(|| wasted_space(right).cmp(&wasted_space(left)))
+            });
+            Ok(parsed)
+        })
+        .await
+        .map_err(|err| format!("Duplicate scan task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (
+            app,
+            min_size,
+            max_groups,
+            max_files_per_group,
+            skip_reparse_points,
+            group_by,
+            io_concurrency,
+            throttle_mb_per_sec,
+            sort_by,
+            hash_mode,
+        );
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Quick redundancy overview: the `limit` (default 20, at most 200) sizes shared by the most
+/// indexed files at or above `min_size`, most copies first. Files are grouped by size only,
+/// so nothing is hashed and groups are likely, not confirmed, duplicates.
+#[tauri::command]
+async fn top_duplicate_offenders(
+    app: tauri::AppHandle,
+    min_size: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let defaults = settings::load_duplicate_defaults(&app);
+        let options = DuplicateScanOptions {
+            min_size: min_size.unwrap_or(defaults.min_size),
+            max_groups: limit.unwrap_or(20).clamp(1, 200),
+            max_files_per_group: defaults.max_files_per_group.clamp(2, 400),
+            skip_reparse_points: true,
+            group_by: 1,
+            io_concurrency: 0,
+            throttle_bytes_per_sec: 0,
+        };
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<DuplicateGroup>, String> {
+            // SAFETY: `options` outlives the call; returns an allocated C string or null.
+            let raw_json = unsafe { omni_find_duplicates_with_options_json(&options) };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to find duplicate files.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let mut groups: Vec<DuplicateGroup> = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid duplicate payload: {err}"))?;
+            for group in &mut groups {
+                group.hash_mode = Some("size_only".to_string());
+            }
+            groups.sort_by(|left, right| {
+                right
+                    .file_count
+                    .cmp(&left.file_count)
+                    .then_with(|| right.size.cmp(&left.size))
+            });
+            Ok(groups)
+        })
+        .await
+        .map_err(|err| format!("Duplicate scan task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, min_size, limit);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Headline numbers for the duplicates across the whole index (group count, files involved
+/// and reclaimable bytes) without sending any group to the UI. Without `verify_content`
+/// files are matched by size alone, which is quick but counts likely rather than confirmed
+/// duplicates; with it every candidate is hashed as in `find_duplicate_groups`. Runs as a
+/// duplicate scan, so `duplicate_scan_status` reports its progress and
+/// `cancel_duplicate_scan` stops it.
+#[tauri::command]
+async fn duplicate_overview(
+    app: tauri::AppHandle,
+    min_size: Option<u64>,
+    verify_content: Option<bool>,
+) -> Result<DuplicateOverview, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let min_size = min_size.unwrap_or(settings::load_duplicate_defaults(&app).min_size);
+        let verify_content = verify_content.unwrap_or(false);
+        tauri::async_runtime::spawn_blocking(move || -> Result<DuplicateOverview, String> {
+            // SAFETY: plain values in; returns an allocated C string or null.
+            let raw_json = unsafe { omni_duplicate_overview_json(min_size, verify_content, true) };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to summarize duplicate files.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let mut overview: DuplicateOverview = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid duplicate overview payload: {err}"))?;
+            overview.verified = verify_content;
+            Ok(overview)
+        })
+        .await
+        .map_err(|err| format!("Duplicate scan task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, min_size, verify_content);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Groups just the given paths (typically the current search results) by size, and with
+/// `verify_content` also by SHA-256, so duplicates among them show up without a full drive
+/// scan. Folders, empty files and unreadable paths are left out; the biggest wins come first.
+#[tauri::command]
+async fn duplicates_within_results(
+    paths: Vec<String>,
+    verify_content: Option<bool>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    use std::{
+        collections::{HashMap, HashSet},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    let verify_content = verify_content.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || {
+        let unix_seconds = |time: std::io::Result<SystemTime>| {
+            time.ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |elapsed| elapsed.as_secs() as i64)
+        };
+        let mut seen = HashSet::new();
+        let mut by_size: HashMap<u64, Vec<DuplicateFile>> = HashMap::new();
+        for path in paths {
+            if !seen.insert(path.to_lowercase()) {
+                continue;
+            }
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            if !metadata.is_file() || metadata.len() == 0 {
+                continue;
+            }
+            let name = std::path::Path::new(&path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            by_size
+                .entry(metadata.len())
+                .or_default()
+                .push(DuplicateFile {
+                    name,
+                    path,
+                    size: metadata.len(),
+                    created_unix: unix_seconds(metadata.created()),
+                    modified_unix: unix_seconds(metadata.modified()),
+                });
+        }
+
+        let mut buckets: Vec<(u64, Vec<DuplicateFile>)> = Vec::new();
+        for (size, files) in by_size {
+            if files.len() < 2 {
+                continue;
+            }
+            if !verify_content {
+                buckets.push((size, files));
+                continue;
+            }
+            let mut by_digest: HashMap<String, Vec<DuplicateFile>> = HashMap::new();
+            for file in files {
+                let hashed = hashing::hash_file(&file.path, hashing::HashAlgorithm::Sha256);
+                if let Some(digest) = hashed.digest {
+                    by_digest.entry(digest).or_default().push(file);
+                }
+            }
+            buckets.extend(
+                by_digest
+                    .into_values()
+                    .filter(|files| files.len() >= 2)
+                    .map(|files| (size, files)),
+            );
+        }
+
+        buckets.sort_by(|(left_size, left), (right_size, right)| {
+            let wasted = |size: u64, count: usize| size * (count as u64 - 1);
+            wasted(*right_size, right.len()).cmp(&wasted(*left_size, left.len()))
+        });
+        buckets
+            .into_iter()
+            .enumerate()
+            .map(|(index, (size, files))| DuplicateGroup {
+                group_id: format!("{size:016x}-{index:08x}"),
+                size,
+                total_bytes: size * files.len() as u64,
+                file_count: files.len() as u32,
+                files,
+                hash_mode: Some(if verify_content { "full" } else { "size_only" }.to_string()),
+            })
+            .collect()
+    })
+    .await
+    .map_err(|err| format!("Duplicate grouping task failed: {err}"))
+}
+
+/// Groups folders whose whole subtrees match (relative file names and sizes, plus content when
+/// `compare_content` is set), reporting each copy as one entry sized by its subtree total.
+/// Matching subfolders of already matching folders are left out. Runs as a duplicate scan, so
+/// `duplicate_scan_status` and `cancel_duplicate_scan` apply.
+#[tauri::command]
+async fn find_duplicate_folders(
+    app: tauri::AppHandle,
+    within_path: Option<String>,
+    compare_content: Option<bool>,
+    max_groups: Option<u32>,
     max_files_per_group: Option<u32>,
 ) -> Result<Vec<DuplicateGroup>, String> {
     #[cfg(target_os = "windows")]
     {
-        let min_size = min_size.unwrap_or(50 * 1024 * 1024);
-        let max_groups = max_groups.unwrap_or(200).clamp(1, 1_000);
-        let max_files_per_group = max_files_per_group.unwrap_or(80).clamp(2, 400);
-        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<DuplicateGroup>, String> {
-            // SAFETY: Inputs are plain integers and function returns an allocated C string or null.
-            let raw_json =
-                unsafe { omni_find_duplicates_json(min_size, max_groups, max_files_per_group) };
+        let within_path = within_path.unwrap_or_default();
+        if !within_path.trim().is_empty() && !std::path::Path::new(&within_path).is_dir() {
+            return Err("Folder does not exist on disk.".to_string());
+        }
+        let defaults = settings::load_duplicate_defaults(&app);
+        let max_groups = max_groups.unwrap_or(defaults.max_groups).clamp(1, 1_000);
+        let max_files_per_group = max_files_per_group
+            .unwrap_or(defaults.max_files_per_group)
+            .clamp(2, 400);
+        let c_within_path =
+            CString::new(within_path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<DuplicateGroup>, String> {
+            // SAFETY: `c_within_path` outlives the call; returns an allocated C string or null.
+            let raw_json = unsafe {
+                omni_find_duplicate_folders_json(
+                    c_within_path.as_ptr(),
+                    compare_content.unwrap_or(false),
+                    max_groups,
+                    max_files_per_group,
+                )
+            };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to find duplicate folders.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let parsed: Vec<DuplicateGroup> = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid duplicate payload: {err}"))?;
+            Ok(parsed)
+        })
+        .await
+        .map_err(|err| format!("Duplicate folder scan task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (
+            app,
+            within_path,
+            compare_content,
+            max_groups,
+            max_files_per_group,
+        );
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Estimates how much a content duplicate scan would hash, so the UI can warn before a long
+/// scan. Reads only file sizes, never contents; `min_size` falls back to the saved default.
+#[tauri::command]
+async fn estimate_duplicate_scan(
+    app: tauri::AppHandle,
+    min_size: Option<u64>,
+    within_path: Option<String>,
+    skip_reparse_points: Option<bool>,
+) -> Result<DuplicateScanEstimate, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let min_size = min_size.unwrap_or(settings::load_duplicate_defaults(&app).min_size);
+        let c_within_path = CString::new(within_path.unwrap_or_default())
+            .map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<DuplicateScanEstimate, String> {
+            // SAFETY: `c_within_path` outlives the call; returns an allocated C string or null.
+            let raw_json = unsafe {
+                omni_estimate_duplicate_scan_json(
+                    min_size,
+                    c_within_path.as_ptr(),
+                    skip_reparse_points.unwrap_or(false),
+                )
+            };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to estimate the duplicate scan.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid duplicate estimate payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Duplicate estimate task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, min_size, within_path, skip_reparse_points);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+fn duplicate_scan_status() -> Result<DuplicateScanStatus, String> {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: No inputs, returns an allocated C string or null.
+        let raw_json = unsafe { omni_duplicate_scan_status_json() };
+        if raw_json.is_null() {
+            return Err(read_last_error()
+                .unwrap_or_else(|| "Failed to read duplicate scan status.".to_string()));
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { omni_free_string(raw_json) };
+
+        let parsed: DuplicateScanStatus = serde_json::from_str(&json)
+            .map_err(|err| format!("Invalid duplicate status payload: {err}"))?;
+        Ok(parsed)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+fn cancel_duplicate_scan() -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: FFI call only flips an atomic flag.
+        let requested = unsafe { omni_cancel_duplicate_scan() };
+        Ok(requested)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+async fn save_index_snapshot(path: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+            // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+            let ok = unsafe { omni_save_index_snapshot(c_path.as_ptr()) };
+            if !ok {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to save the index snapshot.".to_string()));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|err| format!("Index snapshot task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+async fn diff_index(
+    previous_snapshot: String,
+    max_entries: Option<u32>,
+) -> Result<IndexDiff, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::path::PathBuf;
+
+        if !PathBuf::from(&previous_snapshot).is_file() {
+            return Err("Snapshot file does not exist on disk.".to_string());
+        }
+        let c_path =
+            CString::new(previous_snapshot).map_err(|_| "Invalid path parameter".to_string())?;
+        let max_entries = max_entries.unwrap_or(5_000).clamp(1, 100_000);
+        tauri::async_runtime::spawn_blocking(move || -> Result<IndexDiff, String> {
+            // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+            let raw_json = unsafe { omni_diff_index_json(c_path.as_ptr(), max_entries) };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to compare the index snapshot.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let parsed: IndexDiff = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid index diff payload: {err}"))?;
+            Ok(parsed)
+        })
+        .await
+        .map_err(|err| format!("Index diff task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (previous_snapshot, max_entries);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+async fn verify_index(sample_size: u32) -> Result<IndexHealth, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || -> Result<IndexHealth, String> {
+            // SAFETY: Plain integer parameter; returns allocated C string or null.
+            let raw_json = unsafe { omni_verify_index_json(sample_size) };
+            if raw_json.is_null() {
+                return Err(
+                    read_last_error().unwrap_or_else(|| "Failed to verify the index.".to_string())
+                );
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let mut health: IndexHealth = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid index health payload: {err}"))?;
+            if health.sampled > 0 {
+                health.mismatch_ratio =
+                    (health.missing + health.mismatched) as f64 / health.sampled as f64;
+            }
+            health.needs_reindex = health.mismatch_ratio > INDEX_DRIFT_REINDEX_THRESHOLD;
+            Ok(health)
+        })
+        .await
+        .map_err(|err| format!("Index verification task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = sample_size;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Sums the sizes the index keeps for the files beneath `path`; live updates keep them current,
+/// so no file is read from disk.
+#[tauri::command]
+async fn directory_size(path: String) -> Result<DirSize, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::path::PathBuf;
+
+        if !PathBuf::from(&path).is_dir() {
+            return Err("Folder does not exist on disk.".to_string());
+        }
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<DirSize, String> {
+            // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+            let raw_json = unsafe { omni_directory_size_json(c_path.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to compute directory size.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let parsed: DirSize = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid directory size payload: {err}"))?;
+            Ok(parsed)
+        })
+        .await
+        .map_err(|err| format!("Directory size task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+fn delete_path(
+    path: String,
+    recycle_bin: Option<bool>,
+    #[allow(non_snake_case)] recycleBin: Option<bool>,
+) -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let recycle_bin = recycle_bin.or(recycleBin).unwrap_or(false);
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        let ok = unsafe { omni_delete_path(c_path.as_ptr(), recycle_bin) };
+        if !ok {
+            return Err(read_last_error().unwrap_or_else(|| "Delete failed".to_string()));
+        }
+        return Ok(true);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, recycle_bin, recycleBin);
+        Err("Delete is only supported on Windows.".to_string())
+    }
+}
+
+/// Summarizes an indexed folder's descendants: totals plus the top extensions by file count
+/// and by size. A `top` of zero returns ten extensions per list.
+#[tauri::command]
+async fn folder_summary(path: String, top: Option<u32>) -> Result<FolderSummary, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::path::PathBuf;
+
+        if !PathBuf::from(&path).is_dir() {
+            return Err("Folder does not exist on disk.".to_string());
+        }
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<FolderSummary, String> {
+            // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+            let raw_json = unsafe { omni_folder_summary_json(c_path.as_ptr(), top.unwrap_or(0)) };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to summarize the folder.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            let parsed: FolderSummary = serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid folder summary payload: {err}"))?;
+            Ok(parsed)
+        })
+        .await
+        .map_err(|err| format!("Folder summary task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, top);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Re-walks one folder and brings the index entries beneath it up to date, without a full
+/// re-index. Meant for folders the USN journal does not cover, such as network shares.
+#[tauri::command]
+async fn refresh_path(path: String) -> Result<PathRefresh, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<PathRefresh, String> {
+            // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+            let raw_json = unsafe { omni_refresh_path_json(c_path.as_ptr()) };
             if raw_json.is_null() {
                 return Err(read_last_error()
-                    .unwrap_or_else(|| "Failed to find duplicate files.".to_string()));
+                    .unwrap_or_else(|| "Failed to refresh the folder.".to_string()));
             }
 
             // SAFETY: `raw_json` points to a C string allocated by C++.
@@ -404,30 +2905,329 @@ async fn find_duplicate_groups(
             // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
             unsafe { omni_free_string(raw_json) };
 
-            let parsed: Vec<DuplicateGroup> = serde_json::from_str(&json)
-                .map_err(|err| format!("Invalid duplicate payload: {err}"))?;
-            Ok(parsed)
+            serde_json::from_str(&json).map_err(|err| format!("Invalid refresh payload: {err}"))
         })
         .await
-        .map_err(|err| format!("Duplicate scan task failed: {err}"))?
+        .map_err(|err| format!("Refresh task failed: {err}"))?
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = (min_size, max_groups, max_files_per_group);
+        let _ = path;
         Err("OmniSearch scanner is only supported on Windows.".to_string())
     }
 }
 
+/// Re-checks already displayed results against the disk: entries that no longer exist are
+/// dropped and the rest get their current size and timestamps. Much lighter than running the
+/// search again when cached results may be stale.
 #[tauri::command]
-fn duplicate_scan_status() -> Result<DuplicateScanStatus, String> {
+async fn refresh_results(results: Vec<SearchResult>) -> Result<Vec<SearchResult>, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let unix_seconds = |time: std::io::Result<SystemTime>| {
+            time.ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |elapsed| elapsed.as_secs() as i64)
+        };
+        results
+            .into_iter()
+            .filter_map(|mut result| {
+                // Links are checked themselves, so a link to a missing target remains listed
+                // and keeps the size and kind the index reported for it.
+                let metadata = if result.is_reparse_point {
+                    std::fs::symlink_metadata(&result.path)
+                } else {
+                    std::fs::metadata(&result.path)
+                }
+                .ok()?;
+                if !result.is_reparse_point {
+                    result.is_directory = metadata.is_dir();
+                    if !result.is_directory {
+                        result.size = metadata.len();
+                    }
+                }
+                result.created_unix = unix_seconds(metadata.created());
+                result.modified_unix = unix_seconds(metadata.modified());
+                Some(result)
+            })
+            .collect()
+    })
+    .await
+    .map_err(|err| format!("Result refresh task failed: {err}"))
+}
+
+/// Returns the indexed entry stored under exactly `path` (compared case-insensitively), or
+/// `None` when it is not in the index. Backs "go to path" without running a substring search.
+#[tauri::command]
+async fn lookup_path(path: String) -> Result<Option<SearchResult>, String> {
     #[cfg(target_os = "windows")]
     {
-        // SAFETY: No inputs, returns an allocated C string or null.
-        let raw_json = unsafe { omni_duplicate_scan_status_json() };
+        let c_path = CString::new(path.trim()).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Option<SearchResult>, String> {
+            // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+            let raw_json = unsafe { omni_lookup_path_json(c_path.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(
+                    read_last_error().unwrap_or_else(|| "Failed to look up the path.".to_string())
+                );
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            Ok(parse_search_results(&json)?.into_iter().next())
+        })
+        .await
+        .map_err(|err| format!("Lookup task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Returns the indexed entry on `drive` ("C", "C:" or "C:\\") with the given `file_id`, or
+/// `None` when it is not in the index, so tags and history can follow a file after it was
+/// renamed or moved within the volume.
+#[tauri::command]
+async fn lookup_by_file_id(drive: String, file_id: u64) -> Result<Option<SearchResult>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let letter = drive
+            .trim()
+            .trim_end_matches(['\\', '/'])
+            .trim_end_matches(':');
+        if letter.len() != 1 || !letter.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("Invalid drive letter: {}.", drive.trim()));
+        }
+        let c_drive = CString::new(format!("{}:\\", letter.to_ascii_uppercase()))
+            .map_err(|_| "Invalid drive parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Option<SearchResult>, String> {
+            // SAFETY: `c_drive` is a valid NUL-terminated string for the duration of the call.
+            let raw_json = unsafe { omni_lookup_file_id_json(c_drive.as_ptr(), file_id) };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to look up the file id.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            Ok(parse_search_results(&json)?.into_iter().next())
+        })
+        .await
+        .map_err(|err| format!("Lookup task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (drive, file_id);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Locates every indexed entry named exactly (ignoring case) one of `names`, such as the files
+/// of a manifest, in a single pass over the index instead of one search per name. `drives`
+/// limits the lookup to those letters. Hits come grouped by name, in the order of `names`.
+#[tauri::command]
+async fn find_by_names(
+    names: Vec<String>,
+    drives: Option<Vec<String>>,
+) -> Result<Vec<SearchResult>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let names = names
+            .iter()
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return Err("No file names given.".to_string());
+        }
+        let c_names =
+            CString::new(names.join("\n")).map_err(|_| "Invalid names parameter".to_string())?;
+        let c_drives = CString::new(drives.unwrap_or_default().join(","))
+            .map_err(|_| "Invalid drives parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<SearchResult>, String> {
+            // SAFETY: both strings are valid NUL-terminated strings for the duration of the call.
+            let raw_json = unsafe {
+                omni_find_by_names_json(
+                    c_names.as_ptr(),
+                    c_drives.as_ptr(),
+                    FIND_BY_NAMES_MAX_RESULTS,
+                )
+            };
+            if raw_json.is_null() {
+                return Err(read_last_error()
+                    .unwrap_or_else(|| "Failed to look up the file names.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            parse_search_results(&json)
+        })
+        .await
+        .map_err(|err| format!("Lookup task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (names, drives);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
+
+/// Splits `path` into its ancestors, root first and `path` itself last, so each segment can
+/// scope a search through `within_path`.
+#[tauri::command]
+fn path_breadcrumbs(path: String) -> Result<Vec<Breadcrumb>, String> {
+    let mut normalized = path.trim().trim_end_matches(['\\', '/']).to_string();
+    if normalized.is_empty() {
+        normalized = path.trim().chars().take(1).collect();
+    } else if normalized.ends_with(':') {
+        normalized.push(std::path::MAIN_SEPARATOR);
+    }
+    if normalized.is_empty() {
+        return Err("Path is empty.".to_string());
+    }
+
+    let mut breadcrumbs = std::path::Path::new(&normalized)
+        .ancestors()
+        .map(|ancestor| {
+            let path = ancestor.to_string_lossy().to_string();
+            let name = ancestor
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone());
+            Breadcrumb { name, path }
+        })
+        .collect::<Vec<_>>();
+    breadcrumbs.reverse();
+    Ok(breadcrumbs)
+}
+
+/// Current location of the known folder `folder_id`, following redirection.
+#[cfg(windows)]
+fn known_folder_path(folder_id: &windows::core::GUID) -> Result<String, String> {
+    use windows::Win32::{
+        System::Com::CoTaskMemFree,
+        UI::Shell::{SHGetKnownFolderPath, KF_FLAG_DEFAULT},
+    };
+
+    // SAFETY: `folder_id` is a valid known-folder GUID and no access token is passed.
+    let raw_path = unsafe { SHGetKnownFolderPath(folder_id, KF_FLAG_DEFAULT, None) }
+        .map_err(|err| err.to_string())?;
+    // SAFETY: on success `raw_path` is a NUL-terminated wide string owned by the caller.
+    let path = unsafe { raw_path.to_string() };
+    // SAFETY: the shell allocated `raw_path` with the COM task allocator.
+    unsafe { CoTaskMemFree(Some(raw_path.0 as *const c_void)) };
+    path.map_err(|_| "Folder path is not valid UTF-16.".to_string())
+}
+
+/// Resolves a well-known folder (`documents`, `downloads`, `desktop`, `pictures`, `music`,
+/// `videos`, `home`, `appdata`, `localappdata`, `onedrive`) to its real location, following
+/// redirection such as Documents moved into OneDrive, so quick scopes hit the right folder.
+#[tauri::command]
+fn resolve_known_folder(name: String) -> Result<String, String> {
+    #[cfg(windows)]
+    {
+        use windows::Win32::UI::Shell::{
+            FOLDERID_Desktop, FOLDERID_Documents, FOLDERID_Downloads, FOLDERID_LocalAppData,
+            FOLDERID_Music, FOLDERID_Pictures, FOLDERID_Profile, FOLDERID_RoamingAppData,
+            FOLDERID_SkyDrive, FOLDERID_Videos,
+        };
+
+        let folder_id = match name.trim().to_ascii_lowercase().as_str() {
+            "desktop" => FOLDERID_Desktop,
+            "documents" => FOLDERID_Documents,
+            "downloads" => FOLDERID_Downloads,
+            "pictures" => FOLDERID_Pictures,
+            "music" => FOLDERID_Music,
+            "videos" => FOLDERID_Videos,
+            "home" | "profile" => FOLDERID_Profile,
+            "appdata" => FOLDERID_RoamingAppData,
+            "localappdata" => FOLDERID_LocalAppData,
+            "onedrive" => FOLDERID_SkyDrive,
+            _ => return Err(format!("Unknown folder: {}", name.trim())),
+        };
+
+        known_folder_path(&folder_id)
+            .map_err(|err| format!("Failed to resolve folder {}: {err}", name.trim()))
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = name;
+        Err("Known folders are only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+fn rename_path(path: String, new_name: String) -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::fs;
+        use std::path::PathBuf;
+
+        let current_path = PathBuf::from(path);
+        if !current_path.exists() {
+            return Err("File does not exist on disk.".to_string());
+        }
+
+        let trimmed_name = new_name.trim();
+        if trimmed_name.is_empty() {
+            return Err("Name cannot be empty.".to_string());
+        }
+        if trimmed_name.contains('\\') || trimmed_name.contains('/') {
+            return Err("Name must not include path separators.".to_string());
+        }
+
+        let parent = current_path
+            .parent()
+            .ok_or_else(|| "Failed to resolve the parent directory.".to_string())?;
+        let next_path = parent.join(trimmed_name);
+
+        if next_path == current_path {
+            return Ok(current_path.to_string_lossy().into_owned());
+        }
+        if next_path.exists() {
+            return Err("An item with that name already exists.".to_string());
+        }
+
+        fs::rename(&current_path, &next_path)
+            .map_err(|err| format!("Failed to rename item: {err}"))?;
+
+        Ok(next_path.to_string_lossy().into_owned())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, new_name);
+        Err("Rename is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+fn list_drives() -> Result<Vec<DriveInfo>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: No parameters, returns allocated C string or null.
+        let raw_json = unsafe { omni_list_drives_json() };
         if raw_json.is_null() {
-            return Err(read_last_error()
-                .unwrap_or_else(|| "Failed to read duplicate scan status.".to_string()));
+            return Err(
+                read_last_error().unwrap_or_else(|| "Failed to enumerate drives".to_string())
+            );
         }
 
         // SAFETY: `raw_json` points to a C string allocated by C++.
@@ -435,8 +3235,8 @@ fn duplicate_scan_status() -> Result<DuplicateScanStatus, String> {
         // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
         unsafe { omni_free_string(raw_json) };
 
-        let parsed: DuplicateScanStatus = serde_json::from_str(&json)
-            .map_err(|err| format!("Invalid duplicate status payload: {err}"))?;
+        let parsed: Vec<DriveInfo> =
+            serde_json::from_str(&json).map_err(|err| format!("Invalid drives payload: {err}"))?;
         Ok(parsed)
     }
 
@@ -446,193 +3246,541 @@ fn duplicate_scan_status() -> Result<DuplicateScanStatus, String> {
     }
 }
 
+/// Reads the SMART health of the disk holding drive `letter`, with its temperature and
+/// reallocated and pending sector counts when the disk reports them. Disks without SMART
+/// access come back as "unknown" rather than an error.
 #[tauri::command]
-fn cancel_duplicate_scan() -> Result<bool, String> {
+async fn drive_health(letter: String) -> Result<DriveHealth, String> {
     #[cfg(target_os = "windows")]
     {
-        // SAFETY: FFI call only flips an atomic flag.
-        let requested = unsafe { omni_cancel_duplicate_scan() };
-        Ok(requested)
+        let letter = letter
+            .trim()
+            .trim_end_matches([':', '\\'])
+            .to_ascii_uppercase();
+        if letter.len() != 1 || !letter.chars().all(|ch| ch.is_ascii_uppercase()) {
+            return Err("Drive letter must be a single letter such as \"C\".".to_string());
+        }
+        let c_letter = CString::new(letter).map_err(|_| "Invalid drive parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<DriveHealth, String> {
+            // SAFETY: `c_letter` lives long enough for this synchronous call.
+            let raw_json = unsafe { omni_drive_health_json(c_letter.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(
+                    read_last_error().unwrap_or_else(|| "Failed to read drive health.".to_string())
+                );
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { omni_free_string(raw_json) };
+
+            serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid drive health payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Drive health task failed: {err}"))?
     }
 
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = letter;
         Err("OmniSearch scanner is only supported on Windows.".to_string())
     }
 }
 
+/// The legacy `MAX_PATH`; longer paths need the `\\?\` prefix, which `std::fs` adds on its
+/// own and the scanner adds for its file opens.
+#[cfg(windows)]
+const LEGACY_MAX_PATH: usize = 260;
+/// Longest path the `\\?\` form allows, plus the terminator.
+#[cfg(windows)]
+const EXTENDED_PATH_MAX_CHARS: usize = 32_768;
+
+/// The shell cannot open or select paths at or beyond `LEGACY_MAX_PATH`, so those are handed
+/// over in their 8.3 short form; other paths are returned unchanged. Fails when the volume has
+/// no short names, or the short form is still too long.
+#[cfg(windows)]
+fn shell_compatible_path(path: &Path) -> Result<std::path::PathBuf, String> {
+    let text = path.to_string_lossy();
+    if text.encode_utf16().count() < LEGACY_MAX_PATH {
+        return Ok(path.to_path_buf());
+    }
+    let extended = if text.starts_with(r"\\?\") {
+        text.into_owned()
+    } else if let Some(share) = text.strip_prefix(r"\\") {
+        format!(r"\\?\UNC\{share}")
+    } else {
+        format!(r"\\?\{text}")
+    };
+    let extended: Vec<u16> = extended.encode_utf16().chain(Some(0)).collect();
+    let mut buffer = vec![0_u16; EXTENDED_PATH_MAX_CHARS];
+    // SAFETY: `extended` is NUL-terminated and `buffer` is writable for its whole length.
+    let length = unsafe { GetShortPathNameW(PCWSTR(extended.as_ptr()), Some(&mut buffer)) };
+    let length = length as usize;
+    let too_long = || "Path is too long for the Windows shell to open.".to_string();
+    if length == 0 || length >= buffer.len() {
+        return Err(too_long());
+    }
+    let short = String::from_utf16_lossy(&buffer[..length]);
+    let short = match short.strip_prefix(r"\\?\UNC\") {
+        Some(rest) => format!(r"\\{rest}"),
+        None => short.strip_prefix(r"\\?\").unwrap_or(&short).to_string(),
+    };
+    if short.encode_utf16().count() >= LEGACY_MAX_PATH {
+        return Err(too_long());
+    }
+    Ok(std::path::PathBuf::from(short))
+}
+
 #[tauri::command]
-fn delete_path(
-    path: String,
-    recycle_bin: Option<bool>,
-    #[allow(non_snake_case)] recycleBin: Option<bool>,
-) -> Result<bool, String> {
+fn open_file(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::path::PathBuf;
+
+        let target = PathBuf::from(path);
+        if !target.exists() {
+            return Err("File does not exist on disk.".to_string());
+        }
+
+        let target_path = target.to_string_lossy().into_owned();
+        app.opener()
+            .open_path(
+                shell_compatible_path(&target)?.to_string_lossy(),
+                None::<&str>,
+            )
+            .map_err(|err| format!("Failed to open file: {err}"))?;
+        recent::record_open(&app, &target_path);
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, path);
+        Err("File open is only supported on Windows.".to_string())
+    }
+}
+
+/// `file:///` URL for `path`, with the characters that would end or corrupt the path encoded.
+#[cfg(target_os = "windows")]
+fn file_url_for_path(path: &str) -> String {
+    let mut url = String::from("file:///");
+    for ch in path.chars() {
+        match ch {
+            '\\' => url.push('/'),
+            ' ' => url.push_str("%20"),
+            '#' => url.push_str("%23"),
+            '%' => url.push_str("%25"),
+            '?' => url.push_str("%3F"),
+            _ => url.push(ch),
+        }
+    }
+    url
+}
+
+/// Command-line arguments that make `executable` open `path` at `location`, for the readers
+/// and players known to take one; `None` for anything else.
+#[cfg(target_os = "windows")]
+fn open_at_arguments(executable: &str, path: &str, location: &OpenLocation) -> Option<Vec<String>> {
+    let app = Path::new(executable)
+        .file_stem()?
+        .to_string_lossy()
+        .to_ascii_lowercase();
+    match location {
+        OpenLocation::Page { page } => {
+            let page = (*page).max(1);
+            match app.as_str() {
+                "msedge" | "chrome" | "firefox" | "brave" | "opera" => {
+                    Some(vec![format!("{}#page={page}", file_url_for_path(path))])
+                }
+                "acrobat" | "acrord32" | "foxitpdfreader" | "foxitpdfeditor" => Some(vec![
+                    "/A".to_string(),
+                    format!("page={page}"),
+                    path.to_string(),
+                ]),
+                "sumatrapdf" => Some(vec![
+                    "-page".to_string(),
+                    page.to_string(),
+                    path.to_string(),
+                ]),
+                _ => None,
+            }
+        }
+        OpenLocation::Time { seconds } => {
+            let seconds = if seconds.is_finite() {
+                seconds.max(0.0)
+            } else {
+                0.0
+            };
+            match app.as_str() {
+                "vlc" => Some(vec![format!("--start-time={seconds}"), path.to_string()]),
+                "mpv" => Some(vec![format!("--start={seconds}"), path.to_string()]),
+                "mpc-hc" | "mpc-hc64" | "mpc-be" | "mpc-be64" => Some(vec![
+                    path.to_string(),
+                    "/start".to_string(),
+                    ((seconds * 1000.0) as u64).to_string(),
+                ]),
+                "potplayer" | "potplayer64" | "potplayermini" | "potplayermini64" => {
+                    let total = seconds as u64;
+                    Some(vec![
+                        path.to_string(),
+                        format!(
+                            "/seek={:02}:{:02}:{:02}",
+                            total / 3600,
+                            total / 60 % 60,
+                            total % 60
+                        ),
+                    ])
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Opens `path` in the default app at a page (documents) or a playback time (media). Known
+/// PDF readers, browsers and video players get the matching argument; other apps just open
+/// the file. Returns whether the location was passed on.
+#[tauri::command]
+fn open_at(app: tauri::AppHandle, path: String, location: OpenLocation) -> Result<bool, String> {
     #[cfg(target_os = "windows")]
     {
-        let recycle_bin = recycle_bin.or(recycleBin).unwrap_or(false);
-        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
-        let ok = unsafe { omni_delete_path(c_path.as_ptr(), recycle_bin) };
-        if !ok {
-            return Err(read_last_error().unwrap_or_else(|| "Delete failed".to_string()));
+        use std::process::Command;
+
+        if !Path::new(&path).is_file() {
+            return Err("File does not exist on disk.".to_string());
         }
-        return Ok(true);
+        let extension = Path::new(&path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let arguments = apps::default_app_for(extension)
+            .ok()
+            .filter(|default_app| Path::new(&default_app.path).is_file())
+            .and_then(|default_app| {
+                open_at_arguments(&default_app.path, &path, &location)
+                    .map(|arguments| (default_app.path, arguments))
+            });
+        let Some((executable, arguments)) = arguments else {
+            open_file(app, path)?;
+            return Ok(false);
+        };
+
+        Command::new(&executable)
+            .args(&arguments)
+            .spawn()
+            .map_err(|err| format!("Failed to open file: {err}"))?;
+        recent::record_open(&app, &path);
+        Ok(true)
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = (path, recycle_bin, recycleBin);
-        Err("Delete is only supported on Windows.".to_string())
+        let _ = (app, path, location);
+        Err("File open is only supported on Windows.".to_string())
     }
 }
 
+/// Windows caps a command line at 32,767 characters.
+#[cfg(target_os = "windows")]
+const COMMAND_LINE_MAX_CHARS: usize = 32_767;
+
+/// Opens every path with a single process of `app_path`, or of the default app for the first
+/// file's type, so viewers and editors that take several files open one window instead of
+/// one per file.
 #[tauri::command]
-fn rename_path(path: String, new_name: String) -> Result<String, String> {
+fn open_files_in_one(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+    app_path: Option<String>,
+) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
-        use std::fs;
-        use std::path::PathBuf;
+        use std::process::Command;
 
-        let current_path = PathBuf::from(path);
-        if !current_path.exists() {
-            return Err("File does not exist on disk.".to_string());
+        let Some(first) = paths.first() else {
+            return Err("No files to open.".to_string());
+        };
+        if let Some(missing) = paths.iter().find(|path| !Path::new(path).is_file()) {
+            return Err(format!("File does not exist on disk: {missing}"));
         }
 
-        let trimmed_name = new_name.trim();
-        if trimmed_name.is_empty() {
-            return Err("Name cannot be empty.".to_string());
+        let executable = match app_path.filter(|value| !value.trim().is_empty()) {
+            Some(app_path) => app_path,
+            None => {
+                let extension = Path::new(first)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or_default()
+                    .to_string();
+                if extension.is_empty() {
+                    return Err("The first file has no extension to pick an app by.".to_string());
+                }
+                apps::default_app_for(extension)?.path
+            }
+        };
+        if !Path::new(&executable).is_file() {
+            return Err(
+                "The default app cannot be started directly; choose an app to open the files with."
+                    .to_string(),
+            );
         }
-        if trimmed_name.contains('\\') || trimmed_name.contains('/') {
-            return Err("Name must not include path separators.".to_string());
+        // Each argument is quoted and space-separated.
+        let command_line_chars = executable.chars().count()
+            + paths
+                .iter()
+                .map(|path| path.chars().count() + 3)
+                .sum::<usize>()
+            + 2;
+        if command_line_chars > COMMAND_LINE_MAX_CHARS {
+            return Err("Too many files to open in one window.".to_string());
         }
 
-        let parent = current_path
-            .parent()
-            .ok_or_else(|| "Failed to resolve the parent directory.".to_string())?;
-        let next_path = parent.join(trimmed_name);
-
-        if next_path == current_path {
-            return Ok(current_path.to_string_lossy().into_owned());
-        }
-        if next_path.exists() {
-            return Err("An item with that name already exists.".to_string());
+        Command::new(&executable)
+            .args(&paths)
+            .spawn()
+            .map_err(|err| format!("Failed to open the files: {err}"))?;
+        for path in &paths {
+            recent::record_open(&app, path);
         }
-
-        fs::rename(&current_path, &next_path)
-            .map_err(|err| format!("Failed to rename item: {err}"))?;
-
-        Ok(next_path.to_string_lossy().into_owned())
+        Ok(())
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = (path, new_name);
-        Err("Rename is only supported on Windows.".to_string())
+        let _ = (app, paths, app_path);
+        Err("File open is only supported on Windows.".to_string())
     }
 }
 
 #[tauri::command]
-fn list_drives() -> Result<Vec<DriveInfo>, String> {
+fn open_file_elevated(path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
-        // SAFETY: No parameters, returns allocated C string or null.
-        let raw_json = unsafe { omni_list_drives_json() };
-        if raw_json.is_null() {
-            return Err(
-                read_last_error().unwrap_or_else(|| "Failed to enumerate drives".to_string())
-            );
+        use std::path::PathBuf;
+
+        let target = PathBuf::from(&path);
+        if !target.is_file() {
+            return Err("File does not exist on disk.".to_string());
         }
 
-        // SAFETY: `raw_json` points to a C string allocated by C++.
-        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
-        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
-        unsafe { omni_free_string(raw_json) };
+        let extension = target
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !matches!(
+            extension.as_str(),
+            "exe" | "com" | "bat" | "cmd" | "msi" | "msc" | "vbs" | "wsf"
+        ) {
+            return Err("Only executables and scripts can be run as administrator.".to_string());
+        }
 
-        let parsed: Vec<DriveInfo> =
-            serde_json::from_str(&json).map_err(|err| format!("Invalid drives payload: {err}"))?;
-        Ok(parsed)
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_path` lives long enough for this synchronous call.
+        let ok = unsafe { omni_open_elevated(c_path.as_ptr()) };
+        if !ok {
+            return Err(read_last_error()
+                .unwrap_or_else(|| "Failed to run the file as administrator.".to_string()));
+        }
+        Ok(())
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        Err("OmniSearch scanner is only supported on Windows.".to_string())
+        let _ = path;
+        Err("Running files as administrator is only supported on Windows.".to_string())
     }
 }
 
+/// Opens Explorer with `path` selected. With `fallback_to_parent`, a path that no longer
+/// exists opens its parent folder instead, when that still exists. `reveal_mode` "existing"
+/// selects the item in an Explorer window already showing its folder, opening a new window
+/// ("window", the default) only when none does.
 #[tauri::command]
-fn open_file(app: tauri::AppHandle, path: String) -> Result<(), String> {
+fn reveal_in_folder(
+    app: tauri::AppHandle,
+    path: String,
+    fallback_to_parent: Option<bool>,
+    reveal_mode: Option<String>,
+) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         use std::path::PathBuf;
 
+        let reuse_window = match reveal_mode.as_deref().map(str::trim) {
+            None | Some("") | Some("window") => false,
+            Some("existing") => true,
+            Some(other) => return Err(format!("Unsupported reveal mode: {other}.")),
+        };
+
         let target = PathBuf::from(path);
         if !target.exists() {
-            return Err("File does not exist on disk.".to_string());
+            let parent = target
+                .parent()
+                .filter(|parent| fallback_to_parent.unwrap_or(false) && parent.is_dir());
+            let Some(parent) = parent else {
+                return Err("File does not exist on disk.".to_string());
+            };
+            app.opener()
+                .open_path(parent.to_string_lossy(), None::<&str>)
+                .map_err(|err| format!("Failed to open parent folder: {err}"))?;
+            return Ok(());
+        }
+
+        if reuse_window {
+            let c_path = CString::new(target.to_string_lossy().as_ref())
+                .map_err(|_| "Invalid path parameter".to_string())?;
+            // SAFETY: `c_path` lives long enough for this synchronous call.
+            if unsafe { omni_reveal_in_open_window(c_path.as_ptr()) } {
+                return Ok(());
+            }
         }
 
-        let target_path = target.to_string_lossy().into_owned();
         app.opener()
-            .open_path(target_path, None::<&str>)
-            .map_err(|err| format!("Failed to open file: {err}"))?;
+            .reveal_item_in_dir(shell_compatible_path(&target)?)
+            .map_err(|err| format!("Failed to reveal file in folder: {err}"))?;
         Ok(())
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = (app, path);
-        Err("File open is only supported on Windows.".to_string())
+        let _ = (app, path, fallback_to_parent, reveal_mode);
+        Err("Folder reveal is only supported on Windows.".to_string())
+    }
+}
+
+/// Opens one Explorer window per parent folder of `paths`, each with all of its children from
+/// `paths` selected, and returns the number of windows opened. Folders and items are compared
+/// case-insensitively, so repeated paths neither select twice nor open a second window.
+#[cfg(target_os = "windows")]
+fn reveal_grouped_items(paths: Vec<String>) -> Result<u32, String> {
+    use std::path::Path;
+
+    // Group by parent, keeping the order in which folders first appear.
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for path in paths {
+        // `C:/a/b.txt` and `c:\a\b.txt` are the same item in the same folder.
+        let path = path.replace('/', "\\");
+        let target = Path::new(&path);
+        if !target.exists() {
+            continue;
+        }
+        let Some(parent) = target.parent() else {
+            continue;
+        };
+        let parent = parent.to_string_lossy().into_owned();
+        match groups
+            .iter_mut()
+            .find(|(folder, _)| folder.eq_ignore_ascii_case(&parent))
+        {
+            Some((_, items)) => {
+                if !items.iter().any(|item| item.eq_ignore_ascii_case(&path)) {
+                    items.push(path);
+                }
+            }
+            None => groups.push((parent, vec![path])),
+        }
+    }
+    if groups.is_empty() {
+        return Err("File does not exist on disk.".to_string());
+    }
+
+    let mut opened = 0;
+    for (folder, items) in groups {
+        // Grouping uses the real paths; the shell gets forms it can parse.
+        let folder = shell_compatible_path(Path::new(&folder))?;
+        let items = items
+            .iter()
+            .map(|item| {
+                shell_compatible_path(Path::new(item))
+                    .map(|item| item.to_string_lossy().into_owned())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let c_folder = CString::new(folder.to_string_lossy().into_owned())
+            .map_err(|_| "Invalid path parameter".to_string())?;
+        let c_items =
+            CString::new(items.join("\n")).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_folder` and `c_items` live long enough for this synchronous call.
+        let ok = unsafe { omni_reveal_items(c_folder.as_ptr(), c_items.as_ptr()) };
+        if !ok {
+            return Err(read_last_error()
+                .unwrap_or_else(|| "Failed to reveal files in folder.".to_string()));
+        }
+        opened += 1;
     }
+    Ok(opened)
 }
 
+/// Reveals several items with one Explorer window per parent folder, each with all of its
+/// selected children highlighted.
 #[tauri::command]
-fn reveal_in_folder(app: tauri::AppHandle, path: String) -> Result<(), String> {
+fn reveal_items_in_folder(paths: Vec<String>) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
-        use std::path::PathBuf;
+        reveal_grouped_items(paths).map(|_| ())
+    }
 
-        let target = PathBuf::from(path);
-        if !target.exists() {
-            return Err("File does not exist on disk.".to_string());
-        }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = paths;
+        Err("Folder reveal is only supported on Windows.".to_string())
+    }
+}
 
-        app.opener()
-            .reveal_item_in_dir(&target)
-            .map_err(|err| format!("Failed to reveal file in folder: {err}"))?;
-        Ok(())
+/// Like `reveal_items_in_folder`, but reports how many Explorer windows were opened: one per
+/// distinct parent folder, with every matching child selected.
+#[tauri::command]
+fn reveal_grouped(paths: Vec<String>) -> Result<u32, String> {
+    #[cfg(target_os = "windows")]
+    {
+        reveal_grouped_items(paths)
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = (app, path);
+        let _ = paths;
         Err("Folder reveal is only supported on Windows.".to_string())
     }
 }
 
+#[cfg(target_os = "windows")]
+fn resolve_console_directory(path: &str) -> Result<std::path::PathBuf, String> {
+    use std::path::PathBuf;
+
+    let requested_path = PathBuf::from(path);
+    if !requested_path.exists() {
+        return Err("Path does not exist on disk.".to_string());
+    }
+
+    let target_directory = if requested_path.is_dir() {
+        requested_path
+    } else {
+        requested_path
+            .parent()
+            .map(std::path::Path::to_path_buf)
+            .ok_or_else(|| {
+                "Failed to resolve the parent folder for the requested path.".to_string()
+            })?
+    };
+
+    if !target_directory.is_dir() {
+        return Err("Resolved console target is not a directory.".to_string());
+    }
+
+    Ok(target_directory)
+}
+
 #[tauri::command]
 fn open_path_in_console(path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
-        use std::{os::windows::process::CommandExt, path::PathBuf, process::Command};
+        use std::{os::windows::process::CommandExt, process::Command};
         use windows::Win32::System::Threading::CREATE_NEW_CONSOLE;
 
-        let requested_path = PathBuf::from(path);
-        if !requested_path.exists() {
-            return Err("Path does not exist on disk.".to_string());
-        }
-
-        let target_directory = if requested_path.is_dir() {
-            requested_path
-        } else {
-            requested_path
-                .parent()
-                .map(std::path::Path::to_path_buf)
-                .ok_or_else(|| {
-                    "Failed to resolve the parent folder for the requested path.".to_string()
-                })?
-        };
-
-        if !target_directory.is_dir() {
-            return Err("Resolved console target is not a directory.".to_string());
-        }
+        let target_directory = resolve_console_directory(&path)?;
 
         Command::new("cmd.exe")
             .creation_flags(CREATE_NEW_CONSOLE.0)
@@ -650,6 +3798,64 @@ fn open_path_in_console(path: String) -> Result<(), String> {
     }
 }
 
+#[tauri::command]
+fn open_terminal(path: String, shell: Option<String>) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::{os::windows::process::CommandExt, process::Command};
+        use windows::Win32::System::Threading::CREATE_NEW_CONSOLE;
+
+        let target_directory = resolve_console_directory(&path)?;
+        let shell = shell
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "wt".to_string());
+
+        // Windows Terminal falls back to PowerShell and then cmd when it is not installed.
+        let candidates: &[&str] = match shell.as_str() {
+            "wt" | "windows-terminal" | "terminal" => &["wt", "powershell", "cmd"],
+            "powershell" => &["powershell"],
+            "pwsh" => &["pwsh"],
+            "cmd" => &["cmd"],
+            _ => return Err(format!("Unsupported terminal shell: {shell}.")),
+        };
+
+        let mut last_error = None;
+        for candidate in candidates {
+            let spawned = match *candidate {
+                "wt" => Command::new("wt.exe")
+                    .arg("-d")
+                    .arg(&target_directory)
+                    .spawn(),
+                "powershell" | "pwsh" => Command::new(format!("{candidate}.exe"))
+                    .arg("-NoExit")
+                    .creation_flags(CREATE_NEW_CONSOLE.0)
+                    .current_dir(&target_directory)
+                    .spawn(),
+                _ => Command::new("cmd.exe")
+                    .creation_flags(CREATE_NEW_CONSOLE.0)
+                    .current_dir(&target_directory)
+                    .spawn(),
+            };
+            match spawned {
+                Ok(_) => return Ok(()),
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        Err(format!(
+            "Failed to open a terminal for the selected path: {}",
+            last_error.map(|err| err.to_string()).unwrap_or_default()
+        ))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, shell);
+        Err("Opening a terminal for a path is only supported on Windows.".to_string())
+    }
+}
+
 #[tauri::command]
 fn start_native_file_drag(window: tauri::WebviewWindow, path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
@@ -753,8 +3959,17 @@ fn open_external_url(app: tauri::AppHandle, url: String) -> Result<(), String> {
     }
 }
 
+/// Hard cap on one preview read, for files with very long lines (minified JSON, logs).
 #[cfg(target_os = "windows")]
 const TEXT_PREVIEW_READ_LIMIT_BYTES: u64 = 4 * 1024 * 1024;
+#[cfg(target_os = "windows")]
+const TEXT_PREVIEW_DEFAULT_LINES: u32 = 2_000;
+#[cfg(target_os = "windows")]
+const TEXT_PREVIEW_MAX_LINES: u32 = 20_000;
+/// Leading bytes the encoding is detected from, so pages read from an offset decode like the
+/// first one.
+#[cfg(target_os = "windows")]
+const TEXT_PREVIEW_SNIFF_BYTES: u64 = 4 * 1024;
 
 #[cfg(target_os = "windows")]
 enum TextPreviewEncoding {
@@ -850,9 +4065,27 @@ fn decode_utf16_preview(bytes: &[u8], big_endian: bool) -> String {
     String::from_utf16_lossy(&units)
 }
 
+/// Offsets just past each line break in `bytes`, matching `\n` in the unit width of `encoding`.
+#[cfg(target_os = "windows")]
+fn text_preview_line_ends<'a>(
+    bytes: &'a [u8],
+    encoding: &TextPreviewEncoding,
+) -> impl Iterator<Item = usize> + 'a {
+    let (unit_len, line_break): (usize, &'static [u8]) = match encoding {
+        TextPreviewEncoding::Utf16Le => (2, &[b'\n', 0]),
+        TextPreviewEncoding::Utf16Be => (2, &[0, b'\n']),
+        TextPreviewEncoding::Utf8 | TextPreviewEncoding::Ansi => (1, b"\n"),
+    };
+    bytes
+        .chunks_exact(unit_len)
+        .enumerate()
+        .filter(move |(_, unit)| *unit == line_break)
+        .map(move |(index, _)| (index + 1) * unit_len)
+}
+
 #[cfg(target_os = "windows")]
-fn decode_text_preview(bytes: &[u8], requested_mode: Option<&str>) -> String {
-    match resolve_text_preview_encoding(bytes, requested_mode) {
+fn decode_text_preview(bytes: &[u8], encoding: &TextPreviewEncoding) -> String {
+    match encoding {
         TextPreviewEncoding::Utf16Le => decode_utf16_preview(bytes, false),
         TextPreviewEncoding::Utf16Be => decode_utf16_preview(bytes, true),
         TextPreviewEncoding::Utf8 => {
@@ -980,6 +4213,7 @@ fn build_text_preview_payload(
     decoded_text: String,
     max_chars: usize,
     content_query: Option<&str>,
+    end_offset: u64,
 ) -> TextPreviewPayload {
     let normalized_text = normalize_preview_text(decoded_text);
     let effective_max_chars = max_chars.clamp(120, 12_000);
@@ -989,6 +4223,7 @@ fn build_text_preview_payload(
             text: String::new(),
             truncated: false,
             matched: false,
+            end_offset,
         };
     }
 
@@ -1000,6 +4235,7 @@ fn build_text_preview_payload(
                 text,
                 truncated,
                 matched: true,
+                end_offset,
             };
         }
     }
@@ -1009,19 +4245,29 @@ fn build_text_preview_payload(
         text,
         truncated,
         matched: false,
+        end_offset,
     }
 }
 
+/// Previews a text file from its first `max_lines` lines (default 2000) rather than the whole
+/// file, so even huge files stay cheap. Reads end on a line break and report the byte offset
+/// reached; passing it back as `start_offset` continues with the next page.
 #[tauri::command]
 fn load_text_preview(
     path: String,
     max_chars: Option<usize>,
+    max_lines: Option<u32>,
+    start_offset: Option<u64>,
     content_query: Option<String>,
     content_mode: Option<String>,
 ) -> Result<TextPreviewPayload, String> {
     #[cfg(target_os = "windows")]
     {
-        use std::{fs::File, io::Read, path::PathBuf};
+        use std::{
+            fs::File,
+            io::{Read, Seek, SeekFrom},
+            path::PathBuf,
+        };
 
         let file_path = PathBuf::from(path);
         if !file_path.exists() {
@@ -1031,103 +4277,68 @@ fn load_text_preview(
             return Err("Preview target is not a file.".to_string());
         }
 
-        let file =
+        let read_error = |err: std::io::Error| format!("Text preview read failed: {err}");
+        let mut file =
             File::open(&file_path).map_err(|err| format!("Text preview open failed: {err}"))?;
-        let mut reader = file.take(TEXT_PREVIEW_READ_LIMIT_BYTES + 1);
+        let mut header = Vec::new();
+        file.by_ref()
+            .take(TEXT_PREVIEW_SNIFF_BYTES)
+            .read_to_end(&mut header)
+            .map_err(read_error)?;
+        let encoding = resolve_text_preview_encoding(&header, content_mode.as_deref());
+
+        let start_offset = start_offset.unwrap_or(0);
+        file.seek(SeekFrom::Start(start_offset))
+            .map_err(read_error)?;
         let mut bytes = Vec::new();
-        reader
+        file.take(TEXT_PREVIEW_READ_LIMIT_BYTES + 1)
             .read_to_end(&mut bytes)
-            .map_err(|err| format!("Text preview read failed: {err}"))?;
-
-        let byte_truncated = bytes.len() as u64 > TEXT_PREVIEW_READ_LIMIT_BYTES;
-        if byte_truncated {
+            .map_err(read_error)?;
+        let file_continues = bytes.len() as u64 > TEXT_PREVIEW_READ_LIMIT_BYTES;
+        if file_continues {
             bytes.truncate(TEXT_PREVIEW_READ_LIMIT_BYTES as usize);
         }
 
-        let decoded = decode_text_preview(&bytes, content_mode.as_deref());
+        // Stop after the last wanted line; a read that hit the byte cap first falls back to its
+        // last complete line, and only cuts mid-line when a single line fills the whole cap.
+        let max_lines = max_lines
+            .unwrap_or(TEXT_PREVIEW_DEFAULT_LINES)
+            .clamp(1, TEXT_PREVIEW_MAX_LINES);
+        let line_end = match text_preview_line_ends(&bytes, &encoding).nth(max_lines as usize - 1) {
+            Some(end) => end,
+            None if file_continues => text_preview_line_ends(&bytes, &encoding)
+                .last()
+                .unwrap_or(bytes.len()),
+            None => bytes.len(),
+        };
+        let line_truncated = file_continues || line_end < bytes.len();
+        bytes.truncate(line_end);
+
+        let decoded = decode_text_preview(&bytes, &encoding);
         let mut payload = build_text_preview_payload(
             decoded,
             max_chars.unwrap_or(3200),
             content_query.as_deref(),
+            start_offset + line_end as u64,
         );
-        payload.truncated = payload.truncated || byte_truncated;
+        payload.truncated = payload.truncated || line_truncated;
         Ok(payload)
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = (path, max_chars, content_query, content_mode);
+        let _ = (
+            path,
+            max_chars,
+            max_lines,
+            start_offset,
+            content_query,
+            content_mode,
+        );
         Err("Text preview loading is only supported on Windows.".to_string())
     }
 }
 
-#[tauri::command]
-fn load_preview_data_url(path: String) -> Result<String, String> {
-    #[cfg(target_os = "windows")]
-    {
-        use std::fs;
-        use std::path::PathBuf;
-
-        let file_path = PathBuf::from(path);
-        if !file_path.exists() {
-            return Err("Preview target does not exist.".to_string());
-        }
-        if !file_path.is_file() {
-            return Err("Preview target is not a file.".to_string());
-        }
-
-        let extension = file_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or_default()
-            .to_ascii_lowercase();
-
-        let mime = match extension.as_str() {
-            "png" => "image/png",
-            "jpg" | "jpeg" => "image/jpeg",
-            "gif" => "image/gif",
-            "webp" => "image/webp",
-            "bmp" => "image/bmp",
-            "ico" => "image/x-icon",
-            "pdf" => "application/pdf",
-            "mp4" => "video/mp4",
-            "webm" => "video/webm",
-            "mov" => "video/quicktime",
-            "m4v" => "video/x-m4v",
-            "avi" => "video/x-msvideo",
-            "mkv" => "video/x-matroska",
-            "wmv" => "video/x-ms-wmv",
-            _ => return Err("Preview not supported for this file type.".to_string()),
-        };
-
-        let metadata = fs::metadata(&file_path)
-            .map_err(|err| format!("Preview metadata read failed: {err}"))?;
-        let max_preview_bytes = match mime {
-            "application/pdf" => 8 * 1024 * 1024_u64,
-            "video/mp4" | "video/webm" | "video/quicktime" | "video/x-m4v" | "video/x-msvideo"
-            | "video/x-matroska" | "video/x-ms-wmv" => 20 * 1024 * 1024_u64,
-            _ => 12 * 1024 * 1024_u64,
-        };
-
-        if metadata.len() > max_preview_bytes {
-            return Err(format!(
-                "Preview skipped: file too large ({} bytes).",
-                metadata.len()
-            ));
-        }
-
-        let bytes = fs::read(&file_path).map_err(|err| format!("Preview read failed: {err}"))?;
-        let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
-        Ok(format!("data:{mime};base64,{encoded}"))
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = path;
-        Err("Preview loading is only supported on Windows.".to_string())
-    }
-}
-
 #[tauri::command]
 fn start_mobile_sync_server(
     sync_state: tauri::State<'_, Arc<sync_server::SyncState>>,
@@ -1385,32 +4596,147 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             start_indexing,
+            index_drive_with_progress,
             index_status,
+            set_index_memory_budget,
+            await_index_ready,
+            path_index_status,
+            index_diagnostics,
+            backend_info,
+            index_counts,
+            index_totals,
+            present_extensions,
+            path_autocomplete,
+            verify_index,
             search_files,
+            next_page,
+            cross_drive_name_matches,
             cancel_search,
+            advanced_search::advanced_search,
+            saved_searches::list_saved_searches,
+            saved_searches::save_search,
+            saved_searches::delete_saved_search,
+            saved_searches::run_saved_search,
+            search_token::encode_search,
+            search_token::decode_search,
+            session::save_session,
+            session::load_session,
             find_duplicate_groups,
+            top_duplicate_offenders,
+            duplicate_overview,
+            cleanup::find_cleanup_candidates,
+            duplicates_within_results,
+            find_duplicate_folders,
+            estimate_duplicate_scan,
             duplicate_scan_status,
             cancel_duplicate_scan,
+            settings::get_duplicate_defaults,
+            settings::set_duplicate_defaults,
+            settings::get_autoindex_drives,
+            settings::set_autoindex_drives,
+            settings::open_data_dir,
+            settings::export_settings,
+            settings::import_settings,
+            hashing::compute_file_hash,
+            hashing::hash_group,
+            hashing::files_identical,
+            hashing::content_fingerprint,
+            grep::grep_in_files,
+            grep::validate_pattern,
+            directory_size,
+            folder_summary,
+            path_breadcrumbs,
+            resolve_known_folder,
+            refresh_path,
+            refresh_results,
+            lookup_path,
+            lookup_by_file_id,
+            find_by_names,
+            tags::set_tags,
+            tags::get_tags,
+            pinned::pin_folder,
+            pinned::unpin_folder,
+            pinned::pinned_folders,
+            virtual_roots::register_virtual_root,
+            virtual_roots::unregister_virtual_root,
+            virtual_roots::virtual_roots,
+            virtual_roots::search_virtual_root,
+            video::video_filmstrip,
+            archive::create_zip,
+            archive::archive_cover,
+            save_index_snapshot,
+            diff_index,
+            export::export_results,
             delete_path,
+            recycle::probe_delete,
+            recycle::who_locks,
+            recycle::delete_to_recycle_bin,
+            recycle::restore_last_deleted,
             rename_path,
+            rename::bulk_rename,
+            rename::organize_files,
+            quarantine::quarantine_duplicates,
+            properties::set_timestamps,
             list_drives,
+            drive_health,
+            network::list_network_roots,
+            network::add_network_root,
+            network::remove_network_root,
+            watch::watch_folder,
+            watch::unwatch_folder,
+            watch::get_index_update_interval,
+            watch::set_index_update_interval,
             open_file,
+            open_at,
+            recent::recently_opened,
+            open_files_in_one,
+            open_file_elevated,
             reveal_in_folder,
+            reveal_items_in_folder,
+            reveal_grouped,
             open_path_in_console,
+            open_terminal,
             start_native_file_drag,
+            clipboard::copy_content_to_clipboard,
+            clipboard::start_clipboard_watch,
+            clipboard::stop_clipboard_watch,
             open_external_url,
             load_text_preview,
-            load_preview_data_url,
+            preview::supported_preview_types,
+            preview::preview,
+            preview::load_preview_data_url,
+            preview::load_preview_payload,
+            preview::preview_tail,
+            preview::read_text_range,
+            preview::hex_preview,
+            preview::find_extension_mismatches,
+            preview::prefetch_previews,
+            preview::clear_preview_cache,
             apps::list_installed_apps,
             apps::launch_installed_app,
             apps::reveal_installed_app,
             apps::load_installed_app_icon_data_url,
+            apps::extension_icon,
+            apps::default_app_for,
+            properties::list_alternate_streams,
+            properties::unblock_file,
+            properties::set_file_attributes,
+            properties::show_properties_dialog,
+            properties::resolve_shortcut,
+            properties::find_broken_shortcuts,
+            properties::media_metadata,
+            properties::file_version_info,
+            images::find_similar_images,
+            documents::find_similar_documents,
+            images::thumbnails,
             desktop::get_desktop_settings,
             desktop::open_full_window_command,
             desktop::open_quick_window_command,
             desktop::reset_window_layout_command,
             desktop::sync_window_theme_command,
             desktop::update_desktop_settings,
+            desktop::register_global_hotkey,
+            desktop::unregister_global_hotkey,
             start_mobile_sync_server,
             stop_mobile_sync_server,
             get_mobile_sync_server_info,
@@ -1427,6 +4753,12 @@ pub fn run() {
                     eprintln!("{err}");
                 }
 
+                #[cfg(target_os = "windows")]
+                start_autoindex(app.handle());
+                #[cfg(target_os = "windows")]
+                watch::start_index_update_events(app.handle());
+                session::offer_saved_session(app.handle());
+
                 let args = std::env::args().collect::<Vec<_>>();
                 if !shell_send_paths_from_args(&args).is_empty() {
                     let app_handle = app.handle().clone();
@@ -1448,3 +4780,105 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    fn breadcrumb_paths(path: &str) -> Vec<(String, String)> {
+        path_breadcrumbs(path.to_string())
+            .unwrap()
+            .into_iter()
+            .map(|breadcrumb| (breadcrumb.name, breadcrumb.path))
+            .collect()
+    }
+
+    #[test]
+    fn path_breadcrumbs_lists_ancestors_root_first() {
+        let expected = [
+            (r"C:\", r"C:\"),
+            ("Users", r"C:\Users"),
+            ("me", r"C:\Users\me"),
+        ]
+        .map(|(name, path)| (name.to_string(), path.to_string()));
+        assert_eq!(breadcrumb_paths(r"C:\Users\me"), expected);
+        assert_eq!(breadcrumb_paths(r" C:\Users\me\ "), expected);
+    }
+
+    #[test]
+    fn path_breadcrumbs_keeps_roots_whole() {
+        let drive = [(r"C:\".to_string(), r"C:\".to_string())];
+        assert_eq!(breadcrumb_paths("C:"), drive);
+        assert_eq!(breadcrumb_paths(r"C:\"), drive);
+        let share = breadcrumb_paths(r"\\server\share\docs");
+        assert_eq!(share[0].0, r"\\server\share\");
+        assert_eq!(
+            share[1],
+            ("docs".to_string(), r"\\server\share\docs".to_string())
+        );
+    }
+
+    #[test]
+    fn path_breadcrumbs_rejects_empty_paths() {
+        assert!(path_breadcrumbs(String::new()).is_err());
+        assert!(path_breadcrumbs("   ".to_string()).is_err());
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn open_at_arguments_passes_pages_to_known_readers() {
+        let page = OpenLocation::Page { page: 7 };
+        let path = r"C:\Docs\My report #2.pdf";
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\SumatraPDF.exe", path, &page),
+            Some(strings(&["-page", "7", path]))
+        );
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\AcroRd32.exe", path, &page),
+            Some(strings(&["/A", "page=7", path]))
+        );
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\msedge.exe", path, &page),
+            Some(strings(&["file:///C:/Docs/My%20report%20%232.pdf#page=7"]))
+        );
+        let first_page = OpenLocation::Page { page: 0 };
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\SumatraPDF.exe", path, &first_page),
+            Some(strings(&["-page", "1", path]))
+        );
+    }
+
+    #[test]
+    fn open_at_arguments_passes_times_to_known_players() {
+        let time = OpenLocation::Time { seconds: 3725.5 };
+        let path = r"C:\Videos\clip.mkv";
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\vlc.exe", path, &time),
+            Some(strings(&["--start-time=3725.5", path]))
+        );
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\mpc-hc64.exe", path, &time),
+            Some(strings(&[path, "/start", "3725500"]))
+        );
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\PotPlayerMini64.exe", path, &time),
+            Some(strings(&[path, "/seek=01:02:05"]))
+        );
+        let invalid = OpenLocation::Time { seconds: f64::NAN };
+        assert_eq!(
+            open_at_arguments(r"C:\Apps\mpv.exe", path, &invalid),
+            Some(strings(&["--start=0", path]))
+        );
+    }
+
+    #[test]
+    fn open_at_arguments_skips_unknown_apps() {
+        let page = OpenLocation::Page { page: 3 };
+        let path = r"C:\Docs\a.pdf";
+        assert_eq!(open_at_arguments(r"C:\Apps\notepad.exe", path, &page), None);
+        assert_eq!(open_at_arguments("", path, &page), None);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::store::{load_store, save_store};
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime};
+
+#[cfg(target_os = "windows")]
+use std::ffi::CString;
+
+const NETWORK_ROOTS_FILE_NAME: &str = "network-roots.json";
+
+/// Serializes read-modify-write cycles on the network-roots file.
+static NETWORK_ROOTS_LOCK: Mutex<()> = Mutex::new(());
+
+fn normalize_network_root(path: &str) -> Result<String, String> {
+    let root = path.trim().replace('/', "\\");
+    let root = root.trim_end_matches('\\');
+    let is_unc = root.starts_with("\\\\")
+        && root[2..]
+            .split('\\')
+            .filter(|segment| !segment.is_empty())
+            .count()
+            >= 2;
+    if !is_unc {
+        return Err("Network roots must be UNC paths like \\\\server\\share.".to_string());
+    }
+    Ok(root.to_string())
+}
+
+/// Hands the stored network roots to the scanner so the next indexing run walks them.
+/// Shares never use the NTFS fast path: they are enumerated recursively on every run.
+pub(crate) fn sync_network_roots<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let roots: Vec<String> = load_store(app, NETWORK_ROOTS_FILE_NAME);
+
+    #[cfg(target_os = "windows")]
+    {
+        let c_roots =
+            CString::new(roots.join("\n")).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_roots` lives long enough for this synchronous call.
+        let ok = unsafe { crate::omni_set_network_roots(c_roots.as_ptr()) };
+        if !ok {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to register network roots.".to_string()));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = roots;
+        Err("Network roots are only supported on Windows.".to_string())
+    }
+}
+
+pub(crate) fn load_network_roots<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    load_store(app, NETWORK_ROOTS_FILE_NAME)
+}
+
+/// Replaces every network root, as when importing settings. Each must be a UNC path; shares
+/// are not probed, since they may be offline when the settings are applied.
+pub(crate) fn replace_network_roots<R: Runtime>(
+    app: &AppHandle<R>,
+    roots: Vec<String>,
+) -> Result<(), String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(roots.len());
+    for root in roots {
+        let root = normalize_network_root(&root)?;
+        if !normalized
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(&root))
+        {
+            normalized.push(root);
+        }
+    }
+    let _guard = NETWORK_ROOTS_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock network roots".to_string())?;
+    save_store(app, NETWORK_ROOTS_FILE_NAME, &normalized)?;
+    #[cfg(target_os = "windows")]
+    sync_network_roots(app)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_network_roots(app: AppHandle<tauri::Wry>) -> Vec<String> {
+    load_store(&app, NETWORK_ROOTS_FILE_NAME)
+}
+
+/// Registers a `\\server\share` path for indexing. It is picked up by the next `start_indexing`.
+#[tauri::command]
+pub fn add_network_root(app: AppHandle<tauri::Wry>, path: String) -> Result<Vec<String>, String> {
+    let root = normalize_network_root(&path)?;
+    if !std::path::Path::new(&root).is_dir() {
+        return Err("Network share is not accessible.".to_string());
+    }
+
+    let _guard = NETWORK_ROOTS_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock network roots".to_string())?;
+    let mut roots: Vec<String> = load_store(&app, NETWORK_ROOTS_FILE_NAME);
+    if !roots
+        .iter()
+        .any(|existing| existing.eq_ignore_ascii_case(&root))
+    {
+        roots.push(root);
+        save_store(&app, NETWORK_ROOTS_FILE_NAME, &roots)?;
+    }
+    sync_network_roots(&app)?;
+    Ok(roots)
+}
+
+#[tauri::command]
+pub fn remove_network_root(
+    app: AppHandle<tauri::Wry>,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let root = normalize_network_root(&path)?;
+    let _guard = NETWORK_ROOTS_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock network roots".to_string())?;
+    let mut roots: Vec<String> = load_store(&app, NETWORK_ROOTS_FILE_NAME);
+    roots.retain(|existing| !existing.eq_ignore_ascii_case(&root));
+    save_store(&app, NETWORK_ROOTS_FILE_NAME, &roots)?;
+    sync_network_roots(&app)?;
+    Ok(roots)
+}
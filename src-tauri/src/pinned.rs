@@ -0,0 +1,101 @@
+use crate::store::{load_store, save_store};
+use std::{path::Path, sync::Mutex};
+use tauri::{AppHandle, Runtime};
+
+const PINNED_FOLDERS_FILE_NAME: &str = "pinned-folders.json";
+
+/// Serializes read-modify-write cycles on the pinned-folders file.
+static PINNED_FOLDERS_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_pinned_folders() -> Result<std::sync::MutexGuard<'static, ()>, String> {
+    PINNED_FOLDERS_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock pinned folders".to_string())
+}
+
+/// Trims the path and drops a trailing separator, keeping it on drive roots (`C:\`).
+pub(crate) fn normalize_folder(path: &str) -> String {
+    let trimmed = path.trim();
+    let stripped = trimmed.trim_end_matches(['\\', '/']);
+    if stripped.len() == 2 && stripped.ends_with(':') {
+        return format!("{stripped}\\");
+    }
+    stripped.to_string()
+}
+
+pub(crate) fn load_pinned_folders<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let Ok(_guard) = lock_pinned_folders() else {
+        return Vec::new();
+    };
+    load_store(app, PINNED_FOLDERS_FILE_NAME)
+}
+
+/// Replaces every pin, as when importing settings. Folders are not checked here, since pins
+/// missing on this machine are pruned the next time they are listed.
+pub(crate) fn replace_pinned_folders<R: Runtime>(
+    app: &AppHandle<R>,
+    pins: Vec<String>,
+) -> Result<(), String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(pins.len());
+    for pin in pins {
+        let pin = normalize_folder(&pin);
+        if !pin.is_empty()
+            && !normalized
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(&pin))
+        {
+            normalized.push(pin);
+        }
+    }
+    let _guard = lock_pinned_folders()?;
+    save_store(app, PINNED_FOLDERS_FILE_NAME, &normalized)
+}
+
+/// Pins `path` for one-click scoped search and returns the pins in the order they were added.
+/// Pinning a folder twice keeps the first pin.
+#[tauri::command]
+pub fn pin_folder(app: AppHandle<tauri::Wry>, path: String) -> Result<Vec<String>, String> {
+    let path = normalize_folder(&path);
+    if path.is_empty() {
+        return Err("Path is empty.".to_string());
+    }
+    if !Path::new(&path).is_dir() {
+        return Err("Folder does not exist on disk.".to_string());
+    }
+
+    let _guard = lock_pinned_folders()?;
+    let mut pins: Vec<String> = load_store(&app, PINNED_FOLDERS_FILE_NAME);
+    if !pins.iter().any(|pin| pin.eq_ignore_ascii_case(&path)) {
+        pins.push(path);
+        save_store(&app, PINNED_FOLDERS_FILE_NAME, &pins)?;
+    }
+    Ok(pins)
+}
+
+/// Removes the pin for `path`, if any, and returns the remaining pins.
+#[tauri::command]
+pub fn unpin_folder(app: AppHandle<tauri::Wry>, path: String) -> Result<Vec<String>, String> {
+    let path = normalize_folder(&path);
+    let _guard = lock_pinned_folders()?;
+    let mut pins: Vec<String> = load_store(&app, PINNED_FOLDERS_FILE_NAME);
+    let before = pins.len();
+    pins.retain(|pin| !pin.eq_ignore_ascii_case(&path));
+    if pins.len() != before {
+        save_store(&app, PINNED_FOLDERS_FILE_NAME, &pins)?;
+    }
+    Ok(pins)
+}
+
+/// Pinned folders in the order they were added. Pins whose folders no longer exist are
+/// dropped from the store.
+#[tauri::command]
+pub fn pinned_folders(app: AppHandle<tauri::Wry>) -> Result<Vec<String>, String> {
+    let _guard = lock_pinned_folders()?;
+    let mut pins: Vec<String> = load_store(&app, PINNED_FOLDERS_FILE_NAME);
+    let before = pins.len();
+    pins.retain(|pin| Path::new(pin).is_dir());
+    if pins.len() != before {
+        save_store(&app, PINNED_FOLDERS_FILE_NAME, &pins)?;
+    }
+    Ok(pins)
+}
@@ -0,0 +1,1294 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use base64::Engine;
+#[cfg(target_os = "windows")]
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(target_os = "windows")]
+const PREVIEW_CACHE_MAX_ENTRIES: usize = 64;
+#[cfg(target_os = "windows")]
+const PREVIEW_CACHE_MAX_BYTES: usize = 128 * 1024 * 1024;
+#[cfg(target_os = "windows")]
+const PREVIEW_PREFETCH_MAX_PATHS: usize = 64;
+#[cfg(target_os = "windows")]
+const SNIFF_HEADER_BYTES: usize = 512;
+#[cfg(target_os = "windows")]
+const TAIL_READ_CHUNK_BYTES: u64 = 64 * 1024;
+#[cfg(target_os = "windows")]
+const TAIL_MAX_BYTES: u64 = 8 * 1024 * 1024;
+#[cfg(target_os = "windows")]
+const TAIL_DEFAULT_LINES: u32 = 200;
+#[cfg(target_os = "windows")]
+const TAIL_MAX_LINES: u32 = 10_000;
+/// Files checked by one `find_extension_mismatches` run.
+#[cfg(target_os = "windows")]
+const MISMATCH_SCAN_LIMIT: u32 = 50_000;
+#[cfg(target_os = "windows")]
+const HEX_PREVIEW_DEFAULT_BYTES: u32 = 512;
+#[cfg(target_os = "windows")]
+const HEX_PREVIEW_MAX_BYTES: u32 = 4096;
+#[cfg(target_os = "windows")]
+const HEX_PREVIEW_ROW_BYTES: usize = 16;
+#[cfg(target_os = "windows")]
+const METADATA_PREVIEW_ICON_SIZE: u32 = 64;
+/// Edge length, in pixels, of `preview`'s thumbnail mode.
+#[cfg(target_os = "windows")]
+const PREVIEW_THUMBNAIL_SIZE: u32 = 512;
+#[cfg(target_os = "windows")]
+const TEXT_RANGE_DEFAULT_LINES: u32 = 200;
+#[cfg(target_os = "windows")]
+const TEXT_RANGE_MAX_LINES: u32 = 5_000;
+/// Longer lines are cut in the returned chunk; they still count as one line.
+#[cfg(target_os = "windows")]
+const TEXT_RANGE_MAX_LINE_BYTES: usize = 16 * 1024;
+/// A byte offset is remembered every this many lines.
+#[cfg(target_os = "windows")]
+const LINE_INDEX_STRIDE: u64 = 1_000;
+#[cfg(target_os = "windows")]
+const LINE_INDEX_MAX_FILES: usize = 16;
+
+/// Extensions the data-URL preview renders, with the MIME type it serves them as.
+#[cfg(target_os = "windows")]
+const MEDIA_PREVIEW_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/x-icon"),
+    ("svg", "image/svg+xml"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("heic", "image/heic"),
+    ("heif", "image/heic"),
+    ("avif", "image/avif"),
+    ("pdf", "application/pdf"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+    ("mov", "video/quicktime"),
+    ("m4v", "video/x-m4v"),
+    ("avi", "video/x-msvideo"),
+    ("mkv", "video/x-matroska"),
+    ("wmv", "video/x-ms-wmv"),
+    ("mp3", "audio/mpeg"),
+    ("wav", "audio/wav"),
+    ("flac", "audio/flac"),
+    ("ogg", "audio/ogg"),
+    ("m4a", "audio/mp4"),
+    ("aac", "audio/aac"),
+];
+
+/// Extensions `load_text_preview` is expected to decode into readable text.
+#[cfg(target_os = "windows")]
+pub(crate) const TEXT_PREVIEW_EXTENSIONS: &[&str] = &[
+    "txt", "md", "log", "ini", "cfg", "conf", "csv", "json", "xml", "yaml", "yml", "toml", "html",
+    "htm", "css", "js", "jsx", "ts", "tsx", "rs", "py", "java", "c", "cpp", "h", "hpp", "cs",
+    "sql", "bat", "cmd", "ps1",
+];
+
+/// The representation `preview` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewMode {
+    /// Text for text types, the file itself when it fits the size caps, a thumbnail for
+    /// larger images, videos and PDFs, metadata otherwise.
+    Auto,
+    Thumbnail,
+    Full,
+    Text,
+    Metadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewPayload {
+    /// `content` for a rendered preview; `metadata` for types that cannot be previewed, where
+    /// `data_url` holds the file type icon (or is empty) and `metadata` describes the file.
+    /// `preview` also returns `thumbnail` (a PNG data URL) and `text` (in `text`).
+    pub kind: String,
+    pub data_url: String,
+    pub mime: String,
+    /// Type detected from the file's magic bytes, when sniffing decided the MIME.
+    pub detected_type: Option<String>,
+    pub metadata: Option<PreviewMetadata>,
+    /// Opening lines of the file, only for `text` payloads.
+    #[serde(default)]
+    pub text: Option<String>,
+    /// The file continues past `text`.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewMetadata {
+    pub name: String,
+    pub size: u64,
+    /// Lowercase extension without the dot; empty for files without one.
+    pub extension: String,
+    /// Extension-based category, as on search results.
+    pub category: String,
+    pub created_unix: i64,
+    pub modified_unix: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexRow {
+    pub offset: u64,
+    /// Space-separated lowercase byte pairs, e.g. `89 50 4e 47`.
+    pub hex: String,
+    /// Printable ASCII bytes, with `.` standing in for everything else.
+    pub ascii: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexDump {
+    pub path: String,
+    pub file_size: u64,
+    pub rows: Vec<HexRow>,
+    /// The file continues past the dumped bytes.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionMismatch {
+    pub path: String,
+    pub extension: String,
+    /// Type found in the file header, as a canonical extension (`exe`, `zip`, `png`...).
+    pub detected_type: String,
+    /// The content is a Windows executable, the case worth a closer look.
+    pub executable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewType {
+    pub extension: String,
+    /// One of `image`, `video`, `audio`, `pdf` or `text`.
+    pub category: String,
+    pub mime: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextChunk {
+    pub path: String,
+    /// Zero-based line number of the first entry in `lines`.
+    pub start_line: u64,
+    /// Lines without their line breaks, lossily decoded as UTF-8.
+    pub lines: Vec<String>,
+    /// No lines follow the chunk.
+    pub end_of_file: bool,
+    /// Line count of the whole file, once a read has reached its end.
+    pub total_lines: Option<u64>,
+}
+
+/// Byte offsets of every `LINE_INDEX_STRIDE`-th line of a file, extended as reads go deeper,
+/// so paging far into a file seeks close to the target instead of rescanning from the top.
+#[cfg(target_os = "windows")]
+struct LineIndex {
+    modified: Option<SystemTime>,
+    len: u64,
+    /// `checkpoints[k]` is where line `k * LINE_INDEX_STRIDE` starts.
+    checkpoints: Vec<u64>,
+    total_lines: Option<u64>,
+    last_used: u64,
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+struct LineIndexCache {
+    entries: HashMap<String, LineIndex>,
+    clock: u64,
+}
+
+#[cfg(target_os = "windows")]
+fn line_index_cache() -> &'static Mutex<LineIndexCache> {
+    static LINE_INDEX_CACHE: OnceLock<Mutex<LineIndexCache>> = OnceLock::new();
+    LINE_INDEX_CACHE.get_or_init(|| Mutex::new(LineIndexCache::default()))
+}
+
+#[cfg(target_os = "windows")]
+struct CachedPreview {
+    payload: PreviewPayload,
+    modified: Option<SystemTime>,
+    len: u64,
+    /// `hashing::fingerprint_file` of the previewed content, checked when only the modified
+    /// time moved.
+    fingerprint: Option<String>,
+    last_used: u64,
+}
+
+/// Small LRU of preview payloads, bounded by entry count and total bytes.
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+struct PreviewCache {
+    entries: HashMap<String, CachedPreview>,
+    total_bytes: usize,
+    clock: u64,
+}
+
+#[cfg(target_os = "windows")]
+impl PreviewCache {
+    fn get(
+        &mut self,
+        key: &str,
+        path: &str,
+        modified: Option<SystemTime>,
+        len: u64,
+    ) -> Option<PreviewPayload> {
+        self.clock += 1;
+        let clock = self.clock;
+        let entry = self.entries.get_mut(key)?;
+        // A touched file keeps its preview as long as its content fingerprint is unchanged.
+        let touched_only = entry.modified != modified
+            && entry.len == len
+            && entry.fingerprint.is_some()
+            && crate::hashing::fingerprint_file(path).ok() == entry.fingerprint;
+        if touched_only {
+            entry.modified = modified;
+        }
+        if entry.modified != modified || entry.len != len {
+            let stale = self.entries.remove(key)?;
+            self.total_bytes = self
+                .total_bytes
+                .saturating_sub(stale.payload.data_url.len());
+            return None;
+        }
+        entry.last_used = clock;
+        Some(entry.payload.clone())
+    }
+
+    fn insert(&mut self, key: String, mut entry: CachedPreview) {
+        if entry.payload.data_url.len() > PREVIEW_CACHE_MAX_BYTES / 4 {
+            return;
+        }
+        self.clock += 1;
+        entry.last_used = self.clock;
+        self.total_bytes += entry.payload.data_url.len();
+        if let Some(previous) = self.entries.insert(key, entry) {
+            self.total_bytes = self
+                .total_bytes
+                .saturating_sub(previous.payload.data_url.len());
+        }
+
+        while self.entries.len() > PREVIEW_CACHE_MAX_ENTRIES
+            || self.total_bytes > PREVIEW_CACHE_MAX_BYTES
+        {
+            let Some(oldest_key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest_key) {
+                self.total_bytes = self
+                    .total_bytes
+                    .saturating_sub(evicted.payload.data_url.len());
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.total_bytes = 0;
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn preview_cache() -> &'static Mutex<PreviewCache> {
+    static PREVIEW_CACHE: OnceLock<Mutex<PreviewCache>> = OnceLock::new();
+    PREVIEW_CACHE.get_or_init(|| Mutex::new(PreviewCache::default()))
+}
+
+#[cfg(target_os = "windows")]
+fn cached_preview(
+    path: &str,
+    sniff: bool,
+    forced_mime: Option<&'static str>,
+) -> Result<PreviewPayload, String> {
+    let Ok(metadata) = fs::metadata(path) else {
+        return build_preview(path, sniff, forced_mime);
+    };
+    let key = format!(
+        "{}|{sniff}|{}",
+        path.to_lowercase(),
+        forced_mime.unwrap_or_default()
+    );
+    let modified = metadata.modified().ok();
+    if let Ok(mut cache) = preview_cache().lock() {
+        if let Some(cached) = cache.get(&key, path, modified, metadata.len()) {
+            return Ok(cached);
+        }
+    }
+
+    let payload = build_preview(path, sniff, forced_mime)?;
+    let fingerprint = crate::hashing::fingerprint_file(path).ok();
+    if let Ok(mut cache) = preview_cache().lock() {
+        cache.insert(
+            key,
+            CachedPreview {
+                payload: payload.clone(),
+                modified,
+                len: metadata.len(),
+                fingerprint,
+                last_used: 0,
+            },
+        );
+    }
+    Ok(payload)
+}
+
+#[cfg(target_os = "windows")]
+fn transcode_to_png_data_url(bytes: &[u8], format: image::ImageFormat) -> Result<String, String> {
+    let decoded = image::load_from_memory_with_format(bytes, format)
+        .map_err(|err| format!("Preview decode failed: {err}"))?;
+    let mut png_bytes = Vec::new();
+    decoded
+        .write_to(
+            &mut std::io::Cursor::new(&mut png_bytes),
+            image::ImageFormat::Png,
+        )
+        .map_err(|err| format!("Preview encode failed: {err}"))?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png_bytes);
+    Ok(format!("data:image/png;base64,{encoded}"))
+}
+
+#[cfg(target_os = "windows")]
+fn preview_mime_for_extension(extension: &str) -> Option<&'static str> {
+    MEDIA_PREVIEW_TYPES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(_, mime)| *mime)
+}
+
+/// Validates a caller-supplied MIME override against the MIME types the preview can serve.
+#[cfg(target_os = "windows")]
+fn resolve_forced_mime(force_mime: Option<&str>) -> Result<Option<&'static str>, String> {
+    let Some(requested) = force_mime.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    MEDIA_PREVIEW_TYPES
+        .iter()
+        .map(|(_, mime)| *mime)
+        .find(|mime| mime.eq_ignore_ascii_case(requested))
+        .map(Some)
+        .ok_or_else(|| format!("Unsupported preview MIME override: {requested}."))
+}
+
+/// Largest file served as a data URL for `mime`.
+#[cfg(target_os = "windows")]
+fn max_preview_bytes(mime: &str) -> u64 {
+    match preview_category_for_mime(mime) {
+        "pdf" => 8 * 1024 * 1024,
+        "video" | "audio" => 20 * 1024 * 1024,
+        _ => 12 * 1024 * 1024,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn preview_category_for_mime(mime: &str) -> &'static str {
+    match mime.split('/').next().unwrap_or_default() {
+        "image" => "image",
+        "video" => "video",
+        "audio" => "audio",
+        "text" => "text",
+        _ => "pdf",
+    }
+}
+
+/// Detects a previewable type from the file header. Returns the canonical extension.
+#[cfg(target_os = "windows")]
+fn sniff_preview_type(header: &[u8]) -> Option<&'static str> {
+    let starts_with = |magic: &[u8]| header.starts_with(magic);
+    if starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("png");
+    }
+    if starts_with(b"\xFF\xD8\xFF") {
+        return Some("jpg");
+    }
+    if starts_with(b"GIF87a") || starts_with(b"GIF89a") {
+        return Some("gif");
+    }
+    if starts_with(b"%PDF-") {
+        return Some("pdf");
+    }
+    if starts_with(b"II*\0") || starts_with(b"MM\0*") {
+        return Some("tiff");
+    }
+    if starts_with(b"BM") && header.len() >= 14 {
+        return Some("bmp");
+    }
+    if starts_with(b"\0\0\x01\0") {
+        return Some("ico");
+    }
+    if starts_with(b"\x30\x26\xB2\x75\x8E\x66\xCF\x11") {
+        return Some("wmv");
+    }
+    if starts_with(b"RIFF") && header.len() >= 12 {
+        return match &header[8..12] {
+            b"WEBP" => Some("webp"),
+            b"AVI " => Some("avi"),
+            _ => None,
+        };
+    }
+    if starts_with(b"\x1A\x45\xDF\xA3") {
+        let is_webm = header.windows(4).any(|window| window == b"webm");
+        return Some(if is_webm { "webm" } else { "mkv" });
+    }
+    if header.len() >= 12 && &header[4..8] == b"ftyp" {
+        return match &header[8..12] {
+            b"heic" | b"heix" | b"heim" | b"heis" | b"mif1" | b"msf1" => Some("heic"),
+            b"avif" | b"avis" => Some("avif"),
+            b"qt  " => Some("mov"),
+            b"M4V " | b"M4VH" | b"M4VP" => Some("m4v"),
+            _ => Some("mp4"),
+        };
+    }
+
+    let text = String::from_utf8_lossy(header);
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    if (trimmed.starts_with("<?xml") || trimmed.starts_with("<svg")) && trimmed.contains("<svg") {
+        return Some("svg");
+    }
+    None
+}
+
+/// Header types each audited extension may legitimately have. Containers are matched loosely:
+/// Office documents, EPUBs and JARs are all zips underneath.
+#[cfg(target_os = "windows")]
+const MISMATCH_EXPECTED_TYPES: &[(&str, &[&str])] = &[
+    ("jpg", &["jpg"]),
+    ("jpeg", &["jpg"]),
+    ("png", &["png"]),
+    ("gif", &["gif"]),
+    ("bmp", &["bmp"]),
+    ("webp", &["webp"]),
+    ("tif", &["tiff"]),
+    ("tiff", &["tiff"]),
+    ("ico", &["ico"]),
+    ("heic", &["heic"]),
+    ("heif", &["heic"]),
+    ("avif", &["avif"]),
+    ("pdf", &["pdf"]),
+    ("mp4", &["mp4", "m4v", "mov"]),
+    ("m4v", &["mp4", "m4v", "mov"]),
+    ("m4a", &["mp4"]),
+    ("mov", &["mov", "mp4"]),
+    ("mkv", &["mkv", "webm"]),
+    ("webm", &["webm", "mkv"]),
+    ("avi", &["avi"]),
+    ("wmv", &["wmv"]),
+    ("zip", &["zip"]),
+    ("cbz", &["zip"]),
+    ("docx", &["zip"]),
+    ("xlsx", &["zip"]),
+    ("pptx", &["zip"]),
+    ("epub", &["zip"]),
+    ("jar", &["zip"]),
+    ("rar", &["rar"]),
+    ("cbr", &["rar"]),
+    ("7z", &["7z"]),
+    ("gz", &["gzip"]),
+    ("tgz", &["gzip"]),
+    ("doc", &["ole"]),
+    ("xls", &["ole"]),
+    ("ppt", &["ole"]),
+    ("msi", &["ole"]),
+    ("exe", &["exe"]),
+    ("dll", &["exe"]),
+    ("sys", &["exe"]),
+    ("scr", &["exe"]),
+];
+
+/// Like `sniff_preview_type`, but also recognizes executables, archives and OLE documents,
+/// for telling what a file really is rather than how to preview it.
+#[cfg(target_os = "windows")]
+fn sniff_content_type(header: &[u8]) -> Option<&'static str> {
+    let starts_with = |magic: &[u8]| header.starts_with(magic);
+    if starts_with(b"MZ") {
+        return Some("exe");
+    }
+    if starts_with(b"PK\x03\x04") || starts_with(b"PK\x05\x06") {
+        return Some("zip");
+    }
+    if starts_with(b"Rar!\x1A\x07") {
+        return Some("rar");
+    }
+    if starts_with(b"7z\xBC\xAF\x27\x1C") {
+        return Some("7z");
+    }
+    if starts_with(b"\x1F\x8B") {
+        return Some("gzip");
+    }
+    if starts_with(b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1") {
+        return Some("ole");
+    }
+    if starts_with(b"\x7FELF") {
+        return Some("elf");
+    }
+    sniff_preview_type(header)
+}
+
+#[cfg(target_os = "windows")]
+fn read_preview_header(file_path: &Path) -> Result<Vec<u8>, String> {
+    let mut header = Vec::with_capacity(SNIFF_HEADER_BYTES);
+    fs::File::open(file_path)
+        .and_then(|file| {
+            file.take(SNIFF_HEADER_BYTES as u64)
+                .read_to_end(&mut header)
+        })
+        .map_err(|err| format!("Preview read failed: {err}"))?;
+    Ok(header)
+}
+
+#[cfg(target_os = "windows")]
+fn hex_rows(bytes: &[u8]) -> Vec<HexRow> {
+    bytes
+        .chunks(HEX_PREVIEW_ROW_BYTES)
+        .enumerate()
+        .map(|(index, row)| HexRow {
+            offset: (index * HEX_PREVIEW_ROW_BYTES) as u64,
+            hex: row
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            ascii: row
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Reads backwards in chunks until `lines` line breaks are found (or `TAIL_MAX_BYTES` is
+/// reached), then returns the bytes after that break. A trailing newline ends the last line
+/// instead of counting as an empty one.
+#[cfg(target_os = "windows")]
+fn read_tail_bytes(file_path: &Path, lines: u32) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(file_path)?;
+    let mut end = file.metadata()?.len();
+    if end > 0 {
+        let mut last = [0_u8; 1];
+        file.seek(SeekFrom::Start(end - 1))?;
+        file.read_exact(&mut last)?;
+        if last[0] == b'\n' {
+            end -= 1;
+        }
+    }
+
+    let floor = end.saturating_sub(TAIL_MAX_BYTES);
+    let mut start = floor;
+    let mut position = end;
+    let mut newlines = 0_u32;
+    let mut chunk = vec![0_u8; TAIL_READ_CHUNK_BYTES as usize];
+    'scan: while position > floor {
+        let read_len = TAIL_READ_CHUNK_BYTES.min(position - floor);
+        position -= read_len;
+        let window = &mut chunk[..read_len as usize];
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(window)?;
+        for (offset, byte) in window.iter().enumerate().rev() {
+            if *byte == b'\n' {
+                newlines += 1;
+                if newlines == lines {
+                    start = position + offset as u64 + 1;
+                    break 'scan;
+                }
+            }
+        }
+    }
+
+    let mut tail = vec![0_u8; (end - start) as usize];
+    file.seek(SeekFrom::Start(start))?;
+    file.read_exact(&mut tail)?;
+    Ok(tail)
+}
+
+#[cfg(target_os = "windows")]
+fn unix_seconds(time: std::io::Result<SystemTime>) -> i64 {
+    time.ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Describes a file the preview cannot render, so the pane still has its name, size, type,
+/// timestamps and icon to show.
+#[cfg(target_os = "windows")]
+fn metadata_preview(file_path: &Path, extension: &str) -> Result<PreviewPayload, String> {
+    let metadata =
+        fs::metadata(file_path).map_err(|err| format!("Preview metadata read failed: {err}"))?;
+    let icon = crate::apps::load_extension_icon_data_url(extension, METADATA_PREVIEW_ICON_SIZE)
+        .unwrap_or_default();
+    Ok(PreviewPayload {
+        kind: "metadata".to_string(),
+        data_url: icon,
+        mime: "application/octet-stream".to_string(),
+        detected_type: None,
+        metadata: Some(PreviewMetadata {
+            name: file_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            size: metadata.len(),
+            extension: extension.to_string(),
+            category: crate::category::category_for(extension, false).to_string(),
+            created_unix: unix_seconds(metadata.created()),
+            modified_unix: unix_seconds(metadata.modified()),
+        }),
+        text: None,
+        truncated: false,
+    })
+}
+
+/// Reads `line_count` lines starting at zero-based `start_line`, resuming from the closest
+/// remembered line offset and recording new ones on the way.
+#[cfg(target_os = "windows")]
+fn read_text_range_blocking(
+    path: &str,
+    start_line: u64,
+    line_count: u32,
+) -> Result<TextChunk, String> {
+    let read_error = |err: std::io::Error| format!("Text range read failed: {err}");
+    let file = File::open(path).map_err(read_error)?;
+    let metadata = file.metadata().map_err(read_error)?;
+    let modified = metadata.modified().ok();
+    let key = path.to_lowercase();
+
+    let (mut checkpoints, mut total_lines) = {
+        let mut cache = line_index_cache()
+            .lock()
+            .map_err(|_| "Line index is unavailable.".to_string())?;
+        match cache.entries.remove(&key) {
+            Some(index) if index.modified == modified && index.len == metadata.len() => {
+                (index.checkpoints, index.total_lines)
+            }
+            _ => (vec![0], None),
+        }
+    };
+
+    let checkpoint = ((start_line / LINE_INDEX_STRIDE) as usize).min(checkpoints.len() - 1);
+    let mut line = checkpoint as u64 * LINE_INDEX_STRIDE;
+    let mut offset = checkpoints[checkpoint];
+    let mut reader = BufReader::new(file);
+    reader.seek(SeekFrom::Start(offset)).map_err(read_error)?;
+
+    let mut lines = Vec::new();
+    let mut buffer = Vec::new();
+    let mut end_of_file = false;
+    while lines.len() < line_count as usize {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer).map_err(read_error)?;
+        if read == 0 {
+            end_of_file = true;
+            total_lines = Some(line);
+            break;
+        }
+        if line >= start_line {
+            let mut content = buffer.as_slice();
+            while let Some((last, rest)) = content.split_last() {
+                if *last != b'\n' && *last != b'\r' {
+                    break;
+                }
+                content = rest;
+            }
+            let content = &content[..content.len().min(TEXT_RANGE_MAX_LINE_BYTES)];
+            let text = String::from_utf8_lossy(content);
+            let text: &str = if line == 0 {
+                text.trim_start_matches('\u{feff}')
+            } else {
+                &text
+            };
+            lines.push(text.to_string());
+        }
+        offset += read as u64;
+        line += 1;
+        if line % LINE_INDEX_STRIDE == 0 && (line / LINE_INDEX_STRIDE) as usize == checkpoints.len()
+        {
+            checkpoints.push(offset);
+        }
+    }
+    if !end_of_file && total_lines.is_none() {
+        end_of_file = reader.fill_buf().map_err(read_error)?.is_empty();
+        if end_of_file {
+            total_lines = Some(line);
+        }
+    }
+    if let Some(total) = total_lines {
+        end_of_file = end_of_file || line >= total;
+    }
+
+    if let Ok(mut cache) = line_index_cache().lock() {
+        cache.clock += 1;
+        let last_used = cache.clock;
+        cache.entries.insert(
+            key,
+            LineIndex {
+                modified,
+                len: metadata.len(),
+                checkpoints,
+                total_lines,
+                last_used,
+            },
+        );
+        while cache.entries.len() > LINE_INDEX_MAX_FILES {
+            let Some(oldest_key) = cache
+                .entries
+                .iter()
+                .min_by_key(|(_, index)| index.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            cache.entries.remove(&oldest_key);
+        }
+    }
+
+    Ok(TextChunk {
+        path: path.to_string(),
+        start_line,
+        lines,
+        end_of_file,
+        total_lines,
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn build_preview(
+    path: &str,
+    sniff: bool,
+    forced_mime: Option<&'static str>,
+) -> Result<PreviewPayload, String> {
+    let file_path = PathBuf::from(path);
+    if !file_path.exists() {
+        return Err("Preview target does not exist.".to_string());
+    }
+    if !file_path.is_file() {
+        return Err("Preview target is not a file.".to_string());
+    }
+
+    let extension = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    // A forced MIME wins; otherwise magic bytes decide when asked to, or when the
+    // extension is unknown.
+    let extension_mime = forced_mime.or_else(|| preview_mime_for_extension(&extension));
+    let detected_type = if forced_mime.is_none() && (sniff || extension_mime.is_none()) {
+        sniff_preview_type(&read_preview_header(&file_path)?)
+    } else {
+        None
+    };
+    let Some(mime) = detected_type
+        .and_then(preview_mime_for_extension)
+        .or(extension_mime)
+    else {
+        return metadata_preview(&file_path, &extension);
+    };
+
+    let metadata =
+        fs::metadata(&file_path).map_err(|err| format!("Preview metadata read failed: {err}"))?;
+    if metadata.len() > max_preview_bytes(mime) {
+        return Err(format!(
+            "Preview skipped: file too large ({} bytes).",
+            metadata.len()
+        ));
+    }
+
+    let data_url = match mime {
+        // The WebView cannot render HEIC, so let the installed shell codecs decode it.
+        "image/heic" => {
+            crate::apps::load_shell_thumbnail_data_url(&file_path.to_string_lossy(), 1600)?
+        }
+        _ => {
+            let bytes =
+                fs::read(&file_path).map_err(|err| format!("Preview read failed: {err}"))?;
+            if mime == "image/tiff" {
+                transcode_to_png_data_url(&bytes, image::ImageFormat::Tiff)?
+            } else {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+                format!("data:{mime};base64,{encoded}")
+            }
+        }
+    };
+
+    Ok(PreviewPayload {
+        kind: "content".to_string(),
+        data_url,
+        mime: mime.to_string(),
+        detected_type: detected_type.map(str::to_string),
+        metadata: None,
+        text: None,
+        truncated: false,
+    })
+}
+
+/// Lists every extension the backend previews, so the UI only offers previews that will work.
+#[tauri::command]
+pub fn supported_preview_types() -> Vec<PreviewType> {
+    #[cfg(target_os = "windows")]
+    {
+        let media = MEDIA_PREVIEW_TYPES
+            .iter()
+            .map(|(extension, mime)| PreviewType {
+                extension: extension.to_string(),
+                category: preview_category_for_mime(mime).to_string(),
+                mime: mime.to_string(),
+            });
+        let text = TEXT_PREVIEW_EXTENSIONS.iter().map(|extension| PreviewType {
+            extension: extension.to_string(),
+            category: "text".to_string(),
+            mime: "text/plain".to_string(),
+        });
+        media.chain(text).collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Returns the preview as a data URL. For types that cannot be previewed this is the file
+/// type icon; `load_preview_payload` tells the two apart.
+#[tauri::command]
+pub fn load_preview_data_url(
+    path: String,
+    sniff: Option<bool>,
+    force_mime: Option<String>,
+) -> Result<String, String> {
+    load_preview_payload(path, sniff, force_mime).map(|payload| payload.data_url)
+}
+
+/// Unsupported types come back as a `metadata` payload instead of an error.
+/// `force_mime` serves the file as another allowlisted MIME type, for formats the WebView
+/// plays better under a different type (e.g. m4v as `video/mp4`).
+#[tauri::command]
+pub fn load_preview_payload(
+    path: String,
+    sniff: Option<bool>,
+    force_mime: Option<String>,
+) -> Result<PreviewPayload, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let forced_mime = resolve_forced_mime(force_mime.as_deref())?;
+        cached_preview(&path, sniff.unwrap_or(false), forced_mime)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, sniff, force_mime);
+        Err("Preview loading is only supported on Windows.".to_string())
+    }
+}
+
+/// What `PreviewMode::Auto` resolves to for a file with `extension` and `len` bytes.
+#[cfg(target_os = "windows")]
+fn auto_preview_mode(extension: &str, len: u64) -> PreviewMode {
+    if TEXT_PREVIEW_EXTENSIONS.contains(&extension) {
+        return PreviewMode::Text;
+    }
+    match preview_mime_for_extension(extension) {
+        Some(mime) if len <= max_preview_bytes(mime) => PreviewMode::Full,
+        Some(mime) if preview_category_for_mime(mime) != "audio" => PreviewMode::Thumbnail,
+        Some(_) => PreviewMode::Metadata,
+        // Unknown extensions may still sniff as a previewable type.
+        None => PreviewMode::Full,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn preview_in_mode(path: &str, mode: PreviewMode) -> Result<PreviewPayload, String> {
+    let file_path = Path::new(path);
+    let metadata =
+        fs::metadata(file_path).map_err(|_| "Preview target does not exist.".to_string())?;
+    if !metadata.is_file() {
+        return Err("Preview target is not a file.".to_string());
+    }
+    let extension = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let mode = match mode {
+        PreviewMode::Auto => auto_preview_mode(&extension, metadata.len()),
+        mode => mode,
+    };
+    match mode {
+        PreviewMode::Thumbnail => Ok(PreviewPayload {
+            kind: "thumbnail".to_string(),
+            data_url: crate::images::cached_thumbnail(path, PREVIEW_THUMBNAIL_SIZE)?,
+            mime: "image/png".to_string(),
+            detected_type: None,
+            metadata: None,
+            text: None,
+            truncated: false,
+        }),
+        PreviewMode::Text => {
+            let preview = crate::load_text_preview(path.to_string(), None, None, None, None, None)?;
+            Ok(PreviewPayload {
+                kind: "text".to_string(),
+                data_url: String::new(),
+                mime: "text/plain".to_string(),
+                detected_type: None,
+                metadata: None,
+                text: Some(preview.text),
+                truncated: preview.truncated,
+            })
+        }
+        PreviewMode::Metadata => metadata_preview(file_path, &extension),
+        PreviewMode::Full | PreviewMode::Auto => cached_preview(path, false, None),
+    }
+}
+
+/// One entry point for every preview representation of `path`: `thumbnail`, `full` (as
+/// `load_preview_payload`), `text` (the opening lines, as `load_text_preview`), `metadata`,
+/// or `auto` to pick by type and size.
+#[tauri::command]
+pub async fn preview(path: String, mode: PreviewMode) -> Result<PreviewPayload, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || preview_in_mode(&path, mode))
+            .await
+            .map_err(|err| format!("Preview task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, mode);
+        Err("Preview loading is only supported on Windows.".to_string())
+    }
+}
+
+/// Returns the last `lines` lines of a text file without reading the whole file, so
+/// multi-gigabyte logs can be previewed. A `lines` of zero uses the default.
+#[tauri::command]
+pub async fn preview_tail(path: String, lines: u32) -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let file_path = PathBuf::from(path);
+        if !file_path.is_file() {
+            return Err("Preview target is not a file.".to_string());
+        }
+        let lines = if lines == 0 {
+            TAIL_DEFAULT_LINES
+        } else {
+            lines.min(TAIL_MAX_LINES)
+        };
+
+        let tail = tauri::async_runtime::spawn_blocking(move || read_tail_bytes(&file_path, lines))
+            .await
+            .map_err(|err| format!("Tail preview task failed: {err}"))?
+            .map_err(|err| format!("Tail preview read failed: {err}"))?;
+        Ok(String::from_utf8_lossy(&tail).into_owned())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, lines);
+        Err("Tail preview is only supported on Windows.".to_string())
+    }
+}
+
+/// Pages through a large text file: returns `line_count` lines (default 200, at most 5000)
+/// from zero-based `start_line`. Line offsets found along the way are cached per file, so
+/// later pages seek near their start instead of counting lines from the top again.
+#[tauri::command]
+pub async fn read_text_range(
+    path: String,
+    start_line: u64,
+    line_count: u32,
+) -> Result<TextChunk, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !PathBuf::from(&path).is_file() {
+            return Err("Preview target is not a file.".to_string());
+        }
+        let line_count = if line_count == 0 {
+            TEXT_RANGE_DEFAULT_LINES
+        } else {
+            line_count.min(TEXT_RANGE_MAX_LINES)
+        };
+        tauri::async_runtime::spawn_blocking(move || {
+            read_text_range_blocking(&path, start_line, line_count)
+        })
+        .await
+        .map_err(|err| format!("Text range task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, start_line, line_count);
+        Err("Text preview is only supported on Windows.".to_string())
+    }
+}
+
+/// Dumps the first `bytes` bytes of a file as 16-byte offset/hex/ASCII rows, for identifying
+/// formats by their magic bytes. `bytes` is capped at 4 KB; zero uses 512.
+#[tauri::command]
+pub async fn hex_preview(path: String, bytes: u32) -> Result<HexDump, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let file_path = PathBuf::from(&path);
+        if !file_path.is_file() {
+            return Err("Preview target is not a file.".to_string());
+        }
+        let bytes = if bytes == 0 {
+            HEX_PREVIEW_DEFAULT_BYTES
+        } else {
+            bytes.min(HEX_PREVIEW_MAX_BYTES)
+        };
+
+        tauri::async_runtime::spawn_blocking(move || -> Result<HexDump, String> {
+            let file =
+                File::open(&file_path).map_err(|err| format!("Preview read failed: {err}"))?;
+            let file_size = file
+                .metadata()
+                .map_err(|err| format!("Preview read failed: {err}"))?
+                .len();
+            let mut head = Vec::with_capacity(bytes as usize);
+            file.take(u64::from(bytes))
+                .read_to_end(&mut head)
+                .map_err(|err| format!("Preview read failed: {err}"))?;
+            Ok(HexDump {
+                path,
+                file_size,
+                truncated: file_size > head.len() as u64,
+                rows: hex_rows(&head),
+            })
+        })
+        .await
+        .map_err(|err| format!("Hex preview task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, bytes);
+        Err("Hex preview is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn prefetch_previews(paths: Vec<String>) -> Result<u32, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || {
+            let cached = paths
+                .iter()
+                .take(PREVIEW_PREFETCH_MAX_PATHS)
+                .filter(|path| cached_preview(path, false, None).is_ok())
+                .count();
+            cached as u32
+        })
+        .await
+        .map_err(|err| format!("Preview prefetch task failed: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = paths;
+        Err("Preview loading is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn clear_preview_cache() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(mut cache) = preview_cache().lock() {
+            cache.clear();
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Preview loading is only supported on Windows.".to_string())
+    }
+}
+
+/// Flags indexed files whose header does not match their extension, such as a `.jpg` that is
+/// really an executable. Only extensions with a known signature are audited, only the first
+/// 512 bytes of each file are read, and files whose header is not recognized are left out
+/// rather than guessed at. At most 50,000 candidates are checked per run.
+#[tauri::command]
+pub async fn find_extension_mismatches(
+    within_path: Option<String>,
+) -> Result<Vec<ExtensionMismatch>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::ffi::CString;
+
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<ExtensionMismatch>, String> {
+            let c_query = CString::default();
+            let extensions = MISMATCH_EXPECTED_TYPES
+                .iter()
+                .map(|(extension, _)| *extension)
+                .collect::<Vec<_>>()
+                .join(",");
+            let c_extension =
+                CString::new(extensions).map_err(|_| "Invalid extension".to_string())?;
+            let c_within_path = CString::new(within_path.unwrap_or_default())
+                .map_err(|_| "Invalid path parameter".to_string())?;
+            let options = crate::SearchOptions {
+                // Empty files have no header to compare.
+                min_size: 1,
+                limit: MISMATCH_SCAN_LIMIT,
+                max_limit: MISMATCH_SCAN_LIMIT,
+                ..crate::SearchOptions::background(&c_query, &c_extension, &c_within_path)
+            };
+
+            let mut mismatches = Vec::new();
+            for file in crate::run_search(&options)? {
+                if file.is_directory {
+                    continue;
+                }
+                let extension = file.extension.to_ascii_lowercase();
+                let Some((_, expected)) = MISMATCH_EXPECTED_TYPES
+                    .iter()
+                    .find(|(candidate, _)| *candidate == extension)
+                else {
+                    continue;
+                };
+                let Ok(header) = read_preview_header(Path::new(&file.path)) else {
+                    continue;
+                };
+                let Some(detected) = sniff_content_type(&header) else {
+                    continue;
+                };
+                if !expected.contains(&detected) {
+                    mismatches.push(ExtensionMismatch {
+                        path: file.path,
+                        extension,
+                        detected_type: detected.to_string(),
+                        executable: detected == "exe",
+                    });
+                }
+            }
+            // Executables in disguise first, then by path.
+            mismatches.sort_by(|a, b| {
+                b.executable
+                    .cmp(&a.executable)
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            Ok(mismatches)
+        })
+        .await
+        .map_err(|err| format!("Extension audit task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = within_path;
+        Err("Extension audits are only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    fn test_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("omni-search-preview-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn read_tail_bytes_returns_the_last_lines() {
+        let path = test_file("tail.txt", b"one\ntwo\nthree\nfour\n");
+        assert_eq!(read_tail_bytes(&path, 2).unwrap(), b"three\nfour");
+        assert_eq!(
+            read_tail_bytes(&path, 10).unwrap(),
+            b"one\ntwo\nthree\nfour"
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_tail_bytes_handles_missing_trailing_newline_and_empty_files() {
+        let path = test_file("unterminated.txt", b"one\ntwo");
+        assert_eq!(read_tail_bytes(&path, 1).unwrap(), b"two");
+        fs::write(&path, b"").unwrap();
+        assert!(read_tail_bytes(&path, 5).unwrap().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_tail_bytes_spans_chunk_boundaries() {
+        let mut contents = vec![b'x'; TAIL_READ_CHUNK_BYTES as usize + 10];
+        contents.extend_from_slice(b"\nlast\n");
+        let path = test_file("chunked.txt", &contents);
+        assert_eq!(read_tail_bytes(&path, 1).unwrap(), b"last");
+        let two = read_tail_bytes(&path, 2).unwrap();
+        assert_eq!(two.len(), TAIL_READ_CHUNK_BYTES as usize + 15);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_text_range_strips_line_breaks_and_bom() {
+        let path = test_file("range.txt", b"\xef\xbb\xbffirst\r\nsecond\nthird");
+        let path_text = path.to_string_lossy().into_owned();
+        let chunk = read_text_range_blocking(&path_text, 0, 2).unwrap();
+        assert_eq!(chunk.lines, ["first", "second"]);
+        assert!(!chunk.end_of_file);
+        assert_eq!(chunk.total_lines, None);
+
+        let chunk = read_text_range_blocking(&path_text, 1, 10).unwrap();
+        assert_eq!(chunk.start_line, 1);
+        assert_eq!(chunk.lines, ["second", "third"]);
+        assert!(chunk.end_of_file);
+        assert_eq!(chunk.total_lines, Some(3));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_text_range_resumes_from_line_checkpoints() {
+        let contents = (0..2_500)
+            .map(|line| format!("line {line}\n"))
+            .collect::<String>();
+        let path = test_file("checkpoints.txt", contents.as_bytes());
+        let path_text = path.to_string_lossy().into_owned();
+        let chunk = read_text_range_blocking(&path_text, 2_100, 3).unwrap();
+        assert_eq!(chunk.lines, ["line 2100", "line 2101", "line 2102"]);
+
+        // The second read seeks to the checkpoint the first one recorded.
+        let chunk = read_text_range_blocking(&path_text, 1_999, 2).unwrap();
+        assert_eq!(chunk.lines, ["line 1999", "line 2000"]);
+        let chunk = read_text_range_blocking(&path_text, 2_498, 5).unwrap();
+        assert_eq!(chunk.lines, ["line 2498", "line 2499"]);
+        assert!(chunk.end_of_file);
+        assert_eq!(chunk.total_lines, Some(2_500));
+
+        let past_end = read_text_range_blocking(&path_text, 3_000, 5).unwrap();
+        assert!(past_end.lines.is_empty());
+        assert!(past_end.end_of_file);
+        fs::remove_file(path).unwrap();
+    }
+}
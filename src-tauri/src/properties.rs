@@ -0,0 +1,411 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use std::{
+    ffi::{CStr, CString},
+    io::ErrorKind,
+    path::PathBuf,
+};
+
+#[cfg(target_os = "windows")]
+const ZONE_IDENTIFIER_STREAM: &str = "Zone.Identifier";
+#[cfg(target_os = "windows")]
+const FILE_ATTRIBUTE_READONLY: u32 = 0x1;
+#[cfg(target_os = "windows")]
+const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamInfo {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutInfo {
+    pub path: String,
+    /// `None` for shortcuts to shell items that have no file system target.
+    pub target_path: Option<String>,
+    pub arguments: String,
+    pub working_directory: String,
+    pub icon_location: String,
+    pub icon_index: i32,
+    pub description: String,
+}
+
+/// A shortcut whose file system target no longer exists.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenShortcut {
+    pub name: String,
+    pub path: String,
+    /// Where the shortcut still points.
+    pub target_path: String,
+}
+
+/// Media properties from the shell property system, which reflect the capture rather than
+/// the file system copy. Fields a file does not carry are `None`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaMeta {
+    pub path: String,
+    pub duration_ms: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Camera "date taken" of photos, as Unix seconds.
+    pub date_taken_unix: Option<i64>,
+}
+
+/// Version resource strings of an executable or DLL. Fields the resource lacks are `None`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub path: String,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    pub file_version: Option<String>,
+    pub company_name: Option<String>,
+    pub file_description: Option<String>,
+}
+
+#[tauri::command]
+pub fn list_alternate_streams(path: String) -> Result<Vec<StreamInfo>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !PathBuf::from(&path).exists() {
+            return Err("File does not exist on disk.".to_string());
+        }
+
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_path` lives long enough for this synchronous call.
+        let raw_json = unsafe { crate::omni_list_alternate_streams_json(c_path.as_ptr()) };
+        if raw_json.is_null() {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to list alternate data streams.".to_string()));
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { crate::omni_free_string(raw_json) };
+
+        let parsed: Vec<StreamInfo> =
+            serde_json::from_str(&json).map_err(|err| format!("Invalid stream payload: {err}"))?;
+        Ok(parsed)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("Alternate data streams are only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn unblock_file(path: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let file_path = PathBuf::from(&path);
+        if !file_path.exists() {
+            return Err("File does not exist on disk.".to_string());
+        }
+        if !file_path.is_file() {
+            return Err("Only files can be unblocked.".to_string());
+        }
+
+        let stream_path = format!("{path}:{ZONE_IDENTIFIER_STREAM}");
+        match std::fs::remove_file(&stream_path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(format!("Failed to unblock file: {err}")),
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("Unblocking files is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn set_file_attributes(
+    path: String,
+    readonly: Option<bool>,
+    hidden: Option<bool>,
+) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !PathBuf::from(&path).exists() {
+            return Err("Path does not exist on disk.".to_string());
+        }
+
+        let mut mask = 0_u32;
+        let mut value = 0_u32;
+        for (requested, bit) in [
+            (readonly, FILE_ATTRIBUTE_READONLY),
+            (hidden, FILE_ATTRIBUTE_HIDDEN),
+        ] {
+            if let Some(enabled) = requested {
+                mask |= bit;
+                if enabled {
+                    value |= bit;
+                }
+            }
+        }
+        if mask == 0 {
+            return Ok(());
+        }
+
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_path` lives long enough for this synchronous call.
+        let ok = unsafe { crate::omni_set_file_attributes(c_path.as_ptr(), mask, value) };
+        if !ok {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to update file attributes.".to_string()));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, readonly, hidden);
+        Err("File attributes are only supported on Windows.".to_string())
+    }
+}
+
+/// Opens the standard Windows properties sheet for `path`.
+#[tauri::command]
+pub fn show_properties_dialog(path: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !PathBuf::from(&path).exists() {
+            return Err("File does not exist on disk.".to_string());
+        }
+
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_path` lives long enough for this synchronous call.
+        let ok = unsafe { crate::omni_show_properties(c_path.as_ptr()) };
+        if !ok {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to show the properties dialog.".to_string()));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("The properties dialog is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn resolve_shortcut(path: String) -> Result<ShortcutInfo, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let target = PathBuf::from(&path);
+        if !target.is_file() {
+            return Err("File does not exist on disk.".to_string());
+        }
+        let is_shortcut = target
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"));
+        if !is_shortcut {
+            return Err("Only .lnk shortcuts can be resolved.".to_string());
+        }
+
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_path` lives long enough for this synchronous call.
+        let raw_json = unsafe { crate::omni_resolve_shortcut_json(c_path.as_ptr()) };
+        if raw_json.is_null() {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to resolve the shortcut.".to_string()));
+        }
+
+        // SAFETY: `raw_json` points to a C string allocated by C++.
+        let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+        // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+        unsafe { crate::omni_free_string(raw_json) };
+
+        let parsed: ShortcutInfo = serde_json::from_str(&json)
+            .map_err(|err| format!("Invalid shortcut payload: {err}"))?;
+        Ok(parsed)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("Shortcut resolution is only supported on Windows.".to_string())
+    }
+}
+
+/// Resolves every indexed `.lnk` file (under `within_path` when given) and returns those whose
+/// target is missing. Targets on drives or shares that are currently unreachable are not
+/// reported, since they may come back.
+#[tauri::command]
+pub async fn find_broken_shortcuts(
+    within_path: Option<String>,
+) -> Result<Vec<BrokenShortcut>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let within_path = within_path.unwrap_or_default();
+        if !within_path.trim().is_empty() && !PathBuf::from(&within_path).is_dir() {
+            return Err("Folder does not exist on disk.".to_string());
+        }
+        let c_within_path =
+            CString::new(within_path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<BrokenShortcut>, String> {
+            // SAFETY: `c_within_path` lives long enough for this synchronous call.
+            let raw_json =
+                unsafe { crate::omni_find_broken_shortcuts_json(c_within_path.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(crate::read_last_error()
+                    .unwrap_or_else(|| "Failed to find broken shortcuts.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { crate::omni_free_string(raw_json) };
+
+            serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid broken shortcuts payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Broken shortcut scan task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = within_path;
+        Err("Shortcut resolution is only supported on Windows.".to_string())
+    }
+}
+
+/// Reads duration, dimensions and date taken for photos and videos, the values Explorer shows
+/// in its media columns.
+#[tauri::command]
+pub async fn media_metadata(path: String) -> Result<MediaMeta, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !PathBuf::from(&path).is_file() {
+            return Err("File does not exist on disk.".to_string());
+        }
+
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<MediaMeta, String> {
+            // SAFETY: `c_path` lives long enough for this synchronous call.
+            let raw_json = unsafe { crate::omni_media_metadata_json(c_path.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(crate::read_last_error()
+                    .unwrap_or_else(|| "Failed to read media metadata.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { crate::omni_free_string(raw_json) };
+
+            serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid media metadata payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Media metadata task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("Media metadata is only supported on Windows.".to_string())
+    }
+}
+
+/// Reads product name, versions, company and description from the version resource of an
+/// executable or DLL, to tell similarly named binaries apart. Errors when the file has no
+/// version resource.
+#[tauri::command]
+pub async fn file_version_info(path: String) -> Result<VersionInfo, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !PathBuf::from(&path).is_file() {
+            return Err("File does not exist on disk.".to_string());
+        }
+
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<VersionInfo, String> {
+            // SAFETY: `c_path` lives long enough for this synchronous call.
+            let raw_json = unsafe { crate::omni_file_version_info_json(c_path.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(crate::read_last_error()
+                    .unwrap_or_else(|| "Failed to read version info.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { crate::omni_free_string(raw_json) };
+
+            serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid version info payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Version info task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("Version info is only supported on Windows.".to_string())
+    }
+}
+
+/// Sets the created, modified and accessed times of a file or folder to the given unix
+/// seconds, changing only the ones provided; handy after a copy or move lost the originals.
+#[tauri::command]
+pub async fn set_timestamps(
+    path: String,
+    created_unix: Option<i64>,
+    modified_unix: Option<i64>,
+    accessed_unix: Option<i64>,
+) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        if created_unix.is_none() && modified_unix.is_none() && accessed_unix.is_none() {
+            return Err("No timestamps to set.".to_string());
+        }
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || {
+            let as_ptr = |value: &Option<i64>| {
+                value
+                    .as_ref()
+                    .map_or(std::ptr::null(), |value| value as *const i64)
+            };
+            // SAFETY: `c_path` and the timestamps outlive this synchronous call; null
+            // pointers leave the matching time unchanged.
+            let ok = unsafe {
+                crate::omni_set_file_times(
+                    c_path.as_ptr(),
+                    as_ptr(&created_unix),
+                    as_ptr(&modified_unix),
+                    as_ptr(&accessed_unix),
+                )
+            };
+            if !ok {
+                return Err(crate::read_last_error()
+                    .unwrap_or_else(|| "Failed to set timestamps.".to_string()));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|err| format!("Timestamp task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, created_unix, modified_unix, accessed_unix);
+        Err("Setting timestamps is only supported on Windows.".to_string())
+    }
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use crate::rename::{move_to_unique_target, path_key};
+#[cfg(target_os = "windows")]
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy)]
+enum KeeperStrategy {
+    Newest,
+    Oldest,
+    ShortestPath,
+    First,
+}
+
+#[cfg(target_os = "windows")]
+impl KeeperStrategy {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "newest" => Ok(Self::Newest),
+            "oldest" => Ok(Self::Oldest),
+            "shortest_path" => Ok(Self::ShortestPath),
+            "first" => Ok(Self::First),
+            other => Err(format!("Unsupported keeper strategy: {other}.")),
+        }
+    }
+
+    /// Index of the file to keep among `files`, which is never empty.
+    fn pick(self, files: &[&crate::DuplicateFile]) -> usize {
+        let indexed = files.iter().enumerate();
+        let picked = match self {
+            Self::Newest => {
+                indexed.max_by_key(|(index, file)| (file.modified_unix, Reverse(*index)))
+            }
+            Self::Oldest => indexed.min_by_key(|(index, file)| (file.modified_unix, *index)),
+            Self::ShortestPath => {
+                indexed.min_by_key(|(index, file)| (file.path.chars().count(), *index))
+            }
+            Self::First => None,
+        };
+        picked.map(|(index, _)| index).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedFile {
+    pub group_id: String,
+    pub original_path: String,
+    /// Where the copy now lives; `None` when it could not be moved.
+    pub quarantine_path: Option<String>,
+    /// The copy of the group left in place.
+    pub kept_path: String,
+    pub error: Option<String>,
+}
+
+/// Mirrors `path` below the quarantine folder: `C:\Users\a.txt` becomes `C\Users\a.txt` and
+/// `\\server\share\a.txt` becomes `UNC\server\share\a.txt`, so each copy records where it
+/// came from.
+#[cfg(target_os = "windows")]
+fn quarantine_relative_path(path: &Path) -> PathBuf {
+    use std::path::{Component, Prefix};
+
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+                    relative.push(char::from(letter).to_ascii_uppercase().to_string());
+                }
+                Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+                    relative.push("UNC");
+                    relative.push(server);
+                    relative.push(share);
+                }
+                _ => relative.push("other"),
+            },
+            Component::Normal(part) => relative.push(part),
+            Component::RootDir | Component::CurDir | Component::ParentDir => {}
+        }
+    }
+    relative
+}
+
+#[cfg(target_os = "windows")]
+fn quarantine_file(source: &Path, quarantine_dir: &Path) -> Result<PathBuf, String> {
+    let relative = quarantine_relative_path(source);
+    let folder = relative
+        .parent()
+        .map(|parent| quarantine_dir.join(parent))
+        .unwrap_or_else(|| quarantine_dir.to_path_buf());
+    let file_name = source
+        .file_name()
+        .map(|value| value.to_string_lossy().into_owned())
+        .ok_or_else(|| "Failed to resolve the file name.".to_string())?;
+    fs::create_dir_all(&folder).map_err(|err| format!("Failed to create the folder: {err}"))?;
+    move_to_unique_target(source, &folder, &file_name)
+}
+
+/// Volume serial number and file index of `path`, the same for every name of one file.
+#[cfg(target_os = "windows")]
+fn file_identity(path: &Path) -> Option<(u32, u64)> {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::{
+        Foundation::HANDLE,
+        Storage::FileSystem::{GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION},
+    };
+
+    let file = fs::File::open(path).ok()?;
+    let mut info = BY_HANDLE_FILE_INFORMATION::default();
+    // SAFETY: the handle stays open for the call and `info` is writable.
+    unsafe { GetFileInformationByHandle(HANDLE(file.as_raw_handle()), &mut info) }.ok()?;
+    let index = (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow);
+    Some((info.dwVolumeSerialNumber, index))
+}
+
+/// Keeps one file of each duplicate group in place and moves the other copies into
+/// `quarantine_dir`, mirroring their original folders (`<quarantine>\C\Users\...`) so they
+/// can be reviewed and put back. `keeper_strategy` picks the file to keep: "newest"
+/// (default), "oldest", "shortest_path" or "first". Copies that no longer exist are left out
+/// of the choice; every other copy is reported with where it went or why it stayed. Only
+/// groups confirmed by a full content comparison (`hash_mode` "full") are accepted, and paths
+/// reaching the same file as one already in the group (junctions, hard links) count once.
+#[tauri::command]
+pub async fn quarantine_duplicates(
+    groups: Vec<crate::DuplicateGroup>,
+    keeper_strategy: String,
+    quarantine_dir: String,
+) -> Result<Vec<QuarantinedFile>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let strategy = KeeperStrategy::parse(&keeper_strategy)?;
+        if let Some(group) = groups
+            .iter()
+            .find(|group| group.hash_mode.as_deref() != Some("full"))
+        {
+            return Err(format!(
+                "Group {} is not a confirmed duplicate; only groups found with the \"full\" \
+                 hash mode can be quarantined.",
+                group.group_id
+            ));
+        }
+        let quarantine_dir = PathBuf::from(quarantine_dir.trim());
+        if quarantine_dir.as_os_str().is_empty() {
+            return Err("Quarantine folder is empty.".to_string());
+        }
+        fs::create_dir_all(&quarantine_dir)
+            .map_err(|err| format!("Failed to create the quarantine folder: {err}"))?;
+        tauri::async_runtime::spawn_blocking(move || {
+            let quarantine_key = PathBuf::from(path_key(&quarantine_dir));
+            let mut manifest = Vec::new();
+            for group in groups {
+                // Copies already inside the quarantine folder stay where they are, and a path
+                // that is another name for a file already listed is no copy at all.
+                let mut canonical_keys = HashSet::new();
+                let mut identities = HashSet::new();
+                let files = group
+                    .files
+                    .iter()
+                    .filter(|file| Path::new(&file.path).is_file())
+                    .filter(|file| {
+                        !Path::new(&path_key(Path::new(&file.path))).starts_with(&quarantine_key)
+                    })
+                    .filter(|file| {
+                        let path = Path::new(&file.path);
+                        let canonical = fs::canonicalize(path)
+                            .map(|canonical| path_key(&canonical))
+                            .unwrap_or_else(|_| path_key(path));
+                        let new_identity =
+                            file_identity(path).is_none_or(|identity| identities.insert(identity));
+                        canonical_keys.insert(canonical) && new_identity
+                    })
+                    .collect::<Vec<_>>();
+                if files.len() < 2 {
+                    continue;
+                }
+                let keeper = strategy.pick(&files);
+                let kept_path = files[keeper].path.clone();
+                for (index, file) in files.iter().enumerate() {
+                    if index == keeper {
+                        continue;
+                    }
+                    let (quarantine_path, error) =
+                        match quarantine_file(Path::new(&file.path), &quarantine_dir) {
+                            Ok(target) => (Some(target.to_string_lossy().into_owned()), None),
+                            Err(err) => (None, Some(err)),
+                        };
+                    manifest.push(QuarantinedFile {
+                        group_id: group.group_id.clone(),
+                        original_path: file.path.clone(),
+                        quarantine_path,
+                        kept_path: kept_path.clone(),
+                        error,
+                    });
+                }
+            }
+            manifest
+        })
+        .await
+        .map_err(|err| format!("Quarantine task failed: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (groups, keeper_strategy, quarantine_dir);
+        Err("Quarantining duplicates is only supported on Windows.".to_string())
+    }
+}
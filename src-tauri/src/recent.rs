@@ -0,0 +1,84 @@
+use crate::store::{load_store, save_store};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Mutex};
+use tauri::AppHandle;
+
+#[cfg(target_os = "windows")]
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(target_os = "windows")]
+use tauri::Runtime;
+
+const RECENT_OPENS_FILE_NAME: &str = "recently-opened.json";
+const RECENT_OPENS_MAX_ENTRIES: usize = 200;
+const RECENT_OPENS_DEFAULT_LIMIT: u32 = 20;
+
+/// Serializes read-modify-write cycles on the recent-opens file.
+static RECENT_OPENS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentOpen {
+    pub path: String,
+    pub opened_unix: i64,
+}
+
+/// Records `path` as just opened. Failures are logged rather than surfaced, since the
+/// open itself already succeeded.
+#[cfg(target_os = "windows")]
+pub(crate) fn record_open<R: Runtime>(app: &AppHandle<R>, path: &str) {
+    let Ok(_guard) = RECENT_OPENS_LOCK.lock() else {
+        return;
+    };
+    let opened_unix = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0);
+
+    let mut entries: Vec<RecentOpen> = load_store(app, RECENT_OPENS_FILE_NAME);
+    entries.retain(|entry| !entry.path.eq_ignore_ascii_case(path));
+    entries.insert(
+        0,
+        RecentOpen {
+            path: path.to_string(),
+            opened_unix,
+        },
+    );
+    entries.truncate(RECENT_OPENS_MAX_ENTRIES);
+
+    if let Err(err) = save_store(app, RECENT_OPENS_FILE_NAME, &entries) {
+        eprintln!("OmniSearch failed to record a recently opened file: {err}");
+    }
+}
+
+/// Returns the most recently opened files, newest first, dropping files that no longer exist.
+/// A `limit` of zero uses the default.
+#[tauri::command]
+pub fn recently_opened(app: AppHandle<tauri::Wry>, limit: u32) -> Result<Vec<RecentOpen>, String> {
+    let _guard = RECENT_OPENS_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock recently opened files".to_string())?;
+
+    let mut stored: Vec<RecentOpen> = load_store(&app, RECENT_OPENS_FILE_NAME);
+    let stored_len = stored.len();
+    stored.sort_by(|left, right| right.opened_unix.cmp(&left.opened_unix));
+    let mut entries = Vec::with_capacity(stored_len);
+    for entry in stored {
+        let duplicate = entries
+            .iter()
+            .any(|kept: &RecentOpen| kept.path.eq_ignore_ascii_case(&entry.path));
+        if !duplicate && Path::new(&entry.path).exists() {
+            entries.push(entry);
+        }
+    }
+    if entries.len() != stored_len {
+        save_store(&app, RECENT_OPENS_FILE_NAME, &entries)?;
+    }
+
+    let limit = if limit == 0 {
+        RECENT_OPENS_DEFAULT_LIMIT
+    } else {
+        limit
+    } as usize;
+    entries.truncate(limit);
+    Ok(entries)
+}
@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use std::{
+    ffi::{CStr, CString},
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(target_os = "windows")]
+static LAST_DELETED_BATCH: OnceLock<Mutex<Vec<DeletedItem>>> = OnceLock::new();
+
+#[cfg(target_os = "windows")]
+fn last_deleted_batch() -> &'static Mutex<Vec<DeletedItem>> {
+    LAST_DELETED_BATCH.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedItem {
+    pub path: String,
+    pub size: u64,
+    pub is_directory: bool,
+    pub deleted_unix: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockingProcess {
+    pub pid: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteProbe {
+    pub path: String,
+    pub exists: bool,
+    pub is_directory: bool,
+    /// Another process holds the item open without delete sharing.
+    pub locked: bool,
+    pub can_delete: bool,
+    /// Processes holding a locked file, as reported by the Restart Manager.
+    pub processes: Vec<LockingProcess>,
+    pub error: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn probe_delete_path(path: &str) -> Result<DeleteProbe, String> {
+    let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+    // SAFETY: `c_path` lives long enough for this synchronous call.
+    let raw_json = unsafe { crate::omni_probe_delete_json(c_path.as_ptr()) };
+    if raw_json.is_null() {
+        return Err(crate::read_last_error()
+            .unwrap_or_else(|| "Failed to probe the delete target.".to_string()));
+    }
+
+    // SAFETY: `raw_json` points to a C string allocated by C++.
+    let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+    // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+    unsafe { crate::omni_free_string(raw_json) };
+
+    serde_json::from_str(&json).map_err(|err| format!("Invalid delete probe payload: {err}"))
+}
+
+/// Turns a failed probe into the error reported for that path, naming the locking processes.
+#[cfg(target_os = "windows")]
+fn describe_blocked_delete(probe: &DeleteProbe) -> String {
+    if probe.locked && !probe.processes.is_empty() {
+        let holders = probe
+            .processes
+            .iter()
+            .map(|process| format!("{} ({})", process.name, process.pid))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return format!("Delete failed: {} is in use by {holders}.", probe.path);
+    }
+    let reason = probe
+        .error
+        .clone()
+        .unwrap_or_else(|| "File cannot be deleted.".to_string());
+    format!("Delete failed: {reason}")
+}
+
+#[cfg(target_os = "windows")]
+fn indexed_directory_size(path: &str) -> u64 {
+    let Ok(c_path) = CString::new(path) else {
+        return 0;
+    };
+    // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call.
+    let raw_json = unsafe { crate::omni_directory_size_json(c_path.as_ptr()) };
+    if raw_json.is_null() {
+        return 0;
+    }
+    // SAFETY: `raw_json` points to a C string allocated by C++.
+    let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+    // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+    unsafe { crate::omni_free_string(raw_json) };
+    serde_json::from_str::<crate::DirSize>(&json)
+        .map(|size| size.total_bytes)
+        .unwrap_or(0)
+}
+
+#[cfg(target_os = "windows")]
+fn recycle_path(path: &str) -> Result<(), String> {
+    let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+    // SAFETY: `c_path` lives long enough for this synchronous call.
+    let ok = unsafe { crate::omni_delete_path(c_path.as_ptr(), true) };
+    if !ok {
+        return Err(crate::read_last_error().unwrap_or_else(|| "Delete failed".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn restore_recycled_path(item: &DeletedItem) -> Result<(), String> {
+    let c_path =
+        CString::new(item.path.as_str()).map_err(|_| "Invalid path parameter".to_string())?;
+    // SAFETY: `c_path` lives long enough for this synchronous call.
+    let ok = unsafe { crate::omni_restore_recycled_path(c_path.as_ptr(), item.deleted_unix) };
+    if !ok {
+        return Err(crate::read_last_error().unwrap_or_else(|| "Restore failed".to_string()));
+    }
+    Ok(())
+}
+
+/// Reports, for each path, whether it can be deleted and which processes hold it open, so the
+/// UI can warn before a batch delete instead of failing part-way through.
+#[tauri::command]
+pub async fn probe_delete(paths: Vec<String>) -> Result<Vec<DeleteProbe>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || {
+            paths
+                .iter()
+                .map(|path| probe_delete_path(path))
+                .collect::<Result<Vec<_>, String>>()
+        })
+        .await
+        .map_err(|err| format!("Delete probe task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = paths;
+        Err("Delete is only supported on Windows.".to_string())
+    }
+}
+
+/// Lists the processes that have `path` open (PID and application name), so an "access
+/// denied" on delete or rename can name the culprit. Folders always report an empty list,
+/// since the Restart Manager only tracks files.
+#[tauri::command]
+pub async fn who_locks(path: String) -> Result<Vec<LockingProcess>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<LockingProcess>, String> {
+            // SAFETY: `c_path` lives long enough for this synchronous call.
+            let raw_json = unsafe { crate::omni_who_locks_json(c_path.as_ptr()) };
+            if raw_json.is_null() {
+                return Err(crate::read_last_error()
+                    .unwrap_or_else(|| "Failed to look up locking processes.".to_string()));
+            }
+
+            // SAFETY: `raw_json` points to a C string allocated by C++.
+            let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+            // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+            unsafe { crate::omni_free_string(raw_json) };
+
+            serde_json::from_str(&json)
+                .map_err(|err| format!("Invalid locking process payload: {err}"))
+        })
+        .await
+        .map_err(|err| format!("Lock lookup task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err("Lock lookup is only supported on Windows.".to_string())
+    }
+}
+
+/// Moves each path to the Recycle Bin and remembers the batch for `restore_last_deleted`.
+/// Each path is probed first; locked or missing paths are skipped with a descriptive error,
+/// and the call only errors when nothing could be moved.
+#[tauri::command]
+pub async fn delete_to_recycle_bin(paths: Vec<String>) -> Result<Vec<DeletedItem>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<DeletedItem>, String> {
+            let mut deleted = Vec::with_capacity(paths.len());
+            let mut first_error = None;
+            for path in paths {
+                match probe_delete_path(&path) {
+                    Ok(probe) if !probe.can_delete => {
+                        first_error.get_or_insert_with(|| describe_blocked_delete(&probe));
+                        continue;
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                        continue;
+                    }
+                }
+                let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                    first_error.get_or_insert_with(|| "Delete failed: path not found.".to_string());
+                    continue;
+                };
+                let is_directory = metadata.is_dir();
+                let size = if is_directory {
+                    indexed_directory_size(&path)
+                } else {
+                    metadata.len()
+                };
+                let deleted_unix = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_secs() as i64)
+                    .unwrap_or(0);
+                match recycle_path(&path) {
+                    Ok(()) => deleted.push(DeletedItem {
+                        path,
+                        size,
+                        is_directory,
+                        deleted_unix,
+                    }),
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                    }
+                }
+            }
+
+            if deleted.is_empty() {
+                if let Some(err) = first_error {
+                    return Err(err);
+                }
+                return Ok(deleted);
+            }
+            if let Ok(mut batch) = last_deleted_batch().lock() {
+                *batch = deleted.clone();
+            }
+            Ok(deleted)
+        })
+        .await
+        .map_err(|err| format!("Recycle task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = paths;
+        Err("Delete is only supported on Windows.".to_string())
+    }
+}
+
+/// Restores the most recent `delete_to_recycle_bin` batch from the Recycle Bin and returns
+/// the restored paths. Items that could not be restored stay queued so a retry can pick them up.
+#[tauri::command]
+pub async fn restore_last_deleted() -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<String>, String> {
+            let batch = last_deleted_batch()
+                .lock()
+                .map(|mut batch| std::mem::take(&mut *batch))
+                .map_err(|_| "Deleted items are unavailable.".to_string())?;
+            if batch.is_empty() {
+                return Err("Nothing to restore.".to_string());
+            }
+
+            let mut restored = Vec::with_capacity(batch.len());
+            let mut pending = Vec::new();
+            let mut first_error = None;
+            for item in batch {
+                match restore_recycled_path(&item) {
+                    Ok(()) => restored.push(item.path),
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                        pending.push(item);
+                    }
+                }
+            }
+
+            if !pending.is_empty() {
+                if let Ok(mut batch) = last_deleted_batch().lock() {
+                    *batch = pending;
+                }
+            }
+            if restored.is_empty() {
+                return Err(first_error.unwrap_or_else(|| "Restore failed".to_string()));
+            }
+            Ok(restored)
+        })
+        .await
+        .map_err(|err| format!("Restore task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Restore is only supported on Windows.".to_string())
+    }
+}
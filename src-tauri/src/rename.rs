@@ -0,0 +1,639 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+#[cfg(target_os = "windows")]
+const BULK_RENAME_MIN_INDEX_WIDTH: usize = 3;
+#[cfg(target_os = "windows")]
+const INVALID_NAME_CHARS: &[char] = &['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
+/// Win32 error returned when a rename would cross volumes.
+#[cfg(target_os = "windows")]
+const ERROR_NOT_SAME_DEVICE: i32 = 17;
+/// Subfolder for files without an extension under the "by_extension" scheme.
+#[cfg(target_os = "windows")]
+const NO_EXTENSION_FOLDER: &str = "no extension";
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy)]
+enum OrganizeScheme {
+    ByExtension,
+    ByDate,
+    ByCategory,
+}
+
+#[cfg(target_os = "windows")]
+impl OrganizeScheme {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "by_extension" => Ok(Self::ByExtension),
+            "by_date" => Ok(Self::ByDate),
+            "by_category" => Ok(Self::ByCategory),
+            other => Err(format!("Unsupported organize scheme: {other}.")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameResult {
+    pub path: String,
+    /// Final path; `None` when the item was left untouched.
+    pub new_path: Option<String>,
+    pub error: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+struct PlannedRename {
+    index: usize,
+    source: PathBuf,
+    target: PathBuf,
+    staged: Option<PathBuf>,
+}
+
+/// Formats unix seconds as a UTC `YYYY-MM-DD` date.
+#[cfg(target_os = "windows")]
+fn format_unix_date(unix: i64) -> String {
+    // Civil-from-days conversion over the proleptic Gregorian calendar.
+    let days = unix.div_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Expands `{n}` (zero-padded counter), `{name}` (original stem), `{ext}` (original extension)
+/// and `{date}` (modified date) in `pattern`. The original extension is kept when the pattern
+/// does not mention `{ext}` and produces no extension of its own.
+#[cfg(target_os = "windows")]
+fn render_rename_pattern(pattern: &str, source: &Path, number: u64, width: usize) -> String {
+    let stem = source
+        .file_stem()
+        .map(|value| value.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = source
+        .extension()
+        .map(|value| value.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let date = fs::metadata(source)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| format_unix_date(elapsed.as_secs() as i64))
+        .unwrap_or_default();
+
+    let mut name = pattern
+        .replace("{n}", &format!("{number:0width$}"))
+        .replace("{name}", &stem)
+        .replace("{ext}", &extension)
+        .replace("{date}", &date);
+    if !pattern.contains("{ext}") && !extension.is_empty() && Path::new(&name).extension().is_none()
+    {
+        name.push('.');
+        name.push_str(&extension);
+    }
+    name.trim().to_string()
+}
+
+#[cfg(target_os = "windows")]
+fn validate_file_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "." || name == ".." {
+        return Err("Pattern produced an empty name.".to_string());
+    }
+    if name.contains(INVALID_NAME_CHARS) {
+        return Err("Pattern produced a name with invalid characters.".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// Drops, until none is left, every planned rename whose target is the source of a batch item
+/// that is not moving: one that was rejected, failed to stage or keeps its name. Staged files
+/// of the dropped items are put back.
+#[cfg(target_os = "windows")]
+fn drop_blocked_renames(
+    planned: &mut Vec<PlannedRename>,
+    source_keys: &HashSet<String>,
+    results: &mut [RenameResult],
+) {
+    loop {
+        let moving: HashSet<String> = planned
+            .iter()
+            .filter(|item| item.source != item.target)
+            .map(|item| path_key(&item.source))
+            .collect();
+        let before = planned.len();
+        planned.retain(|item| {
+            let target_key = path_key(&item.target);
+            let blocked = item.source != item.target
+                && source_keys.contains(&target_key)
+                && !moving.contains(&target_key);
+            if blocked {
+                if let Some(staged) = &item.staged {
+                    let _ = fs::rename(staged, &item.source);
+                }
+                results[item.index].error =
+                    Some("An item with that name already exists.".to_string());
+            }
+            !blocked
+        });
+        if planned.len() == before {
+            break;
+        }
+    }
+}
+
+/// Renames `source` to `target`, failing with `AlreadyExists` instead of replacing a file that
+/// already exists there (`fs::rename` replaces it on Windows).
+#[cfg(target_os = "windows")]
+fn rename_no_replace(source: &Path, target: &Path) -> std::io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::{
+        core::PCWSTR,
+        Win32::Storage::FileSystem::{MoveFileExW, MOVE_FILE_FLAGS},
+    };
+
+    let wide =
+        |path: &Path| -> Vec<u16> { path.as_os_str().encode_wide().chain(Some(0)).collect() };
+    let source = wide(source);
+    let target = wide(target);
+    // SAFETY: both paths are NUL-terminated and outlive the call; without
+    // MOVEFILE_REPLACE_EXISTING an existing target makes the call fail.
+    unsafe {
+        MoveFileExW(
+            PCWSTR(source.as_ptr()),
+            PCWSTR(target.as_ptr()),
+            MOVE_FILE_FLAGS::default(),
+        )
+    }
+    .map_err(std::io::Error::from)
+}
+
+#[cfg(target_os = "windows")]
+fn bulk_rename_blocking(paths: Vec<String>, pattern: &str, start_index: u32) -> Vec<RenameResult> {
+    let last_number = u64::from(start_index) + paths.len().saturating_sub(1) as u64;
+    let width = last_number
+        .to_string()
+        .len()
+        .max(BULK_RENAME_MIN_INDEX_WIDTH);
+    let mut results: Vec<RenameResult> = paths
+        .iter()
+        .map(|path| RenameResult {
+            path: path.clone(),
+            new_path: None,
+            error: None,
+        })
+        .collect();
+
+    let source_keys: HashSet<String> = paths.iter().map(|path| path_key(Path::new(path))).collect();
+    let mut target_keys = HashSet::new();
+    let mut planned = Vec::with_capacity(paths.len());
+    for (index, path) in paths.iter().enumerate() {
+        let source = PathBuf::from(path);
+        let planned_target = if !source.exists() {
+            Err("File does not exist on disk.".to_string())
+        } else if let Some(parent) = source.parent() {
+            let name = render_rename_pattern(
+                pattern,
+                &source,
+                u64::from(start_index) + index as u64,
+                width,
+            );
+            validate_file_name(&name).map(|()| parent.join(name))
+        } else {
+            Err("Failed to resolve the parent directory.".to_string())
+        };
+
+        let target = match planned_target {
+            Ok(target) => target,
+            Err(err) => {
+                results[index].error = Some(err);
+                continue;
+            }
+        };
+        let target_key = path_key(&target);
+        if !target_keys.insert(target_key.clone()) {
+            results[index].error = Some("Pattern produced a duplicate name.".to_string());
+            continue;
+        }
+        // Targets held by another item in the batch are freed by the staging pass below.
+        if target.exists() && !source_keys.contains(&target_key) {
+            results[index].error = Some("An item with that name already exists.".to_string());
+            continue;
+        }
+        planned.push(PlannedRename {
+            index,
+            source,
+            target,
+            staged: None,
+        });
+    }
+    drop_blocked_renames(&mut planned, &source_keys, &mut results);
+
+    // Move every item to a unique staging name first, so swaps and shifted sequences
+    // (file_002 -> file_001 while file_001 -> file_000) cannot collide.
+    let process_id = std::process::id();
+    for item in &mut planned {
+        if item.source == item.target {
+            continue;
+        }
+        let Some(parent) = item.source.parent() else {
+            continue;
+        };
+        let staged = parent.join(format!(".omni-rename-{process_id}-{}.tmp", item.index));
+        match fs::rename(&item.source, &staged) {
+            Ok(()) => item.staged = Some(staged),
+            Err(err) => results[item.index].error = Some(format!("Failed to rename item: {err}")),
+        }
+    }
+    // Items that failed to stage stay where they are, and so do the items aiming at them.
+    planned.retain(|item| item.source == item.target || item.staged.is_some());
+    drop_blocked_renames(&mut planned, &source_keys, &mut results);
+
+    for item in planned {
+        let result = &mut results[item.index];
+        if item.source == item.target {
+            result.new_path = Some(item.target.to_string_lossy().into_owned());
+            continue;
+        }
+        let Some(staged) = item.staged else {
+            continue;
+        };
+        match rename_no_replace(&staged, &item.target) {
+            Ok(()) => result.new_path = Some(item.target.to_string_lossy().into_owned()),
+            Err(err) => {
+                // Another item may already have taken the original name; the file then keeps
+                // its staging name rather than overwriting that item.
+                if rename_no_replace(&staged, &item.source).is_err() {
+                    result.new_path = Some(staged.to_string_lossy().into_owned());
+                }
+                result.error = Some(format!("Failed to rename item: {err}"));
+            }
+        }
+    }
+
+    results
+}
+
+/// Renames `paths` from a pattern such as `Vacation_{n}` and reports each item in input
+/// order. Numbering begins at `start_index`; `{n}` is zero-padded to at least three digits.
+#[tauri::command]
+pub async fn bulk_rename(
+    paths: Vec<String>,
+    pattern: String,
+    start_index: u32,
+) -> Result<Vec<RenameResult>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if pattern.trim().is_empty() {
+            return Err("Rename pattern cannot be empty.".to_string());
+        }
+        tauri::async_runtime::spawn_blocking(move || {
+            bulk_rename_blocking(paths, &pattern, start_index)
+        })
+        .await
+        .map_err(|err| format!("Bulk rename task failed: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (paths, pattern, start_index);
+        Err("Rename is only supported on Windows.".to_string())
+    }
+}
+
+/// Subfolder a file belongs in: its lowercase extension, its modified month (`YYYY-MM`) or
+/// its category.
+#[cfg(target_os = "windows")]
+fn organize_folder_name(source: &Path, scheme: OrganizeScheme) -> Result<String, String> {
+    let extension = source
+        .extension()
+        .map(|value| value.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match scheme {
+        OrganizeScheme::ByExtension if extension.is_empty() => Ok(NO_EXTENSION_FOLDER.to_string()),
+        OrganizeScheme::ByExtension => Ok(extension),
+        OrganizeScheme::ByCategory => {
+            Ok(crate::category::category_for(&extension, false).to_string())
+        }
+        OrganizeScheme::ByDate => {
+            let modified = fs::metadata(source)
+                .and_then(|metadata| metadata.modified())
+                .map_err(|err| format!("Failed to read the modified date: {err}"))?;
+            let unix = modified
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs() as i64)
+                .unwrap_or(0);
+            Ok(format_unix_date(unix)[..7].to_string())
+        }
+    }
+}
+
+/// Moves `source` to `target` without replacing anything there, failing with `AlreadyExists`
+/// when `target` is taken. Across volumes the file is copied into a newly created `target`
+/// and the original removed.
+#[cfg(target_os = "windows")]
+fn move_file(source: &Path, target: &Path) -> std::io::Result<()> {
+    match rename_no_replace(source, target) {
+        Err(err) if err.raw_os_error() == Some(ERROR_NOT_SAME_DEVICE) => {
+            let metadata = fs::metadata(source)?;
+            let mut reader = fs::File::open(source)?;
+            let mut writer = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(target)?;
+            let copied = std::io::copy(&mut reader, &mut writer)
+                .and_then(|_| writer.set_modified(metadata.modified()?))
+                .and_then(|()| fs::set_permissions(target, metadata.permissions()));
+            drop((reader, writer));
+            if let Err(err) = copied.and_then(|()| fs::remove_file(source)) {
+                let _ = fs::remove_file(target);
+                return Err(err);
+            }
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Moves `source` into `folder` as `file_name`, or as `name (1)`, `name (2)`... when that is
+/// taken, and returns where it ended up. Each name is claimed by the move itself, so a file
+/// appearing there in the meantime is never replaced.
+#[cfg(target_os = "windows")]
+pub(crate) fn move_to_unique_target(
+    source: &Path,
+    folder: &Path,
+    file_name: &str,
+) -> Result<PathBuf, String> {
+    let original = Path::new(file_name);
+    let stem = original
+        .file_stem()
+        .map(|value| value.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file_name.to_string());
+    let extension = original
+        .extension()
+        .map(|value| format!(".{}", value.to_string_lossy()))
+        .unwrap_or_default();
+    for counter in 0_u32.. {
+        let target = if counter == 0 {
+            folder.join(file_name)
+        } else {
+            folder.join(format!("{stem} ({counter}){extension}"))
+        };
+        match move_file(source, &target) {
+            Ok(()) => return Ok(target),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(format!("Failed to move file: {err}")),
+        }
+    }
+    Err("No free file name is left in the folder.".to_string())
+}
+
+/// Moves one file into its scheme folder and returns where it ended up, which is `source`
+/// itself when it already sits in that folder.
+#[cfg(target_os = "windows")]
+fn organize_file(
+    source: &Path,
+    scheme: OrganizeScheme,
+    destination_root: &Path,
+) -> Result<PathBuf, String> {
+    if !source.is_file() {
+        return Err("Only existing files can be organized.".to_string());
+    }
+    let file_name = source
+        .file_name()
+        .map(|value| value.to_string_lossy().into_owned())
+        .ok_or_else(|| "Failed to resolve the file name.".to_string())?;
+    let folder = destination_root.join(organize_folder_name(source, scheme)?);
+    if source
+        .parent()
+        .is_some_and(|parent| path_key(parent) == path_key(&folder))
+    {
+        return Ok(source.to_path_buf());
+    }
+    fs::create_dir_all(&folder).map_err(|err| format!("Failed to create the folder: {err}"))?;
+    move_to_unique_target(source, &folder, &file_name)
+}
+
+/// Moves each file into a subfolder of `destination_root` named by `scheme`: "by_extension"
+/// (`pdf`, `jpg`...), "by_date" (modified month, `2024-05`) or "by_category" (`image`,
+/// `document`...). Subfolders are created as needed, a name already taken gets a ` (1)`
+/// suffix, and each file is reported in input order.
+#[tauri::command]
+pub async fn organize_files(
+    paths: Vec<String>,
+    scheme: String,
+    destination_root: String,
+) -> Result<Vec<RenameResult>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let scheme = OrganizeScheme::parse(&scheme)?;
+        let destination_root = PathBuf::from(destination_root);
+        if !destination_root.is_dir() {
+            return Err("Destination folder does not exist on disk.".to_string());
+        }
+        tauri::async_runtime::spawn_blocking(move || {
+            paths
+                .into_iter()
+                .map(
+                    |path| match organize_file(Path::new(&path), scheme, &destination_root) {
+                        Ok(target) => RenameResult {
+                            path,
+                            new_path: Some(target.to_string_lossy().into_owned()),
+                            error: None,
+                        },
+                        Err(err) => RenameResult {
+                            path,
+                            new_path: None,
+                            error: Some(err),
+                        },
+                    },
+                )
+                .collect()
+        })
+        .await
+        .map_err(|err| format!("Organize task failed: {err}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (paths, scheme, destination_root);
+        Err("Organizing files is only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("omni-search-rename-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn path_string(path: &Path) -> String {
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn format_unix_date_handles_leap_days_and_negative_times() {
+        assert_eq!(format_unix_date(0), "1970-01-01");
+        assert_eq!(format_unix_date(951_827_696), "2000-02-29");
+        assert_eq!(format_unix_date(-1), "1969-12-31");
+    }
+
+    #[test]
+    fn render_rename_pattern_expands_placeholders() {
+        let source = Path::new(r"C:\missing\photo.jpg");
+        assert_eq!(
+            render_rename_pattern("Trip_{n}", source, 7, 3),
+            "Trip_007.jpg"
+        );
+        assert_eq!(
+            render_rename_pattern("{name}-{n}.{ext}", source, 12, 3),
+            "photo-012.jpg"
+        );
+        // A pattern with its own extension replaces the original one.
+        assert_eq!(
+            render_rename_pattern("{name}.png", source, 1, 3),
+            "photo.png"
+        );
+        assert_eq!(
+            render_rename_pattern("{n}", Path::new(r"C:\missing\README"), 1234, 3),
+            "1234"
+        );
+    }
+
+    #[test]
+    fn render_rename_pattern_uses_the_modified_date() {
+        let dir = test_dir("date");
+        let source = dir.join("scan.pdf");
+        fs::write(&source, b"scan").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(951_827_696))
+            .unwrap();
+        assert_eq!(
+            render_rename_pattern("{date}_{n}", &source, 1, 3),
+            "2000-02-29_001.pdf"
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn bulk_rename_swaps_and_shifts_names_within_the_batch() {
+        let dir = test_dir("swap");
+        let first = dir.join("file_001.txt");
+        let second = dir.join("file_002.txt");
+        fs::write(&first, b"first").unwrap();
+        fs::write(&second, b"second").unwrap();
+
+        let results = bulk_rename_blocking(
+            vec![path_string(&second), path_string(&first)],
+            "file_{n}",
+            1,
+        );
+        assert!(results.iter().all(|result| result.error.is_none()));
+        assert_eq!(
+            results[0].new_path.as_deref(),
+            Some(path_string(&first).as_str())
+        );
+        assert_eq!(
+            results[1].new_path.as_deref(),
+            Some(path_string(&second).as_str())
+        );
+        assert_eq!(fs::read(first).unwrap(), b"second");
+        assert_eq!(fs::read(second).unwrap(), b"first");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn bulk_rename_never_replaces_an_existing_item() {
+        let dir = test_dir("existing");
+        let keep = dir.join("keep.txt");
+        let old = dir.join("old.txt");
+        fs::write(&keep, b"keep").unwrap();
+        fs::write(&old, b"old").unwrap();
+
+        let results = bulk_rename_blocking(vec![path_string(&old)], "keep", 1);
+        assert_eq!(
+            results[0].error.as_deref(),
+            Some("An item with that name already exists.")
+        );
+        assert_eq!(results[0].new_path, None);
+        assert_eq!(fs::read(keep).unwrap(), b"keep");
+        assert_eq!(fs::read(old).unwrap(), b"old");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn bulk_rename_keeps_items_aimed_at_a_batch_item_that_stays() {
+        let dir = test_dir("blocked");
+        let x = dir.join("x.txt");
+        let y = dir.join("y.txt");
+        fs::write(&x, b"x").unwrap();
+        fs::write(&y, b"y").unwrap();
+
+        // Both items would be named `y.txt`: the second is rejected as a duplicate, so it
+        // stays in place and the first may not take its name.
+        let results = bulk_rename_blocking(vec![path_string(&x), path_string(&y)], "y", 1);
+        assert_eq!(
+            results[0].error.as_deref(),
+            Some("An item with that name already exists.")
+        );
+        assert_eq!(
+            results[1].error.as_deref(),
+            Some("Pattern produced a duplicate name.")
+        );
+        assert_eq!(fs::read(x).unwrap(), b"x");
+        assert_eq!(fs::read(y).unwrap(), b"y");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn move_to_unique_target_numbers_taken_names() {
+        let dir = test_dir("unique");
+        let folder = dir.join("sorted");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("a.txt"), b"taken").unwrap();
+        fs::write(folder.join("a (1).txt"), b"taken too").unwrap();
+        let source = dir.join("a.txt");
+        fs::write(&source, b"moved").unwrap();
+
+        let target = move_to_unique_target(&source, &folder, "a.txt").unwrap();
+        assert_eq!(target, folder.join("a (2).txt"));
+        assert_eq!(fs::read(target).unwrap(), b"moved");
+        assert_eq!(fs::read(folder.join("a.txt")).unwrap(), b"taken");
+        assert_eq!(fs::read(folder.join("a (1).txt")).unwrap(), b"taken too");
+        assert!(!source.exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
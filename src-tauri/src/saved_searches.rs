@@ -0,0 +1,211 @@
+use crate::store::{load_store, save_store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime};
+
+#[cfg(target_os = "windows")]
+use std::{
+    ffi::CString,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const SAVED_SEARCHES_FILE_NAME: &str = "saved-searches.json";
+
+/// Serializes read-modify-write cycles on the saved-searches file.
+static SAVED_SEARCHES_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    #[serde(default)]
+    pub extension: Option<String>,
+    #[serde(default)]
+    pub within_path: Option<String>,
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    #[serde(default)]
+    pub min_created_unix: Option<i64>,
+    #[serde(default)]
+    pub max_created_unix: Option<i64>,
+    #[serde(default)]
+    pub search_in: Option<String>,
+    /// When the search was last run through `run_saved_search`.
+    #[serde(default)]
+    pub last_run_unix: Option<i64>,
+}
+
+fn lock_saved_searches() -> Result<std::sync::MutexGuard<'static, ()>, String> {
+    SAVED_SEARCHES_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock saved searches".to_string())
+}
+
+pub(crate) fn load_saved_searches<R: Runtime>(app: &AppHandle<R>) -> Vec<SavedSearch> {
+    let Ok(_guard) = lock_saved_searches() else {
+        return Vec::new();
+    };
+    load_store(app, SAVED_SEARCHES_FILE_NAME)
+}
+
+/// Replaces every saved search, as when importing settings. Unnamed entries are dropped and
+/// the first of several with the same name wins.
+pub(crate) fn replace_saved_searches<R: Runtime>(
+    app: &AppHandle<R>,
+    searches: Vec<SavedSearch>,
+) -> Result<(), String> {
+    let mut kept: Vec<SavedSearch> = Vec::with_capacity(searches.len());
+    for mut search in searches {
+        search.name = search.name.trim().to_string();
+        if !search.name.is_empty()
+            && !kept
+                .iter()
+                .any(|existing| existing.name.eq_ignore_ascii_case(&search.name))
+        {
+            kept.push(search);
+        }
+    }
+    let _guard = lock_saved_searches()?;
+    save_store(app, SAVED_SEARCHES_FILE_NAME, &kept)
+}
+
+#[tauri::command]
+pub fn list_saved_searches(app: AppHandle<tauri::Wry>) -> Result<Vec<SavedSearch>, String> {
+    let _guard = lock_saved_searches()?;
+    Ok(load_store(&app, SAVED_SEARCHES_FILE_NAME))
+}
+
+/// Adds a saved search, or replaces the one with the same name while keeping its last run time.
+#[tauri::command]
+pub fn save_search(
+    app: AppHandle<tauri::Wry>,
+    mut search: SavedSearch,
+) -> Result<Vec<SavedSearch>, String> {
+    search.name = search.name.trim().to_string();
+    if search.name.is_empty() {
+        return Err("Saved search name cannot be empty.".to_string());
+    }
+
+    let _guard = lock_saved_searches()?;
+    let mut searches: Vec<SavedSearch> = load_store(&app, SAVED_SEARCHES_FILE_NAME);
+    match searches
+        .iter_mut()
+        .find(|existing| existing.name.eq_ignore_ascii_case(&search.name))
+    {
+        Some(existing) => {
+            search.last_run_unix = existing.last_run_unix;
+            *existing = search;
+        }
+        None => searches.push(search),
+    }
+    save_store(&app, SAVED_SEARCHES_FILE_NAME, &searches)?;
+    Ok(searches)
+}
+
+#[tauri::command]
+pub fn delete_saved_search(
+    app: AppHandle<tauri::Wry>,
+    name: String,
+) -> Result<Vec<SavedSearch>, String> {
+    let _guard = lock_saved_searches()?;
+    let mut searches: Vec<SavedSearch> = load_store(&app, SAVED_SEARCHES_FILE_NAME);
+    let before = searches.len();
+    searches.retain(|search| !search.name.eq_ignore_ascii_case(name.trim()));
+    if searches.len() == before {
+        return Err(format!("No saved search named {name}."));
+    }
+    save_store(&app, SAVED_SEARCHES_FILE_NAME, &searches)?;
+    Ok(searches)
+}
+
+/// Runs a saved search and records the run time once it completes without hitting `limit`.
+/// With `modified_since_last`, only files modified since the previous recorded run started are
+/// returned, so a saved search works as a monitor.
+#[tauri::command]
+pub async fn run_saved_search(
+    app: AppHandle<tauri::Wry>,
+    name: String,
+    limit: Option<u32>,
+    modified_since_last: Option<bool>,
+) -> Result<crate::SearchResponse, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let search = {
+            let _guard = lock_saved_searches()?;
+            let searches: Vec<SavedSearch> = load_store(&app, SAVED_SEARCHES_FILE_NAME);
+            searches
+                .into_iter()
+                .find(|search| search.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| format!("No saved search named {name}."))?
+        };
+        // Taken before the search, so changes made while it runs are reported next time.
+        let run_started_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or(0);
+
+        let min_modified_unix = if modified_since_last.unwrap_or(false) {
+            // From the second the previous run started, so a file changed within that same
+            // second is not missed; a first run has nothing to compare against.
+            search.last_run_unix.unwrap_or(i64::MIN)
+        } else {
+            i64::MIN
+        };
+        let search_in = crate::parse_search_in(search.search_in.as_deref())?;
+
+        let response = tauri::async_runtime::spawn_blocking(move || {
+            let c_query = CString::new(search.query).map_err(|_| "Invalid query".to_string())?;
+            let c_extension = CString::new(search.extension.unwrap_or_default())
+                .map_err(|_| "Invalid extension".to_string())?;
+            let c_within_path = CString::new(search.within_path.unwrap_or_default())
+                .map_err(|_| "Invalid path parameter".to_string())?;
+            let options = crate::SearchOptions {
+                min_size: search.min_size.unwrap_or(0),
+                max_size: search.max_size.unwrap_or(u64::MAX),
+                min_created_unix: search.min_created_unix.unwrap_or(i64::MIN),
+                max_created_unix: search.max_created_unix.unwrap_or(i64::MAX),
+                limit: limit
+                    .unwrap_or(200)
+                    .clamp(1, crate::DEFAULT_RESULT_LIMIT_CEILING),
+                max_limit: crate::DEFAULT_RESULT_LIMIT_CEILING,
+                search_in,
+                min_modified_unix,
+                ..crate::SearchOptions::background(&c_query, &c_extension, &c_within_path)
+            };
+
+            let results = crate::run_search(&options)?;
+            Ok::<_, String>(crate::SearchResponse {
+                truncated: results.len() >= options.limit as usize,
+                results,
+                ..crate::SearchResponse::empty()
+            })
+        })
+        .await
+        .map_err(|err| format!("Saved search task failed: {err}"))??;
+
+        // Only a complete run moves the "modified since last run" window forward; a failed or
+        // truncated one would otherwise hide the changes it did not report.
+        if !response.truncated {
+            let _guard = lock_saved_searches()?;
+            let mut searches: Vec<SavedSearch> = load_store(&app, SAVED_SEARCHES_FILE_NAME);
+            if let Some(search) = searches
+                .iter_mut()
+                .find(|search| search.name.eq_ignore_ascii_case(name.trim()))
+            {
+                search.last_run_unix = Some(run_started_unix);
+                save_store(&app, SAVED_SEARCHES_FILE_NAME, &searches)?;
+            }
+        }
+        Ok(response)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, name, limit, modified_since_last);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
@@ -0,0 +1,256 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Version written into new tokens; `decode_search` reads this and every older version.
+const SEARCH_TOKEN_VERSION: u32 = 1;
+const SEARCH_TOKEN_PREFIX: &str = "os";
+/// Longer tokens are rejected before decoding; real searches stay far below this.
+const SEARCH_TOKEN_MAX_CHARS: usize = 16 * 1024;
+
+/// The filter set of a `search_files` call, as carried by a shareable token. Every field is
+/// optional and omitted fields keep the `search_files` default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchParams {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub within_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bucket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_created_unix: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_created_unix: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes_mask: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes_value: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_in: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fuzzy_max_distance: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fuzzy_min_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_short_names: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_extension: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_position: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_cloud_placeholders: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapse_hardlinks: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub near_time_unix: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub near_time_window_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin_filter: Option<String>,
+}
+
+/// Keeps `value` only when it is one of `allowed`, lowercased, so a token naming an option
+/// this build does not know still opens instead of failing.
+fn known_choice(value: Option<String>, allowed: &[&str]) -> Option<String> {
+    let value = value?.trim().to_ascii_lowercase();
+    allowed.contains(&value.as_str()).then_some(value)
+}
+
+pub(crate) fn sanitize_params(mut params: SearchParams) -> Result<SearchParams, String> {
+    if let (Some(min), Some(max)) = (params.min_size, params.max_size) {
+        if min > max {
+            return Err("Invalid search link: minimum size is above the maximum.".to_string());
+        }
+    }
+    if let (Some(min), Some(max)) = (params.min_created_unix, params.max_created_unix) {
+        if min > max {
+            return Err("Invalid search link: created range is reversed.".to_string());
+        }
+    }
+    if params
+        .fuzzy_min_score
+        .is_some_and(|score| !(0.0..=1.0).contains(&score))
+    {
+        params.fuzzy_min_score = None;
+    }
+    params.search_in = known_choice(params.search_in, &["path", "both", "name"]);
+    params.mode = known_choice(params.mode, &["tokens", "phrase"]);
+    params.sort = known_choice(params.sort, &["index", "relevance_recency"]);
+    params.match_position = known_choice(params.match_position, &["anywhere", "prefix", "suffix"]);
+    params.max_depth = params.max_depth.filter(|depth| *depth > 0);
+    params.origin_filter = known_choice(params.origin_filter, &["user", "app", "system", "other"]);
+    params.size_bucket = known_choice(
+        params.size_bucket,
+        &["empty", "tiny", "small", "medium", "large", "huge"],
+    );
+    Ok(params)
+}
+
+/// Packs `params` into a compact, URL-safe token (`os1.` followed by unpadded base64url JSON)
+/// for bookmarking or sharing an exact search.
+#[tauri::command]
+pub fn encode_search(params: SearchParams) -> Result<String, String> {
+    let params = sanitize_params(params)?;
+    let json =
+        serde_json::to_vec(&params).map_err(|err| format!("Failed to serialize search: {err}"))?;
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json);
+    Ok(format!(
+        "{SEARCH_TOKEN_PREFIX}{SEARCH_TOKEN_VERSION}.{encoded}"
+    ))
+}
+
+/// Unpacks a token from `encode_search`. Tokens from newer versions are rejected; fields and
+/// option values this version does not know are dropped, so older links keep opening with the
+/// filters that still apply.
+#[tauri::command]
+pub fn decode_search(token: String) -> Result<SearchParams, String> {
+    let token = token.trim();
+    if token.len() > SEARCH_TOKEN_MAX_CHARS {
+        return Err("Invalid search link: token is too long.".to_string());
+    }
+    let (header, payload) = token
+        .split_once('.')
+        .ok_or_else(|| "Invalid search link: missing version.".to_string())?;
+    let version = header
+        .strip_prefix(SEARCH_TOKEN_PREFIX)
+        .and_then(|version| version.parse::<u32>().ok())
+        .ok_or_else(|| "Invalid search link: missing version.".to_string())?;
+    if version == 0 || version > SEARCH_TOKEN_VERSION {
+        return Err(format!(
+            "This search link needs a newer version of Omni Search (link version {version})."
+        ));
+    }
+    let json = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|err| format!("Invalid search link: {err}"))?;
+    let params: SearchParams =
+        serde_json::from_slice(&json).map_err(|err| format!("Invalid search link: {err}"))?;
+    sanitize_params(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_for(json: &str) -> String {
+        let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json);
+        format!("{SEARCH_TOKEN_PREFIX}{SEARCH_TOKEN_VERSION}.{encoded}")
+    }
+
+    #[test]
+    fn encoded_search_decodes_to_the_same_filters() {
+        let params = SearchParams {
+            query: "report 2023".to_string(),
+            extension: Some("pdf,docx".to_string()),
+            within_path: Some(r"C:\Users\*\Documents".to_string()),
+            min_size: Some(1024),
+            max_size: Some(1024 * 1024),
+            tags: Some(vec!["work".to_string()]),
+            sort: Some("relevance_recency".to_string()),
+            max_depth: Some(2),
+            ..SearchParams::default()
+        };
+        let token = encode_search(params).unwrap();
+        assert!(token.starts_with("os1."));
+        assert!(!token.contains(['+', '/', '=']));
+
+        let decoded = decode_search(format!("  {token}\n")).unwrap();
+        assert_eq!(decoded.query, "report 2023");
+        assert_eq!(decoded.extension.as_deref(), Some("pdf,docx"));
+        assert_eq!(
+            decoded.within_path.as_deref(),
+            Some(r"C:\Users\*\Documents")
+        );
+        assert_eq!(decoded.min_size, Some(1024));
+        assert_eq!(decoded.max_size, Some(1024 * 1024));
+        assert_eq!(decoded.tags, Some(vec!["work".to_string()]));
+        assert_eq!(decoded.sort.as_deref(), Some("relevance_recency"));
+        assert_eq!(decoded.max_depth, Some(2));
+        assert_eq!(decoded.owner, None);
+    }
+
+    #[test]
+    fn empty_search_encodes_to_an_empty_object() {
+        let token = encode_search(SearchParams::default()).unwrap();
+        assert_eq!(token, token_for("{}"));
+    }
+
+    #[test]
+    fn decode_rejects_malformed_and_newer_tokens() {
+        assert!(decode_search("not-a-token".to_string()).is_err());
+        assert!(decode_search("xx1.e30".to_string()).is_err());
+        assert!(decode_search("os0.e30".to_string()).is_err());
+        assert!(decode_search("os1.!!!".to_string()).is_err());
+        assert!(decode_search(format!("os1.{}", "A".repeat(SEARCH_TOKEN_MAX_CHARS))).is_err());
+        let newer = decode_search(format!("os{}.e30", SEARCH_TOKEN_VERSION + 1)).unwrap_err();
+        assert!(newer.contains("newer version"));
+    }
+
+    #[test]
+    fn decode_drops_unknown_fields_and_choices() {
+        let params = decode_search(token_for(
+            r#"{"query":"x","futureFilter":true,"sort":"Relevance_Recency","mode":"regex"}"#,
+        ))
+        .unwrap();
+        assert_eq!(params.query, "x");
+        assert_eq!(params.sort.as_deref(), Some("relevance_recency"));
+        assert_eq!(params.mode, None);
+    }
+
+    #[test]
+    fn sanitize_rejects_reversed_ranges() {
+        let sizes = SearchParams {
+            min_size: Some(10),
+            max_size: Some(5),
+            ..SearchParams::default()
+        };
+        assert!(sanitize_params(sizes).is_err());
+        let created = SearchParams {
+            min_created_unix: Some(100),
+            max_created_unix: Some(99),
+            ..SearchParams::default()
+        };
+        assert!(sanitize_params(created).is_err());
+    }
+
+    #[test]
+    fn sanitize_clears_values_out_of_range() {
+        let params = sanitize_params(SearchParams {
+            fuzzy_min_score: Some(1.5),
+            max_depth: Some(0),
+            search_in: Some(" NAME ".to_string()),
+            match_position: Some("middle".to_string()),
+            size_bucket: Some("Huge".to_string()),
+            origin_filter: Some("user".to_string()),
+            ..SearchParams::default()
+        })
+        .unwrap();
+        assert_eq!(params.fuzzy_min_score, None);
+        assert_eq!(params.max_depth, None);
+        assert_eq!(params.search_in.as_deref(), Some("name"));
+        assert_eq!(params.match_position, None);
+        assert_eq!(params.size_bucket.as_deref(), Some("huge"));
+        assert_eq!(params.origin_filter.as_deref(), Some("user"));
+    }
+}
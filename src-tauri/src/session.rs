@@ -0,0 +1,81 @@
+use crate::{
+    search_token::{sanitize_params, SearchParams},
+    store::{load_store, save_store},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tauri::{AppHandle, Emitter, Runtime};
+
+const SESSION_FILE_NAME: &str = "session.json";
+/// Sent once at startup with the saved `SessionState`, when there is one.
+const SESSION_AVAILABLE_EVENT: &str = "omni://session-available";
+/// Gives the window time to register its listeners before the startup event goes out.
+const SESSION_OFFER_DELAY: Duration = Duration::from_millis(900);
+
+/// Serializes writes to the session file.
+static SESSION_LOCK: Mutex<()> = Mutex::new(());
+
+/// What the window was showing when it last saved, restored on the next launch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionState {
+    /// The query, filters and sort, as `search_files` takes them.
+    pub search: SearchParams,
+    /// Index of the first visible result row, so the list reopens at the same place whatever
+    /// the window height.
+    pub scroll_offset: u32,
+    pub selected_path: Option<String>,
+    /// Results layout chosen by the UI, such as "list" or "grid".
+    pub view: Option<String>,
+    /// Set by `save_session`.
+    pub saved_unix: Option<i64>,
+}
+
+fn lock_session() -> Result<std::sync::MutexGuard<'static, ()>, String> {
+    SESSION_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock the session".to_string())
+}
+
+fn load_saved_session<R: Runtime>(app: &AppHandle<R>) -> Option<SessionState> {
+    let _guard = lock_session().ok()?;
+    let mut session = load_store::<R, Option<SessionState>>(app, SESSION_FILE_NAME)?;
+    // Filters saved by an older build may no longer be valid; the session is dropped then.
+    session.search = sanitize_params(session.search).ok()?;
+    Some(session)
+}
+
+/// Remembers the current search and results view so the next launch can pick up where this
+/// one left off. Each save replaces the previous session.
+#[tauri::command]
+pub fn save_session(app: AppHandle<tauri::Wry>, mut state: SessionState) -> Result<(), String> {
+    state.search = sanitize_params(state.search)?;
+    state.saved_unix = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|elapsed| elapsed.as_secs() as i64);
+    let _guard = lock_session()?;
+    save_store(&app, SESSION_FILE_NAME, &Some(state))
+}
+
+/// The session last saved by `save_session`, or `None` when there is none or it can no longer
+/// be restored.
+#[tauri::command]
+pub fn load_session(app: AppHandle<tauri::Wry>) -> Option<SessionState> {
+    load_saved_session(&app)
+}
+
+/// Emits `omni://session-available` shortly after startup when a saved session exists, so the
+/// UI can offer to restore it. Nothing is sent on a first launch.
+pub(crate) fn offer_saved_session<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(SESSION_OFFER_DELAY);
+        if let Some(session) = load_saved_session(&app) {
+            let _ = app.emit(SESSION_AVAILABLE_EVENT, session);
+        }
+    });
+}
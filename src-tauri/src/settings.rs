@@ -0,0 +1,268 @@
+use crate::store::{load_store, save_store, store_dir};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_opener::OpenerExt;
+
+const DUPLICATE_DEFAULTS_FILE_NAME: &str = "duplicate-defaults.json";
+pub(crate) const DUPLICATE_DEFAULT_MIN_SIZE: u64 = 50 * 1024 * 1024;
+pub(crate) const DUPLICATE_DEFAULT_MAX_GROUPS: u32 = 200;
+pub(crate) const DUPLICATE_DEFAULT_MAX_FILES_PER_GROUP: u32 = 80;
+
+/// Fallbacks used by `find_duplicate_groups` when the caller omits a parameter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DuplicateDefaults {
+    pub min_size: u64,
+    pub max_groups: u32,
+    pub max_files_per_group: u32,
+}
+
+impl Default for DuplicateDefaults {
+    fn default() -> Self {
+        Self {
+            min_size: DUPLICATE_DEFAULT_MIN_SIZE,
+            max_groups: DUPLICATE_DEFAULT_MAX_GROUPS,
+            max_files_per_group: DUPLICATE_DEFAULT_MAX_FILES_PER_GROUP,
+        }
+    }
+}
+
+pub(crate) fn load_duplicate_defaults<R: Runtime>(app: &AppHandle<R>) -> DuplicateDefaults {
+    load_store(app, DUPLICATE_DEFAULTS_FILE_NAME)
+}
+
+#[tauri::command]
+pub fn get_duplicate_defaults(app: AppHandle<tauri::Wry>) -> DuplicateDefaults {
+    load_duplicate_defaults(&app)
+}
+
+/// Updates the stored duplicate-scan defaults; omitted fields keep their current value.
+#[tauri::command]
+pub fn set_duplicate_defaults(
+    app: AppHandle<tauri::Wry>,
+    min_size: Option<u64>,
+    max_groups: Option<u32>,
+    max_files_per_group: Option<u32>,
+) -> Result<DuplicateDefaults, String> {
+    let mut defaults = load_duplicate_defaults(&app);
+    if let Some(min_size) = min_size {
+        defaults.min_size = min_size;
+    }
+    if let Some(max_groups) = max_groups {
+        defaults.max_groups = max_groups.clamp(1, 1_000);
+    }
+    if let Some(max_files_per_group) = max_files_per_group {
+        defaults.max_files_per_group = max_files_per_group.clamp(2, 400);
+    }
+    save_store(&app, DUPLICATE_DEFAULTS_FILE_NAME, &defaults)?;
+    Ok(defaults)
+}
+
+const AUTOINDEX_DRIVES_FILE_NAME: &str = "autoindex-drives.json";
+
+/// Uppercases drive letters ("c", "D:", "e:\\" all work) and drops repeats.
+fn normalize_drive_letters(drives: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(drives.len());
+    for drive in drives {
+        let trimmed = drive
+            .trim()
+            .trim_end_matches(['\\', '/'])
+            .trim_end_matches(':');
+        let mut chars = trimmed.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(letter), None) if letter.is_ascii_alphabetic() => letter.to_ascii_uppercase(),
+            _ => return Err(format!("Invalid drive letter: {}.", drive.trim())),
+        };
+        let letter = letter.to_string();
+        if !normalized.contains(&letter) {
+            normalized.push(letter);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Drives indexed on startup; empty when none were chosen.
+pub(crate) fn load_autoindex_drives<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let drives: Vec<String> = load_store(app, AUTOINDEX_DRIVES_FILE_NAME);
+    normalize_drive_letters(drives).unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_autoindex_drives(app: AppHandle<tauri::Wry>) -> Vec<String> {
+    load_autoindex_drives(&app)
+}
+
+/// Stores the drive letters to index on every launch; an empty list turns startup indexing
+/// off. Returns the normalized letters.
+#[tauri::command]
+pub fn set_autoindex_drives(
+    app: AppHandle<tauri::Wry>,
+    drives: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let drives = normalize_drive_letters(drives)?;
+    save_store(&app, AUTOINDEX_DRIVES_FILE_NAME, &drives)?;
+    Ok(drives)
+}
+
+/// Opens the folder holding saved searches, tags, pins and the other persisted settings in
+/// the file manager, creating it first if nothing has been saved yet.
+#[tauri::command]
+pub fn open_data_dir(app: AppHandle<tauri::Wry>) -> Result<(), String> {
+    let dir = store_dir(&app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|err| format!("Failed to create the data folder: {err}"))?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|err| format!("Failed to open the data folder: {err}"))
+}
+
+const SETTINGS_BUNDLE_FORMAT: &str = "omni-search-settings";
+const SETTINGS_BUNDLE_VERSION: u32 = 1;
+
+/// Everything `export_settings` writes. Sections missing from an imported bundle are left
+/// untouched. Window state and the recently-opened history are machine-local and not part
+/// of the bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SettingsBundle {
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub autoindex_drives: Option<Vec<String>>,
+    #[serde(default)]
+    pub desktop_settings: Option<crate::desktop::DesktopSettings>,
+    #[serde(default)]
+    pub duplicate_defaults: Option<DuplicateDefaults>,
+    #[serde(default)]
+    pub index_update_interval_ms: Option<u32>,
+    #[serde(default)]
+    pub network_roots: Option<Vec<String>>,
+    #[serde(default)]
+    pub pinned_folders: Option<Vec<String>>,
+    #[serde(default)]
+    pub saved_searches: Option<Vec<crate::saved_searches::SavedSearch>>,
+    #[serde(default)]
+    pub tags: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub virtual_roots: Option<Vec<crate::virtual_roots::VirtualRoot>>,
+}
+
+/// Sections applied by `import_settings`, in the order they were written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImport {
+    pub applied: Vec<String>,
+    /// Sections that failed to apply, as `section: error`.
+    pub errors: Vec<String>,
+}
+
+/// Writes every persisted setting to `path` as a JSON bundle that `import_settings` reads.
+#[tauri::command]
+pub fn export_settings(app: AppHandle<tauri::Wry>, path: String) -> Result<(), String> {
+    let bundle = SettingsBundle {
+        format: SETTINGS_BUNDLE_FORMAT.to_string(),
+        version: SETTINGS_BUNDLE_VERSION,
+        autoindex_drives: Some(load_autoindex_drives(&app)),
+        desktop_settings: Some(crate::desktop::get_desktop_settings(app.clone())?),
+        duplicate_defaults: Some(load_duplicate_defaults(&app)),
+        index_update_interval_ms: Some(crate::watch::load_index_update_interval(&app)),
+        network_roots: Some(crate::network::load_network_roots(&app)),
+        pinned_folders: Some(crate::pinned::load_pinned_folders(&app)),
+        saved_searches: Some(crate::saved_searches::load_saved_searches(&app)),
+        tags: Some(crate::tags::load_tag_map(&app)),
+        virtual_roots: Some(crate::virtual_roots::load_virtual_roots(&app)),
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|err| format!("Failed to serialize settings: {err}"))?;
+    std::fs::write(&path, json).map_err(|err| format!("Failed to write settings file: {err}"))
+}
+
+/// Reads a bundle written by `export_settings` and replaces each section it contains. The
+/// whole file is rejected when it is not a settings bundle, has unknown fields, or comes from
+/// a newer version; after that, each section is applied on its own so one bad section does
+/// not block the rest.
+#[tauri::command]
+pub fn import_settings(app: AppHandle<tauri::Wry>, path: String) -> Result<SettingsImport, String> {
+    let json = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read settings file: {err}"))?;
+    let bundle: SettingsBundle =
+        serde_json::from_str(&json).map_err(|err| format!("Invalid settings file: {err}"))?;
+    if bundle.format != SETTINGS_BUNDLE_FORMAT {
+        return Err("File is not an Omni Search settings export.".to_string());
+    }
+    if bundle.version == 0 || bundle.version > SETTINGS_BUNDLE_VERSION {
+        return Err(format!(
+            "Unsupported settings version {} (expected {SETTINGS_BUNDLE_VERSION}).",
+            bundle.version
+        ));
+    }
+
+    let mut report = SettingsImport {
+        applied: Vec::new(),
+        errors: Vec::new(),
+    };
+    let mut record = |section: &str, result: Result<(), String>| match result {
+        Ok(()) => report.applied.push(section.to_string()),
+        Err(err) => report.errors.push(format!("{section}: {err}")),
+    };
+
+    if let Some(drives) = bundle.autoindex_drives {
+        record(
+            "autoindexDrives",
+            normalize_drive_letters(drives)
+                .and_then(|drives| save_store(&app, AUTOINDEX_DRIVES_FILE_NAME, &drives)),
+        );
+    }
+    if let Some(settings) = bundle.desktop_settings {
+        record(
+            "desktopSettings",
+            crate::desktop::apply_desktop_settings(&app, settings).map(|_| ()),
+        );
+    }
+    if let Some(defaults) = bundle.duplicate_defaults {
+        let defaults = DuplicateDefaults {
+            min_size: defaults.min_size,
+            max_groups: defaults.max_groups.clamp(1, 1_000),
+            max_files_per_group: defaults.max_files_per_group.clamp(2, 400),
+        };
+        record(
+            "duplicateDefaults",
+            save_store(&app, DUPLICATE_DEFAULTS_FILE_NAME, &defaults),
+        );
+    }
+    if let Some(interval_ms) = bundle.index_update_interval_ms {
+        record(
+            "indexUpdateIntervalMs",
+            crate::watch::store_index_update_interval(&app, interval_ms).map(|_| ()),
+        );
+    }
+    if let Some(roots) = bundle.network_roots {
+        record(
+            "networkRoots",
+            crate::network::replace_network_roots(&app, roots),
+        );
+    }
+    if let Some(pins) = bundle.pinned_folders {
+        record(
+            "pinnedFolders",
+            crate::pinned::replace_pinned_folders(&app, pins),
+        );
+    }
+    if let Some(searches) = bundle.saved_searches {
+        record(
+            "savedSearches",
+            crate::saved_searches::replace_saved_searches(&app, searches),
+        );
+    }
+    if let Some(tags) = bundle.tags {
+        record("tags", crate::tags::replace_tag_map(&app, tags));
+    }
+    if let Some(roots) = bundle.virtual_roots {
+        record(
+            "virtualRoots",
+            crate::virtual_roots::replace_virtual_roots(&app, roots),
+        );
+    }
+    Ok(report)
+}
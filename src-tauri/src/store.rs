@@ -0,0 +1,57 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::{io::ErrorKind, path::PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Folder holding every persisted store file.
+pub(crate) fn store_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path().app_config_dir().map_err(|err| err.to_string())
+}
+
+fn store_file_path<R: Runtime>(app: &AppHandle<R>, file_name: &str) -> Result<PathBuf, String> {
+    Ok(store_dir(app)?.join(file_name))
+}
+
+fn log_store_error(action: &str, file_name: &str, err: &str) {
+    eprintln!("OmniSearch store action failed while trying to {action} {file_name}: {err}");
+}
+
+/// Reads a JSON document from the app config directory, falling back to the default value
+/// when the file is missing or unreadable.
+pub(crate) fn load_store<R: Runtime, T: DeserializeOwned + Default>(
+    app: &AppHandle<R>,
+    file_name: &str,
+) -> T {
+    let path = match store_file_path(app, file_name) {
+        Ok(path) => path,
+        Err(err) => {
+            log_store_error("resolve", file_name, &err);
+            return T::default();
+        }
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str::<T>(&contents).unwrap_or_else(|err| {
+            log_store_error("parse", file_name, &err.to_string());
+            T::default()
+        }),
+        Err(err) if err.kind() == ErrorKind::NotFound => T::default(),
+        Err(err) => {
+            log_store_error("read", file_name, &err.to_string());
+            T::default()
+        }
+    }
+}
+
+pub(crate) fn save_store<R: Runtime, T: Serialize>(
+    app: &AppHandle<R>,
+    file_name: &str,
+    value: &T,
+) -> Result<(), String> {
+    let path = store_file_path(app, file_name)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+
+    let payload = serde_json::to_vec_pretty(value).map_err(|err| err.to_string())?;
+    std::fs::write(path, payload).map_err(|err| err.to_string())
+}
@@ -0,0 +1,163 @@
+use crate::store::{load_store, save_store};
+use std::{collections::BTreeMap, path::Path, sync::Mutex};
+use tauri::{AppHandle, Runtime};
+
+#[cfg(target_os = "windows")]
+use std::collections::HashMap;
+
+const TAGS_FILE_NAME: &str = "file-tags.json";
+const TAG_MAX_LENGTH: usize = 64;
+const TAGS_PER_PATH_MAX: usize = 32;
+
+/// Serializes read-modify-write cycles on the tags file.
+static TAGS_LOCK: Mutex<()> = Mutex::new(());
+
+type TagMap = BTreeMap<String, Vec<String>>;
+
+fn lock_tags() -> Result<std::sync::MutexGuard<'static, ()>, String> {
+    TAGS_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock file tags".to_string())
+}
+
+/// Trims and lowercases tags, dropping empty and repeated ones.
+fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || normalized.contains(&tag) {
+            continue;
+        }
+        if tag.chars().count() > TAG_MAX_LENGTH {
+            return Err(format!("Tags are limited to {TAG_MAX_LENGTH} characters."));
+        }
+        normalized.push(tag);
+    }
+    if normalized.len() > TAGS_PER_PATH_MAX {
+        return Err(format!(
+            "A file can carry at most {TAGS_PER_PATH_MAX} tags."
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Finds the stored key for `path`, which may differ from it in case.
+fn stored_key(map: &TagMap, path: &str) -> Option<String> {
+    map.keys()
+        .find(|key| key.eq_ignore_ascii_case(path))
+        .cloned()
+}
+
+/// Drops entries whose paths no longer exist, so tags of renamed or deleted files do not
+/// linger, and saves the map when anything was removed.
+fn prune_missing<R: Runtime>(app: &AppHandle<R>, map: &mut TagMap) {
+    let before = map.len();
+    map.retain(|path, _| Path::new(path).exists());
+    if map.len() != before {
+        if let Err(err) = save_store(app, TAGS_FILE_NAME, map) {
+            eprintln!("OmniSearch failed to prune file tags: {err}");
+        }
+    }
+}
+
+/// Paths carrying every tag in `tags`, after pruning missing files.
+#[cfg(target_os = "windows")]
+pub(crate) fn tagged_paths<R: Runtime>(
+    app: &AppHandle<R>,
+    tags: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let tags = normalize_tags(tags)?;
+    let _guard = lock_tags()?;
+    let mut map: TagMap = load_store(app, TAGS_FILE_NAME);
+    prune_missing(app, &mut map);
+    Ok(map
+        .into_iter()
+        .filter(|(_, path_tags)| tags.iter().all(|tag| path_tags.contains(tag)))
+        .map(|(path, _)| path)
+        .collect())
+}
+
+/// Every stored tag list keyed by lowercased path, for annotating search results. The map is
+/// read as stored; pruning happens in the tag commands and the tag filter.
+#[cfg(target_os = "windows")]
+pub(crate) fn tags_by_path<R: Runtime>(app: &AppHandle<R>) -> HashMap<String, Vec<String>> {
+    let Ok(_guard) = TAGS_LOCK.lock() else {
+        return HashMap::new();
+    };
+    let map: TagMap = load_store(app, TAGS_FILE_NAME);
+    map.into_iter()
+        .map(|(path, tags)| (path.to_lowercase(), tags))
+        .collect()
+}
+
+/// Every stored tag list keyed by path as stored, for exporting settings.
+pub(crate) fn load_tag_map<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, Vec<String>> {
+    let Ok(_guard) = TAGS_LOCK.lock() else {
+        return BTreeMap::new();
+    };
+    load_store(app, TAGS_FILE_NAME)
+}
+
+/// Replaces every tag, as when importing settings. Tags are normalized and paths that do not
+/// exist on this machine are dropped.
+pub(crate) fn replace_tag_map<R: Runtime>(
+    app: &AppHandle<R>,
+    map: BTreeMap<String, Vec<String>>,
+) -> Result<(), String> {
+    let mut normalized = TagMap::new();
+    for (path, tags) in map {
+        let path = path.trim().to_string();
+        let tags = normalize_tags(tags)?;
+        if !path.is_empty() && !tags.is_empty() && Path::new(&path).exists() {
+            normalized.insert(path, tags);
+        }
+    }
+    let _guard = lock_tags()?;
+    save_store(app, TAGS_FILE_NAME, &normalized)
+}
+
+/// Replaces the tags of `path` and returns them normalized (trimmed, lowercased, unique).
+/// An empty list removes the path from the tag store.
+#[tauri::command]
+pub fn set_tags(
+    app: AppHandle<tauri::Wry>,
+    path: String,
+    tags: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let path = path.trim().to_string();
+    if path.is_empty() {
+        return Err("Path is empty.".to_string());
+    }
+    let tags = normalize_tags(tags)?;
+    if !tags.is_empty() && !Path::new(&path).exists() {
+        return Err("Path does not exist on disk.".to_string());
+    }
+
+    let _guard = lock_tags()?;
+    let mut map: TagMap = load_store(&app, TAGS_FILE_NAME);
+    if let Some(key) = stored_key(&map, &path) {
+        map.remove(&key);
+    }
+    if !tags.is_empty() {
+        map.insert(path, tags.clone());
+    }
+    map.retain(|path, _| Path::new(path).exists());
+    save_store(&app, TAGS_FILE_NAME, &map)?;
+    Ok(tags)
+}
+
+/// Returns the tags of `path`, or an empty list. Tags of a path that no longer exists are
+/// dropped.
+#[tauri::command]
+pub fn get_tags(app: AppHandle<tauri::Wry>, path: String) -> Result<Vec<String>, String> {
+    let _guard = lock_tags()?;
+    let mut map: TagMap = load_store(&app, TAGS_FILE_NAME);
+    let Some(key) = stored_key(&map, path.trim()) else {
+        return Ok(Vec::new());
+    };
+    if !Path::new(&key).exists() {
+        prune_missing(&app, &mut map);
+        return Ok(Vec::new());
+    }
+    Ok(map.remove(&key).unwrap_or_default())
+}
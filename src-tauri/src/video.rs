@@ -0,0 +1,107 @@
+#[cfg(target_os = "windows")]
+use base64::Engine;
+#[cfg(target_os = "windows")]
+use image::{imageops::FilterType, DynamicImage, ImageFormat, RgbaImage};
+#[cfg(target_os = "windows")]
+use std::{ffi::CString, io::Cursor};
+
+#[cfg(target_os = "windows")]
+const FILMSTRIP_DEFAULT_FRAMES: u32 = 6;
+#[cfg(target_os = "windows")]
+const FILMSTRIP_MAX_FRAMES: u32 = 16;
+/// Longest edge of each filmstrip frame, in pixels.
+#[cfg(target_os = "windows")]
+const FILMSTRIP_FRAME_EDGE: u32 = 320;
+/// Edge requested from the shell when Media Foundation cannot decode the video.
+#[cfg(target_os = "windows")]
+const FILMSTRIP_POSTER_EDGE: i32 = 320;
+
+/// Decodes the frame at `fraction` of the duration and returns it as a PNG data URL scaled to
+/// fit `FILMSTRIP_FRAME_EDGE`.
+#[cfg(target_os = "windows")]
+fn video_frame_data_url(c_path: &CString, fraction: f64) -> Result<String, String> {
+    let mut width = 0_u32;
+    let mut height = 0_u32;
+    // SAFETY: `c_path` is NUL-terminated and the out pointers are valid for the call.
+    let raw_pixels =
+        unsafe { crate::omni_read_video_frame(c_path.as_ptr(), fraction, &mut width, &mut height) };
+    if raw_pixels.is_null() {
+        return Err(crate::read_last_error()
+            .unwrap_or_else(|| "Failed to decode the video frame.".to_string()));
+    }
+
+    let byte_count = width as usize * height as usize * 4;
+    // SAFETY: C++ allocated `width * height * 4` bytes at `raw_pixels`.
+    let mut pixels = unsafe { std::slice::from_raw_parts(raw_pixels, byte_count).to_vec() };
+    // SAFETY: `raw_pixels` was allocated by C++ and must be released by C++.
+    unsafe { crate::omni_free_string(raw_pixels.cast()) };
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let frame = RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| "Video frame has an unexpected size.".to_string())?;
+    let frame = DynamicImage::ImageRgba8(frame).resize(
+        FILMSTRIP_FRAME_EDGE,
+        FILMSTRIP_FRAME_EDGE,
+        FilterType::Triangle,
+    );
+
+    let mut png_bytes = Vec::new();
+    frame
+        .write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
+        .map_err(|err| format!("Failed to encode video frame: {err}"))?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png_bytes);
+    Ok(format!("data:image/png;base64,{encoded}"))
+}
+
+/// Returns `count` evenly spaced frames of a video as PNG data URLs, in playback order
+/// (default 6, at most 16), each taken from the middle of an equal slice of the duration.
+/// When Media Foundation has no decoder for the video, the shell thumbnail (the poster
+/// frame) is returned as the only frame.
+#[tauri::command]
+pub async fn video_filmstrip(path: String, count: u32) -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if !std::path::Path::new(&path).is_file() {
+            return Err("File does not exist on disk.".to_string());
+        }
+        let count = if count == 0 {
+            FILMSTRIP_DEFAULT_FRAMES
+        } else {
+            count.min(FILMSTRIP_MAX_FRAMES)
+        };
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<String>, String> {
+            let c_path =
+                CString::new(path.as_str()).map_err(|_| "Invalid path parameter".to_string())?;
+            let mut frames = Vec::with_capacity(count as usize);
+            let mut first_error = None;
+            for index in 0..count {
+                let fraction = (f64::from(index) + 0.5) / f64::from(count);
+                match video_frame_data_url(&c_path, fraction) {
+                    Ok(frame) => frames.push(frame),
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                    }
+                }
+            }
+            if !frames.is_empty() {
+                return Ok(frames);
+            }
+
+            crate::apps::load_shell_thumbnail_data_url(&path, FILMSTRIP_POSTER_EDGE)
+                .map(|poster| vec![poster])
+                .map_err(|_| {
+                    first_error.unwrap_or_else(|| "Failed to decode the video.".to_string())
+                })
+        })
+        .await
+        .map_err(|err| format!("Filmstrip task failed: {err}"))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, count);
+        Err("Video filmstrips are only supported on Windows.".to_string())
+    }
+}
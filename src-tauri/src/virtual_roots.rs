@@ -0,0 +1,320 @@
+use crate::store::{load_store, save_store};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Mutex};
+use tauri::{AppHandle, Runtime};
+
+#[cfg(target_os = "windows")]
+use std::ffi::CString;
+
+const VIRTUAL_ROOTS_FILE_NAME: &str = "virtual-roots.json";
+const VIRTUAL_ROOT_MAX_FOLDERS: usize = 64;
+
+/// Serializes read-modify-write cycles on the virtual-roots file.
+static VIRTUAL_ROOTS_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_virtual_roots() -> Result<std::sync::MutexGuard<'static, ()>, String> {
+    VIRTUAL_ROOTS_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock virtual roots".to_string())
+}
+
+/// A named location spanning one or more physical folders, such as a shell library that
+/// aggregates folders on several drives.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualRoot {
+    pub name: String,
+    /// Shell library the folders come from (`documents`, `music`, `pictures` or `videos`);
+    /// its folders are re-read from the shell whenever the root is listed or searched.
+    #[serde(default)]
+    pub library: Option<String>,
+    pub folders: Vec<String>,
+}
+
+/// Current folders of the shell library `name`, in the order the library lists them.
+#[cfg(target_os = "windows")]
+fn library_folders(name: &str) -> Result<Vec<String>, String> {
+    use windows::Win32::{
+        System::Com::{CoTaskMemFree, STGM_READ},
+        UI::Shell::{
+            FOLDERID_DocumentsLibrary, FOLDERID_MusicLibrary, FOLDERID_PicturesLibrary,
+            FOLDERID_VideosLibrary, IShellItemArray, IShellLibrary, SHLoadLibraryFromKnownFolder,
+            LFF_FORCEFILESYSTEM, SIGDN_FILESYSPATH,
+        },
+    };
+
+    let library_id = match name {
+        "documents" => FOLDERID_DocumentsLibrary,
+        "music" => FOLDERID_MusicLibrary,
+        "pictures" => FOLDERID_PicturesLibrary,
+        "videos" => FOLDERID_VideosLibrary,
+        other => return Err(format!("Unknown library: {other}.")),
+    };
+    let _com_guard = crate::apps::ensure_com_initialized()?;
+    // SAFETY: `library_id` is a valid known-folder GUID for a library.
+    let library: IShellLibrary = unsafe { SHLoadLibraryFromKnownFolder(&library_id, STGM_READ) }
+        .map_err(|err| format!("Failed to open the {name} library: {err}"))?;
+    // SAFETY: `library` is a live COM object; the filter only keeps file system folders.
+    let items: IShellItemArray = unsafe { library.GetFolders(LFF_FORCEFILESYSTEM) }
+        .map_err(|err| format!("Failed to list the {name} library: {err}"))?;
+    // SAFETY: `items` is a live shell item array.
+    let count = unsafe { items.GetCount() }
+        .map_err(|err| format!("Failed to list the {name} library: {err}"))?;
+
+    let mut folders = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        // SAFETY: `index` is below the array's item count.
+        let Ok(item) = (unsafe { items.GetItemAt(index) }) else {
+            continue;
+        };
+        // SAFETY: `item` is a live shell item; the name is released below.
+        let Ok(raw_path) = (unsafe { item.GetDisplayName(SIGDN_FILESYSPATH) }) else {
+            continue;
+        };
+        // SAFETY: on success `raw_path` is a NUL-terminated wide string owned by the caller.
+        let path = unsafe { raw_path.to_string() };
+        // SAFETY: the shell allocated `raw_path` with the COM task allocator.
+        unsafe { CoTaskMemFree(Some(raw_path.0 as *const std::ffi::c_void)) };
+        if let Ok(path) = path {
+            folders.push(path);
+        }
+    }
+    Ok(folders)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn library_folders(name: &str) -> Result<Vec<String>, String> {
+    let _ = name;
+    Err("Shell libraries are only supported on Windows.".to_string())
+}
+
+pub(crate) fn load_virtual_roots<R: Runtime>(app: &AppHandle<R>) -> Vec<VirtualRoot> {
+    let Ok(_guard) = lock_virtual_roots() else {
+        return Vec::new();
+    };
+    load_store(app, VIRTUAL_ROOTS_FILE_NAME)
+}
+
+/// Replaces every virtual root, as when importing settings. Folders are not checked here,
+/// since a root whose folders are missing on this machine simply finds nothing in them; the
+/// first root with a given name wins.
+pub(crate) fn replace_virtual_roots<R: Runtime>(
+    app: &AppHandle<R>,
+    roots: Vec<VirtualRoot>,
+) -> Result<(), String> {
+    let mut normalized: Vec<VirtualRoot> = Vec::with_capacity(roots.len());
+    for root in roots {
+        let name = root.name.trim().to_string();
+        if name.is_empty()
+            || normalized
+                .iter()
+                .any(|existing| existing.name.eq_ignore_ascii_case(&name))
+        {
+            continue;
+        }
+        let mut folders: Vec<String> = Vec::new();
+        for folder in root.folders {
+            let folder = crate::pinned::normalize_folder(&folder);
+            if !folder.is_empty()
+                && !folders
+                    .iter()
+                    .any(|existing| existing.eq_ignore_ascii_case(&folder))
+            {
+                folders.push(folder);
+            }
+        }
+        folders.truncate(VIRTUAL_ROOT_MAX_FOLDERS);
+        let library = root
+            .library
+            .map(|library| library.trim().to_ascii_lowercase())
+            .filter(|library| !library.is_empty());
+        // Library roots re-read their folders from the shell, so they may arrive without any.
+        if folders.is_empty() && library.is_none() {
+            continue;
+        }
+        normalized.push(VirtualRoot {
+            name,
+            library,
+            folders,
+        });
+    }
+    let _guard = lock_virtual_roots()?;
+    save_store(app, VIRTUAL_ROOTS_FILE_NAME, &normalized)
+}
+
+/// `root` with a library's folders re-read, keeping the stored ones when the shell cannot
+/// list them.
+fn refreshed(mut root: VirtualRoot) -> VirtualRoot {
+    if let Some(folders) = root
+        .library
+        .as_deref()
+        .and_then(|library| library_folders(library).ok())
+        .filter(|folders| !folders.is_empty())
+    {
+        root.folders = folders;
+    }
+    root
+}
+
+/// Registers a virtual root named `name` over `folders`, or over the folders of the shell
+/// `library` (`documents`, `music`, `pictures` or `videos`). Registering an existing name
+/// replaces that root.
+#[tauri::command]
+pub fn register_virtual_root(
+    app: AppHandle<tauri::Wry>,
+    name: String,
+    folders: Option<Vec<String>>,
+    library: Option<String>,
+) -> Result<VirtualRoot, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Virtual root name is empty.".to_string());
+    }
+    let library = library
+        .map(|library| library.trim().to_ascii_lowercase())
+        .filter(|library| !library.is_empty());
+    let candidates = match (&library, folders) {
+        (Some(_), Some(_)) => {
+            return Err("Give either folders or a library, not both.".to_string());
+        }
+        (Some(library), None) => library_folders(library)?,
+        (None, Some(folders)) => folders,
+        (None, None) => return Err("Give the folders or library to register.".to_string()),
+    };
+
+    let mut folders: Vec<String> = Vec::new();
+    for folder in candidates {
+        let folder = crate::pinned::normalize_folder(&folder);
+        if folder.is_empty()
+            || folders
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(&folder))
+        {
+            continue;
+        }
+        if !Path::new(&folder).is_dir() {
+            return Err(format!("Folder does not exist on disk: {folder}"));
+        }
+        folders.push(folder);
+    }
+    if folders.is_empty() {
+        return Err("Virtual root has no folders.".to_string());
+    }
+    if folders.len() > VIRTUAL_ROOT_MAX_FOLDERS {
+        return Err(format!(
+            "A virtual root spans at most {VIRTUAL_ROOT_MAX_FOLDERS} folders."
+        ));
+    }
+
+    let root = VirtualRoot {
+        name,
+        library,
+        folders,
+    };
+    let _guard = lock_virtual_roots()?;
+    let mut roots: Vec<VirtualRoot> = load_store(&app, VIRTUAL_ROOTS_FILE_NAME);
+    match roots
+        .iter_mut()
+        .find(|existing| existing.name.eq_ignore_ascii_case(&root.name))
+    {
+        Some(existing) => *existing = root.clone(),
+        None => roots.push(root.clone()),
+    }
+    save_store(&app, VIRTUAL_ROOTS_FILE_NAME, &roots)?;
+    Ok(root)
+}
+
+/// Removes the virtual root `name`, if any, and returns the remaining roots.
+#[tauri::command]
+pub fn unregister_virtual_root(
+    app: AppHandle<tauri::Wry>,
+    name: String,
+) -> Result<Vec<VirtualRoot>, String> {
+    let name = name.trim();
+    let _guard = lock_virtual_roots()?;
+    let mut roots: Vec<VirtualRoot> = load_store(&app, VIRTUAL_ROOTS_FILE_NAME);
+    let before = roots.len();
+    roots.retain(|root| !root.name.eq_ignore_ascii_case(name));
+    if roots.len() != before {
+        save_store(&app, VIRTUAL_ROOTS_FILE_NAME, &roots)?;
+    }
+    Ok(roots)
+}
+
+/// Registered virtual roots in the order they were added, with library folders as the shell
+/// currently lists them.
+#[tauri::command]
+pub fn virtual_roots(app: AppHandle<tauri::Wry>) -> Result<Vec<VirtualRoot>, String> {
+    let _guard = lock_virtual_roots()?;
+    let roots: Vec<VirtualRoot> = load_store(&app, VIRTUAL_ROOTS_FILE_NAME);
+    Ok(roots.into_iter().map(refreshed).collect())
+}
+
+/// Searches every folder of the virtual root `name`, so "search my Pictures library" covers
+/// each folder the library aggregates. Results carry `virtual_root`, folders nested in one
+/// another are not reported twice, and only indexed drives contribute. `limit` defaults to
+/// 200.
+#[tauri::command]
+pub async fn search_virtual_root(
+    app: AppHandle<tauri::Wry>,
+    name: String,
+    query: Option<String>,
+    extension: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<crate::SearchResult>, String> {
+    let root = {
+        let _guard = lock_virtual_roots()?;
+        let roots: Vec<VirtualRoot> = load_store(&app, VIRTUAL_ROOTS_FILE_NAME);
+        roots
+            .into_iter()
+            .find(|root| root.name.eq_ignore_ascii_case(name.trim()))
+            .map(refreshed)
+            .ok_or_else(|| format!("Unknown virtual root: {}.", name.trim()))?
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        let limit = limit
+            .unwrap_or(200)
+            .clamp(1, crate::DEFAULT_RESULT_LIMIT_CEILING);
+        let c_query = CString::new(query.unwrap_or_default().trim())
+            .map_err(|_| "Invalid query".to_string())?;
+        let c_extension = CString::new(extension.unwrap_or_default())
+            .map_err(|_| "Invalid extension".to_string())?;
+
+        let mut results = tauri::async_runtime::spawn_blocking(move || {
+            let mut results: Vec<crate::SearchResult> = Vec::new();
+            let mut seen = std::collections::HashSet::new();
+            for folder in &root.folders {
+                let c_within_path = CString::new(folder.as_str())
+                    .map_err(|_| "Invalid path parameter".to_string())?;
+                let options = crate::SearchOptions {
+                    limit,
+                    max_limit: crate::DEFAULT_RESULT_LIMIT_CEILING,
+                    ..crate::SearchOptions::background(&c_query, &c_extension, &c_within_path)
+                };
+                for mut result in crate::run_search(&options)? {
+                    if seen.insert(result.path.to_lowercase()) {
+                        result.virtual_root = Some(root.name.clone());
+                        results.push(result);
+                    }
+                }
+                if results.len() >= limit as usize {
+                    break;
+                }
+            }
+            results.truncate(limit as usize);
+            Ok::<_, String>(results)
+        })
+        .await
+        .map_err(|err| format!("Virtual root search task failed: {err}"))??;
+        crate::attach_tags(&app, &mut results);
+        Ok(results)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, root, query, extension, limit);
+        Err("OmniSearch scanner is only supported on Windows.".to_string())
+    }
+}
@@ -0,0 +1,204 @@
+use crate::store::{load_store, save_store};
+use std::sync::atomic::{AtomicU32, Ordering};
+use tauri::{AppHandle, Runtime};
+
+#[cfg(target_os = "windows")]
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
+use std::ffi::{CStr, CString};
+#[cfg(target_os = "windows")]
+use tauri::Emitter;
+
+#[cfg(target_os = "windows")]
+const FOLDER_CHANGED_EVENT: &str = "omni://folder-changed";
+#[cfg(target_os = "windows")]
+const INDEX_UPDATED_EVENT: &str = "omni://index-updated";
+const INDEX_UPDATE_INTERVAL_FILE_NAME: &str = "index-update-interval.json";
+const INDEX_UPDATE_DEFAULT_INTERVAL_MS: u32 = 1000;
+const INDEX_UPDATE_MIN_INTERVAL_MS: u32 = 100;
+const INDEX_UPDATE_MAX_INTERVAL_MS: u32 = 60_000;
+
+/// Shortest gap between two `omni://index-updated` events, read by the emitter each cycle.
+static INDEX_UPDATE_INTERVAL_MS: AtomicU32 = AtomicU32::new(INDEX_UPDATE_DEFAULT_INTERVAL_MS);
+
+/// How long each wait blocks before checking in again. Stopping a watch wakes the waiter early.
+#[cfg(target_os = "windows")]
+const FOLDER_WATCH_WAIT_MS: u32 = 1000;
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Deserialize)]
+struct FolderChange {
+    /// `added`, `removed`, `modified`, `renamedFrom`, `renamedTo`, or `overflow` when changes
+    /// were dropped and the folder should be rescanned.
+    change: String,
+    path: String,
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FolderChangedEvent {
+    watch_id: u32,
+    change: String,
+    path: String,
+}
+
+/// Blocks for the next batch of changes; `None` once the watch is stopped or fails.
+#[cfg(target_os = "windows")]
+fn wait_folder_changes(watch_id: u32) -> Option<Vec<FolderChange>> {
+    // SAFETY: the watch id is a plain value; the call only blocks this thread.
+    let raw_json = unsafe { crate::omni_wait_folder_changes_json(watch_id, FOLDER_WATCH_WAIT_MS) };
+    if raw_json.is_null() {
+        return None;
+    }
+
+    // SAFETY: `raw_json` points to a C string allocated by C++.
+    let json = unsafe { CStr::from_ptr(raw_json).to_string_lossy().to_string() };
+    // SAFETY: `raw_json` was allocated by C++ and must be released by C++.
+    unsafe { crate::omni_free_string(raw_json) };
+
+    match serde_json::from_str(&json) {
+        Ok(changes) => Some(changes),
+        Err(err) => {
+            eprintln!("OmniSearch received an invalid folder change payload: {err}");
+            Some(Vec::new())
+        }
+    }
+}
+
+/// Starts watching `path` (and its subfolders unless `recursive` is false) and emits an
+/// `omni://folder-changed` event for every change until `unwatch_folder` is called.
+#[tauri::command]
+pub fn watch_folder(
+    app: AppHandle<tauri::Wry>,
+    path: String,
+    recursive: Option<bool>,
+) -> Result<u32, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let c_path = CString::new(path).map_err(|_| "Invalid path parameter".to_string())?;
+        // SAFETY: `c_path` lives long enough for this synchronous call.
+        let watch_id =
+            unsafe { crate::omni_watch_folder(c_path.as_ptr(), recursive.unwrap_or(true)) };
+        if watch_id == 0 {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to watch the folder.".to_string()));
+        }
+
+        std::thread::spawn(move || {
+            while let Some(changes) = wait_folder_changes(watch_id) {
+                for change in changes {
+                    let _ = app.emit(
+                        FOLDER_CHANGED_EVENT,
+                        FolderChangedEvent {
+                            watch_id,
+                            change: change.change,
+                            path: change.path,
+                        },
+                    );
+                }
+            }
+        });
+        Ok(watch_id)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, path, recursive);
+        Err("Folder watching is only supported on Windows.".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn unwatch_folder(watch_id: u32) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        // SAFETY: the watch id is a plain value; unknown ids are reported as an error.
+        let ok = unsafe { crate::omni_unwatch_folder(watch_id) };
+        if !ok {
+            return Err(crate::read_last_error()
+                .unwrap_or_else(|| "Failed to stop watching the folder.".to_string()));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = watch_id;
+        Err("Folder watching is only supported on Windows.".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexUpdatedEvent {
+    added: u64,
+    removed: u64,
+    /// Entries renamed, moved or otherwise rewritten in place.
+    modified: u64,
+}
+
+pub(crate) fn load_index_update_interval<R: Runtime>(app: &AppHandle<R>) -> u32 {
+    let interval: Option<u32> = load_store(app, INDEX_UPDATE_INTERVAL_FILE_NAME);
+    interval
+        .unwrap_or(INDEX_UPDATE_DEFAULT_INTERVAL_MS)
+        .clamp(INDEX_UPDATE_MIN_INTERVAL_MS, INDEX_UPDATE_MAX_INTERVAL_MS)
+}
+
+/// Coalesces live USN updates into at most one `omni://index-updated` event per interval,
+/// carrying how many entries were added, removed and modified since the previous event. A
+/// burst such as extracting thousands of files therefore costs the UI one refresh, and quiet
+/// intervals emit nothing.
+#[cfg(target_os = "windows")]
+pub(crate) fn start_index_update_events(app: &AppHandle<tauri::Wry>) {
+    INDEX_UPDATE_INTERVAL_MS.store(load_index_update_interval(app), Ordering::Relaxed);
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let interval = INDEX_UPDATE_INTERVAL_MS.load(Ordering::Relaxed);
+        std::thread::sleep(std::time::Duration::from_millis(u64::from(interval)));
+        let mut added = 0_u64;
+        let mut removed = 0_u64;
+        let mut modified = 0_u64;
+        // SAFETY: the out pointers are valid for the duration of the call.
+        let changed = unsafe {
+            crate::omni_take_index_update_counts(&mut added, &mut removed, &mut modified)
+        };
+        if changed {
+            let _ = app.emit(
+                INDEX_UPDATED_EVENT,
+                IndexUpdatedEvent {
+                    added,
+                    removed,
+                    modified,
+                },
+            );
+        }
+    });
+}
+
+/// Clamps and stores the interval, returning the stored value.
+pub(crate) fn store_index_update_interval<R: Runtime>(
+    app: &AppHandle<R>,
+    interval_ms: u32,
+) -> Result<u32, String> {
+    let interval = interval_ms.clamp(INDEX_UPDATE_MIN_INTERVAL_MS, INDEX_UPDATE_MAX_INTERVAL_MS);
+    save_store(app, INDEX_UPDATE_INTERVAL_FILE_NAME, &interval)?;
+    INDEX_UPDATE_INTERVAL_MS.store(interval, Ordering::Relaxed);
+    Ok(interval)
+}
+
+#[tauri::command]
+pub fn get_index_update_interval(app: AppHandle<tauri::Wry>) -> u32 {
+    load_index_update_interval(&app)
+}
+
+/// Sets how often, in milliseconds, batched `omni://index-updated` events may fire (100 ms to
+/// one minute) and returns the stored value. Takes effect from the next event.
+#[tauri::command]
+pub fn set_index_update_interval(
+    app: AppHandle<tauri::Wry>,
+    interval_ms: u32,
+) -> Result<u32, String> {
+    store_index_update_interval(&app, interval_ms)
+}
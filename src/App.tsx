@@ -53,6 +53,10 @@ type IndexStatus = {
   ready: boolean;
   indexedCount: number;
   lastError?: string | null;
+  lastIndexedUnix?: number | null;
+  buildDurationMs?: number | null;
+  memoryBytes?: number;
+  trimmed?: boolean;
 };
 
 type ContentSearchMode = "auto" | "ansi" | "utf8" | "utf16" | "utf16be";
@@ -66,6 +70,20 @@ type SearchResult = {
   createdUnix: number;
   modifiedUnix: number;
   isDirectory: boolean;
+  category?: string;
+  owner?: string | null;
+  tags?: string[];
+  shortName?: string | null;
+  score?: number | null;
+  longPath?: boolean;
+  drive?: string | null;
+  isCloudPlaceholder?: boolean;
+  fileId?: number;
+  aliases?: string[];
+  parentPath?: string;
+  parentName?: string;
+  origin?: "user" | "app" | "system" | "other";
+  virtualRoot?: string | null;
   appId?: string;
   launchTarget?: string;
   revealPath?: string | null;
@@ -79,6 +97,7 @@ type TextPreviewPayload = {
   text: string;
   truncated: boolean;
   matched: boolean;
+  endOffset?: number;
 };
 
 type InstalledApp = {
@@ -109,6 +128,7 @@ type DuplicateGroup = {
   totalBytes: number;
   fileCount: number;
   files: DuplicateFile[];
+  hashMode?: "full" | "partial" | "size_only" | "name";
 };
 
 type DuplicateScanStatus = {
@@ -118,6 +138,7 @@ type DuplicateScanStatus = {
   totalFiles: number;
   groupsFound: number;
   progressPercent: number;
+  volatileFiles?: string[];
 };
 
 type DuplicateDeleteCandidate = {
@@ -127,6 +148,14 @@ type DuplicateDeleteCandidate = {
   size: number;
 };
 
+type SearchFilesResponse = {
+  results: SearchResult[];
+  queryRequired: boolean;
+  queryTooShort: boolean;
+  truncated?: boolean;
+  cursor?: string | null;
+};
+
 type SearchResultContextMenuState = {
   x: number;
   y: number;
@@ -156,6 +185,8 @@ type DriveInfo = {
   driveType: string;
   isNtfs: boolean;
   canOpenVolume: boolean;
+  uncTarget?: string | null;
+  scanMode?: "mft" | "usn" | "walk" | "unsupported";
 };
 
 type SocialIconName = "github" | "linkedin" | "telegram";
@@ -3781,7 +3812,10 @@ function App() {
 
     const loadDrives = async () => {
       try {
-        const available = await invoke<DriveInfo[]>("list_drives");
+        // Registered network roots have no letter; they are indexed alongside the drive.
+        const available = (await invoke<DriveInfo[]>("list_drives")).filter(
+          (drive) => drive.letter !== "",
+        );
         if (!active) {
           return;
         }
@@ -4000,24 +4034,20 @@ function App() {
 
         try {
           cancelSearchRequest();
-          const searchArgs = {
+          const params = {
             query: trimmedQuery,
             extension: extension.trim(),
             minSize,
-            min_size: minSize,
             maxSize,
-            max_size: maxSize,
             minCreatedUnix,
-            min_created_unix: minCreatedUnix,
             maxCreatedUnix,
-            max_created_unix: maxCreatedUnix,
             limit: searchLimit,
           };
-          const found = await invoke<SearchResult[]>("search_files", {
-            ...searchArgs,
+          const found = await invoke<SearchFilesResponse>("search_files", {
+            params,
           });
           if (active) {
-            setResults(found.map(normalizeFileSearchResult));
+            setResults(found.results.map(normalizeFileSearchResult));
             setSearchError(null);
           }
         } catch (error) {
@@ -4204,7 +4234,8 @@ function App() {
       groupsFound: 0,
       progressPercent: 0,
     });
-    const minSize = toBytesFromMb(duplicateMinSizeMb) ?? 50 * 1024 * 1024;
+    // Omitted when blank so the backend falls back to the stored duplicate defaults.
+    const minSize = toBytesFromMb(duplicateMinSizeMb);
 
     try {
       const groups = await invoke<DuplicateGroup[]>("find_duplicate_groups", {